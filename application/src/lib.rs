@@ -1,2 +1,5 @@
 pub mod service;
-pub mod use_case;
\ No newline at end of file
+pub mod use_case;
+
+#[cfg(any(test, feature = "test-support"))]
+pub mod test_support;
@@ -0,0 +1,246 @@
+use tracing::info;
+use domain::model::content::{ContentChunk, HtmlContent};
+
+/// Chunk size used when the caller doesn't ask for one.
+pub const DEFAULT_CHUNK_TARGET_CHARS: usize = 1000;
+
+const HEADING_TAGS: &[&str] = &["h1", "h2", "h3", "h4", "h5", "h6"];
+
+/// Splits page text into retrieval-sized chunks along the document's own
+/// structure rather than at fixed offsets.
+#[derive(Debug, Clone, Default)]
+pub struct ContentChunkService;
+
+impl ContentChunkService {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Chunks the content's text blocks, starting a new chunk at every heading
+    /// and otherwise packing whole paragraphs up to `target_chars`. Paragraphs
+    /// longer than that are split between sentences, never inside one, so a
+    /// single long sentence may exceed the target. Without blocks, each line of
+    /// `text_content` is treated as a paragraph.
+    pub fn chunk_content(&self, content: &HtmlContent, target_chars: usize) -> Vec<ContentChunk> {
+        let target_chars = target_chars.max(1);
+        let mut builder = ChunkBuilder::new(target_chars);
+
+        match &content.blocks {
+            Some(blocks) => {
+                for block in blocks {
+                    if HEADING_TAGS.contains(&block.tag.as_str()) {
+                        builder.start_section(&block.text);
+                    } else {
+                        builder.push_paragraph(&block.text);
+                    }
+                }
+            }
+            None => content.text_content.lines().for_each(|line| builder.push_paragraph(line)),
+        }
+
+        let chunks = builder.finish();
+        info!("Split content from {} into {} chunks", content.url, chunks.len());
+        chunks
+    }
+}
+
+struct ChunkBuilder {
+    target_chars: usize,
+    heading: Option<String>,
+    paragraphs: Vec<String>,
+    length: usize,
+    chunks: Vec<ContentChunk>,
+}
+
+impl ChunkBuilder {
+    fn new(target_chars: usize) -> Self {
+        Self {
+            target_chars,
+            heading: None,
+            paragraphs: Vec::new(),
+            length: 0,
+            chunks: Vec::new(),
+        }
+    }
+
+    fn start_section(&mut self, heading: &str) {
+        self.flush();
+        let heading = heading.trim();
+        self.heading = (!heading.is_empty()).then(|| heading.to_string());
+    }
+
+    fn push_paragraph(&mut self, paragraph: &str) {
+        let paragraph = paragraph.trim();
+        if paragraph.is_empty() {
+            return;
+        }
+
+        for piece in split_to_fit(paragraph, self.target_chars) {
+            let piece_length = piece.chars().count();
+            // Paragraphs are joined by a blank line, which counts towards the chunk
+            if !self.paragraphs.is_empty() && self.length + 2 + piece_length > self.target_chars {
+                self.flush();
+            }
+            self.length += if self.paragraphs.is_empty() { piece_length } else { 2 + piece_length };
+            self.paragraphs.push(piece);
+        }
+    }
+
+    fn flush(&mut self) {
+        if self.paragraphs.is_empty() {
+            return;
+        }
+
+        self.chunks.push(ContentChunk {
+            index: self.chunks.len(),
+            heading: self.heading.clone(),
+            text: self.paragraphs.join("\n\n"),
+        });
+        self.paragraphs.clear();
+        self.length = 0;
+    }
+
+    fn finish(mut self) -> Vec<ContentChunk> {
+        self.flush();
+        self.chunks
+    }
+}
+
+/// Returns the paragraph whole if it fits, otherwise runs of its sentences that
+/// each fit (or a lone sentence that is longer than `target_chars` by itself).
+fn split_to_fit(paragraph: &str, target_chars: usize) -> Vec<String> {
+    if paragraph.chars().count() <= target_chars {
+        return vec![paragraph.to_string()];
+    }
+
+    let mut pieces = Vec::new();
+    let mut current = String::new();
+    for sentence in sentences(paragraph) {
+        if !current.is_empty() && current.chars().count() + 1 + sentence.chars().count() > target_chars {
+            pieces.push(std::mem::take(&mut current));
+        }
+        if !current.is_empty() {
+            current.push(' ');
+        }
+        current.push_str(sentence);
+    }
+    if !current.is_empty() {
+        pieces.push(current);
+    }
+    pieces
+}
+
+/// Splits after `.`, `!` or `?` followed by whitespace, keeping the punctuation.
+fn sentences(text: &str) -> Vec<&str> {
+    let mut sentences = Vec::new();
+    let mut start = 0;
+    let mut chars = text.char_indices().peekable();
+
+    while let Some((index, c)) = chars.next() {
+        let at_boundary = matches!(c, '.' | '!' | '?')
+            && chars.peek().is_some_and(|(_, next)| next.is_whitespace());
+        if at_boundary {
+            let end = index + c.len_utf8();
+            sentences.push(text[start..end].trim());
+            start = end;
+        }
+    }
+    sentences.push(text[start..].trim());
+
+    sentences.into_iter().filter(|sentence| !sentence.is_empty()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use domain::model::content::TextBlock;
+
+    fn content_with_blocks(blocks: &[(&str, &str)]) -> HtmlContent {
+        HtmlContent {
+            url: "https://example.com/guide".to_string(),
+            blocks: Some(
+                blocks
+                    .iter()
+                    .map(|(tag, text)| TextBlock { tag: tag.to_string(), text: text.to_string(), lang: None })
+                    .collect(),
+            ),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_chunks_respect_heading_boundaries_and_carry_heading() {
+        let content = content_with_blocks(&[
+            ("p", "Intro before any heading."),
+            ("h2", "Install"),
+            ("p", "Download the binary."),
+            ("li", "Put it on your PATH."),
+            ("h2", "Configure"),
+            ("p", "Edit the config file."),
+        ]);
+
+        let chunks = ContentChunkService::new().chunk_content(&content, 1000);
+
+        assert_eq!(chunks, vec![
+            ContentChunk { index: 0, heading: None, text: "Intro before any heading.".to_string() },
+            ContentChunk {
+                index: 1,
+                heading: Some("Install".to_string()),
+                text: "Download the binary.\n\nPut it on your PATH.".to_string(),
+            },
+            ContentChunk { index: 2, heading: Some("Configure".to_string()), text: "Edit the config file.".to_string() },
+        ]);
+    }
+
+    #[test]
+    fn test_chunks_pack_paragraphs_up_to_target() {
+        let content = content_with_blocks(&[
+            ("h2", "Usage"),
+            ("p", "First paragraph here."),
+            ("p", "Second paragraph here."),
+            ("p", "Third paragraph here."),
+        ]);
+
+        let chunks = ContentChunkService::new().chunk_content(&content, 50);
+
+        let texts: Vec<_> = chunks.iter().map(|chunk| chunk.text.as_str()).collect();
+        assert_eq!(texts, vec![
+            "First paragraph here.\n\nSecond paragraph here.",
+            "Third paragraph here.",
+        ]);
+        assert!(chunks.iter().all(|chunk| chunk.heading.as_deref() == Some("Usage")));
+    }
+
+    #[test]
+    fn test_long_paragraph_splits_between_sentences() {
+        let content = content_with_blocks(&[
+            ("h3", "Notes"),
+            ("p", "One short sentence. Another short one! Is this the third? A final sentence that is rather long."),
+        ]);
+
+        let chunks = ContentChunkService::new().chunk_content(&content, 40);
+
+        let texts: Vec<_> = chunks.iter().map(|chunk| chunk.text.as_str()).collect();
+        assert_eq!(texts, vec![
+            "One short sentence. Another short one!",
+            "Is this the third?",
+            "A final sentence that is rather long.",
+        ]);
+        assert_eq!(chunks[2].index, 2);
+    }
+
+    #[test]
+    fn test_chunks_fall_back_to_text_lines() {
+        let content = HtmlContent {
+            text_content: "Line one.\n\nLine two.".to_string(),
+            ..Default::default()
+        };
+
+        let chunks = ContentChunkService::new().chunk_content(&content, 10);
+
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(chunks[0].text, "Line one.");
+        assert_eq!(chunks[1].text, "Line two.");
+        assert!(chunks.iter().all(|chunk| chunk.heading.is_none()));
+    }
+}
@@ -0,0 +1,103 @@
+use similar::{ChangeTag, TextDiff};
+use tracing::info;
+use domain::model::content::{ContentComparison, HtmlContent};
+
+/// Compares the text of two pages line by line.
+#[derive(Debug, Clone, Default)]
+pub struct ContentDiffService;
+
+impl ContentDiffService {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Diffs the pages' text blocks, one line per block, or the lines of
+    /// `text_content` when a page has no blocks.
+    pub fn compare_content(&self, first: &HtmlContent, second: &HtmlContent) -> ContentComparison {
+        let comparison = self.compare(&content_lines(first), &content_lines(second));
+        info!(
+            "Compared {} with {}: similarity {:.2}",
+            first.url, second.url, comparison.similarity
+        );
+        comparison
+    }
+
+    /// Diffs two texts by line, ignoring blank lines and surrounding whitespace.
+    /// Similarity is the share of lines left unchanged, counted over both texts,
+    /// so identical texts (including two empty ones) score 1.0 and texts with no
+    /// line in common score 0.0.
+    pub fn compare(&self, first: &str, second: &str) -> ContentComparison {
+        let first = normalized_lines(first);
+        let second = normalized_lines(second);
+        let diff = TextDiff::from_slices(&first, &second);
+
+        let mut unchanged = 0;
+        let mut added_lines = Vec::new();
+        let mut removed_lines = Vec::new();
+        for change in diff.iter_all_changes() {
+            match change.tag() {
+                ChangeTag::Equal => unchanged += 1,
+                ChangeTag::Insert => added_lines.push(change.value().to_string()),
+                ChangeTag::Delete => removed_lines.push(change.value().to_string()),
+            }
+        }
+
+        let total = first.len() + second.len();
+        let similarity = if total == 0 {
+            1.0
+        } else {
+            (2 * unchanged) as f32 / total as f32
+        };
+
+        ContentComparison {
+            similarity,
+            added_lines,
+            removed_lines,
+        }
+    }
+}
+
+fn content_lines(content: &HtmlContent) -> String {
+    match &content.blocks {
+        Some(blocks) => blocks.iter().map(|block| block.text.as_str()).collect::<Vec<_>>().join("\n"),
+        None => content.text_content.clone(),
+    }
+}
+
+fn normalized_lines(text: &str) -> Vec<&str> {
+    text.lines().map(str::trim).filter(|line| !line.is_empty()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nearly_identical_texts_are_similar() {
+        let first = "Welcome to the store\nOpen daily from 9 to 5\nFree shipping on orders over $50\nContact us any time";
+        let second = "Welcome to the store\nOpen daily from 9 to 6\nFree shipping on orders over $50\nContact us any time";
+
+        let comparison = ContentDiffService::new().compare(first, second);
+
+        assert!(comparison.similarity >= 0.75, "similarity was {}", comparison.similarity);
+        assert_eq!(comparison.added_lines, vec!["Open daily from 9 to 6"]);
+        assert_eq!(comparison.removed_lines, vec!["Open daily from 9 to 5"]);
+
+        let identical = ContentDiffService::new().compare(first, &format!("\n{}\n\n", first));
+        assert_eq!(identical.similarity, 1.0);
+        assert!(identical.added_lines.is_empty() && identical.removed_lines.is_empty());
+    }
+
+    #[test]
+    fn test_different_texts_are_dissimilar() {
+        let first = "Breaking news\nThe council voted today\nMore at eleven";
+        let second = "Recipe of the week\nPreheat the oven\nServe warm\nEnjoy";
+
+        let comparison = ContentDiffService::new().compare(first, second);
+
+        assert_eq!(comparison.similarity, 0.0);
+        assert_eq!(comparison.added_lines.len(), 4);
+        assert_eq!(comparison.removed_lines.len(), 3);
+        assert_eq!(ContentDiffService::new().compare("", "").similarity, 1.0);
+    }
+}
@@ -1,21 +1,103 @@
 use std::sync::Arc;
 use tracing::info;
-use domain::model::{content::HtmlContent, request::FetchContentRequest};
-use domain::port::content_fetcher::{ContentFetcher, ContentFetcherResult};
+use domain::model::{content::{ContentType, FetchMethod, HtmlContent}, request::{FetchContentRequest, MultipartPart}};
+use domain::port::content_fetcher::{ContentFetcher, ContentFetcherError, ContentFetcherResult};
+
+/// Longest URL accepted by default; longer ones (e.g. data URIs smuggled into a
+/// query string) are rejected before they reach the parser.
+pub const DEFAULT_MAX_URL_LENGTH: usize = 8192;
+
+/// Ports a request URL may name by default. A URL without a port, or with the
+/// default port for its scheme, is always allowed.
+pub const DEFAULT_ALLOWED_PORTS: &[u16] = &[80, 443, 8080, 8443];
+
+/// Timeout applied to requests that don't set `timeout_seconds`.
+pub const DEFAULT_TIMEOUT_SECONDS: u64 = 30;
+
+/// Most retries a single request may ask for.
+pub const MAX_RETRIES: u32 = 5;
+
+/// Most links a single request may ask to have prefetched.
+pub const MAX_PREFETCH_LINKS: usize = 20;
+
+/// Most mirrors a single request may list in `fallback_urls`.
+pub const MAX_FALLBACK_URLS: usize = 5;
 
 pub struct ContentFetchService<F>
 where
-    F: ContentFetcher,
+    F: ContentFetcher + ?Sized,
 {
     content_fetcher: Arc<F>,
+    max_url_length: usize,
+    allowed_ports: Option<Vec<u16>>,
+    default_timeout_seconds: u64,
+    browser_available: bool,
+    allow_file_urls: bool,
 }
 
 impl<F> ContentFetchService<F>
 where
-    F: ContentFetcher,
+    F: ContentFetcher + ?Sized,
 {
     pub fn new(content_fetcher: Arc<F>) -> Self {
-        Self { content_fetcher }
+        Self {
+            content_fetcher,
+            max_url_length: DEFAULT_MAX_URL_LENGTH,
+            allowed_ports: Some(DEFAULT_ALLOWED_PORTS.to_vec()),
+            default_timeout_seconds: DEFAULT_TIMEOUT_SECONDS,
+            browser_available: true,
+            allow_file_urls: false,
+        }
+    }
+
+    /// Rejects request URLs longer than `max_url_length` bytes.
+    pub fn with_max_url_length(mut self, max_url_length: usize) -> Self {
+        self.max_url_length = max_url_length;
+        self
+    }
+
+    /// Rejects request URLs naming a port outside `allowed_ports`, so the server
+    /// can't be used to probe other services; `None` allows any port.
+    pub fn with_allowed_ports(mut self, allowed_ports: Option<Vec<u16>>) -> Self {
+        self.allowed_ports = allowed_ports;
+        self
+    }
+
+    /// Gives requests without their own `timeout_seconds` this many seconds.
+    pub fn with_default_timeout(mut self, seconds: u64) -> Self {
+        self.default_timeout_seconds = seconds;
+        self
+    }
+
+    /// Declares whether the fetcher can render pages in a browser. Without one,
+    /// requests that need the browser are rejected instead of quietly being
+    /// fetched statically.
+    pub fn with_browser_available(mut self, browser_available: bool) -> Self {
+        self.browser_available = browser_available;
+        self
+    }
+
+    /// Accepts `multipart` parts uploading a local file by `file_url`. Off by
+    /// default, since it lets clients send the server's files to any site.
+    pub fn with_file_urls_allowed(mut self, allow_file_urls: bool) -> Self {
+        self.allow_file_urls = allow_file_urls;
+        self
+    }
+
+    pub fn browser_available(&self) -> bool {
+        self.browser_available
+    }
+
+    /// The request's own timeout, or the default one.
+    pub fn timeout_seconds_for(&self, request: &FetchContentRequest) -> u64 {
+        request.timeout_seconds.unwrap_or(self.default_timeout_seconds)
+    }
+
+    fn with_timeout(&self, request: FetchContentRequest) -> FetchContentRequest {
+        FetchContentRequest {
+            timeout_seconds: Some(self.timeout_seconds_for(&request)),
+            ..request
+        }
     }
 
     pub async fn fetch_and_process_content(
@@ -24,27 +106,151 @@ where
     ) -> ContentFetcherResult<HtmlContent> {
         info!("Fetching content from URL: {}", request.url);
         
-        let content = self.content_fetcher.fetch_content(request).await?;
+        let content = self.content_fetcher.fetch_content(self.with_timeout(request)).await?;
         
         info!("Successfully fetched content from URL: {}", content.url);
         Ok(content)
     }
 
+    pub async fn fetch_static_content(
+        &self,
+        request: FetchContentRequest,
+    ) -> ContentFetcherResult<HtmlContent> {
+        info!("Fetching static content from URL: {}", request.url);
+
+        self.content_fetcher.fetch_static(self.with_timeout(request)).await
+    }
+
+    /// URLs the fetcher can serve from its cache, most recently used first.
+    pub fn cached_urls(&self) -> Vec<String> {
+        self.content_fetcher.cached_urls()
+    }
+
     pub async fn validate_request(&self, request: &FetchContentRequest) -> Result<(), String> {
-        if request.url.is_empty() {
-            return Err("URL cannot be empty".to_string());
-        }
+        self.validate_url(&request.url)?;
 
-        if !request.url.starts_with("http://") && !request.url.starts_with("https://") {
-            return Err("URL must start with http:// or https://".to_string());
+        if let Some(fallback_urls) = &request.fallback_urls {
+            if fallback_urls.len() > MAX_FALLBACK_URLS {
+                return Err(format!("fallback_urls cannot list more than {} URLs", MAX_FALLBACK_URLS));
+            }
+            for fallback_url in fallback_urls {
+                self.validate_url(fallback_url)
+                    .map_err(|error| format!("Invalid fallback URL {}: {}", fallback_url, error))?;
+            }
         }
 
         if let Some(timeout) = request.timeout_seconds {
+            // reqwest treats a zero timeout as "fail immediately"
+            if timeout == 0 {
+                return Err("timeout must be at least 1 second".to_string());
+            }
             if timeout > 300 {
                 return Err("Timeout cannot exceed 300 seconds".to_string());
             }
         }
 
+        if let Some(http_version) = &request.http_version {
+            if !matches!(http_version.as_str(), "http1" | "http2" | "auto") {
+                return Err(format!("http_version must be one of http1, http2 or auto, got {}", http_version));
+            }
+        }
+
+        if let Some(ip_version) = &request.ip_version {
+            if !matches!(ip_version.as_str(), "v4" | "v6" | "auto") {
+                return Err(format!("ip_version must be one of v4, v6 or auto, got {}", ip_version));
+            }
+        }
+
+        if request.read_timeout_seconds == Some(0) {
+            return Err("read_timeout_seconds must be at least 1".to_string());
+        }
+
+        if request.max_retries.is_some_and(|retries| retries > MAX_RETRIES) {
+            return Err(format!("max_retries cannot exceed {}", MAX_RETRIES));
+        }
+
+        if request.prefetch_links.is_some_and(|links| links > MAX_PREFETCH_LINKS) {
+            return Err(format!("prefetch_links cannot exceed {}", MAX_PREFETCH_LINKS));
+        }
+
+        if !self.browser_available {
+            if request.force_fetch_method == Some(FetchMethod::Browser) {
+                return Err("browser mode disabled: force_fetch_method browser is not available on this server".to_string());
+            }
+            if request.retry_empty_with_browser == Some(true) {
+                return Err("browser mode disabled: retry_empty_with_browser is not available on this server".to_string());
+            }
+        }
+
+        if request.max_title_length == Some(0) {
+            return Err("max_title_length must be at least 1".to_string());
+        }
+
+        if let Some(referer) = &request.referer {
+            if url::Url::parse(referer).is_err() {
+                return Err(format!("Invalid referer URL: {}", referer));
+            }
+        }
+
+        if let Some(parts) = &request.multipart {
+            self.validate_multipart(request, parts)?;
+        }
+
+        if let Some(format) = request.output_formats.iter().flatten()
+            .find(|format| matches!(format, ContentType::Json | ContentType::Xml))
+        {
+            return Err(format!("output_formats supports text, markdown and html, got {}", format.name()));
+        }
+
+        Ok(())
+    }
+
+    /// Checks a URL the request will fetch: present, not too long, http(s) and
+    /// on an allowed port.
+    fn validate_url(&self, url: &str) -> Result<(), String> {
+        if url.is_empty() {
+            return Err("URL cannot be empty".to_string());
+        }
+
+        if url.len() > self.max_url_length {
+            return Err(ContentFetcherError::InvalidUrl("url too long".to_string()).to_string());
+        }
+
+        if !url.starts_with("http://") && !url.starts_with("https://") {
+            return Err("URL must start with http:// or https://".to_string());
+        }
+
+        if let Some(allowed_ports) = &self.allowed_ports {
+            // `port()` is `None` for the scheme's default port, even when written out
+            let port = url::Url::parse(url).ok().and_then(|url| url.port());
+            if port.is_some_and(|port| !allowed_ports.contains(&port)) {
+                return Err(ContentFetcherError::InvalidUrl("port not allowed".to_string()).to_string());
+            }
+        }
+        Ok(())
+    }
+
+    /// Multipart bodies are posted by the static fetcher, so the browser can't
+    /// be asked for; each part needs exactly one of `value` and `file_url`.
+    fn validate_multipart(&self, request: &FetchContentRequest, parts: &[MultipartPart]) -> Result<(), String> {
+        if request.force_fetch_method == Some(FetchMethod::Browser) || request.retry_empty_with_browser == Some(true) {
+            return Err("multipart requests can't be fetched with the browser".to_string());
+        }
+
+        for part in parts {
+            match (&part.value, &part.file_url) {
+                (Some(_), None) => {}
+                (None, Some(file_url)) => {
+                    if !self.allow_file_urls {
+                        return Err(format!("multipart part {}: file_url is not allowed on this server", part.name));
+                    }
+                    if !url::Url::parse(file_url).is_ok_and(|url| url.scheme() == "file") {
+                        return Err(format!("multipart part {}: file_url must be a file:// URL, got {}", part.name, file_url));
+                    }
+                }
+                _ => return Err(format!("multipart part {} needs exactly one of value or file_url", part.name)),
+            }
+        }
         Ok(())
     }
 }
@@ -90,6 +296,7 @@ mod tests {
                     charset: Some("utf-8".to_string()),
             javascript_detected: None,
             fetch_method: None,
+                    ..Default::default()
                 };
 
                 Ok(HtmlContent {
@@ -98,6 +305,7 @@ mod tests {
                     text_content: "Test content".to_string(),
                     raw_html: "<html><body>Test</body></html>".to_string(),
                     metadata,
+                    ..Default::default()
                 })
             } else {
                 Err(self.return_error.as_ref().unwrap().clone())
@@ -105,6 +313,45 @@ mod tests {
         }
     }
 
+    /// Reports the timeout each request arrived with as the page title.
+    struct TimeoutEchoFetcher;
+
+    #[async_trait]
+    impl ContentFetcher for TimeoutEchoFetcher {
+        async fn fetch_content(&self, request: FetchContentRequest) -> ContentFetcherResult<HtmlContent> {
+            Ok(HtmlContent {
+                url: request.url,
+                title: request.timeout_seconds.map(|seconds| seconds.to_string()),
+                ..Default::default()
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_configured_default_timeout_fills_missing_timeout() {
+        let service = ContentFetchService::new(Arc::new(TimeoutEchoFetcher)).with_default_timeout(90);
+
+        let without_timeout = FetchContentRequest {
+            url: "https://example.com".to_string(),
+            timeout_seconds: None,
+            ..Default::default()
+        };
+        let content = service.fetch_and_process_content(without_timeout.clone()).await.unwrap();
+        assert_eq!(content.title.as_deref(), Some("90"));
+        let content = service.fetch_static_content(without_timeout.clone()).await.unwrap();
+        assert_eq!(content.title.as_deref(), Some("90"));
+
+        let with_timeout = FetchContentRequest {
+            timeout_seconds: Some(5),
+            ..without_timeout.clone()
+        };
+        let content = service.fetch_and_process_content(with_timeout).await.unwrap();
+        assert_eq!(content.title.as_deref(), Some("5"));
+
+        let service = ContentFetchService::new(Arc::new(TimeoutEchoFetcher));
+        let content = service.fetch_and_process_content(without_timeout).await.unwrap();
+        assert_eq!(content.title.as_deref(), Some("30"));
+    }
 
     #[tokio::test]
     async fn test_fetch_and_process_content_success() {
@@ -117,6 +364,7 @@ mod tests {
             follow_redirects: Some(true),
             timeout_seconds: Some(30),
             user_agent: Some("test".to_string()),
+            ..Default::default()
         };
 
         let result = service.fetch_and_process_content(request).await;
@@ -130,7 +378,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_fetch_and_process_content_network_error() {
-        let error = ContentFetcherError::Network("Connection refused".to_string());
+        let error = ContentFetcherError::network("Connection refused".to_string());
         let fetcher = Arc::new(MockContentFetcher::new_with_error(error));
         let service = ContentFetchService::new(fetcher);
 
@@ -140,6 +388,7 @@ mod tests {
             follow_redirects: Some(true),
             timeout_seconds: Some(30),
             user_agent: Some("test".to_string()),
+            ..Default::default()
         };
 
         let result = service.fetch_and_process_content(request).await;
@@ -162,6 +411,7 @@ mod tests {
             follow_redirects: Some(true),
             timeout_seconds: Some(30),
             user_agent: Some("test".to_string()),
+            ..Default::default()
         };
 
         let result = service.fetch_and_process_content(request).await;
@@ -187,6 +437,7 @@ mod tests {
             follow_redirects: Some(true),
             timeout_seconds: Some(30),
             user_agent: Some("test".to_string()),
+            ..Default::default()
         };
 
         let result = service.fetch_and_process_content(request).await;
@@ -208,6 +459,7 @@ mod tests {
             follow_redirects: Some(true),
             timeout_seconds: Some(30),
             user_agent: Some("test".to_string()),
+            ..Default::default()
         };
 
         let result = service.validate_request(&request).await;
@@ -225,6 +477,7 @@ mod tests {
             follow_redirects: Some(true),
             timeout_seconds: Some(30),
             user_agent: Some("test".to_string()),
+            ..Default::default()
         };
 
         let result = service.validate_request(&request).await;
@@ -243,6 +496,7 @@ mod tests {
             follow_redirects: Some(true),
             timeout_seconds: Some(30),
             user_agent: Some("test".to_string()),
+            ..Default::default()
         };
 
         let result = service.validate_request(&request).await;
@@ -261,6 +515,7 @@ mod tests {
             follow_redirects: Some(true),
             timeout_seconds: Some(30),
             user_agent: Some("test".to_string()),
+            ..Default::default()
         };
 
         let result = service.validate_request(&request).await;
@@ -278,6 +533,7 @@ mod tests {
             follow_redirects: Some(true),
             timeout_seconds: Some(400),
             user_agent: Some("test".to_string()),
+            ..Default::default()
         };
 
         let result = service.validate_request(&request).await;
@@ -296,6 +552,7 @@ mod tests {
             follow_redirects: Some(true),
             timeout_seconds: Some(300),
             user_agent: Some("test".to_string()),
+            ..Default::default()
         };
 
         let result = service.validate_request(&request).await;
@@ -313,6 +570,7 @@ mod tests {
             follow_redirects: Some(true),
             timeout_seconds: None,
             user_agent: Some("test".to_string()),
+            ..Default::default()
         };
 
         let result = service.validate_request(&request).await;
@@ -330,12 +588,301 @@ mod tests {
             follow_redirects: Some(true),
             timeout_seconds: Some(0),
             user_agent: Some("test".to_string()),
+            ..Default::default()
+        };
+
+        let result = service.validate_request(&request).await;
+        assert_eq!(result.unwrap_err(), "timeout must be at least 1 second");
+    }
+
+    #[tokio::test]
+    async fn test_validate_request_minimum_timeout() {
+        let fetcher = Arc::new(MockContentFetcher::new_success());
+        let service = ContentFetchService::new(fetcher);
+
+        let request = FetchContentRequest {
+            url: "https://example.com".to_string(),
+            timeout_seconds: Some(1),
+            ..Default::default()
         };
 
         let result = service.validate_request(&request).await;
         assert!(result.is_ok());
     }
 
+    #[tokio::test]
+    async fn test_validate_request_unsupported_output_format() {
+        let fetcher = Arc::new(MockContentFetcher::new_success());
+        let service = ContentFetchService::new(fetcher);
+
+        let request = FetchContentRequest {
+            url: "https://example.com".to_string(),
+            output_formats: Some(vec![ContentType::Markdown, ContentType::Json]),
+            ..Default::default()
+        };
+
+        let result = service.validate_request(&request).await;
+        assert_eq!(result.unwrap_err(), "output_formats supports text, markdown and html, got json");
+    }
+
+    #[tokio::test]
+    async fn test_validate_request_url_at_max_length() {
+        let fetcher = Arc::new(MockContentFetcher::new_success());
+        let service = ContentFetchService::new(fetcher);
+
+        let prefix = "https://example.com/?q=";
+        let request = FetchContentRequest {
+            url: format!("{}{}", prefix, "a".repeat(DEFAULT_MAX_URL_LENGTH - prefix.len())),
+            ..Default::default()
+        };
+
+        assert_eq!(request.url.len(), DEFAULT_MAX_URL_LENGTH);
+        assert!(service.validate_request(&request).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_validate_request_url_too_long() {
+        let fetcher = Arc::new(MockContentFetcher::new_success());
+        let service = ContentFetchService::new(fetcher).with_max_url_length(32);
+
+        let request = FetchContentRequest {
+            url: format!("https://example.com/{}", "a".repeat(13)),
+            ..Default::default()
+        };
+
+        assert_eq!(service.validate_request(&request).await.unwrap_err(), "Invalid URL: url too long");
+    }
+
+    #[tokio::test]
+    async fn test_validate_request_allowed_ports() {
+        let fetcher = Arc::new(MockContentFetcher::new_success());
+        let service = ContentFetchService::new(fetcher.clone());
+        let request = |url: &str| FetchContentRequest {
+            url: url.to_string(),
+            ..Default::default()
+        };
+
+        assert!(service.validate_request(&request("https://example.com:8443/admin")).await.is_ok());
+        assert!(service.validate_request(&request("https://example.com:443/")).await.is_ok());
+        assert!(service.validate_request(&request("http://example.com/")).await.is_ok());
+        assert_eq!(
+            service.validate_request(&request("http://example.com:22/")).await.unwrap_err(),
+            "Invalid URL: port not allowed"
+        );
+
+        let custom = ContentFetchService::new(fetcher.clone()).with_allowed_ports(Some(vec![3000]));
+        assert!(custom.validate_request(&request("http://example.com:3000/")).await.is_ok());
+        assert!(custom.validate_request(&request("http://example.com:80/")).await.is_ok());
+        assert!(custom.validate_request(&request("http://example.com:8080/")).await.is_err());
+
+        let any_port = ContentFetchService::new(fetcher).with_allowed_ports(None);
+        assert!(any_port.validate_request(&request("http://example.com:22/")).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_validate_request_zero_max_title_length() {
+        let fetcher = Arc::new(MockContentFetcher::new_success());
+        let service = ContentFetchService::new(fetcher);
+
+        let request = FetchContentRequest {
+            url: "https://example.com".to_string(),
+            max_title_length: Some(0),
+            ..Default::default()
+        };
+
+        let result = service.validate_request(&request).await;
+        assert_eq!(result.unwrap_err(), "max_title_length must be at least 1");
+    }
+
+    #[tokio::test]
+    async fn test_validate_request_zero_read_timeout() {
+        let fetcher = Arc::new(MockContentFetcher::new_success());
+        let service = ContentFetchService::new(fetcher);
+
+        let request = FetchContentRequest {
+            url: "https://example.com".to_string(),
+            read_timeout_seconds: Some(0),
+            ..Default::default()
+        };
+
+        let result = service.validate_request(&request).await;
+        assert_eq!(result.unwrap_err(), "read_timeout_seconds must be at least 1");
+    }
+
+    #[tokio::test]
+    async fn test_validate_request_http_version() {
+        let fetcher = Arc::new(MockContentFetcher::new_success());
+        let service = ContentFetchService::new(fetcher);
+
+        for version in ["http1", "http2", "auto"] {
+            let request = FetchContentRequest {
+                url: "https://example.com".to_string(),
+                http_version: Some(version.to_string()),
+                ..Default::default()
+            };
+            assert!(service.validate_request(&request).await.is_ok());
+        }
+
+        let request = FetchContentRequest {
+            url: "https://example.com".to_string(),
+            http_version: Some("http3".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            service.validate_request(&request).await.unwrap_err(),
+            "http_version must be one of http1, http2 or auto, got http3"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_validate_request_ip_version() {
+        let fetcher = Arc::new(MockContentFetcher::new_success());
+        let service = ContentFetchService::new(fetcher);
+
+        for version in ["v4", "v6", "auto"] {
+            let request = FetchContentRequest {
+                url: "https://example.com".to_string(),
+                ip_version: Some(version.to_string()),
+                ..Default::default()
+            };
+            assert!(service.validate_request(&request).await.is_ok());
+        }
+
+        let request = FetchContentRequest {
+            url: "https://example.com".to_string(),
+            ip_version: Some("ipv4".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            service.validate_request(&request).await.unwrap_err(),
+            "ip_version must be one of v4, v6 or auto, got ipv4"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_validate_request_max_retries() {
+        let fetcher = Arc::new(MockContentFetcher::new_success());
+        let service = ContentFetchService::new(fetcher);
+        let request = |max_retries| FetchContentRequest {
+            url: "https://example.com".to_string(),
+            max_retries: Some(max_retries),
+            ..Default::default()
+        };
+
+        assert!(service.validate_request(&request(MAX_RETRIES)).await.is_ok());
+        assert_eq!(
+            service.validate_request(&request(MAX_RETRIES + 1)).await.unwrap_err(),
+            format!("max_retries cannot exceed {}", MAX_RETRIES)
+        );
+
+        let prefetch = |prefetch_links| FetchContentRequest {
+            url: "https://example.com".to_string(),
+            prefetch_links: Some(prefetch_links),
+            ..Default::default()
+        };
+        assert!(service.validate_request(&prefetch(MAX_PREFETCH_LINKS)).await.is_ok());
+        assert!(service.validate_request(&prefetch(MAX_PREFETCH_LINKS + 1)).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_validate_request_referer() {
+        let fetcher = Arc::new(MockContentFetcher::new_success());
+        let service = ContentFetchService::new(fetcher);
+
+        let valid = FetchContentRequest {
+            url: "https://example.com".to_string(),
+            referer: Some("https://www.google.com/".to_string()),
+            ..Default::default()
+        };
+        assert!(service.validate_request(&valid).await.is_ok());
+
+        let invalid = FetchContentRequest {
+            url: "https://example.com".to_string(),
+            referer: Some("not a url".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(service.validate_request(&invalid).await.unwrap_err(), "Invalid referer URL: not a url");
+    }
+
+    #[tokio::test]
+    async fn test_validate_request_browser_disabled() {
+        let fetcher = Arc::new(MockContentFetcher::new_success());
+        let service = ContentFetchService::new(fetcher).with_browser_available(false);
+
+        let browser = FetchContentRequest {
+            url: "https://example.com".to_string(),
+            force_fetch_method: Some(FetchMethod::Browser),
+            ..Default::default()
+        };
+        let error = service.validate_request(&browser).await.unwrap_err();
+        assert!(error.starts_with("browser mode disabled"), "{}", error);
+
+        let retry = FetchContentRequest {
+            url: "https://example.com".to_string(),
+            retry_empty_with_browser: Some(true),
+            ..Default::default()
+        };
+        assert!(service.validate_request(&retry).await.unwrap_err().starts_with("browser mode disabled"));
+
+        let static_fetch = FetchContentRequest {
+            url: "https://example.com".to_string(),
+            force_fetch_method: Some(FetchMethod::Static),
+            ..Default::default()
+        };
+        assert!(service.validate_request(&static_fetch).await.is_ok());
+        assert!(!service.browser_available());
+    }
+
+    #[tokio::test]
+    async fn test_validate_request_fallback_urls() {
+        let fetcher = Arc::new(MockContentFetcher::new_success());
+        let service = ContentFetchService::new(fetcher);
+        let with_fallbacks = |fallback_urls: Vec<&str>| FetchContentRequest {
+            url: "https://example.com".to_string(),
+            fallback_urls: Some(fallback_urls.into_iter().map(str::to_string).collect()),
+            ..Default::default()
+        };
+
+        assert!(service.validate_request(&with_fallbacks(vec!["https://mirror.example.com"])).await.is_ok());
+        assert_eq!(
+            service.validate_request(&with_fallbacks(vec!["ftp://mirror.example.com"])).await.unwrap_err(),
+            "Invalid fallback URL ftp://mirror.example.com: URL must start with http:// or https://"
+        );
+        let too_many = with_fallbacks(vec!["https://mirror.example.com"; MAX_FALLBACK_URLS + 1]);
+        assert!(service.validate_request(&too_many).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_validate_request_multipart() {
+        let fetcher = Arc::new(MockContentFetcher::new_success());
+        let service = ContentFetchService::new(fetcher.clone());
+        let upload = |value: Option<&str>, file_url: Option<&str>| FetchContentRequest {
+            url: "https://example.com/upload".to_string(),
+            multipart: Some(vec![MultipartPart {
+                name: "field".to_string(),
+                value: value.map(str::to_string),
+                file_url: file_url.map(str::to_string),
+                content_type: None,
+            }]),
+            ..Default::default()
+        };
+
+        assert!(service.validate_request(&upload(Some("inline"), None)).await.is_ok());
+        let error = service.validate_request(&upload(None, Some("file:///tmp/report.csv"))).await.unwrap_err();
+        assert_eq!(error, "multipart part field: file_url is not allowed on this server");
+        let error = service.validate_request(&upload(Some("inline"), Some("file:///tmp/report.csv"))).await.unwrap_err();
+        assert_eq!(error, "multipart part field needs exactly one of value or file_url");
+        assert!(service.validate_request(&upload(None, None)).await.is_err());
+
+        let browser_upload = FetchContentRequest { force_fetch_method: Some(FetchMethod::Browser), ..upload(Some("inline"), None) };
+        assert!(service.validate_request(&browser_upload).await.is_err());
+
+        let service = ContentFetchService::new(fetcher).with_file_urls_allowed(true);
+        assert!(service.validate_request(&upload(None, Some("file:///tmp/report.csv"))).await.is_ok());
+        let error = service.validate_request(&upload(None, Some("https://example.com/report.csv"))).await.unwrap_err();
+        assert!(error.contains("must be a file:// URL"), "{}", error);
+    }
+
     #[tokio::test]
     async fn test_service_creation() {
         let fetcher = Arc::new(MockContentFetcher::new_success());
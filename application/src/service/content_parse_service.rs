@@ -1,6 +1,7 @@
+use std::collections::HashMap;
 use std::sync::Arc;
 use tracing::info;
-use domain::model::content::HtmlContent;
+use domain::model::{content::{Article, HtmlContent}, request::ParseOptions};
 use domain::port::content_parser::{ContentParser, ContentParserResult};
 
 pub struct ContentParseService<P>
@@ -18,19 +19,49 @@ where
         Self { content_parser }
     }
 
-    pub async fn parse_html_content(
+    /// Parses a fetched document, keeping the `status` and `headers` it was served with.
+    pub async fn parse_html_content_with_options(
         &self,
         raw_html: &str,
         url: &str,
+        status: u16,
+        headers: &HashMap<String, String>,
+        options: &ParseOptions,
     ) -> ContentParserResult<HtmlContent> {
-        info!("Parsing HTML content for URL: {}", url);
-        
-        let content = self.content_parser.parse_html(raw_html, url).await?;
-        
+        info!("Parsing HTML content with options for URL: {}", url);
+
+        let content = self.content_parser.parse_html_with_status(raw_html, url, status, headers, options).await?;
+
         info!("Successfully parsed HTML content for URL: {}", url);
         Ok(content)
     }
 
+    pub async fn convert_to_markdown(
+        &self,
+        raw_html: &str,
+        url: &str,
+    ) -> ContentParserResult<String> {
+        info!("Converting HTML content to Markdown for URL: {}", url);
+
+        let markdown = self.content_parser.to_markdown(raw_html, url).await?;
+
+        info!("Successfully converted content to Markdown");
+        Ok(markdown)
+    }
+
+    pub async fn extract_article(
+        &self,
+        raw_html: &str,
+        url: &str,
+    ) -> ContentParserResult<Article> {
+        info!("Extracting article from HTML content for URL: {}", url);
+
+        let article = self.content_parser.extract_article(raw_html, url).await?;
+
+        info!("Successfully extracted article with {} characters of text", article.body_text.len());
+        Ok(article)
+    }
+
     pub async fn extract_text_only(
         &self,
         html_content: &HtmlContent,
@@ -1,2 +1,4 @@
 pub mod content_fetch_service;
-pub mod content_parse_service;
\ No newline at end of file
+pub mod content_parse_service;
+pub mod content_chunk_service;
+pub mod content_diff_service;
@@ -0,0 +1,538 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::ops::ControlFlow;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use futures::stream::{FuturesUnordered, StreamExt};
+use tokio::sync::{Semaphore, SemaphorePermit};
+use tokio::time::Instant;
+use tracing::{info, debug};
+use url::Url;
+use domain::model::{
+    content::HtmlContent,
+    request::{CrawlRequest, FetchContentRequest},
+    response::CrawlEvent,
+};
+use domain::port::{content_fetcher::ContentFetcher, content_parser::ContentParser};
+use crate::use_case::fetch_web_content_use_case::FetchWebContentUseCase;
+
+/// Pages crawled when the request doesn't say.
+pub const DEFAULT_CRAWL_MAX_PAGES: usize = 10;
+
+/// Most pages a single crawl may fetch.
+pub const MAX_CRAWL_PAGES: usize = 100;
+
+/// Pages fetched at once during a crawl.
+pub const DEFAULT_CRAWL_CONCURRENCY: usize = 4;
+
+/// Longest delay between same-host fetches a request or robots.txt may ask for.
+pub const MAX_CRAWL_DELAY_MS: u64 = 60_000;
+
+/// Breadth-first crawl of the pages linked from a start URL, staying on its origin.
+pub struct CrawlSiteUseCase<F, P>
+where
+    F: ContentFetcher + ?Sized,
+    P: ContentParser,
+{
+    fetch_use_case: Arc<FetchWebContentUseCase<F, P>>,
+    concurrency: usize,
+    fetch_slots: Option<Arc<Semaphore>>,
+}
+
+impl<F, P> CrawlSiteUseCase<F, P>
+where
+    F: ContentFetcher + ?Sized,
+    P: ContentParser,
+{
+    pub fn new(fetch_use_case: Arc<FetchWebContentUseCase<F, P>>) -> Self {
+        Self {
+            fetch_use_case,
+            concurrency: DEFAULT_CRAWL_CONCURRENCY,
+            fetch_slots: None,
+        }
+    }
+
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// Takes a permit of `fetch_slots` for each page fetched, on top of the
+    /// crawl's own `concurrency`.
+    pub fn with_fetch_slots(mut self, fetch_slots: Arc<Semaphore>) -> Self {
+        self.fetch_slots = Some(fetch_slots);
+        self
+    }
+
+    async fn fetch_slot(&self) -> Option<SemaphorePermit<'_>> {
+        self.fetch_slots.as_ref()?.acquire().await.ok()
+    }
+
+    pub fn validate_request(&self, request: &CrawlRequest) -> Result<(), String> {
+        let start = Url::parse(&request.start_url)
+            .map_err(|error| format!("Invalid start_url: {}", error))?;
+        if !matches!(start.scheme(), "http" | "https") {
+            return Err("start_url must start with http:// or https://".to_string());
+        }
+
+        if let Some(max_pages) = request.max_pages {
+            if max_pages == 0 || max_pages > MAX_CRAWL_PAGES {
+                return Err(format!("max_pages must be between 1 and {}", MAX_CRAWL_PAGES));
+            }
+        }
+
+        if request.crawl_delay_ms.is_some_and(|delay| delay > MAX_CRAWL_DELAY_MS) {
+            return Err(format!("crawl_delay_ms must be at most {}", MAX_CRAWL_DELAY_MS));
+        }
+
+        Ok(())
+    }
+
+    /// Crawls up to `max_pages` pages, calling `on_event` for every page as it
+    /// completes and finally with `CrawlEvent::Complete`. Returns the number of
+    /// pages fetched successfully. Fetches from the same host start at least
+    /// `crawl_delay_ms` apart, or the start origin's robots.txt `Crawl-delay`
+    /// when that is longer. The crawl stops early, without a `Complete` event,
+    /// once `on_event` breaks, e.g. because the client has gone away.
+    pub async fn crawl<E>(&self, request: &CrawlRequest, mut on_event: E) -> usize
+    where
+        E: FnMut(CrawlEvent) -> ControlFlow<()>,
+    {
+        let max_pages = request.max_pages.unwrap_or(DEFAULT_CRAWL_MAX_PAGES).min(MAX_CRAWL_PAGES);
+        let Some(start) = normalize_url(&request.start_url) else {
+            let invalid = CrawlEvent::Error {
+                url: request.start_url.clone(),
+                message: "Invalid start_url".to_string(),
+            };
+            if on_event(invalid).is_continue() {
+                let _ = on_event(CrawlEvent::Complete { total: 0 });
+            }
+            return 0;
+        };
+
+        let robots_delay = self.robots_crawl_delay(&start, &request.client_id).await;
+        let delay = request.crawl_delay_ms.map(Duration::from_millis).max(robots_delay).unwrap_or_default();
+        let pacer = HostPacer::new(delay);
+
+        info!("Crawling up to {} pages from {} ({:?} between same-host fetches)", max_pages, start, delay);
+
+        let mut seen = HashSet::from([start.to_string()]);
+        let mut queue = VecDeque::from([start.clone()]);
+        let mut in_flight = FuturesUnordered::new();
+        let mut scheduled = 0;
+        let mut total = 0;
+
+        loop {
+            while in_flight.len() < self.concurrency && scheduled < max_pages {
+                let Some(url) = queue.pop_front() else {
+                    break;
+                };
+                scheduled += 1;
+                in_flight.push(self.fetch_page(url, &pacer, &request.client_id));
+            }
+
+            let Some((url, result)) = in_flight.next().await else {
+                break;
+            };
+
+            let event = match result {
+                Ok(content) => {
+                    total += 1;
+                    for link in content.links.iter().flatten() {
+                        let Some(link) = normalize_url(&link.href) else {
+                            continue;
+                        };
+                        if link.origin() == start.origin() && seen.insert(link.to_string()) {
+                            queue.push_back(link);
+                        }
+                    }
+                    CrawlEvent::Page { url: content.url, title: content.title }
+                }
+                Err(message) => {
+                    debug!("Crawl skipped {}: {}", url, message);
+                    CrawlEvent::Error { url: url.to_string(), message }
+                }
+            };
+            if on_event(event).is_break() {
+                info!("Crawl from {} stopped after {} pages", start, total);
+                return total;
+            }
+        }
+
+        info!("Crawl from {} finished after {} pages", start, total);
+        let _ = on_event(CrawlEvent::Complete { total });
+        total
+    }
+
+    async fn fetch_page(&self, url: Url, pacer: &HostPacer, client_id: &Option<String>) -> (Url, Result<HtmlContent, String>) {
+        pacer.wait_turn(&url).await;
+        let request = FetchContentRequest {
+            url: url.to_string(),
+            include_links: Some(true),
+            client_id: client_id.clone(),
+            ..Default::default()
+        };
+        let _slot = self.fetch_slot().await;
+        let result = self.fetch_use_case.execute_for_api(request).await;
+        (url, result)
+    }
+
+    /// The `Crawl-delay` the start origin's robots.txt sets for all user agents,
+    /// if it can be fetched and sets one.
+    async fn robots_crawl_delay(&self, start: &Url, client_id: &Option<String>) -> Option<Duration> {
+        let robots_url = start.join("/robots.txt").ok()?;
+        let request = FetchContentRequest {
+            url: robots_url.to_string(),
+            client_id: client_id.clone(),
+            ..Default::default()
+        };
+        let robots = {
+            let _slot = self.fetch_slot().await;
+            self.fetch_use_case.execute_for_api(request).await.ok()?
+        };
+        let delay = parse_robots_crawl_delay(&robots.raw_html)?;
+        info!("robots.txt for {} asks for {:?} between fetches", start.origin().ascii_serialization(), delay);
+        Some(delay)
+    }
+}
+
+/// Spaces out fetches from the same host: a fetch gets its turn only once
+/// `delay` has passed since the previous turn for that host. Turns are handed
+/// out under the lock at the moment they're taken, so waiters woken together
+/// (or a little early by the timer) check again rather than go at once. Other
+/// hosts are unaffected.
+struct HostPacer {
+    delay: Duration,
+    last_turn: Mutex<HashMap<String, Instant>>,
+}
+
+impl HostPacer {
+    fn new(delay: Duration) -> Self {
+        Self {
+            delay,
+            last_turn: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Waits for the host's next turn and returns when it was taken.
+    async fn wait_turn(&self, url: &Url) -> Instant {
+        let host = url.host_str().unwrap_or_default().to_string();
+        loop {
+            let next_turn = {
+                let mut last_turn = self.last_turn.lock().unwrap();
+                let now = Instant::now();
+                match last_turn.get(&host).map(|last| *last + self.delay) {
+                    Some(next_turn) if next_turn > now => next_turn,
+                    _ => {
+                        last_turn.insert(host, now);
+                        return now;
+                    }
+                }
+            };
+            tokio::time::sleep_until(next_turn).await;
+        }
+    }
+}
+
+/// Reads the `Crawl-delay` (in seconds, possibly fractional) from the robots.txt
+/// group for `User-agent: *`, capped at `MAX_CRAWL_DELAY_MS`.
+fn parse_robots_crawl_delay(robots_txt: &str) -> Option<Duration> {
+    let mut in_wildcard_group = false;
+    let mut group_has_rules = false;
+
+    for line in robots_txt.lines() {
+        let line = line.split('#').next().unwrap_or_default().trim();
+        let Some((field, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+
+        if field.trim().eq_ignore_ascii_case("user-agent") {
+            // Consecutive User-agent lines share the group that follows them
+            if group_has_rules {
+                in_wildcard_group = false;
+                group_has_rules = false;
+            }
+            in_wildcard_group |= value == "*";
+            continue;
+        }
+
+        group_has_rules = true;
+        if in_wildcard_group && field.trim().eq_ignore_ascii_case("crawl-delay") {
+            let seconds = value.parse::<f64>().ok().filter(|seconds| seconds.is_finite() && *seconds >= 0.0)?;
+            let millis = (seconds * 1000.0).min(MAX_CRAWL_DELAY_MS as f64);
+            return Some(Duration::from_millis(millis as u64));
+        }
+    }
+
+    None
+}
+
+/// Parses an http(s) URL without its fragment, so `page#a` and `page#b` are crawled once.
+fn normalize_url(url: &str) -> Option<Url> {
+    let mut url = Url::parse(url).ok()?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return None;
+    }
+    url.set_fragment(None);
+    Some(url)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use async_trait::async_trait;
+    use domain::model::{content::Link, request::ParseOptions};
+    use domain::port::content_fetcher::{ContentFetcherError, ContentFetcherResult};
+    use domain::port::content_parser::ContentParserResult;
+    use crate::service::{
+        content_fetch_service::ContentFetchService,
+        content_parse_service::ContentParseService,
+    };
+
+    /// Serves a fixed site where each page's `raw_html` is its outgoing links, one per line.
+    struct SiteFetcher {
+        pages: HashMap<String, Vec<String>>,
+        timeouts: Mutex<Vec<Option<u64>>>,
+    }
+
+    #[async_trait]
+    impl ContentFetcher for SiteFetcher {
+        async fn fetch_content(&self, request: FetchContentRequest) -> ContentFetcherResult<HtmlContent> {
+            self.timeouts.lock().unwrap().push(request.timeout_seconds);
+            let links = self.pages.get(&request.url).ok_or(ContentFetcherError::Http {
+                status: 404,
+                message: "HTTP 404 Not Found".to_string(),
+            })?;
+
+            Ok(HtmlContent {
+                title: Some(format!("Title of {}", request.url)),
+                raw_html: links.join("\n"),
+                url: request.url,
+                ..Default::default()
+            })
+        }
+    }
+
+    struct LinkParser;
+
+    #[async_trait]
+    impl ContentParser for LinkParser {
+        async fn parse_html(&self, raw_html: &str, url: &str) -> ContentParserResult<HtmlContent> {
+            Ok(HtmlContent {
+                url: url.to_string(),
+                raw_html: raw_html.to_string(),
+                ..Default::default()
+            })
+        }
+
+        async fn extract_text(&self, html_content: &HtmlContent) -> ContentParserResult<String> {
+            Ok(html_content.text_content.clone())
+        }
+
+        async fn parse_html_with_options(
+            &self,
+            raw_html: &str,
+            url: &str,
+            _options: &ParseOptions,
+        ) -> ContentParserResult<HtmlContent> {
+            let links = raw_html
+                .lines()
+                .map(|href| Link { href: href.to_string(), text: String::new() })
+                .collect();
+            Ok(HtmlContent {
+                links: Some(links),
+                ..self.parse_html(raw_html, url).await?
+            })
+        }
+    }
+
+    fn site(pages: &[(&str, &[&str])]) -> Arc<SiteFetcher> {
+        let pages = pages
+            .iter()
+            .map(|(url, links)| (url.to_string(), links.iter().map(|link| link.to_string()).collect()))
+            .collect();
+        Arc::new(SiteFetcher { pages, timeouts: Mutex::default() })
+    }
+
+    fn crawler(pages: &[(&str, &[&str])]) -> CrawlSiteUseCase<SiteFetcher, LinkParser> {
+        crawler_with(ContentFetchService::new(site(pages)))
+    }
+
+    fn crawler_with(fetch_service: ContentFetchService<SiteFetcher>) -> CrawlSiteUseCase<SiteFetcher, LinkParser> {
+        let fetch_service = Arc::new(fetch_service);
+        let parse_service = Arc::new(ContentParseService::new(Arc::new(LinkParser)));
+        CrawlSiteUseCase::new(Arc::new(FetchWebContentUseCase::new(fetch_service, parse_service)))
+    }
+
+    async fn crawl(crawler: &CrawlSiteUseCase<SiteFetcher, LinkParser>, max_pages: Option<usize>) -> Vec<CrawlEvent> {
+        let request = CrawlRequest {
+            start_url: "https://example.com/".to_string(),
+            max_pages,
+            crawl_delay_ms: None,
+            client_id: None,
+        };
+        let mut events = Vec::new();
+        crawler.crawl(&request, |event| {
+            events.push(event);
+            ControlFlow::Continue(())
+        }).await;
+        events
+    }
+
+    #[tokio::test]
+    async fn test_crawl_follows_same_origin_links_once() {
+        let crawler = crawler(&[
+            ("https://example.com/", &["https://example.com/a", "https://example.com/b#top", "https://other.example/"]),
+            ("https://example.com/a", &["https://example.com/", "https://example.com/b"]),
+            ("https://example.com/b", &["https://example.com/missing"]),
+        ]);
+
+        let events = crawl(&crawler, None).await;
+
+        let mut pages: Vec<_> = events
+            .iter()
+            .filter_map(|event| match event {
+                CrawlEvent::Page { url, .. } => Some(url.as_str()),
+                _ => None,
+            })
+            .collect();
+        pages.sort();
+        assert_eq!(pages, vec!["https://example.com/", "https://example.com/a", "https://example.com/b"]);
+
+        assert!(events.contains(&CrawlEvent::Error {
+            url: "https://example.com/missing".to_string(),
+            message: "HTTP 404: HTTP 404 Not Found".to_string(),
+        }));
+        assert_eq!(events.last(), Some(&CrawlEvent::Complete { total: 3 }));
+        assert_eq!(events.len(), 5);
+    }
+
+    #[tokio::test]
+    async fn test_crawl_stops_at_max_pages() {
+        let crawler = crawler(&[
+            ("https://example.com/", &["https://example.com/a", "https://example.com/b"]),
+            ("https://example.com/a", &[]),
+            ("https://example.com/b", &[]),
+        ]);
+
+        let events = crawl(&crawler, Some(2)).await;
+
+        assert_eq!(events.len(), 3);
+        assert_eq!(events.last(), Some(&CrawlEvent::Complete { total: 2 }));
+    }
+
+    #[tokio::test]
+    async fn test_crawl_stops_when_on_event_breaks() {
+        let site = site(&[
+            ("https://example.com/", &["https://example.com/a"]),
+            ("https://example.com/a", &["https://example.com/b"]),
+            ("https://example.com/b", &[]),
+        ]);
+        let crawler = crawler_with(ContentFetchService::new(site.clone()));
+        let request = CrawlRequest {
+            start_url: "https://example.com/".to_string(),
+            max_pages: None,
+            crawl_delay_ms: None,
+            client_id: None,
+        };
+
+        let mut events = Vec::new();
+        let total = crawler.crawl(&request, |event| {
+            events.push(event);
+            ControlFlow::Break(())
+        }).await;
+
+        assert_eq!(total, 1);
+        assert_eq!(events.len(), 1);
+        // The robots.txt lookup and the start page, none of the pages it links to
+        assert_eq!(site.timeouts.lock().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_crawl_fetches_use_the_configured_default_timeout() {
+        let site = site(&[
+            ("https://example.com/", &["https://example.com/a"]),
+            ("https://example.com/a", &[]),
+        ]);
+        let crawler = crawler_with(ContentFetchService::new(site.clone()).with_default_timeout(7));
+
+        crawl(&crawler, None).await;
+
+        // The robots.txt lookup and both pages
+        let timeouts = site.timeouts.lock().unwrap();
+        assert_eq!(*timeouts, vec![Some(7); 3]);
+    }
+
+    #[tokio::test]
+    async fn test_pacer_spaces_turns_per_host() {
+        let pacer = HostPacer::new(Duration::from_millis(50));
+        let [a, b, c, other] = ["https://example.com/a", "https://example.com/b", "https://example.com/c", "https://other.example/"]
+            .map(|url| Url::parse(url).unwrap());
+
+        let started = Instant::now();
+        let (first, second, third, other_host) = tokio::join!(
+            pacer.wait_turn(&a),
+            pacer.wait_turn(&b),
+            pacer.wait_turn(&c),
+            pacer.wait_turn(&other),
+        );
+
+        let mut same_host = [first, second, third];
+        same_host.sort();
+        for pair in same_host.windows(2) {
+            let gap = pair[1].duration_since(pair[0]);
+            assert!(gap >= Duration::from_millis(50), "turns only {:?} apart", gap);
+        }
+        assert!(other_host.duration_since(started) < Duration::from_millis(50));
+    }
+
+    #[tokio::test]
+    async fn test_crawl_spaces_same_host_fetches() {
+        let crawler = crawler(&[
+            ("https://example.com/", &["https://example.com/a", "https://example.com/b"]),
+            ("https://example.com/a", &[]),
+            ("https://example.com/b", &[]),
+        ]);
+        let request = CrawlRequest {
+            start_url: "https://example.com/".to_string(),
+            max_pages: None,
+            crawl_delay_ms: Some(100),
+            client_id: None,
+        };
+
+        let started = Instant::now();
+        assert_eq!(crawler.crawl(&request, |_| ControlFlow::Continue(())).await, 3);
+
+        // Three fetches from one host need two full delays between them
+        assert!(started.elapsed() >= Duration::from_millis(200), "crawl took {:?}", started.elapsed());
+    }
+
+    #[test]
+    fn test_parse_robots_crawl_delay() {
+        let robots = "User-agent: Googlebot\nCrawl-delay: 1\n\nUser-agent: Bingbot\nUser-agent: *\nDisallow: /private\nCrawl-delay: 2.5 # seconds\n";
+        assert_eq!(parse_robots_crawl_delay(robots), Some(Duration::from_millis(2500)));
+
+        assert_eq!(parse_robots_crawl_delay("User-agent: Googlebot\nCrawl-delay: 1\n"), None);
+        assert_eq!(parse_robots_crawl_delay("User-agent: *\nCrawl-delay: 3600\n"), Some(Duration::from_millis(MAX_CRAWL_DELAY_MS)));
+        assert_eq!(parse_robots_crawl_delay("<html><body>Not found</body></html>"), None);
+    }
+
+    #[test]
+    fn test_validate_request() {
+        let crawler = crawler(&[]);
+        let request = |start_url: &str, max_pages| CrawlRequest { start_url: start_url.to_string(), max_pages, crawl_delay_ms: None, client_id: None };
+
+        assert!(crawler.validate_request(&request("https://example.com", None)).is_ok());
+        assert!(crawler.validate_request(&request("https://example.com", Some(MAX_CRAWL_PAGES))).is_ok());
+        assert!(crawler.validate_request(&request("ftp://example.com", None)).is_err());
+        assert!(crawler.validate_request(&request("not a url", None)).is_err());
+        assert_eq!(
+            crawler.validate_request(&request("https://example.com", Some(0))).unwrap_err(),
+            format!("max_pages must be between 1 and {}", MAX_CRAWL_PAGES)
+        );
+
+        let too_slow = CrawlRequest { crawl_delay_ms: Some(MAX_CRAWL_DELAY_MS + 1), ..request("https://example.com", None) };
+        assert!(crawler.validate_request(&too_slow).is_err());
+    }
+}
@@ -1,31 +1,45 @@
+use std::collections::HashMap;
 use std::sync::Arc;
-use tracing::{info, error};
+use std::time::{Duration, Instant, SystemTime};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use chrono::{DateTime, SecondsFormat, Utc};
+use tracing::{field, info, error, warn, Span};
 use domain::model::{
-    request::FetchContentRequest,
+    request::{FetchContentRequest, ParseOptions},
     response::{FetchContentResponse, McpResponse, McpError},
-    content::HtmlContent,
+    content::{Article, ContentChunk, ContentComparison, ContentMetadata, ContentType, FetchMethod, HtmlContent},
 };
 use domain::port::{
+    audit_sink::{AuditEntry, AuditSink},
     content_fetcher::{ContentFetcher, ContentFetcherError},
-    content_parser::ContentParser,
+    content_parser::{ContentParser, ContentParserResult},
+    content_transformer::ContentTransformer,
+    fetch_guard::FetchGuard,
 };
 use crate::service::{
+    content_chunk_service::ContentChunkService,
+    content_diff_service::ContentDiffService,
     content_fetch_service::ContentFetchService,
     content_parse_service::ContentParseService,
 };
 
 pub struct FetchWebContentUseCase<F, P>
 where
-    F: ContentFetcher,
+    F: ContentFetcher + ?Sized,
     P: ContentParser,
 {
     fetch_service: Arc<ContentFetchService<F>>,
-    _parse_service: Arc<ContentParseService<P>>, // Keep for potential future use
+    parse_service: Arc<ContentParseService<P>>,
+    transformers: Vec<Arc<dyn ContentTransformer>>,
+    chunk_service: ContentChunkService,
+    diff_service: ContentDiffService,
+    fetch_guard: Option<Arc<dyn FetchGuard>>,
+    audit_sink: Option<Arc<dyn AuditSink>>,
 }
 
 impl<F, P> FetchWebContentUseCase<F, P>
 where
-    F: ContentFetcher,
+    F: ContentFetcher + ?Sized,
     P: ContentParser,
 {
     pub fn new(
@@ -34,33 +48,281 @@ where
     ) -> Self {
         Self {
             fetch_service,
-            _parse_service: parse_service,
+            parse_service,
+            transformers: Vec::new(),
+            chunk_service: ContentChunkService::new(),
+            diff_service: ContentDiffService::new(),
+            fetch_guard: None,
+            audit_sink: None,
         }
     }
 
+    /// Adds a transformer run on every fetched page, after the ones already added.
+    pub fn with_transformer(mut self, transformer: Arc<dyn ContentTransformer>) -> Self {
+        self.transformers.push(transformer);
+        self
+    }
+
+    /// Consults `guard` before every fetch; a denied URL fails as an invalid URL
+    /// without being requested. A page that ends up at a denied URL after
+    /// redirects fails the same way; the static fetcher should also be given
+    /// the guard so it refuses such redirects before following them.
+    pub fn with_fetch_guard(mut self, guard: Arc<dyn FetchGuard>) -> Self {
+        self.fetch_guard = Some(guard);
+        self
+    }
+
+    /// Records every URL fetched, including each attempt of a retry or fallback,
+    /// in `sink`. URLs refused before fetching aren't recorded, and neither are
+    /// the cache's background prefetches; give the cache the same sink for those.
+    pub fn with_audit_sink(mut self, sink: Arc<dyn AuditSink>) -> Self {
+        self.audit_sink = Some(sink);
+        self
+    }
+
+    async fn record_audit(&self, url: String, client_id: Option<String>, result: &Result<HtmlContent, ContentFetcherError>) {
+        let Some(sink) = &self.audit_sink else {
+            return;
+        };
+        let timestamp = DateTime::<Utc>::from(SystemTime::now()).to_rfc3339_opts(SecondsFormat::Millis, true);
+        sink.record(AuditEntry::for_fetch(timestamp, url, client_id, result)).await;
+    }
+
+    async fn check_fetch_guard(&self, url: &str) -> Result<(), ContentFetcherError> {
+        let Some(guard) = &self.fetch_guard else {
+            return Ok(());
+        };
+        let url = url::Url::parse(url).map_err(|error| ContentFetcherError::InvalidUrl(error.to_string()))?;
+        guard.allow(&url).await.map_err(|reason| {
+            info!("Fetch guard denied {}: {}", url, reason);
+            ContentFetcherError::InvalidUrl(reason)
+        })
+    }
+
+    /// Fails a fetch that was redirected to a URL the guard denies. The browser
+    /// follows redirects where the guard can't see them, so this is its only check.
+    async fn check_final_url(&self, requested_url: &str, result: Result<HtmlContent, ContentFetcherError>) -> Result<HtmlContent, ContentFetcherError> {
+        let content = result?;
+        if content.url != requested_url {
+            self.check_fetch_guard(&content.url).await?;
+        }
+        Ok(content)
+    }
+
+    async fn fetch(&self, request: FetchContentRequest) -> Result<HtmlContent, ContentFetcherError> {
+        self.check_fetch_guard(&request.url).await?;
+        let (url, client_id) = (request.url.clone(), request.client_id.clone());
+        let result = self.fetch_service.fetch_and_process_content(request).await;
+        let result = self.check_final_url(&url, result).await;
+        self.record_audit(url, client_id, &result).await;
+        result
+    }
+
+    /// Fetches the page, and with `retry_empty_with_browser` fetches it again with
+    /// the browser when a static fetch of a script-driven page came back nearly
+    /// empty, keeping whichever result has more text. A failed retry leaves the
+    /// static result in place.
+    async fn fetch_with_browser_retry(&self, request: FetchContentRequest) -> Result<HtmlContent, ContentFetcherError> {
+        if !request.retry_empty_with_browser.unwrap_or(false) {
+            return self.fetch(request).await;
+        }
+
+        let browser_request = FetchContentRequest {
+            force_fetch_method: Some(FetchMethod::Browser),
+            ..request.clone()
+        };
+        let mut content = self.fetch(request).await?;
+        content.metadata.retried_with_browser = Some(false);
+        if content.metadata.fetch_method == Some(FetchMethod::Browser) || !is_empty_script_page(&content) {
+            return Ok(content);
+        }
+
+        info!("Static fetch of {} yielded almost no text, retrying with the browser", content.url);
+        match self.fetch(browser_request).await {
+            Ok(mut browser_content) if text_chars(&browser_content) > text_chars(&content) => {
+                browser_content.metadata.fetch_method = Some(FetchMethod::Browser);
+                browser_content.metadata.retried_with_browser = Some(true);
+                Ok(browser_content)
+            }
+            Ok(_) => Ok(content),
+            Err(error) => {
+                warn!("Browser retry failed for {}, using the static result: {}", content.url, error);
+                content.metadata.fallback_reason = Some(format!("Browser retry failed: {}", error));
+                Ok(content)
+            }
+        }
+    }
+
+    /// Fetches the page, and with `fallback_urls` tries each mirror in turn
+    /// after the primary URL fails, recording which URL answered and what the
+    /// earlier ones failed with. When every URL fails, the last error is returned.
+    /// A multipart upload is only ever sent to the primary URL.
+    async fn fetch_with_fallback_chain(&self, request: FetchContentRequest) -> Result<HtmlContent, ContentFetcherError> {
+        let Some(fallback_urls) = request.fallback_urls.clone().filter(|_| request.multipart.is_none()) else {
+            return self.fetch_with_browser_retry(request).await;
+        };
+
+        // One timeout for the whole chain; each attempt gets what is left of it,
+        // to the nearest second
+        let deadline = Instant::now() + Duration::from_secs(self.fetch_service.timeout_seconds_for(&request));
+        let mut fallback_urls = fallback_urls.into_iter();
+        let mut url = request.url.clone();
+        let mut errors = Vec::new();
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now()).as_secs_f64().round() as u64;
+            let attempt = FetchContentRequest {
+                url: url.clone(),
+                timeout_seconds: Some(remaining),
+                fallback_urls: None,
+                ..request.clone()
+            };
+            match self.fetch_with_browser_retry(attempt).await {
+                Ok(mut content) => {
+                    content.metadata.url_used = Some(url);
+                    content.metadata.fallback_errors = Some(errors);
+                    return Ok(content);
+                }
+                Err(error) => {
+                    let Some(next_url) = fallback_urls.next() else {
+                        return Err(error);
+                    };
+                    // The fetcher treats a zero timeout as already expired
+                    if deadline.saturating_duration_since(Instant::now()) < Duration::from_secs(1) {
+                        warn!("Not trying {}, the request timeout has run out: {}", next_url, error);
+                        return Err(error);
+                    }
+                    warn!("Fetching {} failed, trying {}: {}", url, next_url, error);
+                    errors.push(format!("{}: {}", url, error));
+                    url = next_url;
+                }
+            }
+        }
+    }
+
+    async fn fetch_static_guarded(&self, request: FetchContentRequest) -> Result<HtmlContent, ContentFetcherError> {
+        self.check_fetch_guard(&request.url).await?;
+        let (url, client_id) = (request.url.clone(), request.client_id.clone());
+        let result = self.fetch_service.fetch_static_content(request).await;
+        let result = self.check_final_url(&url, result).await;
+        self.record_audit(url, client_id, &result).await;
+        result
+    }
+
+    /// Applies the request's parse options, then each transformer in order, then
+    /// renders any extra output formats from the result.
+    async fn post_process(
+        &self,
+        content: HtmlContent,
+        options: &ParseOptions,
+        output_formats: &[ContentType],
+    ) -> ContentParserResult<HtmlContent> {
+        let mut content = self.apply_parse_options(content, options).await?;
+        for transformer in &self.transformers {
+            content = transformer.transform(content).await?;
+        }
+        self.render_output_formats(content, output_formats).await
+    }
+
+    async fn render_output_formats(
+        &self,
+        mut content: HtmlContent,
+        output_formats: &[ContentType],
+    ) -> ContentParserResult<HtmlContent> {
+        if output_formats.is_empty() {
+            return Ok(content);
+        }
+
+        let mut renderings = HashMap::new();
+        for format in output_formats {
+            let rendering = match format {
+                ContentType::Markdown => self.parse_service
+                    .convert_to_markdown(&content.raw_html, &content.url)
+                    .await?,
+                ContentType::Html => content.raw_html.clone(),
+                // Rejected by validation; fall back to the text rather than failing the fetch
+                ContentType::PlainText | ContentType::Json | ContentType::Xml => content.text_content.clone(),
+            };
+            renderings.insert(format.name().to_string(), rendering);
+        }
+
+        content.alternate_contents = Some(renderings);
+        Ok(content)
+    }
+
+    /// Runs the parser over the fetched document when the request asks for extraction
+    /// the fetcher doesn't perform itself, copying the extra fields onto the content.
+    async fn apply_parse_options(
+        &self,
+        mut content: HtmlContent,
+        options: &ParseOptions,
+    ) -> ContentParserResult<HtmlContent> {
+        if options.is_empty() {
+            return Ok(content);
+        }
+
+        let headers = response_headers(&content.metadata);
+        let parsed = self.parse_service
+            .parse_html_content_with_options(&content.raw_html, &content.url, content.metadata.status_code, &headers, options)
+            .await?;
+
+        if options.max_title_length.is_some() {
+            content.title = parsed.title;
+        }
+        if options.raw_text || options.inline_link_urls {
+            content.text_content = parsed.text_content;
+        }
+        if options.prettify_html {
+            content.raw_html = parsed.raw_html;
+        }
+        content.blocks = parsed.blocks;
+        content.contacts = parsed.contacts;
+        content.links = parsed.links;
+        content.images = parsed.images;
+        content.forms = parsed.forms;
+        content.microdata = parsed.microdata;
+        content.breadcrumbs = parsed.breadcrumbs;
+        content.content_html = parsed.content_html;
+        content.feeds = parsed.feeds;
+        content.broken_anchors = parsed.broken_anchors;
+        content.metadata.elements_truncated = parsed.metadata.elements_truncated;
+        content.metadata.keywords = parsed.metadata.keywords;
+        Ok(content)
+    }
+
+    /// Logged inside a `fetch` span carrying the `url` and, once known, the
+    /// `fetch_method`; the API's request span around it holds the request ID.
+    #[tracing::instrument(name = "fetch", skip_all, fields(url = %request.url, fetch_method = field::Empty))]
     pub async fn execute_for_api(&self, request: FetchContentRequest) -> Result<HtmlContent, String> {
-        // Convert optional fields to required ones with defaults
+        // Convert optional fields to required ones with defaults; the fetch service
+        // supplies the default timeout
         let processed_request = FetchContentRequest {
             url: request.url.clone(),
-            extract_text_only: request.extract_text_only.or(Some(true)),
             follow_redirects: request.follow_redirects.or(Some(true)),
-            timeout_seconds: request.timeout_seconds.or(Some(30)),
             user_agent: request.user_agent.or(Some("html-api-reader/0.1.0".to_string())),
+            ..request
         };
 
         if let Err(validation_error) = self.fetch_service.validate_request(&processed_request).await {
             return Err(format!("Invalid parameters: {}", validation_error));
         }
 
-        match self.fetch_service.fetch_and_process_content(processed_request).await {
+        let parse_options = ParseOptions::from(&processed_request);
+        let output_formats = processed_request.output_formats.clone().unwrap_or_default();
+        let finishing = Finishing::from(&processed_request);
+
+        match self.fetch_with_fallback_chain(processed_request).await {
             Ok(content) => {
+                record_fetch_method(&content);
                 info!("Successfully fetched content from: {}", content.url);
-                Ok(content)
+                self.post_process(content, &parse_options, &output_formats)
+                    .await
+                    .map(|content| finishing.apply(content))
+                    .map_err(|error| error.to_string())
             }
             Err(error) => {
                 error!("Failed to fetch content: {:?}", error);
                 let message = match error {
-                    ContentFetcherError::Network(msg) => format!("Network error: {}", msg),
+                    ContentFetcherError::Network { message: msg, .. } => format!("Network error: {}", msg),
                     ContentFetcherError::InvalidUrl(msg) => format!("Invalid URL: {}", msg),
                     ContentFetcherError::Timeout(seconds) => format!("Request timeout after {} seconds", seconds),
                     ContentFetcherError::Http { status, message } => format!("HTTP {}: {}", status, message),
@@ -71,8 +333,26 @@ where
         }
     }
 
+    /// Fetches the page like `execute_for_api` and renders its HTML as Markdown.
+    pub async fn execute_markdown(&self, request: FetchContentRequest) -> Result<(HtmlContent, String), String> {
+        let content = self.execute_for_api(request).await?;
+        let markdown = self.parse_service
+            .convert_to_markdown(&content.raw_html, &content.url)
+            .await
+            .map_err(|error| error.to_string())?;
+        Ok((content, markdown))
+    }
+
+    /// Logged inside a `fetch` span carrying the `request_id` returned in the
+    /// response, the `url` and, once known, the `fetch_method`.
+    #[tracing::instrument(
+        name = "fetch",
+        skip_all,
+        fields(request_id = field::Empty, url = %request.url, fetch_method = field::Empty)
+    )]
     pub async fn execute(&self, request: FetchContentRequest) -> McpResponse<FetchContentResponse> {
         let request_id = uuid::Uuid::new_v4().to_string();
+        Span::current().record("request_id", field::display(&request_id));
 
         if let Err(validation_error) = self.fetch_service.validate_request(&request).await {
             return McpResponse {
@@ -86,40 +366,401 @@ where
             };
         }
 
-        match self.fetch_service.fetch_and_process_content(request).await {
+        let parse_options = ParseOptions::from(&request);
+        let output_formats = request.output_formats.clone().unwrap_or_default();
+        let finishing = Finishing::from(&request);
+
+        match self.fetch_with_fallback_chain(request).await {
             Ok(content) => {
+                record_fetch_method(&content);
                 info!("Successfully fetched content from: {}", content.url);
-                McpResponse {
-                    id: request_id,
-                    result: Some(FetchContentResponse {
-                        content,
-                        success: true,
-                        message: Some("Content fetched successfully".to_string()),
-                    }),
-                    error: None,
+                match self.post_process(content, &parse_options, &output_formats).await {
+                    Ok(content) => McpResponse {
+                        id: request_id,
+                        result: Some(FetchContentResponse {
+                            content: finishing.apply(content),
+                            success: true,
+                            message: Some("Content fetched successfully".to_string()),
+                        }),
+                        error: None,
+                    },
+                    Err(error) => {
+                        error!("Failed to parse content: {:?}", error);
+                        McpResponse {
+                            id: request_id,
+                            result: None,
+                            error: Some(McpError {
+                                code: -32004,
+                                message: error.to_string(),
+                                data: None,
+                            }),
+                        }
+                    }
                 }
             }
             Err(error) => {
                 error!("Failed to fetch content: {:?}", error);
-                let (code, message) = match error {
-                    ContentFetcherError::Network(msg) => (-32001, format!("Network error: {}", msg)),
-                    ContentFetcherError::InvalidUrl(msg) => (-32602, format!("Invalid URL: {}", msg)),
-                    ContentFetcherError::Timeout(seconds) => (-32002, format!("Request timeout after {} seconds", seconds)),
-                    ContentFetcherError::Http { status, message } => (-32003, format!("HTTP {}: {}", status, message)),
-                    ContentFetcherError::Parse(msg) => (-32004, format!("Parse error: {}", msg)),
-                };
-
                 McpResponse {
                     id: request_id,
                     result: None,
-                    error: Some(McpError {
-                        code,
-                        message,
-                        data: None,
-                    }),
+                    error: Some(fetch_error_to_mcp(error)),
+                }
+            }
+        }
+    }
+
+    /// Fetches the page and isolates its main article from the surrounding chrome.
+    pub async fn extract_article(&self, request: FetchContentRequest) -> Result<Article, McpError> {
+        if let Err(validation_error) = self.fetch_service.validate_request(&request).await {
+            return Err(McpError {
+                code: -32602,
+                message: format!("Invalid parameters: {}", validation_error),
+                data: None,
+            });
+        }
+
+        let content = self.fetch_with_fallback_chain(request).await.map_err(|error| {
+            error!("Failed to fetch content: {:?}", error);
+            fetch_error_to_mcp(error)
+        })?;
+
+        self.parse_service
+            .extract_article(&content.raw_html, &content.url)
+            .await
+            .map_err(|error| {
+                error!("Failed to extract article: {:?}", error);
+                McpError {
+                    code: -32004,
+                    message: error.to_string(),
+                    data: None,
                 }
+            })
+    }
+
+    /// Fetches the page and splits its text into chunks of about `target_chars`
+    /// characters along heading and paragraph boundaries.
+    pub async fn chunk_content(
+        &self,
+        request: FetchContentRequest,
+        target_chars: usize,
+    ) -> Result<Vec<ContentChunk>, McpError> {
+        let validation = match target_chars {
+            0 => Err("target_chars must be at least 1".to_string()),
+            _ => self.fetch_service.validate_request(&request).await,
+        };
+        if let Err(validation_error) = validation {
+            return Err(McpError {
+                code: -32602,
+                message: format!("Invalid parameters: {}", validation_error),
+                data: None,
+            });
+        }
+
+        // Blocks carry the headings that chunk boundaries follow
+        let request = FetchContentRequest {
+            include_blocks: Some(true),
+            ..request
+        };
+        let parse_options = ParseOptions::from(&request);
+
+        let content = self.fetch_with_fallback_chain(request).await.map_err(|error| {
+            error!("Failed to fetch content: {:?}", error);
+            fetch_error_to_mcp(error)
+        })?;
+
+        let content = self.apply_parse_options(content, &parse_options).await.map_err(|error| {
+            error!("Failed to parse content for chunking: {:?}", error);
+            McpError {
+                code: -32004,
+                message: error.to_string(),
+                data: None,
+            }
+        })?;
+
+        Ok(self.chunk_service.chunk_content(&content, target_chars))
+    }
+
+    /// Fetches both pages at once and diffs their text, one line per text block.
+    pub async fn compare_content(
+        &self,
+        first: FetchContentRequest,
+        second: FetchContentRequest,
+    ) -> Result<ContentComparison, McpError> {
+        for request in [&first, &second] {
+            if let Err(validation_error) = self.fetch_service.validate_request(request).await {
+                return Err(McpError {
+                    code: -32602,
+                    message: format!("Invalid parameters: {}", validation_error),
+                    data: None,
+                });
+            }
+        }
+
+        let (first, second) = futures::try_join!(
+            self.fetch_text_blocks(first),
+            self.fetch_text_blocks(second),
+        )?;
+
+        Ok(self.diff_service.compare_content(&first, &second))
+    }
+
+    /// Fetches a page and parses it into text blocks, which give the diff its lines.
+    async fn fetch_text_blocks(&self, request: FetchContentRequest) -> Result<HtmlContent, McpError> {
+        let request = FetchContentRequest {
+            include_blocks: Some(true),
+            ..request
+        };
+        let parse_options = ParseOptions::from(&request);
+
+        let content = self.fetch_with_fallback_chain(request).await.map_err(|error| {
+            error!("Failed to fetch content: {:?}", error);
+            fetch_error_to_mcp(error)
+        })?;
+
+        self.apply_parse_options(content, &parse_options).await.map_err(|error| {
+            error!("Failed to parse content for comparison: {:?}", error);
+            McpError {
+                code: -32004,
+                message: error.to_string(),
+                data: None,
             }
+        })
+    }
+
+    /// Parses HTML the caller already has, without fetching anything. `url`
+    /// is only the base that relative links are resolved against.
+    pub async fn parse_html(&self, raw_html: &str, url: &str, options: &ParseOptions) -> Result<HtmlContent, McpError> {
+        // Nothing was fetched, so the parser's defaults (a 200 text/html response) stand
+        self.parse_service
+            .parse_html_content_with_options(raw_html, url, 200, &HashMap::new(), options)
+            .await
+            .map_err(|error| {
+                error!("Failed to parse HTML: {:?}", error);
+                McpError {
+                    code: -32004,
+                    message: error.to_string(),
+                    data: None,
+                }
+            })
+    }
+
+    /// Whether pages can be rendered in a browser, as the fetch service was told.
+    pub fn browser_available(&self) -> bool {
+        self.fetch_service.browser_available()
+    }
+
+    /// Recently fetched URLs still held in the fetcher's cache, most recent first.
+    pub fn cached_urls(&self) -> Vec<String> {
+        self.fetch_service.cached_urls()
+    }
+
+    /// Validates the request and fetches the page without JavaScript rendering,
+    /// for callers that only need to inspect the raw document.
+    pub async fn fetch_static(&self, request: FetchContentRequest) -> Result<HtmlContent, McpError> {
+        if let Err(validation_error) = self.fetch_service.validate_request(&request).await {
+            return Err(McpError {
+                code: -32602,
+                message: format!("Invalid parameters: {}", validation_error),
+                data: None,
+            });
+        }
+
+        self.fetch_static_guarded(request).await.map_err(|error| {
+            error!("Failed to fetch static content: {:?}", error);
+            fetch_error_to_mcp(error)
+        })
+    }
+}
+
+/// Phrases that mark a successful response as an error page or paywall.
+const SOFT_ERROR_PHRASES: &[&str] = &[
+    "page not found",
+    "404 not found",
+    "page you requested could not be found",
+    "page doesn't exist",
+    "page does not exist",
+    "subscribe to continue",
+    "subscribe to read",
+    "subscribers only",
+    "sign in to continue reading",
+    "log in to continue reading",
+];
+
+/// Pages with less extracted text than this are flagged as likely soft errors.
+const SOFT_ERROR_MIN_TEXT_CHARS: usize = 100;
+
+/// A static fetch with less text than this counts as empty for `retry_empty_with_browser`.
+const RETRY_EMPTY_MAX_TEXT_CHARS: usize = 200;
+
+/// Query parameters that only track where a visitor came from, removed by
+/// `strip_tracking_params`. Parameters starting with `utm_` are removed too.
+const TRACKING_PARAMS: &[&str] = &[
+    "fbclid", "gclid", "dclid", "gbraid", "wbraid", "msclkid", "yclid", "twclid", "ttclid",
+    "igshid", "li_fat_id", "mc_cid", "mc_eid", "_ga", "_gl",
+];
+
+/// Request options applied to the finished content, after parsing and transformers.
+struct Finishing {
+    /// The URL as requested, when `strip_tracking_params` asks for the returned one to be cleaned.
+    strip_tracking_from: Option<String>,
+    detect_soft_errors: bool,
+    min_content_length: Option<usize>,
+    lowercase_text: bool,
+    max_text_bytes: Option<usize>,
+    encode_base64: bool,
+}
+
+impl From<&FetchContentRequest> for Finishing {
+    fn from(request: &FetchContentRequest) -> Self {
+        Self {
+            strip_tracking_from: request.strip_tracking_params.unwrap_or(false).then(|| request.url.clone()),
+            detect_soft_errors: request.detect_soft_errors.unwrap_or(false),
+            min_content_length: request.min_content_length,
+            lowercase_text: request.lowercase_text.unwrap_or(false),
+            max_text_bytes: request.max_text_bytes,
+            encode_base64: request.encode_base64.unwrap_or(false),
+        }
+    }
+}
+
+impl Finishing {
+    fn apply(&self, mut content: HtmlContent) -> HtmlContent {
+        if let Some(requested_url) = &self.strip_tracking_from {
+            content.url = strip_tracking_params(&content.url);
+            content.metadata.effective_url = content.metadata.effective_url.as_deref().map(strip_tracking_params);
+            content.metadata.requested_url = Some(requested_url.clone());
+        }
+        if self.detect_soft_errors {
+            content.metadata.likely_soft_error = Some(is_likely_soft_error(&content.text_content));
+        }
+        // Measured before `max_text_bytes` cuts the text, so only the page itself counts
+        if let Some(min_length) = self.min_content_length {
+            content.metadata.content_below_minimum = Some(content.text_content.trim().len() < min_length);
         }
+        // Lowercasing can change the byte length ("İ" grows), so it comes before the byte cap
+        if self.lowercase_text {
+            content.text_content = content.text_content.to_lowercase();
+        }
+        if let Some(max_bytes) = self.max_text_bytes {
+            let truncated = truncate_at_char_boundary(&mut content.text_content, max_bytes);
+            content.metadata.text_truncated = Some(truncated);
+        }
+        if self.encode_base64 {
+            content = encode_base64(content);
+        }
+        content
+    }
+}
+
+/// `url` without its `TRACKING_PARAMS` and `utm_*` query parameters. The
+/// other parameters are kept in order and exactly as written. URLs that don't
+/// parse are returned as they are.
+fn strip_tracking_params(url: &str) -> String {
+    let Ok(mut parsed) = url::Url::parse(url) else {
+        return url.to_string();
+    };
+    let Some(query) = parsed.query() else {
+        return url.to_string();
+    };
+
+    let kept: Vec<&str> = query
+        .split('&')
+        .filter(|pair| {
+            let name = pair.split('=').next().unwrap_or_default();
+            !name.starts_with("utm_") && !TRACKING_PARAMS.contains(&name)
+        })
+        .collect();
+    let kept = (!kept.is_empty()).then(|| kept.join("&"));
+    parsed.set_query(kept.as_deref());
+    parsed.to_string()
+}
+
+/// Whether the extracted text reads like an error page or paywall: it contains
+/// one of `SOFT_ERROR_PHRASES` or is too short to be a real page.
+fn is_likely_soft_error(text: &str) -> bool {
+    let text = text.trim();
+    if text.chars().count() < SOFT_ERROR_MIN_TEXT_CHARS {
+        return true;
+    }
+
+    let text = text.to_lowercase();
+    SOFT_ERROR_PHRASES.iter().any(|phrase| text.contains(phrase))
+}
+
+/// Fills in the `fetch_method` field of the current `fetch` span.
+fn record_fetch_method(content: &HtmlContent) {
+    if let Some(method) = content.metadata.fetch_method {
+        Span::current().record("fetch_method", field::debug(method));
+    }
+}
+
+/// The response headers the fetcher kept in `metadata`, for the parser to
+/// record again instead of its defaults.
+fn response_headers(metadata: &ContentMetadata) -> HashMap<String, String> {
+    let mut headers = HashMap::new();
+    if !metadata.content_type.is_empty() {
+        let content_type = match &metadata.charset {
+            Some(charset) => format!("{}; charset={}", metadata.content_type, charset),
+            None => metadata.content_type.clone(),
+        };
+        headers.insert("content-type".to_string(), content_type);
+    }
+    if let Some(last_modified) = &metadata.last_modified {
+        headers.insert("last-modified".to_string(), last_modified.clone());
+    }
+    headers
+}
+
+fn text_chars(content: &HtmlContent) -> usize {
+    content.text_content.trim().chars().count()
+}
+
+/// Whether a static result looks like the shell of a page its scripts render:
+/// hardly any text, and either the fetcher detected JavaScript or the HTML
+/// carries scripts.
+fn is_empty_script_page(content: &HtmlContent) -> bool {
+    let uses_javascript = content.metadata.javascript_detected == Some(true)
+        || content.raw_html.to_ascii_lowercase().contains("<script");
+    text_chars(content) < RETRY_EMPTY_MAX_TEXT_CHARS && uses_javascript
+}
+
+/// Shortens `text` to at most `max_bytes` bytes, backing off to the previous
+/// character boundary so no multibyte character is split. Returns whether it was cut.
+fn truncate_at_char_boundary(text: &mut String, max_bytes: usize) -> bool {
+    if text.len() <= max_bytes {
+        return false;
+    }
+
+    let mut end = max_bytes;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    text.truncate(end);
+    true
+}
+
+/// Encodes the document text for transports that mangle non-ASCII characters.
+/// Clients decode `text_content` and `raw_html` when `metadata.content_encoding` is `base64`.
+fn encode_base64(mut content: HtmlContent) -> HtmlContent {
+    content.text_content = STANDARD.encode(content.text_content.as_bytes());
+    content.raw_html = STANDARD.encode(content.raw_html.as_bytes());
+    content.metadata.content_encoding = Some("base64".to_string());
+    content
+}
+
+fn fetch_error_to_mcp(error: ContentFetcherError) -> McpError {
+    let (code, message) = match error {
+        ContentFetcherError::Network { message: msg, .. } => (-32001, format!("Network error: {}", msg)),
+        ContentFetcherError::InvalidUrl(msg) => (-32602, format!("Invalid URL: {}", msg)),
+        ContentFetcherError::Timeout(seconds) => (-32002, format!("Request timeout after {} seconds", seconds)),
+        ContentFetcherError::Http { status, message } => (-32003, format!("HTTP {}: {}", status, message)),
+        ContentFetcherError::Parse(msg) => (-32004, format!("Parse error: {}", msg)),
+    };
+
+    McpError {
+        code,
+        message,
+        data: None,
     }
 }
 
@@ -128,24 +769,32 @@ mod tests {
     use super::*;
     use std::sync::Arc;
     use async_trait::async_trait;
-    use domain::model::content::{ContentMetadata, HtmlContent};
+    use domain::model::request::MultipartPart;
+    use domain::model::content::{ContentMetadata, HtmlContent, TextBlock};
     use domain::port::content_fetcher::{ContentFetcher, ContentFetcherError, ContentFetcherResult};
     use domain::port::content_parser::{ContentParser, ContentParserError, ContentParserResult};
     use crate::service::{
         content_fetch_service::ContentFetchService,
         content_parse_service::ContentParseService,
     };
+    use crate::test_support::CapturedLogs;
 
     struct MockContentFetcher {
         should_succeed: bool,
         return_error: Option<ContentFetcherError>,
+        text_content: String,
     }
 
     impl MockContentFetcher {
         fn new_success() -> Self {
+            Self::new_with_text("Test content")
+        }
+
+        fn new_with_text(text_content: &str) -> Self {
             Self {
                 should_succeed: true,
                 return_error: None,
+                text_content: text_content.to_string(),
             }
         }
 
@@ -153,6 +802,7 @@ mod tests {
             Self {
                 should_succeed: false,
                 return_error: Some(error),
+                text_content: String::new(),
             }
         }
     }
@@ -169,14 +819,16 @@ mod tests {
                     charset: Some("utf-8".to_string()),
             javascript_detected: None,
             fetch_method: None,
+                    ..Default::default()
                 };
 
                 Ok(HtmlContent {
                     url: request.url,
                     title: Some("Test Title".to_string()),
-                    text_content: "Test content".to_string(),
+                    text_content: self.text_content.clone(),
                     raw_html: "<html><body>Test</body></html>".to_string(),
                     metadata,
+                    ..Default::default()
                 })
             } else {
                 Err(self.return_error.as_ref().unwrap().clone())
@@ -206,6 +858,7 @@ mod tests {
                     charset: Some("utf-8".to_string()),
             javascript_detected: None,
             fetch_method: None,
+                    ..Default::default()
                 };
 
                 Ok(HtmlContent {
@@ -214,6 +867,7 @@ mod tests {
                     text_content: "Parsed content".to_string(),
                     raw_html: raw_html.to_string(),
                     metadata,
+                    ..Default::default()
                 })
             } else {
                 Err(ContentParserError::Parse("Parse failed".to_string()))
@@ -227,8 +881,41 @@ mod tests {
                 Err(ContentParserError::Parse("Text extraction failed".to_string()))
             }
         }
+
+        async fn to_markdown(&self, _raw_html: &str, url: &str) -> ContentParserResult<String> {
+            Ok(format!("# Parsed Title\n\nFrom {}", url))
+        }
+
+        async fn parse_html_with_options(
+            &self,
+            raw_html: &str,
+            url: &str,
+            options: &ParseOptions,
+        ) -> ContentParserResult<HtmlContent> {
+            let mut content = self.parse_html(raw_html, url).await?;
+            if options.include_blocks {
+                content.blocks = Some(vec![TextBlock {
+                    tag: "p".to_string(),
+                    text: "Parsed content".to_string(),
+                    lang: None,
+                }]);
+            }
+            Ok(content)
+        }
+    }
+
+    /// A use case over `fetcher` and `parser` with default services.
+    fn use_case_with<F: ContentFetcher + 'static, P: ContentParser>(fetcher: Arc<F>, parser: Arc<P>) -> FetchWebContentUseCase<F, P> {
+        FetchWebContentUseCase::new(Arc::new(ContentFetchService::new(fetcher)), Arc::new(ContentParseService::new(parser)))
     }
 
+    /// The page `use_case_with` a mock fetcher returning `text_content` makes of `request`.
+    async fn execute_text(text_content: &str, request: FetchContentRequest) -> HtmlContent {
+        let use_case = use_case_with(Arc::new(MockContentFetcher::new_with_text(text_content)), Arc::new(MockContentParser::new_success()));
+        let response = use_case.execute(FetchContentRequest { url: "https://example.com".to_string(), ..request }).await;
+        assert!(response.error.is_none(), "{:?}", response.error);
+        response.result.unwrap().content
+    }
 
     #[tokio::test]
     async fn test_execute_success() {
@@ -246,6 +933,7 @@ mod tests {
             follow_redirects: Some(true),
             timeout_seconds: Some(30),
             user_agent: Some("test".to_string()),
+            ..Default::default()
         };
 
         let response = use_case.execute(request).await;
@@ -275,6 +963,7 @@ mod tests {
             follow_redirects: Some(true),
             timeout_seconds: Some(30),
             user_agent: Some("test".to_string()),
+            ..Default::default()
         };
 
         let response = use_case.execute(request).await;
@@ -290,7 +979,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_execute_network_error() {
-        let error = ContentFetcherError::Network("Connection refused".to_string());
+        let error = ContentFetcherError::network("Connection refused".to_string());
         let fetcher = Arc::new(MockContentFetcher::new_with_error(error));
         let parser = Arc::new(MockContentParser::new_success());
         
@@ -305,6 +994,7 @@ mod tests {
             follow_redirects: Some(true),
             timeout_seconds: Some(30),
             user_agent: Some("test".to_string()),
+            ..Default::default()
         };
 
         let response = use_case.execute(request).await;
@@ -334,6 +1024,7 @@ mod tests {
             follow_redirects: Some(true),
             timeout_seconds: Some(30),
             user_agent: Some("test".to_string()),
+            ..Default::default()
         };
 
         let response = use_case.execute(request).await;
@@ -366,6 +1057,7 @@ mod tests {
             follow_redirects: Some(true),
             timeout_seconds: Some(30),
             user_agent: Some("test".to_string()),
+            ..Default::default()
         };
 
         let response = use_case.execute(request).await;
@@ -395,6 +1087,7 @@ mod tests {
             follow_redirects: Some(true),
             timeout_seconds: Some(30),
             user_agent: Some("test".to_string()),
+            ..Default::default()
         };
 
         let response = use_case.execute(request).await;
@@ -424,6 +1117,7 @@ mod tests {
             follow_redirects: Some(true),
             timeout_seconds: Some(30),
             user_agent: Some("test".to_string()),
+            ..Default::default()
         };
 
         let response = use_case.execute(request).await;
@@ -452,6 +1146,7 @@ mod tests {
             follow_redirects: Some(true),
             timeout_seconds: Some(30),
             user_agent: Some("test".to_string()),
+            ..Default::default()
         };
 
         let response = use_case.execute(request).await;
@@ -480,6 +1175,7 @@ mod tests {
             follow_redirects: Some(true),
             timeout_seconds: Some(400), // Too high
             user_agent: Some("test".to_string()),
+            ..Default::default()
         };
 
         let response = use_case.execute(request).await;
@@ -493,13 +1189,860 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn test_use_case_creation() {
+    async fn test_execute_with_blocks() {
         let fetcher = Arc::new(MockContentFetcher::new_success());
         let parser = Arc::new(MockContentParser::new_success());
-        
+
         let fetch_service = Arc::new(ContentFetchService::new(fetcher));
         let parse_service = Arc::new(ContentParseService::new(parser));
-        
-        let _use_case = FetchWebContentUseCase::new(fetch_service, parse_service);
+
+        let use_case = FetchWebContentUseCase::new(fetch_service, parse_service);
+
+        let request = FetchContentRequest {
+            url: "https://example.com".to_string(),
+            include_blocks: Some(true),
+            ..Default::default()
+        };
+
+        let response = use_case.execute(request).await;
+        let content = response.result.unwrap().content;
+
+        // Fetched fields are kept; only the parser's extras are merged in
+        assert_eq!(content.text_content, "Test content");
+        assert_eq!(content.blocks.unwrap()[0].tag, "p");
+    }
+
+    #[tokio::test]
+    async fn test_chunk_content_uses_parsed_blocks() {
+        let fetcher = Arc::new(MockContentFetcher::new_success());
+        let parser = Arc::new(MockContentParser::new_success());
+
+        let fetch_service = Arc::new(ContentFetchService::new(fetcher));
+        let parse_service = Arc::new(ContentParseService::new(parser));
+
+        let use_case = FetchWebContentUseCase::new(fetch_service, parse_service);
+
+        let request = FetchContentRequest {
+            url: "https://example.com".to_string(),
+            ..Default::default()
+        };
+
+        let chunks = use_case.chunk_content(request.clone(), 500).await.unwrap();
+        assert_eq!(chunks, vec![ContentChunk { index: 0, heading: None, text: "Parsed content".to_string() }]);
+
+        let error = use_case.chunk_content(request, 0).await.unwrap_err();
+        assert_eq!(error.code, -32602);
+    }
+
+    #[tokio::test]
+    async fn test_compare_content_of_identical_pages() {
+        let fetcher = Arc::new(MockContentFetcher::new_success());
+        let parser = Arc::new(MockContentParser::new_success());
+
+        let fetch_service = Arc::new(ContentFetchService::new(fetcher));
+        let parse_service = Arc::new(ContentParseService::new(parser));
+
+        let use_case = FetchWebContentUseCase::new(fetch_service, parse_service);
+
+        let request = FetchContentRequest {
+            url: "https://example.com".to_string(),
+            ..Default::default()
+        };
+        let other = FetchContentRequest {
+            url: "https://example.com/mirror".to_string(),
+            ..Default::default()
+        };
+
+        let comparison = use_case.compare_content(request.clone(), other).await.unwrap();
+        assert_eq!(comparison.similarity, 1.0);
+        assert!(comparison.added_lines.is_empty());
+
+        let invalid = FetchContentRequest {
+            url: "not a url".to_string(),
+            ..Default::default()
+        };
+        let error = use_case.compare_content(request, invalid).await.unwrap_err();
+        assert_eq!(error.code, -32602);
+    }
+
+    struct DenyHostGuard(&'static str);
+
+    #[async_trait]
+    impl FetchGuard for DenyHostGuard {
+        async fn allow(&self, url: &url::Url) -> Result<(), String> {
+            match url.host_str() {
+                Some(host) if host == self.0 => Err(format!("{} is not on the allowlist", host)),
+                _ => Ok(()),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fetch_guard_denies_host() {
+        let fetcher = Arc::new(MockContentFetcher::new_success());
+        let parser = Arc::new(MockContentParser::new_success());
+
+        let fetch_service = Arc::new(ContentFetchService::new(fetcher));
+        let parse_service = Arc::new(ContentParseService::new(parser));
+
+        let use_case = FetchWebContentUseCase::new(fetch_service, parse_service)
+            .with_fetch_guard(Arc::new(DenyHostGuard("blocked.example.com")));
+
+        let denied = FetchContentRequest {
+            url: "https://blocked.example.com/page".to_string(),
+            ..Default::default()
+        };
+        let response = use_case.execute(denied.clone()).await;
+        let error = response.error.unwrap();
+        assert_eq!(error.code, -32602);
+        assert_eq!(error.message, "Invalid URL: blocked.example.com is not on the allowlist");
+
+        let error = use_case.fetch_static(denied.clone()).await.unwrap_err();
+        assert_eq!(error.code, -32602);
+        let error = use_case.execute_for_api(denied).await.unwrap_err();
+        assert_eq!(error, "Invalid URL: blocked.example.com is not on the allowlist");
+
+        let allowed = FetchContentRequest {
+            url: "https://example.com/page".to_string(),
+            ..Default::default()
+        };
+        assert!(use_case.execute(allowed).await.result.is_some());
+    }
+
+    /// Answers every request with a page that was redirected to `self.0`.
+    struct RedirectingFetcher(&'static str);
+
+    #[async_trait]
+    impl ContentFetcher for RedirectingFetcher {
+        async fn fetch_content(&self, _request: FetchContentRequest) -> ContentFetcherResult<HtmlContent> {
+            Ok(HtmlContent {
+                url: self.0.to_string(),
+                ..Default::default()
+            })
+        }
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn test_fetch_guard_denies_redirect_target() {
+        let fetcher = Arc::new(RedirectingFetcher("https://blocked.example.com/landing"));
+        let parser = Arc::new(MockContentParser::new_success());
+        let use_case = FetchWebContentUseCase::new(Arc::new(ContentFetchService::new(fetcher)), Arc::new(ContentParseService::new(parser)))
+            .with_fetch_guard(Arc::new(DenyHostGuard("blocked.example.com")));
+
+        let request = FetchContentRequest {
+            url: "https://example.com/page".to_string(),
+            ..Default::default()
+        };
+        let error = use_case.execute_for_api(request.clone()).await.unwrap_err();
+        assert_eq!(error, "Invalid URL: blocked.example.com is not on the allowlist");
+        let error = use_case.fetch_static(request).await.unwrap_err();
+        assert_eq!(error.code, -32602);
+    }
+
+    /// Answers every request with a 404 page served as Latin-1.
+    struct NotFoundFetcher;
+
+    #[async_trait]
+    impl ContentFetcher for NotFoundFetcher {
+        async fn fetch_content(&self, request: FetchContentRequest) -> ContentFetcherResult<HtmlContent> {
+            Ok(HtmlContent {
+                url: request.url,
+                raw_html: "<html><body>No such page</body></html>".to_string(),
+                metadata: ContentMetadata {
+                    content_type: "text/html".to_string(),
+                    status_code: 404,
+                    charset: Some("iso-8859-1".to_string()),
+                    ..Default::default()
+                },
+                ..Default::default()
+            })
+        }
+    }
+
+    /// Keeps the metadata of every document it parses.
+    #[derive(Default)]
+    struct MetadataRecordingParser {
+        parsed: std::sync::Mutex<Vec<ContentMetadata>>,
+    }
+
+    #[async_trait]
+    impl ContentParser for MetadataRecordingParser {
+        async fn parse_html(&self, raw_html: &str, url: &str) -> ContentParserResult<HtmlContent> {
+            Ok(HtmlContent {
+                url: url.to_string(),
+                raw_html: raw_html.to_string(),
+                metadata: ContentMetadata { content_type: "text/html".to_string(), status_code: 200, ..Default::default() },
+                ..Default::default()
+            })
+        }
+
+        async fn extract_text(&self, html_content: &HtmlContent) -> ContentParserResult<String> {
+            Ok(html_content.text_content.clone())
+        }
+
+        async fn parse_html_with_status(
+            &self,
+            raw_html: &str,
+            url: &str,
+            status: u16,
+            headers: &HashMap<String, String>,
+            _options: &ParseOptions,
+        ) -> ContentParserResult<HtmlContent> {
+            let mut content = self.parse_html(raw_html, url).await?;
+            content.metadata.apply_response(status, headers);
+            self.parsed.lock().unwrap().push(content.metadata.clone());
+            Ok(content)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_parsed_metadata_keeps_the_response_status() {
+        let parser = Arc::new(MetadataRecordingParser::default());
+        let use_case = use_case_with(Arc::new(NotFoundFetcher), parser.clone());
+
+        let response = use_case.execute(FetchContentRequest {
+            url: "https://example.com/missing".to_string(),
+            include_links: Some(true),
+            ..Default::default()
+        }).await;
+        assert_eq!(response.result.unwrap().content.metadata.status_code, 404);
+
+        let parsed = parser.parsed.lock().unwrap();
+        assert_eq!(parsed.len(), 1);
+        assert_eq!(parsed[0].status_code, 404);
+        assert_eq!(parsed[0].charset, Some("iso-8859-1".to_string()));
+    }
+
+    struct UppercaseTransformer;
+
+    #[async_trait]
+    impl ContentTransformer for UppercaseTransformer {
+        async fn transform(&self, mut content: HtmlContent) -> ContentParserResult<HtmlContent> {
+            content.text_content = content.text_content.to_uppercase();
+            Ok(content)
+        }
+    }
+
+    struct SuffixTransformer(&'static str);
+
+    #[async_trait]
+    impl ContentTransformer for SuffixTransformer {
+        async fn transform(&self, mut content: HtmlContent) -> ContentParserResult<HtmlContent> {
+            content.text_content.push_str(self.0);
+            Ok(content)
+        }
+    }
+
+    struct FailingTransformer;
+
+    #[async_trait]
+    impl ContentTransformer for FailingTransformer {
+        async fn transform(&self, _content: HtmlContent) -> ContentParserResult<HtmlContent> {
+            Err(ContentParserError::Parse("transform failed".to_string()))
+        }
+    }
+
+    fn create_use_case_with_transformers(
+        transformers: Vec<Arc<dyn ContentTransformer>>,
+    ) -> FetchWebContentUseCase<MockContentFetcher, MockContentParser> {
+        let fetch_service = Arc::new(ContentFetchService::new(Arc::new(MockContentFetcher::new_success())));
+        let parse_service = Arc::new(ContentParseService::new(Arc::new(MockContentParser::new_success())));
+
+        transformers.into_iter().fold(
+            FetchWebContentUseCase::new(fetch_service, parse_service),
+            |use_case, transformer| use_case.with_transformer(transformer),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_execute_runs_transformers_in_order() {
+        let use_case = create_use_case_with_transformers(vec![
+            Arc::new(UppercaseTransformer),
+            Arc::new(SuffixTransformer("!")),
+        ]);
+
+        let request = FetchContentRequest {
+            url: "https://example.com".to_string(),
+            ..Default::default()
+        };
+
+        let content = use_case.execute(request.clone()).await.result.unwrap().content;
+        assert_eq!(content.text_content, "TEST CONTENT!");
+
+        let content = use_case.execute_for_api(request).await.unwrap();
+        assert_eq!(content.text_content, "TEST CONTENT!");
+    }
+
+    #[tokio::test]
+    async fn test_execute_transformer_error() {
+        let use_case = create_use_case_with_transformers(vec![Arc::new(FailingTransformer)]);
+
+        let request = FetchContentRequest {
+            url: "https://example.com".to_string(),
+            ..Default::default()
+        };
+
+        let response = use_case.execute(request).await;
+        let error = response.error.unwrap();
+        assert_eq!(error.code, -32004);
+        assert!(error.message.contains("transform failed"));
+    }
+
+    #[tokio::test]
+    async fn test_execute_output_formats_text_and_markdown() {
+        let use_case = create_use_case_with_transformers(Vec::new());
+
+        let request = FetchContentRequest {
+            url: "https://example.com".to_string(),
+            output_formats: Some(vec![ContentType::PlainText, ContentType::Markdown]),
+            ..Default::default()
+        };
+
+        let content = use_case.execute(request).await.result.unwrap().content;
+        let alternates = content.alternate_contents.unwrap();
+
+        assert_eq!(content.text_content, "Test content");
+        assert_eq!(alternates.len(), 2);
+        assert_eq!(alternates["text"], "Test content");
+        assert_eq!(alternates["markdown"], "# Parsed Title\n\nFrom https://example.com");
+    }
+
+    #[tokio::test]
+    async fn test_execute_without_output_formats_has_no_alternates() {
+        let use_case = create_use_case_with_transformers(Vec::new());
+
+        let request = FetchContentRequest {
+            url: "https://example.com".to_string(),
+            ..Default::default()
+        };
+
+        let content = use_case.execute(request).await.result.unwrap().content;
+        assert!(content.alternate_contents.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_execute_max_text_bytes_respects_char_boundaries() {
+        // "é" and "☕" take two and three bytes, so byte 2 and bytes 5-6 fall inside characters
+        let text = "aé☕b";
+        let expected = [(0, ""), (1, "a"), (2, "a"), (3, "aé"), (5, "aé"), (6, "aé☕")];
+
+        for (max_bytes, truncated) in expected {
+            let content = execute_text(text, FetchContentRequest { max_text_bytes: Some(max_bytes), ..Default::default() }).await;
+            assert_eq!(content.text_content, truncated, "max_text_bytes: {}", max_bytes);
+            assert!(content.text_content.len() <= max_bytes);
+            assert_eq!(content.metadata.text_truncated, Some(true));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_execute_max_text_bytes_within_budget() {
+        let content = execute_text("aé☕b", FetchContentRequest { max_text_bytes: Some(7), ..Default::default() }).await;
+
+        assert_eq!(content.text_content, "aé☕b");
+        assert_eq!(content.metadata.text_truncated, Some(false));
+    }
+
+    #[tokio::test]
+    async fn test_execute_raw_text_uses_parser_text() {
+        let fetcher = Arc::new(MockContentFetcher::new_success());
+        let parser = Arc::new(MockContentParser::new_success());
+
+        let fetch_service = Arc::new(ContentFetchService::new(fetcher));
+        let parse_service = Arc::new(ContentParseService::new(parser));
+
+        let use_case = FetchWebContentUseCase::new(fetch_service, parse_service);
+
+        let request = FetchContentRequest {
+            url: "https://example.com".to_string(),
+            raw_text: Some(true),
+            ..Default::default()
+        };
+
+        let content = use_case.execute(request).await.result.unwrap().content;
+        assert_eq!(content.text_content, "Parsed content");
+    }
+
+    #[tokio::test]
+    async fn test_execute_detects_soft_404() {
+        let body = "Oops! Page Not Found. The page you are looking for may have been moved, \
+                    renamed or deleted. Try searching our site or head back to the home page.";
+
+        let content = execute_text(body, FetchContentRequest { detect_soft_errors: Some(true), ..Default::default() }).await;
+        assert_eq!(content.metadata.likely_soft_error, Some(true));
+    }
+
+    #[tokio::test]
+    async fn test_execute_detects_short_and_paywalled_pages() {
+        let paywall = format!("{} Subscribe to continue reading this story.", "The council voted on Tuesday. ".repeat(5));
+
+        let detecting = || FetchContentRequest { detect_soft_errors: Some(true), ..Default::default() };
+
+        assert_eq!(execute_text("Loading...", detecting()).await.metadata.likely_soft_error, Some(true));
+        assert_eq!(execute_text(&paywall, detecting()).await.metadata.likely_soft_error, Some(true));
+    }
+
+    #[tokio::test]
+    async fn test_execute_normal_page_not_soft_error() {
+        let body = "The city council approved the new transit plan on Tuesday, adding three bus \
+                    routes and extending service hours on weekends. Construction begins in spring.";
+
+        let detecting = FetchContentRequest { detect_soft_errors: Some(true), ..Default::default() };
+
+        assert_eq!(execute_text(body, detecting).await.metadata.likely_soft_error, Some(false));
+        assert_eq!(execute_text(body, FetchContentRequest::default()).await.metadata.likely_soft_error, None);
+    }
+
+    #[tokio::test]
+    async fn test_execute_flags_content_below_minimum() {
+        let article = "The city council approved the new transit plan on Tuesday. ".repeat(5);
+        let with_minimum = || FetchContentRequest { min_content_length: Some(50), ..Default::default() };
+
+        assert_eq!(execute_text("  \n ", with_minimum()).await.metadata.content_below_minimum, Some(true));
+        assert_eq!(execute_text(&article, with_minimum()).await.metadata.content_below_minimum, Some(false));
+        assert_eq!(execute_text(&article, FetchContentRequest::default()).await.metadata.content_below_minimum, None);
+    }
+
+    #[tokio::test]
+    async fn test_execute_lowercases_multibyte_text() {
+        let content = execute_text("İSTANBUL Straße ΣΟΦΙΑ", FetchContentRequest { lowercase_text: Some(true), ..Default::default() }).await;
+
+        // "İ" lowercases to "i" plus a combining dot; "ß" is already lowercase; final sigma becomes "ς"
+        assert_eq!(content.text_content, "i\u{307}stanbul straße σοφια");
+        assert_eq!(content.title.as_deref(), Some("Test Title"));
+        assert_eq!(content.raw_html, "<html><body>Test</body></html>");
+    }
+
+    #[tokio::test]
+    async fn test_execute_keeps_case_without_lowercase_flag() {
+        let keeping_case = FetchContentRequest { lowercase_text: Some(false), ..Default::default() };
+
+        assert_eq!(execute_text("İSTANBUL Straße", FetchContentRequest::default()).await.text_content, "İSTANBUL Straße");
+        assert_eq!(execute_text("İSTANBUL Straße", keeping_case).await.text_content, "İSTANBUL Straße");
+    }
+
+    /// Serves `static_text` from a static fetch and a fully rendered page when
+    /// the browser is forced, counting browser fetches.
+    struct MockBrowserFetcher {
+        static_text: String,
+        browser_fetches: std::sync::atomic::AtomicUsize,
+    }
+
+    impl MockBrowserFetcher {
+        fn new(static_text: &str) -> Self {
+            Self {
+                static_text: static_text.to_string(),
+                browser_fetches: std::sync::atomic::AtomicUsize::new(0),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl ContentFetcher for MockBrowserFetcher {
+        async fn fetch_content(&self, request: FetchContentRequest) -> ContentFetcherResult<HtmlContent> {
+            let (method, text_content) = if request.force_fetch_method == Some(FetchMethod::Browser) {
+                self.browser_fetches.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                (FetchMethod::Browser, "Rendered dashboard with the full quarterly report. ".repeat(10))
+            } else {
+                (FetchMethod::Static, self.static_text.clone())
+            };
+
+            Ok(HtmlContent {
+                url: request.url,
+                text_content,
+                raw_html: r#"<html><body><div id="root"></div><script src="/app.js"></script></body></html>"#.to_string(),
+                metadata: ContentMetadata {
+                    status_code: 200,
+                    fetch_method: Some(method),
+                    ..Default::default()
+                },
+                ..Default::default()
+            })
+        }
+    }
+
+    async fn fetch_with_browser_retry(fetcher: Arc<MockBrowserFetcher>) -> HtmlContent {
+        let fetch_service = Arc::new(ContentFetchService::new(fetcher));
+        let parse_service = Arc::new(ContentParseService::new(Arc::new(MockContentParser::new_success())));
+        let use_case = FetchWebContentUseCase::new(fetch_service, parse_service);
+
+        let request = FetchContentRequest {
+            url: "https://app.example.com/reports".to_string(),
+            retry_empty_with_browser: Some(true),
+            ..Default::default()
+        };
+
+        use_case.execute(request).await.result.unwrap().content
+    }
+
+    #[tokio::test]
+    async fn test_empty_static_result_retries_with_browser() {
+        let fetcher = Arc::new(MockBrowserFetcher::new("Loading…"));
+
+        let content = fetch_with_browser_retry(fetcher.clone()).await;
+
+        assert_eq!(fetcher.browser_fetches.load(std::sync::atomic::Ordering::SeqCst), 1);
+        assert!(content.text_content.starts_with("Rendered dashboard"));
+        assert_eq!(content.metadata.fetch_method, Some(FetchMethod::Browser));
+        assert_eq!(content.metadata.retried_with_browser, Some(true));
+    }
+
+    #[tokio::test]
+    async fn test_execute_logs_within_fetch_span() {
+        let (logs, _guard) = CapturedLogs::capture(tracing::Level::INFO);
+
+        let fetch_service = Arc::new(ContentFetchService::new(Arc::new(MockBrowserFetcher::new("Quarterly report"))));
+        let parse_service = Arc::new(ContentParseService::new(Arc::new(MockContentParser::new_success())));
+        let use_case = FetchWebContentUseCase::new(fetch_service, parse_service);
+        let request = FetchContentRequest {
+            url: "https://app.example.com/reports".to_string(),
+            ..Default::default()
+        };
+
+        let response = use_case.execute(request).await;
+
+        let output = logs.output();
+        let line = output
+            .lines()
+            .find(|line| line.contains("Successfully fetched content from: "))
+            .unwrap_or_else(|| panic!("no fetch log line in {}", output));
+        assert!(line.contains(&format!("request_id={}", response.id)), "{}", line);
+        assert!(line.contains("url=https://app.example.com/reports"), "{}", line);
+        assert!(line.contains("fetch_method=Static"), "{}", line);
+    }
+
+    #[tokio::test]
+    async fn test_substantial_static_result_skips_browser_retry() {
+        let article = "The quarterly report shows revenue growth across every region. ".repeat(5);
+        let fetcher = Arc::new(MockBrowserFetcher::new(&article));
+
+        let content = fetch_with_browser_retry(fetcher.clone()).await;
+
+        assert_eq!(fetcher.browser_fetches.load(std::sync::atomic::Ordering::SeqCst), 0);
+        assert_eq!(content.text_content, article);
+        assert_eq!(content.metadata.fetch_method, Some(FetchMethod::Static));
+        assert_eq!(content.metadata.retried_with_browser, Some(false));
+    }
+
+    #[tokio::test]
+    async fn test_execute_without_blocks() {
+        let fetcher = Arc::new(MockContentFetcher::new_success());
+        let parser = Arc::new(MockContentParser::new_success());
+
+        let fetch_service = Arc::new(ContentFetchService::new(fetcher));
+        let parse_service = Arc::new(ContentParseService::new(parser));
+
+        let use_case = FetchWebContentUseCase::new(fetch_service, parse_service);
+
+        let request = FetchContentRequest {
+            url: "https://example.com".to_string(),
+            ..Default::default()
+        };
+
+        let response = use_case.execute(request).await;
+        assert!(response.result.unwrap().content.blocks.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_execute_encode_base64_round_trip() {
+        let text = "Café – naïve 日本語 🚀";
+        let fetcher = Arc::new(MockContentFetcher::new_with_text(text));
+        let parser = Arc::new(MockContentParser::new_success());
+
+        let fetch_service = Arc::new(ContentFetchService::new(fetcher));
+        let parse_service = Arc::new(ContentParseService::new(parser));
+
+        let use_case = FetchWebContentUseCase::new(fetch_service, parse_service);
+
+        let request = FetchContentRequest {
+            url: "https://example.com".to_string(),
+            encode_base64: Some(true),
+            ..Default::default()
+        };
+
+        let content = use_case.execute(request).await.result.unwrap().content;
+
+        assert_eq!(content.metadata.content_encoding, Some("base64".to_string()));
+        let decoded = STANDARD.decode(&content.text_content).unwrap();
+        assert_eq!(String::from_utf8(decoded).unwrap(), text);
+        let decoded = STANDARD.decode(&content.raw_html).unwrap();
+        assert_eq!(String::from_utf8(decoded).unwrap(), "<html><body>Test</body></html>");
+    }
+
+    #[tokio::test]
+    async fn test_execute_for_api_without_encoding() {
+        let text = "Café 日本語";
+        let fetcher = Arc::new(MockContentFetcher::new_with_text(text));
+        let parser = Arc::new(MockContentParser::new_success());
+
+        let fetch_service = Arc::new(ContentFetchService::new(fetcher));
+        let parse_service = Arc::new(ContentParseService::new(parser));
+
+        let use_case = FetchWebContentUseCase::new(fetch_service, parse_service);
+
+        let request = FetchContentRequest {
+            url: "https://example.com".to_string(),
+            ..Default::default()
+        };
+
+        let content = use_case.execute_for_api(request).await.unwrap();
+
+        assert_eq!(content.text_content, text);
+        assert_eq!(content.metadata.content_encoding, None);
+    }
+
+    /// Remembers the URLs it was asked for and echoes them back as the page URL.
+    #[derive(Default)]
+    struct UrlRecordingFetcher {
+        fetched: std::sync::Mutex<Vec<String>>,
+    }
+
+    #[async_trait]
+    impl ContentFetcher for UrlRecordingFetcher {
+        async fn fetch_content(&self, request: FetchContentRequest) -> ContentFetcherResult<HtmlContent> {
+            self.fetched.lock().unwrap().push(request.url.clone());
+            Ok(HtmlContent {
+                url: request.url,
+                metadata: ContentMetadata { status_code: 200, ..Default::default() },
+                ..Default::default()
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_strip_tracking_params_from_returned_url() {
+        let fetcher = Arc::new(UrlRecordingFetcher::default());
+        let fetch_service = Arc::new(ContentFetchService::new(fetcher.clone()));
+        let parse_service = Arc::new(ContentParseService::new(Arc::new(MockContentParser::new_success())));
+        let use_case = FetchWebContentUseCase::new(fetch_service, parse_service);
+        let url = "https://example.com/post?id=7&utm_source=news&utm_medium=email&q=a%20b&fbclid=XYZ#comments";
+
+        let request = FetchContentRequest {
+            url: url.to_string(),
+            strip_tracking_params: Some(true),
+            ..Default::default()
+        };
+        let content = use_case.execute_for_api(request).await.unwrap();
+
+        assert_eq!(content.url, "https://example.com/post?id=7&q=a%20b#comments");
+        assert_eq!(content.metadata.requested_url.as_deref(), Some(url));
+        assert_eq!(*fetcher.fetched.lock().unwrap(), vec![url.to_string()]);
+
+        // Only tracking parameters: the query goes entirely
+        assert_eq!(strip_tracking_params("https://example.com/?gclid=1&utm_campaign=x"), "https://example.com/");
+
+        // Not requested: the URL comes back as fetched
+        let request = FetchContentRequest { url: url.to_string(), ..Default::default() };
+        let content = use_case.execute_for_api(request).await.unwrap();
+        assert_eq!(content.url, url);
+        assert_eq!(content.metadata.requested_url, None);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_static_invalid_url() {
+        let fetcher = Arc::new(MockContentFetcher::new_success());
+        let parser = Arc::new(MockContentParser::new_success());
+
+        let fetch_service = Arc::new(ContentFetchService::new(fetcher));
+        let parse_service = Arc::new(ContentParseService::new(parser));
+
+        let use_case = FetchWebContentUseCase::new(fetch_service, parse_service);
+
+        let request = FetchContentRequest {
+            url: "ftp://example.com".to_string(),
+            ..Default::default()
+        };
+
+        let error = use_case.fetch_static(request).await.unwrap_err();
+        assert_eq!(error.code, -32602);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_static_network_error() {
+        let fetcher = Arc::new(MockContentFetcher::new_with_error(
+            ContentFetcherError::network("Connection refused".to_string()),
+        ));
+        let parser = Arc::new(MockContentParser::new_success());
+
+        let fetch_service = Arc::new(ContentFetchService::new(fetcher));
+        let parse_service = Arc::new(ContentParseService::new(parser));
+
+        let use_case = FetchWebContentUseCase::new(fetch_service, parse_service);
+
+        let request = FetchContentRequest {
+            url: "https://example.com".to_string(),
+            ..Default::default()
+        };
+
+        let error = use_case.fetch_static(request).await.unwrap_err();
+        assert_eq!(error.code, -32001);
+        assert_eq!(error.message, "Network error: Connection refused");
+    }
+
+    #[tokio::test]
+    async fn test_use_case_creation() {
+        let fetcher = Arc::new(MockContentFetcher::new_success());
+        let parser = Arc::new(MockContentParser::new_success());
+        
+        let fetch_service = Arc::new(ContentFetchService::new(fetcher));
+        let parse_service = Arc::new(ContentParseService::new(parser));
+        
+        let _use_case = FetchWebContentUseCase::new(fetch_service, parse_service);
+    }
+
+    /// Fails for the URLs in `down`, after `delay`, and records every request.
+    struct MirrorFetcher {
+        down: Vec<&'static str>,
+        delay: std::time::Duration,
+        requests: std::sync::Mutex<Vec<FetchContentRequest>>,
+    }
+
+    #[async_trait]
+    impl ContentFetcher for MirrorFetcher {
+        async fn fetch_content(&self, request: FetchContentRequest) -> ContentFetcherResult<HtmlContent> {
+            self.requests.lock().unwrap().push(request.clone());
+            if self.down.contains(&request.url.as_str()) {
+                tokio::time::sleep(self.delay).await;
+                return Err(ContentFetcherError::Http { status: 503, message: "Service Unavailable".to_string() });
+            }
+            Ok(HtmlContent {
+                url: request.url,
+                metadata: ContentMetadata { status_code: 200, ..Default::default() },
+                ..Default::default()
+            })
+        }
+    }
+
+    impl MirrorFetcher {
+        fn new(down: Vec<&'static str>) -> Arc<Self> {
+            Arc::new(Self { down, delay: std::time::Duration::ZERO, requests: Default::default() })
+        }
+
+        fn fetched(&self) -> Vec<String> {
+            self.requests.lock().unwrap().iter().map(|request| request.url.clone()).collect()
+        }
+    }
+
+    fn mirrored_request() -> FetchContentRequest {
+        FetchContentRequest {
+            url: "https://primary.example/doc".to_string(),
+            fallback_urls: Some(vec!["https://mirror1.example/doc".to_string(), "https://mirror2.example/doc".to_string()]),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fallback_urls_tried_after_primary_fails() {
+        let fetcher = MirrorFetcher::new(vec!["https://primary.example/doc"]);
+        let use_case = use_case_with(fetcher.clone(), Arc::new(MockContentParser::new_success()));
+
+        let content = use_case.execute_for_api(mirrored_request()).await.unwrap();
+
+        assert_eq!(content.url, "https://mirror1.example/doc");
+        assert_eq!(content.metadata.url_used.as_deref(), Some("https://mirror1.example/doc"));
+        assert_eq!(
+            content.metadata.fallback_errors,
+            Some(vec!["https://primary.example/doc: HTTP error: 503 - Service Unavailable".to_string()])
+        );
+        assert_eq!(fetcher.fetched(), vec!["https://primary.example/doc", "https://mirror1.example/doc"]);
+
+        // Every URL down: the last one's error
+        let fetcher = MirrorFetcher::new(vec![
+            "https://primary.example/doc",
+            "https://mirror1.example/doc",
+            "https://mirror2.example/doc",
+        ]);
+        let use_case = use_case_with(fetcher.clone(), Arc::new(MockContentParser::new_success()));
+        let error = use_case.execute_for_api(mirrored_request()).await.unwrap_err();
+        assert_eq!(error, "HTTP 503: Service Unavailable");
+        assert_eq!(fetcher.fetched().len(), 3);
+
+        // The primary answers: no mirror is asked
+        let fetcher = MirrorFetcher::new(vec![]);
+        let use_case = use_case_with(fetcher.clone(), Arc::new(MockContentParser::new_success()));
+        let content = use_case.execute_for_api(mirrored_request()).await.unwrap();
+        assert_eq!(content.metadata.url_used.as_deref(), Some("https://primary.example/doc"));
+        assert_eq!(content.metadata.fallback_errors, Some(vec![]));
+        assert_eq!(fetcher.fetched().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_fallback_urls_share_the_request_timeout() {
+        let fetcher = Arc::new(MirrorFetcher {
+            down: vec!["https://primary.example/doc", "https://mirror1.example/doc"],
+            delay: std::time::Duration::from_millis(1100),
+            requests: Default::default(),
+        });
+        let use_case = use_case_with(fetcher.clone(), Arc::new(MockContentParser::new_success()));
+        let request = FetchContentRequest { timeout_seconds: Some(3), ..mirrored_request() };
+
+        let error = use_case.execute_for_api(request).await.unwrap_err();
+
+        // Two slow failures leave under a second, too little for the last mirror
+        assert_eq!(error, "HTTP 503: Service Unavailable");
+        let requests = fetcher.requests.lock().unwrap();
+        let timeouts: Vec<_> = requests.iter().map(|request| request.timeout_seconds).collect();
+        assert_eq!(timeouts, vec![Some(3), Some(2)]);
+        assert!(requests.iter().all(|request| request.fallback_urls.is_none()));
+    }
+
+    #[tokio::test]
+    async fn test_multipart_upload_not_sent_to_fallback_urls() {
+        let fetcher = MirrorFetcher::new(vec!["https://primary.example/doc"]);
+        let use_case = use_case_with(fetcher.clone(), Arc::new(MockContentParser::new_success()));
+        let request = FetchContentRequest {
+            multipart: Some(vec![MultipartPart {
+                name: "comment".to_string(),
+                value: Some("hello".to_string()),
+                file_url: None,
+                content_type: None,
+            }]),
+            ..mirrored_request()
+        };
+
+        let error = use_case.execute_for_api(request).await.unwrap_err();
+
+        assert_eq!(error, "HTTP 503: Service Unavailable");
+        assert_eq!(fetcher.fetched(), vec!["https://primary.example/doc"]);
+    }
+
+    #[derive(Default)]
+    struct MemoryAuditSink {
+        entries: std::sync::Mutex<Vec<AuditEntry>>,
+    }
+
+    #[async_trait]
+    impl AuditSink for MemoryAuditSink {
+        async fn record(&self, entry: AuditEntry) {
+            self.entries.lock().unwrap().push(entry);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_audit_sink_records_each_fetch() {
+        let use_case = use_case_with(MirrorFetcher::new(vec!["https://primary.example/doc"]), Arc::new(MockContentParser::new_success()));
+        let sink = Arc::new(MemoryAuditSink::default());
+        let use_case = use_case.with_audit_sink(sink.clone());
+
+        let request = FetchContentRequest {
+            url: "https://mirror1.example/doc".to_string(),
+            client_id: Some("203.0.113.7".to_string()),
+            ..Default::default()
+        };
+        use_case.execute_for_api(request).await.unwrap();
+
+        let entries = sink.entries.lock().unwrap().clone();
+        assert_eq!(entries.len(), 1);
+        let entry = &entries[0];
+        assert_eq!(entry.url, "https://mirror1.example/doc");
+        assert_eq!(entry.status, Some(200));
+        assert_eq!(entry.bytes, Some(0));
+        assert_eq!(entry.client_id.as_deref(), Some("203.0.113.7"));
+        assert!(chrono::DateTime::parse_from_rfc3339(&entry.timestamp).is_ok(), "{}", entry.timestamp);
+
+        // A failed primary and the mirror that answered are both recorded
+        use_case.execute_for_api(mirrored_request()).await.unwrap();
+        let entries = sink.entries.lock().unwrap().clone();
+        let recorded: Vec<_> = entries[1..].iter().map(|entry| (entry.url.as_str(), entry.status, entry.bytes)).collect();
+        assert_eq!(recorded, vec![("https://primary.example/doc", Some(503), None), ("https://mirror1.example/doc", Some(200), Some(0))]);
+    }
+}
@@ -1 +1,2 @@
+pub mod crawl_site_use_case;
 pub mod fetch_web_content_use_case;
\ No newline at end of file
@@ -1,28 +1,308 @@
+use std::collections::HashMap;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct HtmlContent {
     pub url: String,
     pub title: Option<String>,
     pub text_content: String,
     pub raw_html: String,
     pub metadata: ContentMetadata,
+    pub blocks: Option<Vec<TextBlock>>,
+    pub contacts: Option<Contacts>,
+    pub links: Option<Vec<Link>>,
+    pub images: Option<Vec<Image>>,
+    pub forms: Option<Vec<FormDef>>,
+    pub microdata: Option<Vec<MicrodataItem>>,
+    /// Breadcrumb trail from the site root to this page, as (label, href) pairs.
+    pub breadcrumbs: Option<Vec<(String, Option<String>)>>,
+    /// The main content region as sanitized HTML, from `output_html_fragment`.
+    pub content_html: Option<String>,
+    /// RSS, Atom and JSON feeds the page advertises, from `include_feeds`.
+    pub feeds: Option<Vec<FeedLink>>,
+    /// In-page `#fragment` links with no matching target, from `validate_anchors`.
+    pub broken_anchors: Option<Vec<String>>,
+    /// The page rendered in each format requested through `output_formats`, keyed by format name.
+    pub alternate_contents: Option<HashMap<String, String>>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// The main article of a page, separated from navigation, sidebars and footers.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Article {
+    pub title: String,
+    pub byline: Option<String>,
+    /// Body paragraphs and headings as plain text, separated by blank lines.
+    pub body_text: String,
+    /// The same body elements as HTML.
+    pub body_html: String,
+    /// Publish date as RFC3339, like `ContentMetadata::published_at`.
+    pub published: Option<String>,
+    /// Absolute URL of the article's main image.
+    pub lead_image: Option<String>,
+}
+
+/// A section of a page's text sized for retrieval, with the heading it falls under.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ContentChunk {
+    pub index: usize,
+    /// Text of the nearest heading before the chunk, if any.
+    pub heading: Option<String>,
+    /// Whole paragraphs (or whole sentences of an oversized paragraph), separated by blank lines.
+    pub text: String,
+}
+
+/// How the text of two pages differs, line by line.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ContentComparison {
+    /// Share of lines the two pages have in common, from 0.0 (nothing) to 1.0 (identical).
+    pub similarity: f32,
+    /// Lines only the second page has.
+    pub added_lines: Vec<String>,
+    /// Lines only the first page has.
+    pub removed_lines: Vec<String>,
+}
+
+/// A run of text tagged with the block-level element it came from (`p`, `li`, `h2`, ...).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TextBlock {
+    pub tag: String,
+    pub text: String,
+    /// Language of the nearest enclosing `lang` attribute, when requested.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lang: Option<String>,
+}
+
+/// Contact details found in a page's text and `mailto:`/`tel:` links.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Contacts {
+    pub emails: Vec<String>,
+    pub phones: Vec<String>,
+}
+
+/// An `<a href>` resolved to an absolute URL, with its normalized anchor text.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Link {
+    pub href: String,
+    pub text: String,
+}
+
+/// An `<img src>` resolved to an absolute URL.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Image {
+    pub src: String,
+    pub alt: Option<String>,
+    /// Every `srcset` candidate as (absolute URL, descriptor), e.g. `640w` or
+    /// `2x`; a candidate without a descriptor gets `1x`.
+    #[serde(default)]
+    pub srcset_candidates: Vec<(String, String)>,
+}
+
+/// A feed advertised by `<link rel="alternate">`, with `href` resolved to an absolute URL.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FeedLink {
+    pub href: String,
+    pub title: Option<String>,
+    pub feed_type: FeedType,
+}
+
+/// The syndication format of a `FeedLink`, from its `type` attribute.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FeedType {
+    /// `application/rss+xml` or `application/rdf+xml`.
+    Rss,
+    /// `application/atom+xml`.
+    Atom,
+    /// `application/feed+json`.
+    Json,
+}
+
+impl FeedType {
+    /// The feed type for a `type` attribute value, ignoring case and parameters.
+    pub fn from_mime(mime: &str) -> Option<Self> {
+        let essence = mime.split(';').next().unwrap_or_default().trim().to_ascii_lowercase();
+        match essence.as_str() {
+            "application/rss+xml" | "application/rdf+xml" => Some(Self::Rss),
+            "application/atom+xml" => Some(Self::Atom),
+            "application/feed+json" => Some(Self::Json),
+            _ => None,
+        }
+    }
+}
+
+/// A `<form>` and the named controls it would submit.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FormDef {
+    pub action: Option<String>,
+    pub method: String,
+    pub fields: Vec<FormField>,
+}
+
+/// An `<input>`, `<select>` or `<textarea>`; `field_type` is the input's `type`
+/// (default `text`) or the element name for selects and textareas.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FormField {
+    pub name: String,
+    pub field_type: String,
+    pub value: Option<String>,
+}
+
+/// An `itemscope` element with its `itemtype` and `itemprop` properties, in document order.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MicrodataItem {
+    pub item_type: Option<String>,
+    pub properties: Vec<MicrodataProperty>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MicrodataProperty {
+    pub name: String,
+    pub value: MicrodataValue,
+}
+
+/// A property's value: the element's `content`, `href` or `src` attribute or
+/// its text, or a nested item when the property element is itself an `itemscope`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum MicrodataValue {
+    Text(String),
+    Item(MicrodataItem),
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ContentMetadata {
     pub content_type: String,
     pub status_code: u16,
     pub content_length: Option<usize>,
     pub last_modified: Option<String>,
     pub charset: Option<String>,
+    /// How sure the fetcher is of `charset`, from 0 to 1: 1 when the server
+    /// declared it, lower when it was guessed from the bytes.
+    pub charset_confidence: Option<f32>,
     pub javascript_detected: Option<bool>,
     pub fetch_method: Option<FetchMethod>,
+    /// Protocol the response was served over, e.g. `HTTP/1.1` or `HTTP/2.0`.
+    pub http_version: Option<String>,
+    /// Article publish date as RFC3339, when the page declares one.
+    pub published_at: Option<String>,
+    /// Set to `base64` when `text_content` and `raw_html` were encoded for transport.
+    pub content_encoding: Option<String>,
+    /// Set when `detect_soft_errors` was requested: whether the page looks like an
+    /// error page or paywall despite a successful status.
+    pub likely_soft_error: Option<bool>,
+    /// Why the browser fetch was abandoned in favour of the static result, if it was.
+    pub fallback_reason: Option<String>,
+    /// Set when `max_text_bytes` was requested: whether `text_content` was cut to fit.
+    pub text_truncated: Option<bool>,
+    /// Whether the HTML ends before its document was closed, e.g. because the
+    /// transfer was cut short; extraction from such pages is best-effort.
+    pub html_truncated: Option<bool>,
+    /// The URL that was fetched instead of the requested one, after `www_fallback`
+    /// toggled its `www.` prefix, `upgrade_insecure` switched it to HTTPS or
+    /// `archive_fallback` turned to a snapshot.
+    pub effective_url: Option<String>,
+    /// Set when `min_content_length` was requested: whether the extracted text is
+    /// shorter than that, as placeholder and empty pages are.
+    pub content_below_minimum: Option<bool>,
+    /// Set when a page cache is in use: whether this content was served from it.
+    pub from_cache: Option<bool>,
+    /// For content served from cache, how long ago it was fetched.
+    pub cache_age_seconds: Option<u64>,
+    /// Hex SHA-256 of the response body as received, before decoding.
+    pub content_hash: Option<String>,
+    /// Caching headers of the response, for working out when the page may be re-fetched.
+    pub cache_directives: Option<CacheDirectives>,
+    /// Set when `retry_empty_with_browser` was requested: whether this content
+    /// comes from a browser re-fetch of a page whose static text was nearly empty.
+    pub retried_with_browser: Option<bool>,
+    /// Set when `max_elements` capped extracted links or images: whether either
+    /// list had more entries than were returned.
+    pub elements_truncated: Option<bool>,
+    /// Set when `archive_fallback` replaced a failed fetch: whether this content
+    /// is a Wayback Machine snapshot rather than the live page.
+    pub from_archive: Option<bool>,
+    /// When the archived snapshot was captured, as RFC3339.
+    pub archive_timestamp: Option<String>,
+    /// With `include_keywords`, the page's meta keywords and `article:tag` values.
+    pub keywords: Option<Vec<String>>,
+    /// Set when `upgrade_insecure` was requested for an `http://` URL: the
+    /// scheme the page was fetched over, `https` or `http`.
+    pub scheme: Option<String>,
+    /// How long the fetch took, phase by phase.
+    pub timing: Option<PhaseTiming>,
+    /// Set when `strip_tracking_params` was requested: the URL as requested,
+    /// tracking parameters included.
+    pub requested_url: Option<String>,
+    /// Set when `fallback_urls` was requested: the URL, primary or fallback,
+    /// this content was fetched from.
+    pub url_used: Option<String>,
+    /// Set when `fallback_urls` was requested: the errors of the URLs tried
+    /// before `url_used`, in order.
+    pub fallback_errors: Option<Vec<String>>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl ContentMetadata {
+    /// Records the status and headers of the response the HTML came from:
+    /// `content-type` (and its charset) and `last-modified`. Header names are
+    /// matched case-insensitively.
+    pub fn apply_response(&mut self, status: u16, headers: &HashMap<String, String>) {
+        let header = |name: &str| {
+            headers
+                .iter()
+                .find(|(key, _)| key.eq_ignore_ascii_case(name))
+                .map(|(_, value)| value.trim())
+        };
+
+        self.status_code = status;
+        if let Some(content_type) = header("content-type") {
+            self.content_type = content_type.split(';').next().unwrap_or_default().trim().to_string();
+            if let Some(charset) = content_type.split(';').find_map(|param| param.trim().strip_prefix("charset=")) {
+                self.charset = Some(charset.trim_matches('"').to_ascii_lowercase());
+            }
+        }
+        if let Some(last_modified) = header("last-modified") {
+            self.last_modified = Some(last_modified.to_string());
+        }
+    }
+}
+
+/// Milliseconds spent in each phase of a fetch. Phases a fetcher can't observe
+/// are left out: reqwest hides DNS and connection setup inside time to first
+/// byte, and the browser exposes only the parse.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PhaseTiming {
+    pub dns_ms: Option<u64>,
+    pub connect_ms: Option<u64>,
+    /// From sending the request until the response headers arrived.
+    pub ttfb_ms: Option<u64>,
+    /// Reading and decoding the body.
+    pub download_ms: Option<u64>,
+    /// Extracting the title, text and metadata from the page.
+    pub parse_ms: Option<u64>,
+    /// The whole fetch, retries and fallbacks included; set by `TimingFetcher`.
+    pub total_ms: Option<u64>,
+}
+
+/// What a response's `Cache-Control`, `Expires` and `ETag` headers say about reusing it.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CacheDirectives {
+    /// Seconds the response stays fresh, from `Cache-Control: max-age`.
+    pub max_age_seconds: Option<u64>,
+    /// `Cache-Control: no-store`: the response must not be kept at all.
+    pub no_store: bool,
+    /// `Cache-Control: no-cache`: a kept copy must be revalidated before reuse.
+    pub no_cache: bool,
+    /// When the response goes stale, from `Expires`, as RFC3339.
+    pub expires: Option<String>,
+    /// The `ETag` validator, quotes included, for conditional re-fetches.
+    pub etag: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum FetchMethod {
+    #[serde(alias = "static")]
     Static,
+    #[serde(alias = "browser")]
     Browser,
 }
 
@@ -35,20 +315,51 @@ pub struct BrowserOptions {
     pub user_agent: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum ContentType {
+    #[default]
+    #[serde(alias = "html")]
     Html,
+    #[serde(alias = "text")]
     PlainText,
+    #[serde(alias = "json")]
     Json,
+    #[serde(alias = "xml")]
     Xml,
+    #[serde(alias = "markdown")]
+    Markdown,
 }
 
-impl Default for ContentType {
-    fn default() -> Self {
-        ContentType::Html
+impl ContentType {
+    /// The content type named by a `Content-Type` value, ignoring case and
+    /// parameters; `None` for types outside these formats.
+    pub fn from_mime(mime: &str) -> Option<Self> {
+        let essence = mime.split(';').next().unwrap_or_default().trim().to_ascii_lowercase();
+        match essence.as_str() {
+            "text/html" | "application/xhtml+xml" => Some(Self::Html),
+            "text/plain" => Some(Self::PlainText),
+            "text/markdown" => Some(Self::Markdown),
+            "application/json" => Some(Self::Json),
+            "application/xml" | "text/xml" => Some(Self::Xml),
+            _ if essence.ends_with("+json") => Some(Self::Json),
+            _ if essence.ends_with("+xml") => Some(Self::Xml),
+            _ => None,
+        }
+    }
+
+    /// Short lowercase name, used as the key in `HtmlContent::alternate_contents`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            ContentType::Html => "html",
+            ContentType::PlainText => "text",
+            ContentType::Json => "json",
+            ContentType::Xml => "xml",
+            ContentType::Markdown => "markdown",
+        }
     }
 }
 
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -64,6 +375,7 @@ mod tests {
             charset: Some("utf-8".to_string()),
             javascript_detected: None,
             fetch_method: None,
+            ..Default::default()
         };
 
         let content = HtmlContent {
@@ -72,6 +384,7 @@ mod tests {
             text_content: "Test content".to_string(),
             raw_html: "<html><body>Test</body></html>".to_string(),
             metadata,
+            ..Default::default()
         };
 
         assert_eq!(content.url, "https://example.com");
@@ -91,6 +404,7 @@ mod tests {
             charset: None,
             javascript_detected: None,
             fetch_method: None,
+            ..Default::default()
         };
 
         let content = HtmlContent {
@@ -99,6 +413,7 @@ mod tests {
             text_content: "Not found".to_string(),
             raw_html: "<html><body>404</body></html>".to_string(),
             metadata,
+            ..Default::default()
         };
 
         assert_eq!(content.title, None);
@@ -117,6 +432,7 @@ mod tests {
             charset: Some("".to_string()),
             javascript_detected: None,
             fetch_method: None,
+            ..Default::default()
         };
 
         assert_eq!(metadata.content_type, "");
@@ -145,6 +461,14 @@ mod tests {
         assert!(matches!(xml, ContentType::Xml));
     }
 
+    #[test]
+    fn test_content_type_accepts_short_names() {
+        let formats: Vec<ContentType> = serde_json::from_str(r#"["text", "markdown", "Html"]"#).unwrap();
+
+        assert_eq!(formats, vec![ContentType::PlainText, ContentType::Markdown, ContentType::Html]);
+        assert_eq!(formats.iter().map(ContentType::name).collect::<Vec<_>>(), vec!["text", "markdown", "html"]);
+    }
+
     #[test]
     fn test_html_content_serialization() {
         let metadata = ContentMetadata {
@@ -155,6 +479,7 @@ mod tests {
             charset: Some("utf-8".to_string()),
             javascript_detected: None,
             fetch_method: None,
+            ..Default::default()
         };
 
         let content = HtmlContent {
@@ -163,6 +488,7 @@ mod tests {
             text_content: "Test content".to_string(),
             raw_html: "<html><body>Test</body></html>".to_string(),
             metadata,
+            ..Default::default()
         };
 
         let serialized = serde_json::to_string(&content).unwrap();
@@ -207,6 +533,7 @@ mod tests {
             charset: Some("utf-8".to_string()),
             javascript_detected: None,
             fetch_method: None,
+            ..Default::default()
         };
 
         let content = HtmlContent {
@@ -215,6 +542,7 @@ mod tests {
             text_content: "Test content".to_string(),
             raw_html: "<html><body>Test</body></html>".to_string(),
             metadata,
+            ..Default::default()
         };
 
         let cloned = content.clone();
@@ -237,6 +565,7 @@ mod tests {
             charset: Some("utf-8".to_string()),
             javascript_detected: None,
             fetch_method: None,
+            ..Default::default()
         };
 
         let content = HtmlContent {
@@ -245,6 +574,7 @@ mod tests {
             text_content: large_text.clone(),
             raw_html: large_html.clone(),
             metadata,
+            ..Default::default()
         };
 
         assert_eq!(content.text_content.len(), 1_000_000);
@@ -262,10 +592,10 @@ mod tests {
             user_agent: Some("Mozilla/5.0 test".to_string()),
         };
 
-        assert_eq!(options.wait_for_js, true);
+        assert!(options.wait_for_js);
         assert_eq!(options.timeout_ms, 30000);
         assert_eq!(options.wait_for_selector, Some("#main-content".to_string()));
-        assert_eq!(options.disable_images, true);
+        assert!(options.disable_images);
         assert_eq!(options.user_agent, Some("Mozilla/5.0 test".to_string()));
     }
 
@@ -278,6 +608,33 @@ mod tests {
         assert!(matches!(browser_method, FetchMethod::Browser));
     }
 
+    #[test]
+    fn test_fetch_method_deserialize_lowercase_alias() {
+        let method: FetchMethod = serde_json::from_str("\"browser\"").unwrap();
+        assert_eq!(method, FetchMethod::Browser);
+        let method: FetchMethod = serde_json::from_str("\"Static\"").unwrap();
+        assert_eq!(method, FetchMethod::Static);
+        assert_eq!(serde_json::to_string(&FetchMethod::Static).unwrap(), "\"Static\"");
+    }
+
+    #[test]
+    fn test_html_content_blocks_serialization() {
+        let content = HtmlContent {
+            url: "https://example.com".to_string(),
+            blocks: Some(vec![TextBlock {
+                tag: "p".to_string(),
+                text: "Paragraph".to_string(),
+                lang: None,
+            }]),
+            ..Default::default()
+        };
+
+        let serialized = serde_json::to_string(&content).unwrap();
+        let deserialized: HtmlContent = serde_json::from_str(&serialized).unwrap();
+
+        assert_eq!(deserialized.blocks, content.blocks);
+    }
+
     #[test]
     fn test_content_metadata_with_browser_fields() {
         let metadata = ContentMetadata {
@@ -288,6 +645,7 @@ mod tests {
             charset: Some("utf-8".to_string()),
             javascript_detected: Some(true),
             fetch_method: Some(FetchMethod::Browser),
+            ..Default::default()
         };
 
         assert_eq!(metadata.javascript_detected, Some(true));
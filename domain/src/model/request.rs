@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use super::content::{ContentType, FetchMethod};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FetchContentRequest {
@@ -6,7 +7,184 @@ pub struct FetchContentRequest {
     pub extract_text_only: Option<bool>,
     pub follow_redirects: Option<bool>,
     pub timeout_seconds: Option<u64>,
+    pub read_timeout_seconds: Option<u64>,
     pub user_agent: Option<String>,
+    pub http_version: Option<String>,
+    /// Skips JavaScript detection and fetches with this method when set.
+    pub force_fetch_method: Option<FetchMethod>,
+    pub include_blocks: Option<bool>,
+    pub max_title_length: Option<usize>,
+    pub include_contacts: Option<bool>,
+    pub include_links: Option<bool>,
+    pub include_images: Option<bool>,
+    pub referer: Option<String>,
+    pub expected_content_types: Option<Vec<String>>,
+    pub prettify_html: Option<bool>,
+    pub include_forms: Option<bool>,
+    pub include_microdata: Option<bool>,
+    /// Returns the page text exactly as written, whitespace and blank lines included.
+    pub raw_text: Option<bool>,
+    /// Base64-encodes `text_content` and `raw_html` in the response.
+    pub encode_base64: Option<bool>,
+    /// Flags pages that look like error pages or paywalls in `metadata.likely_soft_error`.
+    pub detect_soft_errors: Option<bool>,
+    /// Falls back to the static fetch when browser rendering fails (default true).
+    pub browser_fallback: Option<bool>,
+    /// Extra renderings (`text`, `markdown`, `html`) returned in `alternate_contents`.
+    pub output_formats: Option<Vec<ContentType>>,
+    /// Address family to connect over: `v4`, `v6` or `auto` (default).
+    pub ip_version: Option<String>,
+    /// Caps `text_content` at this many bytes, on a UTF-8 character boundary.
+    pub max_text_bytes: Option<usize>,
+    /// Retries after network errors, timeouts and 5xx/429 responses; `timeout_seconds` bounds them all.
+    pub max_retries: Option<u32>,
+    /// Retries once with the `www.` prefix toggled when connecting to the host fails.
+    pub www_fallback: Option<bool>,
+    /// Sets `content_below_minimum` when the extracted text is shorter than this many bytes.
+    pub min_content_length: Option<usize>,
+    /// Tags each block with the language of its nearest `lang` attribute.
+    pub include_block_lang: Option<bool>,
+    /// Lowercase `text_content` (Unicode-aware); `title` and `raw_html` are untouched.
+    pub lowercase_text: Option<bool>,
+    /// Returns the page's breadcrumb trail in `breadcrumbs`.
+    pub include_breadcrumbs: Option<bool>,
+    /// With a page cache, fetches up to this many same-host links into it in the background.
+    pub prefetch_links: Option<usize>,
+    /// Returns the main content as sanitized HTML in `content_html`.
+    pub output_html_fragment: Option<bool>,
+    /// Re-fetches script-rendered pages with the browser when the static text is nearly empty.
+    pub retry_empty_with_browser: Option<bool>,
+    /// Caps `links` and `images` at this many entries each.
+    pub max_elements: Option<usize>,
+    /// Falls back to the Wayback Machine's closest snapshot when the origin is down.
+    pub archive_fallback: Option<bool>,
+    /// Representation to prefer in the `Accept` header; HTML remains an accepted fallback.
+    pub output_format: Option<ContentType>,
+    /// Returns the feeds the page advertises in `feeds`.
+    pub include_feeds: Option<bool>,
+    /// Sorts `links` by relevance rather than document order.
+    pub rank_links: Option<bool>,
+    /// Returns the page's meta keywords and `article:tag` values in `metadata.keywords`.
+    pub include_keywords: Option<bool>,
+    /// Tries `https://` before plain `http://` for insecure URLs.
+    pub upgrade_insecure: Option<bool>,
+    /// Removes tracking query parameters from the returned URL.
+    pub strip_tracking_params: Option<bool>,
+    /// Reports in-page `#fragment` links without a matching target in `broken_anchors`.
+    pub validate_anchors: Option<bool>,
+    /// Sends the request as a `multipart/form-data` POST with these parts.
+    pub multipart: Option<Vec<MultipartPart>>,
+    /// Removes `style` attributes from `content_html` as well as event handlers.
+    pub strip_inline_styles: Option<bool>,
+    /// Mirrors tried in order after `url` fails; the first to succeed is returned.
+    pub fallback_urls: Option<Vec<String>>,
+    /// Follows each link's text in `text_content` with ` (url)`.
+    pub inline_link_urls: Option<bool>,
+    /// Who asked for the fetch, for the audit log. Set by the server, never
+    /// read from the request body.
+    #[serde(skip)]
+    pub client_id: Option<String>,
+}
+
+/// One part of a `multipart` request body: an inline `value`, or the file at
+/// `file_url` (a `file://` URL) uploaded under its file name.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MultipartPart {
+    pub name: String,
+    pub value: Option<String>,
+    pub file_url: Option<String>,
+    /// MIME type of the part, e.g. `text/csv`; none is sent when unset.
+    pub content_type: Option<String>,
+}
+
+/// Extraction options honoured by `ContentParser::parse_html_with_options`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ParseOptions {
+    pub include_blocks: bool,
+    pub max_title_length: Option<usize>,
+    pub include_contacts: bool,
+    pub include_links: bool,
+    pub include_images: bool,
+    pub prettify_html: bool,
+    pub include_forms: bool,
+    pub include_microdata: bool,
+    pub raw_text: bool,
+    /// Follow each link's text in the extracted text with its URL in parentheses.
+    pub inline_link_urls: bool,
+    pub include_block_lang: bool,
+    pub include_breadcrumbs: bool,
+    pub output_html_fragment: bool,
+    /// Drop `style` attributes from the HTML fragment too.
+    pub strip_inline_styles: bool,
+    pub include_feeds: bool,
+    pub include_keywords: bool,
+    pub validate_anchors: bool,
+    /// Keep at most this many links and images each.
+    pub max_elements: Option<usize>,
+    /// Order links by relevance, so a `max_elements` cap keeps the most relevant.
+    pub rank_links: bool,
+    /// Parse the HTML as a fragment rather than a whole document, for all
+    /// extraction. Fetched pages are always parsed as documents.
+    pub fragment: bool,
+}
+
+impl ParseOptions {
+    /// Whether any option requires a parser pass beyond what the fetcher already produced.
+    pub fn is_empty(&self) -> bool {
+        // A cap or link order alone has nothing to apply to
+        Self { max_elements: None, rank_links: false, ..self.clone() } == Self::default()
+    }
+}
+
+impl From<&FetchContentRequest> for ParseOptions {
+    fn from(request: &FetchContentRequest) -> Self {
+        Self {
+            include_blocks: request.include_blocks.unwrap_or(false),
+            max_title_length: request.max_title_length,
+            include_contacts: request.include_contacts.unwrap_or(false),
+            include_links: request.include_links.unwrap_or(false),
+            include_images: request.include_images.unwrap_or(false),
+            prettify_html: request.prettify_html.unwrap_or(false),
+            include_forms: request.include_forms.unwrap_or(false),
+            include_microdata: request.include_microdata.unwrap_or(false),
+            raw_text: request.raw_text.unwrap_or(false),
+            inline_link_urls: request.inline_link_urls.unwrap_or(false),
+            include_block_lang: request.include_block_lang.unwrap_or(false),
+            include_breadcrumbs: request.include_breadcrumbs.unwrap_or(false),
+            output_html_fragment: request.output_html_fragment.unwrap_or(false),
+            strip_inline_styles: request.strip_inline_styles.unwrap_or(false),
+            include_feeds: request.include_feeds.unwrap_or(false),
+            include_keywords: request.include_keywords.unwrap_or(false),
+            validate_anchors: request.validate_anchors.unwrap_or(false),
+            max_elements: request.max_elements,
+            rank_links: request.rank_links.unwrap_or(false),
+            fragment: false,
+        }
+    }
+}
+
+/// Body of `POST /api/batch`: the pages to fetch, each with the default options.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchFetchRequest {
+    pub urls: Vec<String>,
+}
+
+/// Where a crawl starts and how many pages it may fetch.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CrawlRequest {
+    pub start_url: String,
+    pub max_pages: Option<usize>,
+    /// Least time between the starts of two fetches from the same host.
+    pub crawl_delay_ms: Option<u64>,
+    /// Who started the crawl, for the audit log; set by the server.
+    #[serde(skip)]
+    pub client_id: Option<String>,
+}
+
+/// Query string of `GET /api/markdown`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MarkdownQuery {
+    pub url: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -19,6 +197,8 @@ pub struct ApiErrorResponse {
 pub struct HealthResponse {
     pub status: String,
     pub version: String,
+    /// Whether requests may ask for browser rendering.
+    pub browser_available: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -28,14 +208,68 @@ pub struct McpRequest {
     pub params: serde_json::Value,
 }
 
+impl FetchContentRequest {
+    /// Whether to reduce a response with this `Content-Type` to its text:
+    /// `extract_text_only` when set, otherwise only for HTML (or an
+    /// unrecognized type), so JSON, XML, plain text and Markdown come back as
+    /// they were sent.
+    pub fn extracts_text_for(&self, content_type: &str) -> bool {
+        self.extract_text_only
+            .unwrap_or_else(|| ContentType::from_mime(content_type).is_none_or(|content_type| content_type == ContentType::Html))
+    }
+}
+
 impl Default for FetchContentRequest {
     fn default() -> Self {
         Self {
             url: String::new(),
-            extract_text_only: Some(true),
+            extract_text_only: None,
             follow_redirects: Some(true),
-            timeout_seconds: Some(30),
+            timeout_seconds: None,
+            read_timeout_seconds: None,
             user_agent: Some("html-api-reader/0.1.0".to_string()),
+            http_version: None,
+            force_fetch_method: None,
+            include_blocks: None,
+            max_title_length: None,
+            include_contacts: None,
+            include_links: None,
+            include_images: None,
+            referer: None,
+            expected_content_types: None,
+            prettify_html: None,
+            include_forms: None,
+            include_microdata: None,
+            raw_text: None,
+            encode_base64: None,
+            detect_soft_errors: None,
+            browser_fallback: None,
+            output_formats: None,
+            ip_version: None,
+            max_text_bytes: None,
+            max_retries: None,
+            www_fallback: None,
+            min_content_length: None,
+            include_block_lang: None,
+            lowercase_text: None,
+            include_breadcrumbs: None,
+            prefetch_links: None,
+            output_html_fragment: None,
+            retry_empty_with_browser: None,
+            max_elements: None,
+            archive_fallback: None,
+            output_format: None,
+            include_feeds: None,
+            rank_links: None,
+            include_keywords: None,
+            upgrade_insecure: None,
+            strip_tracking_params: None,
+            validate_anchors: None,
+            multipart: None,
+            strip_inline_styles: None,
+            fallback_urls: None,
+            inline_link_urls: None,
+            client_id: None,
         }
     }
 }
@@ -50,10 +284,13 @@ mod tests {
         let request = FetchContentRequest::default();
         
         assert_eq!(request.url, "");
-        assert_eq!(request.extract_text_only, Some(true));
+        assert_eq!(request.extract_text_only, None); // Decided by the response content type
         assert_eq!(request.follow_redirects, Some(true));
-        assert_eq!(request.timeout_seconds, Some(30));
+        assert_eq!(request.timeout_seconds, None); // Filled in from the configured default
         assert_eq!(request.user_agent, Some("html-api-reader/0.1.0".to_string()));
+        assert_eq!(request.include_blocks, None);
+        assert!(request.extracts_text_for("text/html"));
+        assert!(!request.extracts_text_for("application/json"));
     }
 
     #[test]
@@ -64,6 +301,7 @@ mod tests {
             follow_redirects: Some(false),
             timeout_seconds: Some(60),
             user_agent: Some("custom-agent/1.0".to_string()),
+            ..Default::default()
         };
 
         assert_eq!(request.url, "https://example.com");
@@ -81,6 +319,7 @@ mod tests {
             follow_redirects: None,
             timeout_seconds: None,
             user_agent: None,
+            ..Default::default()
         };
 
         assert_eq!(request.url, "");
@@ -98,6 +337,7 @@ mod tests {
             follow_redirects: Some(true),
             timeout_seconds: Some(45),
             user_agent: Some("test-agent".to_string()),
+            ..Default::default()
         };
 
         let serialized = serde_json::to_string(&request).unwrap();
@@ -110,6 +350,20 @@ mod tests {
         assert_eq!(request.user_agent, deserialized.user_agent);
     }
 
+    #[test]
+    fn test_parse_options_from_request() {
+        let request = FetchContentRequest {
+            url: "https://example.com".to_string(),
+            include_blocks: Some(true),
+            ..Default::default()
+        };
+
+        let options = ParseOptions::from(&request);
+        assert!(options.include_blocks);
+        assert!(!options.is_empty());
+        assert!(ParseOptions::from(&FetchContentRequest::default()).is_empty());
+    }
+
     #[test]
     fn test_api_error_response() {
         let error = ApiErrorResponse {
@@ -126,6 +380,7 @@ mod tests {
         let health = HealthResponse {
             status: "healthy".to_string(),
             version: "0.1.0".to_string(),
+            browser_available: true,
         };
 
         assert_eq!(health.status, "healthy");
@@ -140,6 +395,7 @@ mod tests {
             follow_redirects: None,
             timeout_seconds: None,
             user_agent: None,
+            ..Default::default()
         };
 
         assert_eq!(request.url, "https://example.com");
@@ -159,6 +415,7 @@ mod tests {
         let health = HealthResponse {
             status: "healthy".to_string(),
             version: "0.1.0".to_string(),
+            browser_available: true,
         };
 
         let error_json = serde_json::to_string(&error).unwrap();
@@ -22,11 +22,49 @@ pub struct FetchContentResponse {
     pub message: Option<String>,
 }
 
+/// Outcome of one URL in a batch; exactly one of `content` and `error` is set.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchResult {
+    pub url: String,
+    pub content: Option<HtmlContent>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchResponse {
+    pub results: Vec<BatchResult>,
+}
+
+/// Progress reported while crawling, serialized as `{"event": "page", ...}`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "lowercase")]
+pub enum CrawlEvent {
+    Page { url: String, title: Option<String> },
+    /// A page that couldn't be fetched; the crawl carries on without it.
+    Error { url: String, message: String },
+    /// Always the last event; `total` counts the pages fetched successfully.
+    Complete { total: usize },
+}
+
+impl CrawlEvent {
+    /// The value of the `event` tag.
+    pub fn name(&self) -> &'static str {
+        match self {
+            CrawlEvent::Page { .. } => "page",
+            CrawlEvent::Error { .. } => "error",
+            CrawlEvent::Complete { .. } => "complete",
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ToolCapabilities {
     pub name: String,
     pub description: String,
     pub input_schema: serde_json::Value,
+    /// Sample `arguments` objects showing typical calls, each valid against `input_schema`.
+    #[serde(default)]
+    pub examples: Vec<serde_json::Value>,
 }
 
 #[cfg(test)]
@@ -45,6 +83,7 @@ mod tests {
             charset: Some("utf-8".to_string()),
             javascript_detected: None,
             fetch_method: None,
+            ..Default::default()
         };
 
         let content = HtmlContent {
@@ -53,6 +92,7 @@ mod tests {
             text_content: "Test content".to_string(),
             raw_html: "<html><body>Test</body></html>".to_string(),
             metadata,
+            ..Default::default()
         };
 
         let fetch_response = FetchContentResponse {
@@ -117,6 +157,7 @@ mod tests {
             charset: Some("utf-8".to_string()),
             javascript_detected: None,
             fetch_method: None,
+            ..Default::default()
         };
 
         let content = HtmlContent {
@@ -125,6 +166,7 @@ mod tests {
             text_content: "Test content".to_string(),
             raw_html: "<html><body>Test</body></html>".to_string(),
             metadata,
+            ..Default::default()
         };
 
         let response = FetchContentResponse {
@@ -148,6 +190,7 @@ mod tests {
             charset: None,
             javascript_detected: None,
             fetch_method: None,
+            ..Default::default()
         };
 
         let content = HtmlContent {
@@ -156,6 +199,7 @@ mod tests {
             text_content: "".to_string(),
             raw_html: "".to_string(),
             metadata,
+            ..Default::default()
         };
 
         let response = FetchContentResponse {
@@ -183,11 +227,13 @@ mod tests {
             name: "fetch_web_content".to_string(),
             description: "Fetch content from a web URL".to_string(),
             input_schema: schema,
+            examples: vec![serde_json::json!({"url": "https://example.com"})],
         };
 
         assert_eq!(capabilities.name, "fetch_web_content");
         assert_eq!(capabilities.description, "Fetch content from a web URL");
         assert_eq!(capabilities.input_schema["type"], "object");
+        assert_eq!(capabilities.examples[0]["url"], "https://example.com");
     }
 
     #[test]
@@ -200,6 +246,7 @@ mod tests {
             charset: Some("utf-8".to_string()),
             javascript_detected: None,
             fetch_method: None,
+            ..Default::default()
         };
 
         let content = HtmlContent {
@@ -208,6 +255,7 @@ mod tests {
             text_content: "Test content".to_string(),
             raw_html: "<html><body>Test</body></html>".to_string(),
             metadata,
+            ..Default::default()
         };
 
         let fetch_response = FetchContentResponse {
@@ -260,6 +308,7 @@ mod tests {
             charset: Some("utf-8".to_string()),
             javascript_detected: None,
             fetch_method: None,
+            ..Default::default()
         };
 
         let content = HtmlContent {
@@ -268,6 +317,7 @@ mod tests {
             text_content: "".to_string(),
             raw_html: "".to_string(),
             metadata,
+            ..Default::default()
         };
 
         let response = FetchContentResponse {
@@ -0,0 +1,38 @@
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use crate::model::content::HtmlContent;
+use crate::port::content_fetcher::{ContentFetcherError, ContentFetcherResult};
+
+/// One URL the server fetched, successfully or not.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    /// When the fetch finished, as RFC3339.
+    pub timestamp: String,
+    pub url: String,
+    /// The response status, absent when no response arrived (e.g. on a
+    /// connection error or timeout).
+    pub status: Option<u16>,
+    /// Size of the fetched document, for successful fetches.
+    pub bytes: Option<usize>,
+    /// Who asked for the fetch, when the server can tell (the REST API's client address).
+    pub client_id: Option<String>,
+}
+
+impl AuditEntry {
+    /// The entry for a fetch of `url` that finished at `timestamp` with `result`.
+    pub fn for_fetch(timestamp: String, url: String, client_id: Option<String>, result: &ContentFetcherResult<HtmlContent>) -> Self {
+        let (status, bytes) = match result {
+            Ok(content) => (Some(content.metadata.status_code), Some(content.metadata.content_length.unwrap_or(content.raw_html.len()))),
+            Err(ContentFetcherError::Http { status, .. }) => (Some(*status), None),
+            Err(_) => (None, None),
+        };
+        Self { timestamp, url, status, bytes, client_id }
+    }
+}
+
+/// Keeps an audit trail of every URL fetched. Recording never fails the
+/// fetch; a sink that can't store an entry reports that itself.
+#[async_trait]
+pub trait AuditSink: Send + Sync {
+    async fn record(&self, entry: AuditEntry);
+}
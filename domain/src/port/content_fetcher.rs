@@ -1,12 +1,33 @@
+use std::error::Error;
+use std::ops::Deref;
+use std::sync::Arc;
 use async_trait::async_trait;
 use crate::model::{content::HtmlContent, request::FetchContentRequest};
 
 pub type ContentFetcherResult<T> = Result<T, ContentFetcherError>;
 
+/// Underlying cause attached to an error. It is shared so `ContentFetcherError` stays
+/// `Clone`, and derefs to the original error so `Error::source` yields that error itself
+/// (downcastable) rather than a wrapper around it.
+#[derive(Debug, Clone)]
+pub struct ErrorSource(Arc<dyn Error + Send + Sync>);
+
+impl Deref for ErrorSource {
+    type Target = dyn Error + Send + Sync + 'static;
+
+    fn deref(&self) -> &Self::Target {
+        &*self.0
+    }
+}
+
 #[derive(Debug, Clone, thiserror::Error)]
 pub enum ContentFetcherError {
-    #[error("Network error: {0}")]
-    Network(String),
+    #[error("Network error: {message}")]
+    Network {
+        message: String,
+        #[source]
+        source: Option<ErrorSource>,
+    },
     #[error("Invalid URL: {0}")]
     InvalidUrl(String),
     #[error("Timeout: Request timed out after {0} seconds")]
@@ -17,9 +38,42 @@ pub enum ContentFetcherError {
     Parse(String),
 }
 
+impl ContentFetcherError {
+    pub fn network(message: impl Into<String>) -> Self {
+        ContentFetcherError::Network {
+            message: message.into(),
+            source: None,
+        }
+    }
+
+    /// A network error that keeps the originating error reachable through `Error::source`.
+    pub fn network_with_source(message: impl Into<String>, source: impl Error + Send + Sync + 'static) -> Self {
+        ContentFetcherError::Network {
+            message: message.into(),
+            source: Some(ErrorSource(Arc::new(source))),
+        }
+    }
+}
+
 #[async_trait]
 pub trait ContentFetcher: Send + Sync {
     async fn fetch_content(&self, request: FetchContentRequest) -> ContentFetcherResult<HtmlContent>;
+
+    /// Fetches the page without rendering JavaScript. Fetchers that never render
+    /// can rely on the default, which is the same as `fetch_content`.
+    async fn fetch_static(&self, request: FetchContentRequest) -> ContentFetcherResult<HtmlContent> {
+        self.fetch_content(request).await
+    }
+
+    /// Releases long-lived resources such as browser processes before exit.
+    /// Fetchers holding nothing beyond memory can rely on the default no-op.
+    async fn shutdown(&self) {}
+
+    /// URLs of pages this fetcher can currently serve from memory, most recently
+    /// used first. Fetchers without a cache can rely on the default, which is empty.
+    fn cached_urls(&self) -> Vec<String> {
+        Vec::new()
+    }
 }
 
 #[cfg(test)]
@@ -28,10 +82,23 @@ mod tests {
 
     #[test]
     fn test_content_fetcher_error_network() {
-        let error = ContentFetcherError::Network("Connection refused".to_string());
+        let error = ContentFetcherError::network("Connection refused".to_string());
         assert_eq!(error.to_string(), "Network error: Connection refused");
     }
 
+    #[test]
+    fn test_content_fetcher_error_network_source_chain() {
+        let io_error = std::io::Error::new(std::io::ErrorKind::ConnectionRefused, "refused by peer");
+        let error = ContentFetcherError::network_with_source("Connection failed: refused by peer", io_error);
+
+        assert_eq!(error.to_string(), "Network error: Connection failed: refused by peer");
+        let source = error.source().expect("source should be attached");
+        assert_eq!(source.to_string(), "refused by peer");
+        assert!(source.downcast_ref::<std::io::Error>().is_some());
+
+        assert!(ContentFetcherError::network("no cause").source().is_none());
+    }
+
     #[test]
     fn test_content_fetcher_error_invalid_url() {
         let error = ContentFetcherError::InvalidUrl("not-a-url".to_string());
@@ -61,7 +128,7 @@ mod tests {
 
     #[test]
     fn test_content_fetcher_error_debug() {
-        let error = ContentFetcherError::Network("test".to_string());
+        let error = ContentFetcherError::network("test".to_string());
         let debug_str = format!("{:?}", error);
         assert!(debug_str.contains("Network"));
         assert!(debug_str.contains("test"));
@@ -79,6 +146,7 @@ mod tests {
             charset: Some("utf-8".to_string()),
             javascript_detected: None,
             fetch_method: None,
+            ..Default::default()
         };
 
         let content = HtmlContent {
@@ -87,6 +155,7 @@ mod tests {
             text_content: "Test content".to_string(),
             raw_html: "<html><body>Test</body></html>".to_string(),
             metadata,
+            ..Default::default()
         };
 
         let result: ContentFetcherResult<HtmlContent> = Ok(content);
@@ -95,7 +164,7 @@ mod tests {
 
     #[test]
     fn test_content_fetcher_result_err() {
-        let error = ContentFetcherError::Network("Connection failed".to_string());
+        let error = ContentFetcherError::network("Connection failed".to_string());
         let result: ContentFetcherResult<HtmlContent> = Err(error);
         assert!(result.is_err());
         
@@ -121,7 +190,7 @@ mod tests {
 
     #[test]
     fn test_content_fetcher_error_empty_strings() {
-        let network_error = ContentFetcherError::Network("".to_string());
+        let network_error = ContentFetcherError::network("".to_string());
         assert_eq!(network_error.to_string(), "Network error: ");
 
         let url_error = ContentFetcherError::InvalidUrl("".to_string());
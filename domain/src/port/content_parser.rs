@@ -1,5 +1,6 @@
+use std::collections::HashMap;
 use async_trait::async_trait;
-use crate::model::content::HtmlContent;
+use crate::model::{content::{Article, HtmlContent}, request::ParseOptions};
 
 pub type ContentParserResult<T> = Result<T, ContentParserError>;
 
@@ -17,6 +18,42 @@ pub enum ContentParserError {
 pub trait ContentParser: Send + Sync {
     async fn parse_html(&self, raw_html: &str, url: &str) -> ContentParserResult<HtmlContent>;
     async fn extract_text(&self, html_content: &HtmlContent) -> ContentParserResult<String>;
+
+    /// Parses the document honouring the requested extraction options. Parsers that
+    /// don't support any option fall back to a plain `parse_html`.
+    async fn parse_html_with_options(
+        &self,
+        raw_html: &str,
+        url: &str,
+        _options: &ParseOptions,
+    ) -> ContentParserResult<HtmlContent> {
+        self.parse_html(raw_html, url).await
+    }
+
+    /// Parses a document fetched with `status` and response `headers`, which
+    /// replace the parser's defaults (a 200 `text/html` response) in the metadata.
+    async fn parse_html_with_status(
+        &self,
+        raw_html: &str,
+        url: &str,
+        status: u16,
+        headers: &HashMap<String, String>,
+        options: &ParseOptions,
+    ) -> ContentParserResult<HtmlContent> {
+        let mut content = self.parse_html_with_options(raw_html, url, options).await?;
+        content.metadata.apply_response(status, headers);
+        Ok(content)
+    }
+
+    /// Renders the document as Markdown, resolving relative links against `url`.
+    async fn to_markdown(&self, _raw_html: &str, _url: &str) -> ContentParserResult<String> {
+        Err(ContentParserError::Parse("Markdown conversion is not supported by this parser".to_string()))
+    }
+
+    /// Isolates the page's main article: title, byline, body, publish date and lead image.
+    async fn extract_article(&self, _raw_html: &str, _url: &str) -> ContentParserResult<Article> {
+        Err(ContentParserError::Parse("Article extraction is not supported by this parser".to_string()))
+    }
 }
 
 #[cfg(test)]
@@ -61,6 +98,7 @@ mod tests {
             charset: Some("utf-8".to_string()),
             javascript_detected: None,
             fetch_method: None,
+            ..Default::default()
         };
 
         let content = HtmlContent {
@@ -69,6 +107,7 @@ mod tests {
             text_content: "Test content".to_string(),
             raw_html: "<html><body>Test</body></html>".to_string(),
             metadata,
+            ..Default::default()
         };
 
         let result: ContentParserResult<HtmlContent> = Ok(content);
@@ -0,0 +1,11 @@
+use async_trait::async_trait;
+use crate::model::content::HtmlContent;
+use super::content_parser::ContentParserResult;
+
+/// Post-processing step run on fetched content before it is returned, e.g. to
+/// strip personal data or normalize text. Transformers run in the order they
+/// were registered, each receiving the previous one's output.
+#[async_trait]
+pub trait ContentTransformer: Send + Sync {
+    async fn transform(&self, content: HtmlContent) -> ContentParserResult<HtmlContent>;
+}
@@ -0,0 +1,9 @@
+use async_trait::async_trait;
+use url::Url;
+
+/// Consulted before every fetch, e.g. to check an allowlist or a domain
+/// reputation service. A denial's reason is reported as an invalid URL.
+#[async_trait]
+pub trait FetchGuard: Send + Sync {
+    async fn allow(&self, url: &Url) -> Result<(), String>;
+}
@@ -1,2 +1,5 @@
+pub mod audit_sink;
 pub mod content_fetcher;
-pub mod content_parser;
\ No newline at end of file
+pub mod content_parser;
+pub mod content_transformer;
+pub mod fetch_guard;
@@ -0,0 +1,301 @@
+use std::collections::HashMap;
+use ego_tree::NodeId;
+use scraper::{ElementRef, Html, Selector};
+use url::Url;
+use domain::model::content::Article;
+
+/// Elements that never hold article content, however much text they contain.
+const UNLIKELY_TAGS: &[&str] = &[
+    "nav", "aside", "footer", "header", "form", "script", "style", "noscript", "template", "button",
+];
+
+/// `class`/`id` fragments marking page chrome rather than the article.
+const NEGATIVE_HINTS: &[&str] = &[
+    "nav", "menu", "sidebar", "footer", "header", "comment", "share", "social",
+    "related", "promo", "advert", "banner", "breadcrumb", "subscribe", "widget",
+];
+
+/// `class`/`id` fragments marking the main content.
+const POSITIVE_HINTS: &[&str] = &["article", "content", "post", "entry", "story", "main", "body", "text"];
+
+/// `class`/`id` fragments of the byline, which is reported separately from the body.
+const BYLINE_HINTS: &[&str] = &["byline", "author"];
+
+/// Elements copied into the article body.
+const CONTENT_TAGS: &[&str] = &[
+    "p", "h2", "h3", "h4", "h5", "h6", "ul", "ol", "pre", "blockquote", "figure", "table",
+];
+
+/// Paragraphs shorter than this don't count towards a container's score.
+const MIN_PARAGRAPH_CHARS: usize = 25;
+
+/// Readability-style article extraction: paragraphs score their parent and
+/// grandparent by length and comma count, scores are adjusted by `class`/`id`
+/// hints and discounted by link density, and the best container becomes the body.
+pub struct ArticleExtractor<'a> {
+    base: Option<&'a Url>,
+}
+
+impl<'a> ArticleExtractor<'a> {
+    pub fn new(base: Option<&'a Url>) -> Self {
+        Self { base }
+    }
+
+    /// Extracts everything but `published`, which callers fill from the raw HTML.
+    pub fn extract(&self, document: &Html) -> Article {
+        let container = self.best_container(document);
+        let blocks = container.map(|container| self.content_blocks(container)).unwrap_or_default();
+
+        Article {
+            title: self.title(document),
+            byline: self.byline(document),
+            body_text: blocks
+                .iter()
+                .map(|block| normalize_text(block))
+                .filter(|text| !text.is_empty())
+                .collect::<Vec<_>>()
+                .join("\n\n"),
+            body_html: blocks.iter().map(|block| block.html()).collect::<Vec<_>>().join("\n"),
+            published: None,
+            lead_image: self.lead_image(document, container),
+        }
+    }
+
+    /// The element holding the page's main content, with navigation, sidebars
+    /// and footers left out, or `None` when no paragraph scores.
+    pub fn main_content<'d>(&self, document: &'d Html) -> Option<ElementRef<'d>> {
+        self.best_container(document)
+    }
+
+    fn best_container<'d>(&self, document: &'d Html) -> Option<ElementRef<'d>> {
+        let paragraphs = Selector::parse("p, pre, td, blockquote").ok()?;
+        let mut scores: HashMap<NodeId, f64> = HashMap::new();
+
+        for paragraph in document.select(&paragraphs).filter(|p| !is_unlikely(*p)) {
+            let text = normalize_text(&paragraph);
+            let length = text.chars().count();
+            if length < MIN_PARAGRAPH_CHARS {
+                continue;
+            }
+
+            let score = 1.0 + text.matches(',').count() as f64 + (length as f64 / 100.0).min(3.0);
+            let ancestors = paragraph.ancestors().filter_map(ElementRef::wrap).take(2);
+            for (level, ancestor) in ancestors.enumerate() {
+                let share = if level == 0 { score } else { score / 2.0 };
+                *scores.entry(ancestor.id()).or_insert_with(|| initial_score(ancestor)) += share;
+            }
+        }
+
+        scores
+            .into_iter()
+            .filter_map(|(id, score)| {
+                let element = ElementRef::wrap(document.tree.get(id)?)?;
+                Some((element, score * (1.0 - link_density(element))))
+            })
+            .max_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(element, _)| element)
+    }
+
+    /// The container's content elements in document order, skipping page chrome
+    /// and anything nested inside an element already taken.
+    fn content_blocks<'d>(&self, container: ElementRef<'d>) -> Vec<ElementRef<'d>> {
+        let mut blocks: Vec<ElementRef<'d>> = Vec::new();
+
+        for element in container.descendants().filter_map(ElementRef::wrap) {
+            if !CONTENT_TAGS.contains(&element.value().name()) {
+                continue;
+            }
+            let inside_taken = element.ancestors().any(|ancestor| blocks.iter().any(|block| block.id() == ancestor.id()));
+            let inside_chrome = element
+                .ancestors()
+                .take_while(|ancestor| ancestor.id() != container.id())
+                .filter_map(ElementRef::wrap)
+                .any(is_unlikely_element);
+            let hints = attribute_hints(element);
+            let is_byline = BYLINE_HINTS.iter().any(|hint| hints.contains(hint));
+            let is_empty = element.value().name() != "figure" && normalize_text(&element).is_empty();
+            if inside_taken || inside_chrome || is_byline || is_empty {
+                continue;
+            }
+            blocks.push(element);
+        }
+
+        blocks
+    }
+
+    fn title(&self, document: &Html) -> String {
+        meta_content(document, "meta[property='og:title']")
+            .or_else(|| first_text(document, "article h1"))
+            .or_else(|| first_text(document, "h1"))
+            .or_else(|| first_text(document, "title"))
+            .unwrap_or_default()
+    }
+
+    fn byline(&self, document: &Html) -> Option<String> {
+        let byline = meta_content(document, "meta[name='author']")
+            .or_else(|| first_text(document, "[rel='author']"))
+            .or_else(|| first_text(document, "[itemprop='author']"))
+            .or_else(|| first_text(document, ".byline, .author"))?;
+
+        let byline = byline.strip_prefix("By ").or_else(|| byline.strip_prefix("by ")).unwrap_or(&byline);
+        Some(byline.trim().to_string()).filter(|byline| !byline.is_empty())
+    }
+
+    fn lead_image(&self, document: &Html, container: Option<ElementRef>) -> Option<String> {
+        let image_selector = Selector::parse("img[src]").ok()?;
+        meta_content(document, "meta[property='og:image']")
+            .or_else(|| {
+                container?
+                    .select(&image_selector)
+                    .next()
+                    .and_then(|image| image.value().attr("src").map(str::to_string))
+            })
+            .and_then(|src| Url::options().base_url(self.base).parse(src.trim()).ok())
+            .map(|url| url.to_string())
+    }
+}
+
+fn initial_score(element: ElementRef) -> f64 {
+    let tag_score = match element.value().name() {
+        "article" => 10.0,
+        "div" | "main" => 5.0,
+        "section" | "pre" | "td" | "blockquote" => 3.0,
+        "ol" | "ul" | "dl" | "dd" | "dt" | "li" => -3.0,
+        _ => 0.0,
+    };
+    tag_score + hint_score(element)
+}
+
+/// +25 per positive and -25 per negative hint found in the element's `class` and `id`.
+fn hint_score(element: ElementRef) -> f64 {
+    let hints = attribute_hints(element);
+    let positive = POSITIVE_HINTS.iter().any(|hint| hints.contains(hint));
+    let negative = NEGATIVE_HINTS.iter().any(|hint| hints.contains(hint));
+    (positive as i32 - negative as i32) as f64 * 25.0
+}
+
+fn attribute_hints(element: ElementRef) -> String {
+    let value = element.value();
+    format!("{} {}", value.attr("class").unwrap_or(""), value.attr("id").unwrap_or("")).to_lowercase()
+}
+
+fn is_unlikely_element(element: ElementRef) -> bool {
+    if UNLIKELY_TAGS.contains(&element.value().name()) {
+        return true;
+    }
+    let hints = attribute_hints(element);
+    NEGATIVE_HINTS.iter().any(|hint| hints.contains(hint)) && !POSITIVE_HINTS.iter().any(|hint| hints.contains(hint))
+}
+
+/// Whether the element or any ancestor is page chrome.
+fn is_unlikely(element: ElementRef) -> bool {
+    is_unlikely_element(element) || element.ancestors().filter_map(ElementRef::wrap).any(is_unlikely_element)
+}
+
+/// Share of the element's text that sits inside links.
+fn link_density(element: ElementRef) -> f64 {
+    let total = normalize_text(&element).chars().count();
+    if total == 0 {
+        return 1.0;
+    }
+
+    let Ok(links) = Selector::parse("a") else {
+        return 0.0;
+    };
+    let linked: usize = element.select(&links).map(|link| normalize_text(&link).chars().count()).sum();
+    linked as f64 / total as f64
+}
+
+fn normalize_text(element: &ElementRef) -> String {
+    element.text().collect::<String>().split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn first_text(document: &Html, selector: &str) -> Option<String> {
+    let selector = Selector::parse(selector).ok()?;
+    document
+        .select(&selector)
+        .map(|element| normalize_text(&element))
+        .find(|text| !text.is_empty())
+}
+
+fn meta_content(document: &Html, selector: &str) -> Option<String> {
+    let selector = Selector::parse(selector).ok()?;
+    document
+        .select(&selector)
+        .filter_map(|meta| meta.value().attr("content"))
+        .map(|content| content.trim().to_string())
+        .find(|content| !content.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BLOG_POST: &str = r#"<html><head><title>Tuning Postgres | Dev Blog</title></head><body>
+        <header class="site-header"><nav><a href="/">Home</a> <a href="/archive">Archive</a> <a href="/about">About</a></nav></header>
+        <div class="layout">
+          <aside class="sidebar">
+            <h3>Popular posts</h3>
+            <p>Ten things every developer should know about caching, indexing, and queues.</p>
+            <ul><li><a href="/a">Why we moved to Rust</a></li><li><a href="/b">Kubernetes at scale</a></li></ul>
+          </aside>
+          <article class="post">
+            <h1>Tuning Postgres for Write-Heavy Workloads</h1>
+            <p class="byline">By Jane Doe</p>
+            <img src="/img/hero.png" alt="Graph">
+            <p>Write-heavy workloads stress Postgres in ways that read-heavy ones never do, from WAL volume to checkpoint spikes.</p>
+            <h2>Checkpoints</h2>
+            <p>Raising max_wal_size spreads checkpoints out, which smooths I/O, reduces full-page writes, and shortens stalls.</p>
+            <div class="share-buttons"><p>Share this post on your favourite social network, today!</p></div>
+            <p>Finally, measure everything: pg_stat_statements, iostat, and the WAL generation rate tell the real story.</p>
+          </article>
+        </div>
+        <footer><p>Copyright 2024 Dev Blog, all rights reserved, including the right to be boring.</p></footer>
+    </body></html>"#;
+
+    fn extract(html: &str) -> Article {
+        let base = Url::parse("https://blog.example.com/posts/postgres").unwrap();
+        ArticleExtractor::new(Some(&base)).extract(&Html::parse_document(html))
+    }
+
+    #[test]
+    fn test_extract_blog_post() {
+        let article = extract(BLOG_POST);
+
+        assert_eq!(article.title, "Tuning Postgres for Write-Heavy Workloads");
+        assert_eq!(article.byline.as_deref(), Some("Jane Doe"));
+        assert_eq!(article.lead_image.as_deref(), Some("https://blog.example.com/img/hero.png"));
+        assert_eq!(
+            article.body_text,
+            "Write-heavy workloads stress Postgres in ways that read-heavy ones never do, from WAL volume to checkpoint spikes.\n\n\
+             Checkpoints\n\n\
+             Raising max_wal_size spreads checkpoints out, which smooths I/O, reduces full-page writes, and shortens stalls.\n\n\
+             Finally, measure everything: pg_stat_statements, iostat, and the WAL generation rate tell the real story."
+        );
+        assert!(article.body_html.contains("<h2>Checkpoints</h2>"));
+
+        for chrome in ["Archive", "Popular posts", "caching, indexing", "Share this post", "Copyright", "Jane Doe"] {
+            assert!(!article.body_text.contains(chrome), "body should not contain {:?}", chrome);
+            assert!(!article.body_html.contains(chrome), "body html should not contain {:?}", chrome);
+        }
+    }
+
+    #[test]
+    fn test_extract_prefers_og_metadata() {
+        let html = r#"<html><head>
+            <meta property="og:title" content="Shared Title">
+            <meta property="og:image" content="https://cdn.example.com/card.jpg">
+            <meta name="author" content="Sam Lee">
+        </head><body><div id="content">
+            <h1>On-page Title</h1>
+            <p>A paragraph long enough to count as content, with a comma, and some more words.</p>
+        </div></body></html>"#;
+
+        let article = extract(html);
+
+        assert_eq!(article.title, "Shared Title");
+        assert_eq!(article.byline.as_deref(), Some("Sam Lee"));
+        assert_eq!(article.lead_image.as_deref(), Some("https://cdn.example.com/card.jpg"));
+        assert_eq!(article.body_text, "A paragraph long enough to count as content, with a comma, and some more words.");
+    }
+}
@@ -1,18 +1,94 @@
+use std::sync::LazyLock;
 use async_trait::async_trait;
-use scraper::{Html, Selector};
-use tracing::{info, debug};
-use domain::model::content::{HtmlContent, ContentMetadata};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, SecondsFormat};
+use ego_tree::{iter::Edge, NodeRef};
+use percent_encoding::percent_decode_str;
+use regex::Regex;
+use scraper::{ElementRef, Html, Node, Selector};
+use tracing::{info, debug, warn};
+use url::Url;
+use domain::model::content::{
+    Article, Contacts, ContentMetadata, FeedLink, FeedType, FormDef, FormField, HtmlContent, Image, Link,
+    MicrodataItem, MicrodataProperty, MicrodataValue, TextBlock,
+};
+use domain::model::request::ParseOptions;
 use domain::port::content_parser::{ContentParser, ContentParserResult};
+use super::article_extractor::ArticleExtractor;
+use super::html_sanitizer::HtmlSanitizer;
+use super::markdown_converter::MarkdownConverter;
 
-pub struct HtmlParserAdapter;
+/// Elements without content or a closing tag.
+const VOID_TAGS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input",
+    "link", "meta", "source", "track", "wbr",
+];
+
+/// Elements whose end tag HTML lets authors leave out, so a document that
+/// never closes them isn't necessarily cut off.
+const OPTIONAL_END_TAGS: &[&str] = &[
+    "html", "head", "body", "p", "li", "dt", "dd", "rb", "rt", "rtc", "rp",
+    "optgroup", "option", "colgroup", "caption", "thead", "tbody", "tfoot", "tr", "td", "th",
+];
+
+/// Elements whose content is text up to their end tag, even if it looks like markup.
+const RAW_TEXT_TAGS: &[&str] = &["script", "style", "textarea", "title"];
+
+/// Elements whose content is emitted verbatim by `prettify_html`, since
+/// re-indenting it would change what it means.
+const VERBATIM_TAGS: &[&str] = &["pre", "textarea", "script", "style"];
+
+/// Elements treated as text blocks when `include_blocks` is requested.
+const BLOCK_TAGS: &[&str] = &[
+    "h1", "h2", "h3", "h4", "h5", "h6",
+    "p", "li", "blockquote", "pre",
+    "dt", "dd", "figcaption", "td", "th",
+];
+
+/// Email addresses in page text and `mailto:` links.
+static EMAIL_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"[A-Za-z0-9._%+-]+@[A-Za-z0-9.-]+\.[A-Za-z]{2,}").unwrap());
+
+/// Phone number candidates in page text, narrowed by `is_plausible_phone`.
+static PHONE_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\+?\(?\d{1,4}\)?(?:[\s.-]?\(?\d{1,4}\)?){2,5}").unwrap());
+
+/// Dates the phone regex also matches.
+static DATE_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^\d{4}[-/.]\d{1,2}[-/.]\d{1,2}$").unwrap());
+
+/// How far into a page `</head>` is looked for when only the head is needed
+/// for the title or meta tags; pages whose head runs longer are parsed whole.
+pub const HEAD_SCAN_WINDOW_BYTES: usize = 256 * 1024;
+
+/// Parses `raw_html` as a complete document, or with `fragment` as a
+/// fragment, which isn't wrapped in implied `<html>`, `<head>` and `<body>`
+/// elements.
+fn parse_markup(raw_html: &str, fragment: bool) -> Html {
+    if fragment {
+        Html::parse_fragment(raw_html)
+    } else {
+        Html::parse_document(raw_html)
+    }
+}
+
+pub struct HtmlParserAdapter {
+    /// Every parse the `ContentParser` methods make goes through this.
+    parse_markup: fn(&str, bool) -> Html,
+}
 
 impl HtmlParserAdapter {
     pub fn new() -> Self {
-        Self
+        Self { parse_markup }
+    }
+
+    fn title_of(&self, document: &Html, max_length: Option<usize>) -> Option<String> {
+        Self::first_title(document).map(|title| match max_length {
+            Some(max_length) => self.truncate_title(&title, max_length),
+            None => title,
+        })
     }
 
-    fn extract_title_from_raw_html(&self, raw_html: &str) -> Option<String> {
-        let document = Html::parse_document(raw_html);
+    fn first_title(document: &Html) -> Option<String> {
         let title_selector = Selector::parse("title").ok()?;
         document
             .select(&title_selector)
@@ -21,6 +97,69 @@ impl HtmlParserAdapter {
             .filter(|title| !title.is_empty())
     }
 
+    /// The page's `<title>` and publish date, for fetchers. They're read from
+    /// `document` when the fetcher has already parsed the page. Otherwise just
+    /// the `<head>` is parsed when it can be found and holds both, and the
+    /// whole page is parsed once when it doesn't.
+    pub fn title_and_published_at(raw_html: &str, document: Option<&Html>) -> (Option<String>, Option<String>) {
+        let head = match document {
+            Some(_) => None,
+            None => Self::head_section(raw_html).map(|head| parse_markup(head, false)),
+        };
+        let title = head.as_ref().and_then(Self::first_title);
+        let published_at = head.as_ref().and_then(Self::published_time_meta);
+        if title.is_some() && published_at.is_some() {
+            return (title, published_at);
+        }
+
+        let parsed;
+        let document = match document {
+            Some(document) => document,
+            None => {
+                parsed = parse_markup(raw_html, false);
+                &parsed
+            }
+        };
+        (
+            title.or_else(|| Self::first_title(document)),
+            published_at.or_else(|| Self::published_at_in(document)),
+        )
+    }
+
+    /// The start of `raw_html` up to and including its `</head>` tag, when the
+    /// head closes within the first `HEAD_SCAN_WINDOW_BYTES`.
+    fn head_section(raw_html: &str) -> Option<&str> {
+        let mut window_end = raw_html.len().min(HEAD_SCAN_WINDOW_BYTES);
+        while !raw_html.is_char_boundary(window_end) {
+            window_end -= 1;
+        }
+        // ASCII lowercasing keeps byte offsets, so they index `raw_html` too
+        let window = raw_html[..window_end].to_ascii_lowercase();
+        let close = window.find("</head")?;
+        let end = close + window[close..].find('>')? + 1;
+        Some(&raw_html[..end])
+    }
+
+    /// Shortens `title` to at most `max_length` characters (ellipsis included),
+    /// cutting back to the last word boundary so words aren't split.
+    fn truncate_title(&self, title: &str, max_length: usize) -> String {
+        if title.chars().count() <= max_length {
+            return title.to_string();
+        }
+
+        let budget = max_length.saturating_sub(1);
+        let cut = title.char_indices().nth(budget).map(|(i, _)| i).unwrap_or(title.len());
+        let (head, rest) = title.split_at(cut);
+
+        let head = if rest.starts_with(char::is_whitespace) {
+            head
+        } else {
+            head.rfind(char::is_whitespace).map(|i| &head[..i]).unwrap_or(head)
+        };
+
+        format!("{}…", head.trim_end())
+    }
+
 
     fn clean_text_content(&self, text: String) -> String {
         text.lines()
@@ -31,44 +170,167 @@ impl HtmlParserAdapter {
     }
 }
 
+impl Default for HtmlParserAdapter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[async_trait]
 impl ContentParser for HtmlParserAdapter {
     async fn parse_html(&self, raw_html: &str, url: &str) -> ContentParserResult<HtmlContent> {
+        self.parse_html_with_options(raw_html, url, &ParseOptions::default()).await
+    }
+
+    async fn extract_text(&self, html_content: &HtmlContent) -> ContentParserResult<String> {
+        self.extract_text_from_html(&html_content.raw_html)
+    }
+
+    async fn parse_html_with_options(
+        &self,
+        raw_html: &str,
+        url: &str,
+        options: &ParseOptions,
+    ) -> ContentParserResult<HtmlContent> {
+        Ok(self.parse_content(raw_html, url, options))
+    }
+
+    async fn to_markdown(&self, raw_html: &str, url: &str) -> ContentParserResult<String> {
+        let document = (self.parse_markup)(raw_html, false);
+        let base = self.resolve_base_url(&document, url);
+        Ok(MarkdownConverter::new(base.as_ref()).convert(&document))
+    }
+
+    async fn extract_article(&self, raw_html: &str, url: &str) -> ContentParserResult<Article> {
+        let document = (self.parse_markup)(raw_html, false);
+        let base = self.resolve_base_url(&document, url);
+        let mut article = ArticleExtractor::new(base.as_ref()).extract(&document);
+        article.published = Self::published_at_in(&document);
+        Ok(article)
+    }
+}
+
+impl HtmlParserAdapter {
+    /// Parses `raw_html` once and runs every extraction `options` asks for
+    /// over that one document.
+    fn parse_content(&self, raw_html: &str, url: &str, options: &ParseOptions) -> HtmlContent {
         debug!("Parsing HTML content for URL: {}", url);
 
-        let title = self.extract_title_from_raw_html(raw_html);
-        let text_content = self.extract_text_from_html(raw_html)?;
+        let document = (self.parse_markup)(raw_html, options.fragment);
+        let title = self.title_of(&document, options.max_title_length);
+        let text_content = match (options.inline_link_urls, options.raw_text) {
+            (true, raw_text) => self.text_with_link_urls(&document, url, raw_text),
+            (false, true) => self.raw_text_of(&document),
+            (false, false) => self.text_of(&document),
+        };
 
-        let metadata = ContentMetadata {
+        let truncated = Self::is_truncated(raw_html);
+        if truncated {
+            warn!("HTML for {} looks truncated, extracting what is there", url);
+        }
+
+        let mut metadata = ContentMetadata {
             content_type: "text/html".to_string(),
-            status_code: 200, // This should come from the HTTP response
+            // Replaced by the real response's through parse_html_with_status
+            status_code: 200,
             content_length: Some(raw_html.len()),
             last_modified: None,
             charset: Some("utf-8".to_string()),
+            charset_confidence: None,
             javascript_detected: None,
             fetch_method: None,
+            http_version: None,
+            published_at: Self::published_at_in(&document),
+            content_encoding: None,
+            likely_soft_error: None,
+            fallback_reason: None,
+            text_truncated: None,
+            html_truncated: Some(truncated),
+            effective_url: None,
+            content_below_minimum: None,
+            from_cache: None,
+            cache_age_seconds: None,
+            content_hash: None,
+            cache_directives: None,
+            retried_with_browser: None,
+            elements_truncated: None,
+            from_archive: None,
+            archive_timestamp: None,
+            keywords: options.include_keywords.then(|| Self::keywords_in(&document)),
+            scheme: None,
+            timing: None,
+            requested_url: None,
+            url_used: None,
+            fallback_errors: None,
         };
 
         info!("Successfully parsed HTML content with {} characters", text_content.len());
 
-        Ok(HtmlContent {
+        let blocks = options.include_blocks.then(|| self.extract_blocks(&document, options.include_block_lang));
+        let contacts = options.include_contacts.then(|| self.extract_contacts(&text_content, &document));
+
+        let (links, images) = if options.include_links || options.include_images {
+            let base = self.resolve_base_url(&document, url);
+            (
+                options.include_links.then(|| match options.rank_links {
+                    true => self.extract_ranked_links(&document, base.as_ref()),
+                    false => self.extract_links(&document, base.as_ref()),
+                }),
+                options.include_images.then(|| self.extract_images(&document, base.as_ref())),
+            )
+        } else {
+            (None, None)
+        };
+        let (links, images) = match options.max_elements {
+            Some(max_elements) if links.is_some() || images.is_some() => {
+                let (links, links_cut) = cap_elements(links, max_elements);
+                let (images, images_cut) = cap_elements(images, max_elements);
+                metadata.elements_truncated = Some(links_cut || images_cut);
+                (links, images)
+            }
+            _ => (links, images),
+        };
+
+        let forms = options.include_forms.then(|| self.extract_forms(&document));
+        let microdata = options.include_microdata.then(|| self.extract_microdata(&document));
+        let breadcrumbs = options.include_breadcrumbs.then(|| self.extract_breadcrumbs(&document, url)).flatten();
+        let content_html = options
+            .output_html_fragment
+            .then(|| self.extract_content_html(&document, url, options.strip_inline_styles));
+        let feeds = options.include_feeds.then(|| self.extract_feeds(&document, url));
+        let broken_anchors = options.validate_anchors.then(|| self.extract_broken_anchors(&document));
+
+        let raw_html = if options.prettify_html {
+            self.prettify_html(&document)
+        } else {
+            raw_html.to_string()
+        };
+
+        HtmlContent {
             url: url.to_string(),
             title,
             text_content,
-            raw_html: raw_html.to_string(),
+            raw_html,
             metadata,
-        })
+            blocks,
+            contacts,
+            links,
+            images,
+            forms,
+            microdata,
+            breadcrumbs,
+            content_html,
+            feeds,
+            broken_anchors,
+            alternate_contents: None,
+        }
     }
 
-    async fn extract_text(&self, html_content: &HtmlContent) -> ContentParserResult<String> {
-        self.extract_text_from_html(&html_content.raw_html)
+    fn extract_text_from_html(&self, raw_html: &str) -> ContentParserResult<String> {
+        Ok(self.text_of(&(self.parse_markup)(raw_html, false)))
     }
-}
 
-impl HtmlParserAdapter {
-    fn extract_text_from_html(&self, raw_html: &str) -> ContentParserResult<String> {
-        let document = Html::parse_document(raw_html);
-        
+    fn text_of(&self, document: &Html) -> String {
         // Use a simple approach: select all text content and filter out script/style
         let body_selector = Selector::parse("body").unwrap();
         
@@ -80,8 +342,792 @@ impl HtmlParserAdapter {
             document.root_element().text().collect::<Vec<_>>().join(" ")
         };
 
-        let cleaned_text = self.clean_text_content(text_content);
-        Ok(cleaned_text)
+        self.clean_text_content(text_content)
+    }
+
+    /// The body's text nodes concatenated as they appear in the source, without
+    /// `clean_text_content`; only character references are decoded.
+    fn raw_text_of(&self, document: &Html) -> String {
+        let body_selector = Selector::parse("body").unwrap();
+
+        match document.select(&body_selector).next() {
+            Some(body) => body.text().collect(),
+            None => document.root_element().text().collect(),
+        }
+    }
+
+    /// The body's text like `text_of` (or `raw_text_of`, with `raw_text`), with
+    /// each http(s) link's text followed by ` (url)`, resolved against the page.
+    fn text_with_link_urls(&self, document: &Html, page_url: &str, raw_text: bool) -> String {
+        let body_selector = Selector::parse("body").unwrap();
+        let base = self.resolve_base_url(document, page_url);
+        let root = document.select(&body_selector).next().unwrap_or_else(|| document.root_element());
+
+        let mut pieces = Vec::new();
+        for edge in root.traverse() {
+            match edge {
+                Edge::Open(node) => {
+                    if let Node::Text(text) = node.value() {
+                        pieces.push(text.to_string());
+                    }
+                }
+                Edge::Close(node) => {
+                    let Some(link) = ElementRef::wrap(node).filter(|element| element.value().name() == "a") else {
+                        continue;
+                    };
+                    let Some(href) = link.value().attr("href").and_then(|href| self.resolve_url(base.as_ref(), href)) else {
+                        continue;
+                    };
+                    // Right after the link's last word, even when its text ends in whitespace
+                    let link_text = link.text().any(|text| !text.trim().is_empty());
+                    match pieces.iter_mut().rev().find(|piece| !piece.trim().is_empty()).filter(|_| link_text) {
+                        Some(text) => {
+                            text.truncate(text.trim_end().len());
+                            text.push_str(&format!(" ({})", href));
+                        }
+                        None => pieces.push(format!("({})", href)),
+                    }
+                }
+            }
+        }
+
+        if raw_text {
+            pieces.concat()
+        } else {
+            self.clean_text_content(pieces.join(" "))
+        }
+    }
+
+    /// With `with_lang`, each block is tagged with the language it is written in.
+    fn extract_blocks(&self, document: &Html, with_lang: bool) -> Vec<TextBlock> {
+        let block_selector = Selector::parse(&BLOCK_TAGS.join(", ")).unwrap();
+
+        document
+            .select(&block_selector)
+            .filter_map(|element| {
+                let text = self.own_block_text(element);
+                if text.is_empty() {
+                    None
+                } else {
+                    Some(TextBlock {
+                        tag: element.value().name().to_string(),
+                        text,
+                        lang: if with_lang { Self::element_lang(element) } else { None },
+                    })
+                }
+            })
+            .collect()
+    }
+
+    /// The `lang` of the element or its nearest ancestor that declares one, so
+    /// blocks outside any `lang` section get the `<html lang>`. An empty `lang`
+    /// explicitly marks the language as unknown.
+    fn element_lang(element: ElementRef) -> Option<String> {
+        std::iter::once(element)
+            .chain(element.ancestors().filter_map(ElementRef::wrap))
+            .find_map(|element| element.value().attr("lang"))
+            .map(str::trim)
+            .filter(|lang| !lang.is_empty())
+            .map(str::to_string)
+    }
+
+    /// The URL relative references resolve against: the document's `<base href>`
+    /// (itself resolved against the page URL) when present, otherwise the page URL.
+    fn resolve_base_url(&self, document: &Html, page_url: &str) -> Option<Url> {
+        let page_url = Url::parse(page_url).ok();
+        let base_selector = Selector::parse("base[href]").unwrap();
+
+        let base_href = document
+            .select(&base_selector)
+            .next()
+            .and_then(|base| base.value().attr("href"))
+            .map(str::trim);
+
+        match base_href {
+            Some(href) => Url::options()
+                .base_url(page_url.as_ref())
+                .parse(href)
+                .ok()
+                .or(page_url),
+            None => page_url,
+        }
+    }
+
+    /// Resolves `reference` against `base`, keeping only http(s) results so
+    /// `javascript:`, `mailto:` and `data:` references are dropped.
+    fn resolve_url(&self, base: Option<&Url>, reference: &str) -> Option<String> {
+        let resolved = Url::options().base_url(base).parse(reference.trim()).ok()?;
+        matches!(resolved.scheme(), "http" | "https").then(|| resolved.to_string())
+    }
+
+    fn extract_links(&self, document: &Html, base: Option<&Url>) -> Vec<Link> {
+        let link_selector = Selector::parse("a[href]").unwrap();
+
+        document
+            .select(&link_selector)
+            .filter_map(|link| {
+                let href = self.resolve_url(base, link.value().attr("href")?)?;
+                let text = link.text().collect::<Vec<_>>().join(" ").split_whitespace().collect::<Vec<_>>().join(" ");
+                Some(Link { href, text })
+            })
+            .collect()
+    }
+
+    /// The links ordered by `link_score`, highest first; equally scored links
+    /// keep their document order.
+    fn extract_ranked_links(&self, document: &Html, base: Option<&Url>) -> Vec<Link> {
+        let link_selector = Selector::parse("a[href]").unwrap();
+        let page_host = base.and_then(Url::host_str);
+
+        let mut scored: Vec<(i32, Link)> = document
+            .select(&link_selector)
+            .filter_map(|element| {
+                let href = self.resolve_url(base, element.value().attr("href")?)?;
+                let text = element.text().collect::<Vec<_>>().join(" ").split_whitespace().collect::<Vec<_>>().join(" ");
+                let link = Link { href, text };
+                Some((link_score(element, &link, page_host), link))
+            })
+            .collect();
+        scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+        scored.into_iter().map(|(_, link)| link).collect()
+    }
+
+    fn extract_images(&self, document: &Html, base: Option<&Url>) -> Vec<Image> {
+        let image_selector = Selector::parse("img[src]").unwrap();
+
+        document
+            .select(&image_selector)
+            .filter_map(|image| {
+                let src = self.resolve_url(base, image.value().attr("src")?)?;
+                let alt = image.value().attr("alt").map(|alt| alt.trim().to_string()).filter(|alt| !alt.is_empty());
+                let srcset_candidates = image
+                    .value()
+                    .attr("srcset")
+                    .map(|srcset| {
+                        parse_srcset(srcset)
+                            .into_iter()
+                            .filter_map(|(url, descriptor)| Some((self.resolve_url(base, url)?, descriptor.to_string())))
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                Some(Image { src, alt, srcset_candidates })
+            })
+            .collect()
+    }
+
+    /// Returns the top-level microdata items, i.e. `itemscope` elements that
+    /// aren't themselves the value of another item's property.
+    pub fn extract_microdata(&self, document: &Html) -> Vec<MicrodataItem> {
+        let Ok(selector) = Selector::parse("[itemscope]:not([itemprop])") else {
+            return Vec::new();
+        };
+
+        document.select(&selector).map(|scope| self.microdata_item(scope)).collect()
+    }
+
+    fn microdata_item(&self, scope: ElementRef) -> MicrodataItem {
+        let mut properties = Vec::new();
+        self.collect_microdata_properties(scope, &mut properties);
+
+        MicrodataItem {
+            item_type: scope.value().attr("itemtype").map(|t| t.to_string()),
+            properties,
+        }
+    }
+
+    /// Walks down from `parent` collecting `itemprop`s, without descending into
+    /// nested scopes, whose properties belong to the nested item.
+    fn collect_microdata_properties(&self, parent: ElementRef, properties: &mut Vec<MicrodataProperty>) {
+        for child in parent.children().filter_map(ElementRef::wrap) {
+            if let Some(names) = child.value().attr("itemprop") {
+                let value = self.microdata_value(child);
+                for name in names.split_whitespace() {
+                    properties.push(MicrodataProperty {
+                        name: name.to_string(),
+                        value: value.clone(),
+                    });
+                }
+            }
+
+            if child.value().attr("itemscope").is_none() {
+                self.collect_microdata_properties(child, properties);
+            }
+        }
+    }
+
+    fn microdata_value(&self, element: ElementRef) -> MicrodataValue {
+        if element.value().attr("itemscope").is_some() {
+            return MicrodataValue::Item(self.microdata_item(element));
+        }
+
+        let attribute = ["content", "href", "src"]
+            .iter()
+            .find_map(|name| element.value().attr(name));
+
+        MicrodataValue::Text(match attribute {
+            Some(value) => value.trim().to_string(),
+            None => element.text().collect::<Vec<_>>().join(" ").split_whitespace().collect::<Vec<_>>().join(" "),
+        })
+    }
+
+    /// The main content region picked by `ArticleExtractor` (or the whole body
+    /// when nothing scores), reduced by `HtmlSanitizer` to safe formatting tags.
+    pub fn extract_content_html(&self, document: &Html, url: &str, strip_inline_styles: bool) -> String {
+        let base = self.resolve_base_url(document, url);
+        let body = Selector::parse("body").unwrap();
+
+        ArticleExtractor::new(base.as_ref())
+            .main_content(document)
+            .or_else(|| document.select(&body).next())
+            .map(|region| {
+                HtmlSanitizer::new(base.as_ref())
+                    .with_inline_styles_stripped(strip_inline_styles)
+                    .sanitize_children(region)
+            })
+            .unwrap_or_default()
+    }
+
+    /// Collects the feeds advertised by `link[rel=alternate]` elements whose
+    /// `type` is an RSS, Atom or JSON Feed MIME type, in document order. Hrefs
+    /// are resolved against the page's base URL; a feed listed twice is kept once.
+    pub fn extract_feeds(&self, document: &Html, url: &str) -> Vec<FeedLink> {
+        let base = self.resolve_base_url(document, url);
+        let selector = Selector::parse(r#"link[rel~="alternate" i][href][type]"#).unwrap();
+
+        let mut feeds: Vec<FeedLink> = Vec::new();
+        for element in document.select(&selector) {
+            let Some(feed_type) = element.value().attr("type").and_then(FeedType::from_mime) else {
+                continue;
+            };
+            let Some(href) = element.value().attr("href").and_then(|href| self.resolve_url(base.as_ref(), href)) else {
+                continue;
+            };
+            if feeds.iter().any(|feed| feed.href == href) {
+                continue;
+            }
+            let title = element
+                .value()
+                .attr("title")
+                .map(str::trim)
+                .filter(|title| !title.is_empty())
+                .map(str::to_string);
+            feeds.push(FeedLink { href, title, feed_type });
+        }
+        feeds
+    }
+
+    /// Collects the in-page links (`href="#..."`) whose fragment matches neither
+    /// an element `id` nor an `<a name>`, as written, in document order and each
+    /// once. A fragment matches when it does either as written or percent-decoded;
+    /// `#` and `#top` scroll to the top of the page, so they always match.
+    pub fn extract_broken_anchors(&self, document: &Html) -> Vec<String> {
+        let targets_selector = Selector::parse("[id], a[name]").unwrap();
+        let links_selector = Selector::parse(r##"a[href^="#"], area[href^="#"]"##).unwrap();
+
+        let mut targets = std::collections::HashSet::new();
+        for element in document.select(&targets_selector) {
+            targets.extend(element.value().attr("id"));
+            if element.value().name() == "a" {
+                targets.extend(element.value().attr("name"));
+            }
+        }
+
+        let mut broken: Vec<String> = Vec::new();
+        for element in document.select(&links_selector) {
+            let Some(href) = element.value().attr("href") else {
+                continue;
+            };
+            let anchor = &href[1..];
+            if anchor.is_empty() || anchor.eq_ignore_ascii_case("top") || targets.contains(anchor) {
+                continue;
+            }
+            let decoded = percent_decode_str(anchor).decode_utf8_lossy();
+            if targets.contains(decoded.as_ref()) || broken.iter().any(|seen| seen == href) {
+                continue;
+            }
+            broken.push(href.to_string());
+        }
+        broken
+    }
+
+    /// Finds the page's breadcrumb trail as (label, href) pairs, preferring a
+    /// JSON-LD `BreadcrumbList`, whose items carry explicit positions and URLs,
+    /// over `nav[aria-label="breadcrumb"]` or `ol.breadcrumb` markup. Markup
+    /// items are the container's `li` elements (or its links when it has no
+    /// list), each labelled by its text and linked by its first `a[href]`.
+    /// Returns `None` when the page has no breadcrumbs.
+    pub fn extract_breadcrumbs(&self, document: &Html, url: &str) -> Option<Vec<(String, Option<String>)>> {
+        let base = self.resolve_base_url(document, url);
+
+        let json_ld = Selector::parse(r#"script[type="application/ld+json"]"#).unwrap();
+        let from_json_ld = document
+            .select(&json_ld)
+            .filter_map(|element| serde_json::from_str::<serde_json::Value>(&element.text().collect::<String>()).ok())
+            .find_map(|value| self.json_ld_breadcrumbs(&value, base.as_ref()));
+        if from_json_ld.is_some() {
+            return from_json_ld;
+        }
+
+        let containers = Selector::parse(r#"nav[aria-label="breadcrumb" i], ol.breadcrumb"#).unwrap();
+        let items = Selector::parse("li").unwrap();
+        let links = Selector::parse("a[href]").unwrap();
+        document.select(&containers).find_map(|container| {
+            let mut entries: Vec<ElementRef> = container.select(&items).collect();
+            if entries.is_empty() {
+                entries = container.select(&links).collect();
+            }
+
+            let trail: Vec<(String, Option<String>)> = entries
+                .into_iter()
+                .filter_map(|entry| {
+                    let label = entry.text().collect::<Vec<_>>().join(" ").split_whitespace().collect::<Vec<_>>().join(" ");
+                    if label.is_empty() {
+                        return None;
+                    }
+                    let link = if entry.value().name() == "a" {
+                        Some(entry)
+                    } else {
+                        entry.select(&links).next()
+                    };
+                    let href = link
+                        .and_then(|link| link.value().attr("href"))
+                        .and_then(|href| self.resolve_url(base.as_ref(), href));
+                    Some((label, href))
+                })
+                .collect();
+            (!trail.is_empty()).then_some(trail)
+        })
+    }
+
+    /// Reads the first `BreadcrumbList` in a JSON-LD value (including inside
+    /// `@graph` or arrays), ordering its items by `position`.
+    fn json_ld_breadcrumbs(&self, value: &serde_json::Value, base: Option<&Url>) -> Option<Vec<(String, Option<String>)>> {
+        match value {
+            serde_json::Value::Object(map) if map.get("@type").and_then(|t| t.as_str()) == Some("BreadcrumbList") => {
+                let mut items: Vec<&serde_json::Value> = map.get("itemListElement")?.as_array()?.iter().collect();
+                items.sort_by_key(|item| item.get("position").and_then(|p| p.as_u64()).unwrap_or(u64::MAX));
+
+                let trail: Vec<(String, Option<String>)> = items
+                    .into_iter()
+                    .filter_map(|item| {
+                        let target = item.get("item");
+                        let label = item
+                            .get("name")
+                            .or_else(|| target.and_then(|target| target.get("name")))
+                            .and_then(|name| name.as_str())
+                            .map(|name| name.split_whitespace().collect::<Vec<_>>().join(" "))
+                            .filter(|name| !name.is_empty())?;
+                        let href = target
+                            .and_then(|target| target.as_str().or_else(|| target.get("@id").and_then(|id| id.as_str())))
+                            .and_then(|href| self.resolve_url(base, href));
+                        Some((label, href))
+                    })
+                    .collect();
+                (!trail.is_empty()).then_some(trail)
+            }
+            serde_json::Value::Object(map) => map.values().find_map(|value| self.json_ld_breadcrumbs(value, base)),
+            serde_json::Value::Array(items) => items.iter().find_map(|item| self.json_ld_breadcrumbs(item, base)),
+            _ => None,
+        }
+    }
+
+    /// The comma-separated entries of `<meta name="keywords">` followed by
+    /// the `article:tag` values, trimmed, without empty entries and keeping
+    /// only the first of entries that differ just in case. Only the `<head>`
+    /// is parsed when it can be found.
+    pub fn extract_keywords(raw_html: &str) -> Vec<String> {
+        Self::keywords_in(&parse_markup(Self::head_section(raw_html).unwrap_or(raw_html), false))
+    }
+
+    fn keywords_in(document: &Html) -> Vec<String> {
+        let keywords_meta = Selector::parse(r#"meta[name="keywords" i][content]"#).unwrap();
+        let tag_meta = Selector::parse(r#"meta[property="article:tag"][content]"#).unwrap();
+
+        let from_keywords = document
+            .select(&keywords_meta)
+            .filter_map(|element| element.value().attr("content"))
+            .flat_map(|content| content.split(','));
+        let from_tags = document
+            .select(&tag_meta)
+            .filter_map(|element| element.value().attr("content"));
+
+        let mut keywords: Vec<String> = Vec::new();
+        for keyword in from_keywords.chain(from_tags).map(str::trim) {
+            if !keyword.is_empty() && !keywords.iter().any(|seen| seen.to_lowercase() == keyword.to_lowercase()) {
+                keywords.push(keyword.to_string());
+            }
+        }
+        keywords
+    }
+
+    fn published_time_meta(document: &Html) -> Option<String> {
+        let meta = Selector::parse(r#"meta[property="article:published_time"][content]"#).ok()?;
+        document
+            .select(&meta)
+            .filter_map(|element| element.value().attr("content"))
+            .find_map(Self::normalize_date)
+    }
+
+    /// Finds the page's publish date, preferring `article:published_time`,
+    /// then the first `<time datetime>`, then JSON-LD `datePublished`.
+    /// Dates that can't be read are skipped in favour of the next source.
+    fn published_at_in(document: &Html) -> Option<String> {
+        let from_meta = Self::published_time_meta(document);
+        if from_meta.is_some() {
+            return from_meta;
+        }
+
+        let time = Selector::parse("time[datetime]").ok()?;
+        let from_time = document
+            .select(&time)
+            .filter_map(|element| element.value().attr("datetime"))
+            .find_map(Self::normalize_date);
+        if from_time.is_some() {
+            return from_time;
+        }
+
+        let json_ld = Selector::parse(r#"script[type="application/ld+json"]"#).ok()?;
+        document
+            .select(&json_ld)
+            .filter_map(|element| serde_json::from_str(&element.text().collect::<String>()).ok())
+            .find_map(|value| Self::find_date_published(&value))
+    }
+
+    fn find_date_published(value: &serde_json::Value) -> Option<String> {
+        match value {
+            serde_json::Value::Object(map) => map
+                .get("datePublished")
+                .and_then(|date| date.as_str())
+                .and_then(Self::normalize_date)
+                .or_else(|| map.values().find_map(Self::find_date_published)),
+            serde_json::Value::Array(items) => items.iter().find_map(Self::find_date_published),
+            _ => None,
+        }
+    }
+
+    /// Converts the date formats seen in the wild to RFC3339; dates without an
+    /// offset are taken as UTC and bare dates as midnight.
+    fn normalize_date(raw: &str) -> Option<String> {
+        let raw = raw.trim();
+        let parsed = DateTime::parse_from_rfc3339(raw)
+            .or_else(|_| DateTime::parse_from_str(raw, "%Y-%m-%dT%H:%M:%S%z"))
+            .or_else(|_| DateTime::parse_from_rfc2822(raw))
+            .ok()
+            .or_else(|| {
+                NaiveDateTime::parse_from_str(raw, "%Y-%m-%dT%H:%M:%S")
+                    .or_else(|_| NaiveDateTime::parse_from_str(raw, "%Y-%m-%dT%H:%M"))
+                    .or_else(|_| NaiveDate::parse_from_str(raw, "%Y-%m-%d").map(|date| date.and_time(Default::default())))
+                    .ok()
+                    .map(|naive| naive.and_utc().fixed_offset())
+            })?;
+
+        Some(parsed.to_rfc3339_opts(SecondsFormat::Secs, true))
+    }
+
+    /// Whether the document looks cut off: a tag, comment or `<script>`-like
+    /// element still open at the end of the input, or an element left unclosed
+    /// whose end tag HTML doesn't allow omitting. Unclosed `<html>`, `<body>`,
+    /// `<p>`, `<li>` and the like are valid HTML and don't count.
+    pub fn is_truncated(raw_html: &str) -> bool {
+        let mut open: Vec<String> = Vec::new();
+        let mut pos = 0;
+
+        while let Some(offset) = raw_html[pos..].find('<') {
+            let rest = &raw_html[pos + offset + 1..];
+            if let Some(comment) = rest.strip_prefix("!--") {
+                let Some(end) = comment.find("-->") else {
+                    return true;
+                };
+                pos += offset + 1 + 3 + end + 3;
+                continue;
+            }
+
+            let closing = rest.starts_with('/');
+            let tag = rest.strip_prefix('/').unwrap_or(rest);
+            let name_len = tag.find(|c: char| !c.is_ascii_alphanumeric()).unwrap_or(tag.len());
+            let name = tag[..name_len].to_ascii_lowercase();
+            // A bare `<` in text
+            if name.is_empty() && !closing && !rest.starts_with('!') {
+                pos += offset + 1;
+                continue;
+            }
+            let Some(end) = rest.find('>') else {
+                return true;
+            };
+            pos += offset + 1 + end + 1;
+
+            if name.is_empty() {
+                // A doctype or `</>`
+            } else if closing {
+                if let Some(index) = open.iter().rposition(|element| *element == name) {
+                    open.truncate(index);
+                }
+            } else if RAW_TEXT_TAGS.contains(&name.as_str()) {
+                let Some(close) = find_ignore_ascii_case(&raw_html[pos..], &format!("</{}", name)) else {
+                    return true;
+                };
+                pos += close;
+            } else if !VOID_TAGS.contains(&name.as_str()) && !OPTIONAL_END_TAGS.contains(&name.as_str()) && !rest[..end].ends_with('/') {
+                open.push(name);
+            }
+        }
+
+        !open.is_empty()
+    }
+
+    fn extract_forms(&self, document: &Html) -> Vec<FormDef> {
+        let form_selector = Selector::parse("form").unwrap();
+        let field_selector = Selector::parse("input[name], select[name], textarea[name]").unwrap();
+
+        document
+            .select(&form_selector)
+            .map(|form| FormDef {
+                action: form.value().attr("action").map(|action| action.trim().to_string()),
+                method: form.value().attr("method").unwrap_or("get").trim().to_ascii_uppercase(),
+                fields: form.select(&field_selector).filter_map(|field| self.form_field(field)).collect(),
+            })
+            .collect()
+    }
+
+    fn form_field(&self, field: ElementRef) -> Option<FormField> {
+        let name = field.value().attr("name")?.to_string();
+
+        let (field_type, value) = match field.value().name() {
+            "select" => {
+                let option_selector = Selector::parse("option").unwrap();
+                let options: Vec<_> = field.select(&option_selector).collect();
+                let selected = options
+                    .iter()
+                    .find(|option| option.value().attr("selected").is_some())
+                    .or(options.first());
+                let value = selected.map(|option| {
+                    option.value().attr("value").map(str::to_string)
+                        .unwrap_or_else(|| option.text().collect::<String>().trim().to_string())
+                });
+                ("select".to_string(), value)
+            }
+            "textarea" => ("textarea".to_string(), Some(field.text().collect::<String>())),
+            _ => (
+                field.value().attr("type").unwrap_or("text").trim().to_ascii_lowercase(),
+                field.value().attr("value").map(str::to_string),
+            ),
+        };
+
+        Some(FormField { name, field_type, value })
+    }
+
+    /// Re-serializes the parsed DOM with one node per line, indented two spaces per
+    /// level. html5ever repairs malformed markup while parsing, so broken input
+    /// still comes out as a well-formed, indented document.
+    fn prettify_html(&self, document: &Html) -> String {
+        let mut output = String::new();
+        for child in document.tree.root().children() {
+            self.write_pretty_node(child, 0, &mut output);
+        }
+        output
+    }
+
+    fn write_pretty_node(&self, node: NodeRef<Node>, depth: usize, output: &mut String) {
+        let indent = "  ".repeat(depth);
+
+        match node.value() {
+            Node::Doctype(doctype) => {
+                output.push_str(&format!("{}<!DOCTYPE {}>\n", indent, doctype.name()));
+            }
+            Node::Comment(comment) => {
+                output.push_str(&format!("{}<!--{}-->\n", indent, &**comment));
+            }
+            Node::Text(text) => {
+                let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+                if !text.is_empty() {
+                    output.push_str(&format!("{}{}\n", indent, html_escape::encode_text(&text)));
+                }
+            }
+            Node::Element(element) => {
+                let name = element.name();
+                let mut open_tag = format!("{}<{}", indent, name);
+                for (attr, value) in element.attrs() {
+                    open_tag.push_str(&format!(" {}=\"{}\"", attr, html_escape::encode_double_quoted_attribute(value)));
+                }
+                open_tag.push('>');
+                output.push_str(&open_tag);
+
+                if VOID_TAGS.contains(&name) {
+                    output.push('\n');
+                } else if VERBATIM_TAGS.contains(&name) {
+                    let inner = ElementRef::wrap(node).map(|element| element.inner_html()).unwrap_or_default();
+                    output.push_str(&format!("{}</{}>\n", inner, name));
+                } else if !node.has_children() {
+                    output.push_str(&format!("</{}>\n", name));
+                } else {
+                    output.push('\n');
+                    for child in node.children() {
+                        self.write_pretty_node(child, depth + 1, output);
+                    }
+                    output.push_str(&format!("{}</{}>\n", indent, name));
+                }
+            }
+            Node::Document | Node::Fragment | Node::ProcessingInstruction(_) => {}
+        }
+    }
+
+    /// Collects email addresses and phone numbers from the extracted text and from
+    /// `mailto:`/`tel:` links, deduplicated in order of first appearance.
+    fn extract_contacts(&self, text: &str, document: &Html) -> Contacts {
+        let mut emails: Vec<String> = EMAIL_REGEX.find_iter(text).map(|m| m.as_str().to_string()).collect();
+        let mut phones: Vec<String> = PHONE_REGEX
+            .find_iter(text)
+            .map(|m| m.as_str().trim().to_string())
+            .filter(|candidate| self.is_plausible_phone(candidate))
+            .collect();
+
+        let link_selector = Selector::parse("a[href]").unwrap();
+        for href in document.select(&link_selector).filter_map(|link| link.value().attr("href")) {
+            let href = href.trim();
+            if let Some(address) = href.strip_prefix("mailto:") {
+                let address = address.split('?').next().unwrap_or_default().trim();
+                if EMAIL_REGEX.is_match(address) {
+                    emails.push(address.to_string());
+                }
+            } else if let Some(number) = href.strip_prefix("tel:") {
+                let number = number.trim();
+                if !number.is_empty() {
+                    phones.push(number.to_string());
+                }
+            }
+        }
+
+        let mut seen_emails = std::collections::HashSet::new();
+        emails.retain(|email| seen_emails.insert(email.to_lowercase()));
+
+        let mut seen_phones = std::collections::HashSet::new();
+        phones.retain(|phone| seen_phones.insert(phone.chars().filter(|c| c.is_ascii_digit()).collect::<String>()));
+
+        Contacts { emails, phones }
+    }
+
+    /// Filters phone regex matches down to things that look like phone numbers
+    /// rather than dates, prices or bare identifiers.
+    fn is_plausible_phone(&self, candidate: &str) -> bool {
+        let digits = candidate.chars().filter(|c| c.is_ascii_digit()).count();
+        let has_structure = candidate.starts_with('+')
+            || candidate.contains(|c: char| c.is_whitespace() || c == '-' || c == '.' || c == '(');
+
+        (7..=15).contains(&digits) && has_structure && !DATE_REGEX.is_match(candidate)
+    }
+
+    /// Text belonging directly to this block, excluding text inside nested blocks
+    /// (e.g. a `<p>` inside an `<li>`), which is reported as its own block.
+    fn own_block_text(&self, element: ElementRef) -> String {
+        element
+            .descendants()
+            .filter(|node| {
+                node.ancestors()
+                    .take_while(|ancestor| ancestor.id() != element.id())
+                    .filter_map(|ancestor| ancestor.value().as_element())
+                    .all(|ancestor| !BLOCK_TAGS.contains(&ancestor.name()))
+            })
+            .filter_map(|node| node.value().as_text().map(|text| &**text))
+            .collect::<Vec<_>>()
+            .join(" ")
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+/// Byte offset of the first match of `needle` in `haystack`, ignoring ASCII case.
+fn find_ignore_ascii_case(haystack: &str, needle: &str) -> Option<usize> {
+    haystack
+        .as_bytes()
+        .windows(needle.len())
+        .position(|window| window.eq_ignore_ascii_case(needle.as_bytes()))
+}
+
+/// Keeps the first `max` elements of an extracted list, reporting whether any were dropped.
+fn cap_elements<T>(elements: Option<Vec<T>>, max: usize) -> (Option<Vec<T>>, bool) {
+    match elements {
+        Some(mut elements) if elements.len() > max => {
+            elements.truncate(max);
+            (Some(elements), true)
+        }
+        elements => (elements, false),
+    }
+}
+
+/// How relevant a link is likely to be to the page's content. Links inside
+/// `main` or `article` score 3 and links in page chrome (`nav`, `header`,
+/// `aside`, `footer`) lose 3; up to 3 more for anchor text, a point per 10
+/// characters; and 1 for staying on the page's host.
+fn link_score(element: ElementRef, link: &Link, page_host: Option<&str>) -> i32 {
+    let mut in_content = false;
+    let mut in_chrome = false;
+    for ancestor in element.ancestors().filter_map(ElementRef::wrap) {
+        let role = ancestor.value().attr("role");
+        match ancestor.value().name() {
+            "main" | "article" => in_content = true,
+            "nav" | "header" | "aside" | "footer" => in_chrome = true,
+            _ if role == Some("main") => in_content = true,
+            _ if matches!(role, Some("navigation" | "banner" | "complementary" | "contentinfo")) => in_chrome = true,
+            _ => {}
+        }
+    }
+
+    let position = match (in_content, in_chrome) {
+        // Chrome inside the content, such as an article's footer, is still chrome
+        (_, true) => -3,
+        (true, false) => 3,
+        (false, false) => 0,
+    };
+    let text = (link.text.chars().count() / 10).min(3) as i32;
+    let same_host = Url::parse(&link.href).ok().is_some_and(|href| href.host_str() == page_host && page_host.is_some());
+
+    position + text + same_host as i32
+}
+
+/// Splits a `srcset` attribute into (URL, descriptor) pairs following the HTML
+/// parsing rules: a URL runs to the next whitespace, so it may contain commas,
+/// and trailing commas end a candidate that has no descriptor. The descriptor
+/// runs to the next comma outside parentheses and defaults to `1x`.
+fn parse_srcset(srcset: &str) -> Vec<(&str, &str)> {
+    let mut candidates = Vec::new();
+    let mut rest = srcset;
+
+    loop {
+        rest = rest.trim_start_matches(|c: char| c.is_whitespace() || c == ',');
+        if rest.is_empty() {
+            return candidates;
+        }
+
+        let url_end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+        let url = &rest[..url_end];
+        rest = &rest[url_end..];
+
+        let trimmed_url = url.trim_end_matches(',');
+        let descriptor = if trimmed_url.len() < url.len() {
+            ""
+        } else {
+            let mut depth = 0usize;
+            let descriptor_end = rest
+                .char_indices()
+                .find(|&(_, c)| {
+                    match c {
+                        '(' => depth += 1,
+                        ')' => depth = depth.saturating_sub(1),
+                        ',' if depth == 0 => return true,
+                        _ => {}
+                    }
+                    false
+                })
+                .map_or(rest.len(), |(index, _)| index);
+            let descriptor = rest[..descriptor_end].trim();
+            rest = &rest[descriptor_end..];
+            descriptor
+        };
+
+        if !trimmed_url.is_empty() {
+            candidates.push((trimmed_url, if descriptor.is_empty() { "1x" } else { descriptor }));
+        }
     }
 }
 
@@ -90,6 +1136,17 @@ mod tests {
     use super::*;
     use domain::model::content::{HtmlContent, ContentMetadata};
 
+    thread_local! {
+        /// Documents parsed by `counting_parse_markup` on this thread.
+        static DOCUMENT_PARSES: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+    }
+
+    /// `parse_markup`, counting each call in `DOCUMENT_PARSES`.
+    fn counting_parse_markup(raw_html: &str, fragment: bool) -> Html {
+        DOCUMENT_PARSES.with(|parses| parses.set(parses.get() + 1));
+        parse_markup(raw_html, fragment)
+    }
+
     fn create_test_html_content(url: &str, raw_html: &str) -> HtmlContent {
         let metadata = ContentMetadata {
             content_type: "text/html".to_string(),
@@ -99,6 +1156,7 @@ mod tests {
             charset: Some("utf-8".to_string()),
             javascript_detected: None,
             fetch_method: None,
+            ..Default::default()
         };
 
         HtmlContent {
@@ -107,6 +1165,7 @@ mod tests {
             text_content: "Test content".to_string(),
             raw_html: raw_html.to_string(),
             metadata,
+            ..Default::default()
         }
     }
 
@@ -127,6 +1186,24 @@ mod tests {
         assert_eq!(content.metadata.status_code, 200);
     }
 
+    #[tokio::test]
+    async fn test_parse_html_with_status_keeps_response_status() {
+        let adapter = HtmlParserAdapter::new();
+        let html = "<html><head><title>Not Found</title></head><body>No such page</body></html>";
+        let headers = std::collections::HashMap::from([
+            ("Content-Type".to_string(), "text/html; charset=ISO-8859-1".to_string()),
+            ("Last-Modified".to_string(), "Wed, 21 Oct 2015 07:28:00 GMT".to_string()),
+        ]);
+
+        let content = adapter.parse_html_with_status(html, "https://example.com/missing", 404, &headers, &ParseOptions::default()).await.unwrap();
+
+        assert_eq!(content.metadata.status_code, 404);
+        assert_eq!(content.metadata.content_type, "text/html");
+        assert_eq!(content.metadata.charset, Some("iso-8859-1".to_string()));
+        assert_eq!(content.metadata.last_modified, Some("Wed, 21 Oct 2015 07:28:00 GMT".to_string()));
+        assert_eq!(content.title, Some("Not Found".to_string()));
+    }
+
     #[tokio::test]
     async fn test_parse_html_no_title() {
         let adapter = HtmlParserAdapter::new();
@@ -262,33 +1339,161 @@ mod tests {
         
         // Test normal title
         let html = "<html><head><title>Test Title</title></head></html>";
-        let title = adapter.extract_title_from_raw_html(html);
+        let title = adapter.title_of(&Html::parse_document(html), None);
         assert_eq!(title, Some("Test Title".to_string()));
         
         // Test no title
         let html = "<html><head></head></html>";
-        let title = adapter.extract_title_from_raw_html(html);
+        let title = adapter.title_of(&Html::parse_document(html), None);
         assert_eq!(title, None);
         
         // Test empty title
         let html = "<html><head><title></title></head></html>";
-        let title = adapter.extract_title_from_raw_html(html);
+        let title = adapter.title_of(&Html::parse_document(html), None);
         assert_eq!(title, None);
         
         // Test whitespace title
         let html = "<html><head><title>   </title></head></html>";
-        let title = adapter.extract_title_from_raw_html(html);
+        let title = adapter.title_of(&Html::parse_document(html), None);
         assert_eq!(title, None);
     }
 
 
     #[tokio::test]
-    async fn test_clean_text_content() {
-        let adapter = HtmlParserAdapter::new();
-        
-        // Test with whitespace and empty lines
-        let text = "  Line 1  \n\n  \nLine 2\n   \n  Line 3  ".to_string();
-        let cleaned = adapter.clean_text_content(text);
+    async fn test_every_extraction_shares_one_parse() {
+        let adapter = HtmlParserAdapter { parse_markup: counting_parse_markup };
+        let html = r##"<html><head>
+            <title>Release notes</title>
+            <meta name="keywords" content="release, changelog">
+            <meta property="article:published_time" content="2024-06-01T10:00:00Z">
+            <link rel="alternate" type="application/rss+xml" href="/feed.xml">
+        </head><body>
+            <nav aria-label="breadcrumb"><ol><li><a href="/">Home</a></li><li>Notes</li></ol></nav>
+            <article><h1 id="v2">Version 2</h1><p>Write to <a href="mailto:team@example.com">the team</a>. <a href="#v3">Next</a></p>
+            <img src="/shot.png" alt="Screenshot"></article>
+            <form action="/subscribe"><input name="email" type="email"></form>
+            <div itemscope itemtype="https://schema.org/Event"><span itemprop="name">Launch</span></div>
+        </body></html>"##;
+        let options = ParseOptions {
+            include_blocks: true,
+            include_contacts: true,
+            include_links: true,
+            include_images: true,
+            prettify_html: true,
+            include_forms: true,
+            include_microdata: true,
+            include_breadcrumbs: true,
+            output_html_fragment: true,
+            include_feeds: true,
+            include_keywords: true,
+            validate_anchors: true,
+            ..Default::default()
+        };
+
+        DOCUMENT_PARSES.with(|parses| parses.set(0));
+        let content = adapter.parse_html_with_options(html, "https://example.com/notes", &options).await.unwrap();
+        assert_eq!(DOCUMENT_PARSES.with(|parses| parses.get()), 1);
+
+        assert_eq!(content.title.as_deref(), Some("Release notes"));
+        assert!(content.text_content.contains("Version 2"));
+        assert_eq!(content.metadata.published_at.as_deref(), Some("2024-06-01T10:00:00Z"));
+        assert_eq!(content.metadata.keywords, Some(vec!["release".to_string(), "changelog".to_string()]));
+        assert!(content.blocks.unwrap().iter().any(|block| block.text == "Version 2"));
+        assert_eq!(content.contacts.unwrap().emails, vec!["team@example.com"]);
+        assert!(content.links.unwrap().iter().any(|link| link.href == "https://example.com/"));
+        assert_eq!(content.images.unwrap()[0].src, "https://example.com/shot.png");
+        assert!(content.raw_html.contains("\n  <head>"));
+        assert_eq!(content.forms.unwrap()[0].fields[0].name, "email");
+        assert_eq!(content.microdata.unwrap()[0].item_type.as_deref(), Some("https://schema.org/Event"));
+        assert_eq!(content.breadcrumbs.unwrap()[0], ("Home".to_string(), Some("https://example.com/".to_string())));
+        assert!(content.content_html.unwrap().contains("Version 2"));
+        assert_eq!(content.feeds.unwrap()[0].href, "https://example.com/feed.xml");
+        assert_eq!(content.broken_anchors, Some(vec!["#v3".to_string()]));
+    }
+
+    #[test]
+    fn test_extract_title_scans_only_the_head() {
+        let body = "<p>Long article paragraph with <a href=\"/x\">links</a>.</p>".repeat(50_000);
+        let html = format!(
+            "<!DOCTYPE html><html><HEAD><title>Big &amp; Slow</title><meta property=\"article:published_time\" content=\"2024-05-01\"></HEAD><body>{}</body></html>",
+            body
+        );
+
+        let head = HtmlParserAdapter::head_section(&html).unwrap();
+        assert!(head.ends_with("</HEAD>"));
+        assert!(head.len() < 200, "scanned {} bytes", head.len());
+        assert_eq!(
+            HtmlParserAdapter::title_and_published_at(&html, None),
+            (Some("Big & Slow".to_string()), Some("2024-05-01T00:00:00Z".to_string()))
+        );
+
+        // No closing head within the window: the whole document is parsed
+        let headless = "<title>Fragment page</title><p>Body text</p>";
+        assert!(HtmlParserAdapter::head_section(headless).is_none());
+        assert_eq!(HtmlParserAdapter::title_and_published_at(headless, None).0, Some("Fragment page".to_string()));
+        let late_head = format!("<html><head><script>{}</script><title>Late</title></head></html>", "x".repeat(HEAD_SCAN_WINDOW_BYTES));
+        assert!(HtmlParserAdapter::head_section(&late_head).is_none());
+        assert_eq!(HtmlParserAdapter::title_and_published_at(&late_head, None).0, Some("Late".to_string()));
+    }
+
+    #[test]
+    fn test_title_and_published_at_read_from_a_parsed_document() {
+        let document = Html::parse_document(
+            r#"<html><head><title>Parsed</title></head><body><time datetime="2024-05-01">May 1</time></body></html>"#,
+        );
+
+        // The raw markup isn't parsed again when the document is given
+        assert_eq!(
+            HtmlParserAdapter::title_and_published_at("<title>Unparsed</title>", Some(&document)),
+            (Some("Parsed".to_string()), Some("2024-05-01T00:00:00Z".to_string()))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_extract_title_truncated_at_word_boundary() {
+        let adapter = HtmlParserAdapter::new();
+        let html = "<html><head><title>The quick brown fox jumps over the lazy dog</title></head></html>";
+
+        let title = adapter.title_of(&Html::parse_document(html), Some(20));
+        assert_eq!(title, Some("The quick brown fox…".to_string()));
+
+        let title = adapter.title_of(&Html::parse_document(html), Some(18));
+        assert_eq!(title, Some("The quick brown…".to_string()));
+        assert!(title.unwrap().chars().count() <= 18);
+    }
+
+    #[tokio::test]
+    async fn test_extract_title_short_title_untouched() {
+        let adapter = HtmlParserAdapter::new();
+        let html = "<html><head><title>Short title</title></head></html>";
+
+        let title = adapter.title_of(&Html::parse_document(html), Some(20));
+        assert_eq!(title, Some("Short title".to_string()));
+
+        let title = adapter.title_of(&Html::parse_document(html), Some(11));
+        assert_eq!(title, Some("Short title".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_parse_html_with_max_title_length() {
+        let adapter = HtmlParserAdapter::new();
+        let html = "<html><head><title>Überlange Überschrift für einen Artikel</title></head><body>Text</body></html>";
+        let options = ParseOptions {
+            max_title_length: Some(25),
+            ..Default::default()
+        };
+
+        let content = adapter.parse_html_with_options(html, "https://example.com", &options).await.unwrap();
+        assert_eq!(content.title, Some("Überlange Überschrift…".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_clean_text_content() {
+        let adapter = HtmlParserAdapter::new();
+        
+        // Test with whitespace and empty lines
+        let text = "  Line 1  \n\n  \nLine 2\n   \n  Line 3  ".to_string();
+        let cleaned = adapter.clean_text_content(text);
         assert_eq!(cleaned, "Line 1\nLine 2\nLine 3");
         
         // Test with only whitespace
@@ -331,6 +1536,787 @@ mod tests {
         // Note: scraper may include script content, but main content should be there
     }
 
+    #[tokio::test]
+    async fn test_parse_html_with_blocks() {
+        let adapter = HtmlParserAdapter::new();
+        let html = r#"
+            <html>
+                <body>
+                    <h1>Main Heading</h1>
+                    <p>A <strong>short</strong> paragraph.</p>
+                    <ul><li>First item</li></ul>
+                </body>
+            </html>
+        "#;
+        let options = ParseOptions {
+            include_blocks: true,
+            ..Default::default()
+        };
+
+        let content = adapter.parse_html_with_options(html, "https://example.com", &options).await.unwrap();
+        let blocks = content.blocks.unwrap();
+
+        assert_eq!(blocks.len(), 3);
+        assert_eq!(blocks[0], TextBlock { tag: "h1".to_string(), text: "Main Heading".to_string(), lang: None });
+        assert_eq!(blocks[1], TextBlock { tag: "p".to_string(), text: "A short paragraph.".to_string(), lang: None });
+        assert_eq!(blocks[2], TextBlock { tag: "li".to_string(), text: "First item".to_string(), lang: None });
+    }
+
+    #[tokio::test]
+    async fn test_parse_html_blocks_tagged_with_nearest_lang() {
+        let adapter = HtmlParserAdapter::new();
+        let html = r#"
+            <html lang="en">
+                <body>
+                    <h1>Welcome</h1>
+                    <div lang="es">
+                        <p>Bienvenidos a nuestro sitio.</p>
+                        <p lang="fr">Bienvenue.</p>
+                    </div>
+                    <p>Back to English.</p>
+                </body>
+            </html>
+        "#;
+        let options = ParseOptions {
+            include_blocks: true,
+            include_block_lang: true,
+            ..Default::default()
+        };
+
+        let content = adapter.parse_html_with_options(html, "https://example.com", &options).await.unwrap();
+        let langs: Vec<_> = content
+            .blocks
+            .unwrap()
+            .into_iter()
+            .map(|block| (block.text, block.lang))
+            .collect();
+
+        assert_eq!(langs, vec![
+            ("Welcome".to_string(), Some("en".to_string())),
+            ("Bienvenidos a nuestro sitio.".to_string(), Some("es".to_string())),
+            ("Bienvenue.".to_string(), Some("fr".to_string())),
+            ("Back to English.".to_string(), Some("en".to_string())),
+        ]);
+    }
+
+    #[tokio::test]
+    async fn test_parse_html_blocks_nested() {
+        let adapter = HtmlParserAdapter::new();
+        let html = "<ul><li>Outer<blockquote>Quoted</blockquote></li></ul>";
+
+        let blocks = adapter.extract_blocks(&Html::parse_document(html), false);
+
+        assert_eq!(blocks.len(), 2);
+        assert_eq!(blocks[0], TextBlock { tag: "li".to_string(), text: "Outer".to_string(), lang: None });
+        assert_eq!(blocks[1], TextBlock { tag: "blockquote".to_string(), text: "Quoted".to_string(), lang: None });
+    }
+
+    #[tokio::test]
+    async fn test_parse_html_without_blocks_option() {
+        let adapter = HtmlParserAdapter::new();
+        let html = "<html><body><p>Paragraph</p></body></html>";
+
+        let content = adapter
+            .parse_html_with_options(html, "https://example.com", &ParseOptions::default())
+            .await
+            .unwrap();
+
+        assert!(content.blocks.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_extract_contacts_emails() {
+        let adapter = HtmlParserAdapter::new();
+        let html = r#"
+            <html><body>
+                <p>Write to sales@example.com or Support@Example.com.</p>
+                <a href="mailto:support@example.com?subject=Hi">Email support</a>
+                <a href="mailto:press@example.org">Press</a>
+            </body></html>
+        "#;
+        let text = adapter.extract_text_from_html(html).unwrap();
+
+        let contacts = adapter.extract_contacts(&text, &Html::parse_document(html));
+
+        assert_eq!(contacts.emails, vec![
+            "sales@example.com".to_string(),
+            "Support@Example.com".to_string(),
+            "press@example.org".to_string(),
+        ]);
+    }
+
+    #[tokio::test]
+    async fn test_extract_contacts_phones() {
+        let adapter = HtmlParserAdapter::new();
+        let html = r#"
+            <html><body>
+                <p>Call (555) 123-4567 or +44 20 7946 0958.</p>
+                <p>Fax: 555.123.4567, published 2024-01-15, order 42.</p>
+                <a href="tel:+15551234567">Call us</a>
+            </body></html>
+        "#;
+        let text = adapter.extract_text_from_html(html).unwrap();
+
+        let contacts = adapter.extract_contacts(&text, &Html::parse_document(html));
+
+        assert_eq!(contacts.phones, vec![
+            "(555) 123-4567".to_string(),
+            "+44 20 7946 0958".to_string(),
+            "+15551234567".to_string(),
+        ]);
+        assert!(contacts.emails.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_parse_html_with_contacts() {
+        let adapter = HtmlParserAdapter::new();
+        let html = "<html><body><p>Contact info@example.com</p></body></html>";
+        let options = ParseOptions {
+            include_contacts: true,
+            ..Default::default()
+        };
+
+        let content = adapter.parse_html_with_options(html, "https://example.com", &options).await.unwrap();
+        assert_eq!(content.contacts.unwrap().emails, vec!["info@example.com".to_string()]);
+
+        let content = adapter.parse_html(html, "https://example.com").await.unwrap();
+        assert!(content.contacts.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_extract_links_relative_to_page_url() {
+        let adapter = HtmlParserAdapter::new();
+        let html = r#"
+            <html><body>
+                <a href="/about">About   us</a>
+                <a href="docs/intro.html">Intro</a>
+                <a href="https://other.example.org/x">External</a>
+                <a href="javascript:void(0)">Menu</a>
+                <img src="img/logo.png" alt="Logo">
+            </body></html>
+        "#;
+        let document = Html::parse_document(html);
+        let base = adapter.resolve_base_url(&document, "https://example.com/blog/post");
+
+        let links = adapter.extract_links(&document, base.as_ref());
+        let images = adapter.extract_images(&document, base.as_ref());
+
+        assert_eq!(links, vec![
+            Link { href: "https://example.com/about".to_string(), text: "About us".to_string() },
+            Link { href: "https://example.com/blog/docs/intro.html".to_string(), text: "Intro".to_string() },
+            Link { href: "https://other.example.org/x".to_string(), text: "External".to_string() },
+        ]);
+        assert_eq!(images, vec![
+            Image {
+                src: "https://example.com/blog/img/logo.png".to_string(),
+                alt: Some("Logo".to_string()),
+                srcset_candidates: Vec::new(),
+            },
+        ]);
+    }
+
+    #[test]
+    fn test_extract_images_resolves_every_srcset_candidate() {
+        let adapter = HtmlParserAdapter::new();
+        let html = r#"
+            <html><body>
+                <img src="img/hero.jpg" alt="Hero"
+                     srcset="img/hero-640.jpg 640w, /cdn/hero,q_80-1280.jpg 1280w,
+                             https://images.example.org/hero-1920.jpg 1920w">
+                <img src="img/logo.png" srcset="img/logo.png, img/logo@2x.png 2x">
+            </body></html>
+        "#;
+        let document = Html::parse_document(html);
+        let base = adapter.resolve_base_url(&document, "https://example.com/blog/post");
+
+        let images = adapter.extract_images(&document, base.as_ref());
+
+        let candidates = |index: usize| -> Vec<(&str, &str)> {
+            images[index].srcset_candidates.iter().map(|(url, descriptor)| (url.as_str(), descriptor.as_str())).collect()
+        };
+        assert_eq!(candidates(0), vec![
+            ("https://example.com/blog/img/hero-640.jpg", "640w"),
+            ("https://example.com/cdn/hero,q_80-1280.jpg", "1280w"),
+            ("https://images.example.org/hero-1920.jpg", "1920w"),
+        ]);
+        assert_eq!(candidates(1), vec![
+            ("https://example.com/blog/img/logo.png", "1x"),
+            ("https://example.com/blog/img/logo@2x.png", "2x"),
+        ]);
+    }
+
+    #[tokio::test]
+    async fn test_extract_links_relative_to_base_href() {
+        let adapter = HtmlParserAdapter::new();
+        let html = r#"
+            <html><head><base href="/static/v2/"></head><body>
+                <a href="docs/intro.html">Intro</a>
+                <a href="/about">About</a>
+                <img src="img/logo.png">
+            </body></html>
+        "#;
+        let document = Html::parse_document(html);
+        let base = adapter.resolve_base_url(&document, "https://example.com/blog/post");
+
+        let links = adapter.extract_links(&document, base.as_ref());
+        let images = adapter.extract_images(&document, base.as_ref());
+
+        assert_eq!(links[0].href, "https://example.com/static/v2/docs/intro.html");
+        assert_eq!(links[1].href, "https://example.com/about");
+        assert_eq!(images[0].src, "https://example.com/static/v2/img/logo.png");
+        assert_eq!(images[0].alt, None);
+    }
+
+    #[tokio::test]
+    async fn test_parse_html_with_links_and_images() {
+        let adapter = HtmlParserAdapter::new();
+        let html = r#"<html><head><base href="https://cdn.example.net/"></head>
+            <body><a href="page">Page</a><img src="pic.jpg" alt="Pic"></body></html>"#;
+        let options = ParseOptions {
+            include_links: true,
+            ..Default::default()
+        };
+
+        let content = adapter.parse_html_with_options(html, "https://example.com/", &options).await.unwrap();
+
+        assert_eq!(content.links.unwrap()[0].href, "https://cdn.example.net/page");
+        assert!(content.images.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_max_elements_caps_links_and_images() {
+        let adapter = HtmlParserAdapter::new();
+        let links = (1..=50).map(|n| format!(r#"<a href="/page/{n}">Page {n}</a>"#)).collect::<String>();
+        let html = format!(r#"<html><body>{links}<img src="/a.png"><img src="/b.png"></body></html>"#);
+        let options = ParseOptions {
+            include_links: true,
+            include_images: true,
+            max_elements: Some(10),
+            ..Default::default()
+        };
+
+        let content = adapter.parse_html_with_options(&html, "https://example.com/", &options).await.unwrap();
+
+        let links = content.links.unwrap();
+        assert_eq!(links.len(), 10);
+        assert_eq!(links[0].href, "https://example.com/page/1");
+        assert_eq!(links[9].href, "https://example.com/page/10");
+        assert_eq!(content.images.unwrap().len(), 2);
+        assert_eq!(content.metadata.elements_truncated, Some(true));
+    }
+
+    #[tokio::test]
+    async fn test_max_elements_above_count_is_not_truncated() {
+        let adapter = HtmlParserAdapter::new();
+        let html = r#"<html><body><a href="/one">One</a><a href="/two">Two</a></body></html>"#;
+        let capped = ParseOptions { include_links: true, max_elements: Some(2), ..Default::default() };
+
+        let content = adapter.parse_html_with_options(html, "https://example.com/", &capped).await.unwrap();
+        assert_eq!(content.links.unwrap().len(), 2);
+        assert_eq!(content.metadata.elements_truncated, Some(false));
+
+        let uncapped = ParseOptions { include_links: true, ..Default::default() };
+        let content = adapter.parse_html_with_options(html, "https://example.com/", &uncapped).await.unwrap();
+        assert_eq!(content.metadata.elements_truncated, None);
+        assert!(ParseOptions { max_elements: Some(2), ..Default::default() }.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_prettify_html_indents_nested_elements() {
+        let adapter = HtmlParserAdapter::new();
+        let html = r#"<!DOCTYPE html><html><head><title>T</title></head><body><div class="a" id="main"><p>Hello <b>world</b></p><br><img src="x.png" alt="X &amp; Y"></div></body></html>"#;
+
+        let pretty = adapter.prettify_html(&Html::parse_document(html));
+
+        let expected = "\
+<!DOCTYPE html>
+<html>
+  <head>
+    <title>
+      T
+    </title>
+  </head>
+  <body>
+    <div class=\"a\" id=\"main\">
+      <p>
+        Hello
+        <b>
+          world
+        </b>
+      </p>
+      <br>
+      <img alt=\"X &amp; Y\" src=\"x.png\">
+    </div>
+  </body>
+</html>
+";
+        assert_eq!(pretty, expected);
+    }
+
+    #[tokio::test]
+    async fn test_prettify_html_malformed_and_verbatim() {
+        let adapter = HtmlParserAdapter::new();
+        let html = "<div><p>Unclosed <span>text<pre>  keep\n    spacing</pre>";
+
+        let pretty = adapter.prettify_html(&Html::parse_document(html));
+
+        assert!(pretty.contains("\n      <p>\n        Unclosed\n        <span>\n          text\n"));
+        assert!(pretty.contains("<pre>  keep\n    spacing</pre>\n"));
+        assert!(pretty.trim_end().ends_with("</html>"));
+    }
+
+    #[tokio::test]
+    async fn test_parse_html_with_prettify_option() {
+        let adapter = HtmlParserAdapter::new();
+        let html = "<html><body><p>Hi</p></body></html>";
+        let options = ParseOptions {
+            prettify_html: true,
+            ..Default::default()
+        };
+
+        let content = adapter.parse_html_with_options(html, "https://example.com", &options).await.unwrap();
+        assert!(content.raw_html.contains("  <body>\n    <p>\n      Hi\n    </p>\n"));
+        assert_eq!(content.text_content, "Hi");
+
+        let content = adapter.parse_html(html, "https://example.com").await.unwrap();
+        assert_eq!(content.raw_html, html);
+    }
+
+    #[tokio::test]
+    async fn test_extract_forms_login() {
+        let adapter = HtmlParserAdapter::new();
+        let html = r#"
+            <html><body>
+                <form action="/session" method="post">
+                    <input type="hidden" name="csrf" value="abc123">
+                    <input name="username" placeholder="Username">
+                    <input type="password" name="password">
+                    <input type="submit" value="Sign in">
+                </form>
+                <form>
+                    <select name="lang"><option value="en">English</option><option value="es" selected>Español</option></select>
+                    <textarea name="bio">Hi there</textarea>
+                </form>
+            </body></html>
+        "#;
+
+        let forms = adapter.extract_forms(&Html::parse_document(html));
+
+        assert_eq!(forms.len(), 2);
+        assert_eq!(forms[0].action, Some("/session".to_string()));
+        assert_eq!(forms[0].method, "POST");
+        assert_eq!(forms[0].fields, vec![
+            FormField { name: "csrf".to_string(), field_type: "hidden".to_string(), value: Some("abc123".to_string()) },
+            FormField { name: "username".to_string(), field_type: "text".to_string(), value: None },
+            FormField { name: "password".to_string(), field_type: "password".to_string(), value: None },
+        ]);
+
+        assert_eq!(forms[1].action, None);
+        assert_eq!(forms[1].method, "GET");
+        assert_eq!(forms[1].fields, vec![
+            FormField { name: "lang".to_string(), field_type: "select".to_string(), value: Some("es".to_string()) },
+            FormField { name: "bio".to_string(), field_type: "textarea".to_string(), value: Some("Hi there".to_string()) },
+        ]);
+    }
+
+    #[tokio::test]
+    async fn test_parse_html_raw_text_preserves_whitespace() {
+        let adapter = HtmlParserAdapter::new();
+        let html = "<html><body><p>  Indented &amp; spaced  </p>\n\n\n<pre>line one\n\tline two</pre></body></html>";
+
+        let options = ParseOptions { raw_text: true, ..Default::default() };
+        let raw = adapter.parse_html_with_options(html, "https://example.com", &options).await.unwrap();
+        assert_eq!(raw.text_content, "  Indented & spaced  \n\n\nline one\n\tline two");
+
+        let cleaned = adapter.parse_html(html, "https://example.com").await.unwrap();
+        assert_eq!(cleaned.text_content, "Indented & spaced\nline one\nline two");
+    }
+
+    #[tokio::test]
+    async fn test_inline_link_urls_follow_link_text() {
+        let adapter = HtmlParserAdapter::new();
+        let options = ParseOptions { inline_link_urls: true, ..Default::default() };
+        let html = r#"<html><body><p>To install, <a href="/docs/install"><em>click</em> here </a>or <a href="mailto:help@example.com">mail us</a>.</p>
+            <a href="https://cdn.example.com/full.png"><img src="thumb.png"></a></body></html>"#;
+
+        let content = adapter.parse_html_with_options(html, "https://example.com/start", &options).await.unwrap();
+
+        assert!(content.text_content.contains("click  here (https://example.com/docs/install) or"), "{}", content.text_content);
+        assert!(content.text_content.contains("mail us ."), "{}", content.text_content);
+        assert!(content.text_content.ends_with("(https://cdn.example.com/full.png)"), "{}", content.text_content);
+
+        let raw = ParseOptions { inline_link_urls: true, raw_text: true, ..Default::default() };
+        let content = adapter.parse_html_with_options(html, "https://example.com/start", &raw).await.unwrap();
+        assert!(content.text_content.starts_with("To install, click here (https://example.com/docs/install)or mail us."), "{}", content.text_content);
+
+        // Without links the text is what it would be anyway
+        let plain = "<html><body><h1>Title</h1><p>Just  <strong>text</strong> here.</p></body></html>";
+        let with_option = adapter.parse_html_with_options(plain, "https://example.com", &options).await.unwrap();
+        let without = adapter.parse_html(plain, "https://example.com").await.unwrap();
+        assert_eq!(with_option.text_content, without.text_content);
+    }
+
+    #[tokio::test]
+    async fn test_extract_microdata_product() {
+        let adapter = HtmlParserAdapter::new();
+        let html = r#"
+            <html><body>
+                <div itemscope itemtype="https://schema.org/Product">
+                    <h1 itemprop="name">Trail   Shoe</h1>
+                    <img itemprop="image" src="/shoe.jpg" alt="">
+                    <div itemprop="offers" itemscope itemtype="https://schema.org/Offer">
+                        <span itemprop="price" content="89.99">$89.99</span>
+                        <meta itemprop="priceCurrency" content="USD">
+                        <link itemprop="availability" href="https://schema.org/InStock">
+                    </div>
+                    <div itemprop="brand" itemscope itemtype="https://schema.org/Brand">
+                        <span itemprop="name">Acme</span>
+                    </div>
+                </div>
+                <p itemprop="orphan">Not inside a scope</p>
+            </body></html>
+        "#;
+
+        let items = adapter.extract_microdata(&Html::parse_document(html));
+
+        let text = |s: &str| MicrodataValue::Text(s.to_string());
+        let property = |name: &str, value: MicrodataValue| MicrodataProperty { name: name.to_string(), value };
+
+        assert_eq!(items, vec![MicrodataItem {
+            item_type: Some("https://schema.org/Product".to_string()),
+            properties: vec![
+                property("name", text("Trail Shoe")),
+                property("image", text("/shoe.jpg")),
+                property("offers", MicrodataValue::Item(MicrodataItem {
+                    item_type: Some("https://schema.org/Offer".to_string()),
+                    properties: vec![
+                        property("price", text("89.99")),
+                        property("priceCurrency", text("USD")),
+                        property("availability", text("https://schema.org/InStock")),
+                    ],
+                })),
+                property("brand", MicrodataValue::Item(MicrodataItem {
+                    item_type: Some("https://schema.org/Brand".to_string()),
+                    properties: vec![property("name", text("Acme"))],
+                })),
+            ],
+        }]);
+    }
+
+    #[test]
+    fn test_extract_breadcrumbs_from_markup() {
+        let adapter = HtmlParserAdapter::new();
+        let html = r#"<html><body>
+            <nav aria-label="Breadcrumb">
+                <ol>
+                    <li><a href="/">Home</a></li>
+                    <li><a href="/docs/">Docs</a></li>
+                    <li aria-current="page">Installing  the CLI</li>
+                </ol>
+            </nav>
+        </body></html>"#;
+
+        let breadcrumbs = adapter.extract_breadcrumbs(&Html::parse_document(html), "https://example.com/docs/install").unwrap();
+
+        assert_eq!(breadcrumbs, vec![
+            ("Home".to_string(), Some("https://example.com/".to_string())),
+            ("Docs".to_string(), Some("https://example.com/docs/".to_string())),
+            ("Installing the CLI".to_string(), None),
+        ]);
+        assert_eq!(adapter.extract_breadcrumbs(&Html::parse_document("<html><body><p>No trail</p></body></html>"), "https://example.com/"), None);
+    }
+
+    #[tokio::test]
+    async fn test_extract_breadcrumbs_from_json_ld() {
+        let adapter = HtmlParserAdapter::new();
+        let html = r#"<html><head>
+            <script type="application/ld+json">
+            {"@context": "https://schema.org", "@graph": [
+                {"@type": "WebPage", "name": "Blue kettle"},
+                {"@type": "BreadcrumbList", "itemListElement": [
+                    {"@type": "ListItem", "position": 3, "name": "Blue kettle"},
+                    {"@type": "ListItem", "position": 1, "name": "Shop", "item": "https://shop.example.com/"},
+                    {"@type": "ListItem", "position": 2, "item": {"@id": "/kitchen", "name": "Kitchen"}}
+                ]}
+            ]}
+            </script>
+        </head><body><ol class="breadcrumb"><li><a href="/">Ignored</a></li></ol></body></html>"#;
+
+        let expected = vec![
+            ("Shop".to_string(), Some("https://shop.example.com/".to_string())),
+            ("Kitchen".to_string(), Some("https://shop.example.com/kitchen".to_string())),
+            ("Blue kettle".to_string(), None),
+        ];
+        assert_eq!(adapter.extract_breadcrumbs(&Html::parse_document(html), "https://shop.example.com/kitchen/blue-kettle"), Some(expected.clone()));
+
+        let without = adapter.parse_html(html, "https://shop.example.com/kitchen/blue-kettle").await.unwrap();
+        assert!(without.breadcrumbs.is_none());
+        let options = ParseOptions { include_breadcrumbs: true, ..Default::default() };
+        let with = adapter
+            .parse_html_with_options(html, "https://shop.example.com/kitchen/blue-kettle", &options)
+            .await
+            .unwrap();
+        assert_eq!(with.breadcrumbs, Some(expected));
+    }
+
+    #[tokio::test]
+    async fn test_output_html_fragment_returns_sanitized_main_content() {
+        let adapter = HtmlParserAdapter::new();
+        let html = r#"<html><body>
+            <nav><a href="/">Home</a><a href="/about">About</a></nav>
+            <article>
+                <h1>Release notes</h1>
+                <p onclick="track()">This release makes the parser <strong>much</strong> faster on large documents and fixes several bugs.<script>steal()</script></p>
+                <p>See the <a href="/changelog" onclick="track()">full changelog</a> for every change that went into this version.</p>
+            </article>
+            <footer>Copyright</footer>
+        </body></html>"#;
+
+        let without = adapter.parse_html(html, "https://example.com/news").await.unwrap();
+        assert!(without.content_html.is_none());
+
+        let options = ParseOptions { output_html_fragment: true, ..Default::default() };
+        let with = adapter.parse_html_with_options(html, "https://example.com/news", &options).await.unwrap();
+        let fragment = with.content_html.unwrap();
+
+        assert!(fragment.contains("<h1>Release notes</h1>"), "{}", fragment);
+        assert!(fragment.contains("<strong>much</strong>"), "{}", fragment);
+        assert!(fragment.contains(r#"<a href="https://example.com/changelog">full changelog</a>"#), "{}", fragment);
+        for removed in ["onclick", "script", "steal", "Home", "Copyright"] {
+            assert!(!fragment.contains(removed), "{} survived in {}", removed, fragment);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fragment_option_parses_bare_list() {
+        let adapter = HtmlParserAdapter::new();
+        let html = r#"<ul><li>First step</li><li><a href="/next">Next step</a></li></ul>"#;
+        let options = ParseOptions { fragment: true, include_links: true, ..Default::default() };
+
+        let content = adapter.parse_html_with_options(html, "https://example.com/guide/", &options).await.unwrap();
+
+        assert_eq!(content.text_content, "First step Next step");
+        assert_eq!(content.title, None);
+        assert_eq!(content.links, Some(vec![Link {
+            href: "https://example.com/next".to_string(),
+            text: "Next step".to_string(),
+        }]));
+        assert_eq!(content.raw_html, html);
+
+        // No implied document structure is added around the fragment
+        let document = parse_markup(html, true);
+        assert!(document.select(&Selector::parse("body").unwrap()).next().is_none());
+        assert!(parse_markup(html, false).select(&Selector::parse("body").unwrap()).next().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_rank_links_puts_content_links_before_footer_links() {
+        let adapter = HtmlParserAdapter::new();
+        let html = r#"<html><body>
+            <nav><a href="/">Home</a><a href="/pricing">Pricing</a></nav>
+            <main><article>
+                <p>Read the <a href="/guides/getting-started">getting started guide for new teams</a>
+                or the <a href="https://partner.example.org/integration">partner integration notes</a>.</p>
+            </article></main>
+            <footer><a href="/privacy">Privacy</a><a href="/terms">Terms</a></footer>
+        </body></html>"#;
+        let options = ParseOptions { include_links: true, rank_links: true, max_elements: Some(2), ..Default::default() };
+
+        let content = adapter.parse_html_with_options(html, "https://example.com/", &options).await.unwrap();
+
+        let hrefs: Vec<&str> = content.links.as_ref().unwrap().iter().map(|link| link.href.as_str()).collect();
+        assert_eq!(hrefs, vec!["https://example.com/guides/getting-started", "https://partner.example.org/integration"]);
+        assert_eq!(content.metadata.elements_truncated, Some(true));
+
+        let options = ParseOptions { include_links: true, rank_links: true, ..Default::default() };
+        let content = adapter.parse_html_with_options(html, "https://example.com/", &options).await.unwrap();
+        let hrefs: Vec<&str> = content.links.as_ref().unwrap().iter().map(|link| link.href.as_str()).collect();
+        assert_eq!(&hrefs[4..], ["https://example.com/privacy", "https://example.com/terms"]);
+    }
+
+    #[test]
+    fn test_extract_keywords_splits_keywords_meta() {
+        let html = r#"<html><head><meta name="keywords" content="rust, web scraping ,MCP,, html parsing"></head><body></body></html>"#;
+
+        assert_eq!(HtmlParserAdapter::extract_keywords(html), vec!["rust", "web scraping", "MCP", "html parsing"]);
+        assert!(HtmlParserAdapter::extract_keywords("<html><body><p>None</p></body></html>").is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_include_keywords_collects_article_tags() {
+        let adapter = HtmlParserAdapter::new();
+        let html = r#"<html><head>
+            <meta name="keywords" content="Elections, city council">
+            <meta property="article:tag" content="Local news">
+            <meta property="article:tag" content="elections">
+            <meta property="article:tag" content="Budget">
+        </head><body><p>Story</p></body></html>"#;
+
+        let without = adapter.parse_html(html, "https://news.example.com/vote").await.unwrap();
+        assert!(without.metadata.keywords.is_none());
+
+        let options = ParseOptions { include_keywords: true, ..Default::default() };
+        let with = adapter.parse_html_with_options(html, "https://news.example.com/vote", &options).await.unwrap();
+        assert_eq!(with.metadata.keywords, Some(vec![
+            "Elections".to_string(),
+            "city council".to_string(),
+            "Local news".to_string(),
+            "Budget".to_string(),
+        ]));
+    }
+
+    #[tokio::test]
+    async fn test_validate_anchors_reports_dangling_links() {
+        let adapter = HtmlParserAdapter::new();
+        let html = r##"<html><body>
+            <nav><a href="#install">Install</a> <a href="#usage">Usage</a> <a href="#missing">FAQ</a> <a href="#top">Top</a></nav>
+            <h2 id="install">Install</h2>
+            <a name="usage"></a><h2>Usage</h2>
+            <h2 id="café">Café</h2>
+            <p>See <a href="#caf%C3%A9">café</a>, <a href="https://example.com/other#nowhere">elsewhere</a> and <a href="#missing">FAQ</a> again.</p>
+        </body></html>"##;
+
+        let without = adapter.parse_html(html, "https://example.com/docs").await.unwrap();
+        assert!(without.broken_anchors.is_none());
+
+        let options = ParseOptions { validate_anchors: true, ..Default::default() };
+        let with = adapter.parse_html_with_options(html, "https://example.com/docs", &options).await.unwrap();
+        assert_eq!(with.broken_anchors, Some(vec!["#missing".to_string()]));
+
+        let valid = r##"<html><body><a href="#intro">Intro</a><section id="intro">Hi</section></body></html>"##;
+        assert!(adapter.extract_broken_anchors(&Html::parse_document(valid)).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_include_feeds_collects_rss_and_atom_links() {
+        let adapter = HtmlParserAdapter::new();
+        let html = r#"<html><head>
+            <link rel="stylesheet" type="text/css" href="/style.css">
+            <link rel="alternate" type="application/rss+xml" title="Blog (RSS)" href="/feed.xml">
+            <link rel="alternate" type="application/atom+xml" href="https://feeds.example.com/atom">
+            <link rel="alternate" hreflang="fr" type="text/html" href="/fr/">
+            <link rel="alternate" type="application/rss+xml" href="feed.xml">
+        </head><body><p>Posts</p></body></html>"#;
+
+        let without = adapter.parse_html(html, "https://example.com/blog").await.unwrap();
+        assert!(without.feeds.is_none());
+
+        let options = ParseOptions { include_feeds: true, ..Default::default() };
+        let with = adapter.parse_html_with_options(html, "https://example.com/blog", &options).await.unwrap();
+
+        assert_eq!(with.feeds, Some(vec![
+            FeedLink {
+                href: "https://example.com/feed.xml".to_string(),
+                title: Some("Blog (RSS)".to_string()),
+                feed_type: FeedType::Rss,
+            },
+            FeedLink {
+                href: "https://feeds.example.com/atom".to_string(),
+                title: None,
+                feed_type: FeedType::Atom,
+            },
+        ]));
+    }
+
+    #[tokio::test]
+    async fn test_parse_html_with_microdata_option() {
+        let adapter = HtmlParserAdapter::new();
+        let html = r#"<html><body><div itemscope><span itemprop="name">Widget</span></div></body></html>"#;
+
+        let without = adapter.parse_html(html, "https://example.com").await.unwrap();
+        assert!(without.microdata.is_none());
+
+        let options = ParseOptions { include_microdata: true, ..Default::default() };
+        let with = adapter.parse_html_with_options(html, "https://example.com", &options).await.unwrap();
+        let items = with.microdata.unwrap();
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].item_type, None);
+        assert_eq!(items[0].properties[0].value, MicrodataValue::Text("Widget".to_string()));
+    }
+
+    #[test]
+    fn test_extract_published_at_from_meta() {
+        let html = r#"<html><head>
+            <meta property="article:published_time" content="2024-03-05T08:15:00+01:00">
+            </head><body><time datetime="2020-01-01">old</time></body></html>"#;
+
+        assert_eq!(
+            HtmlParserAdapter::published_at_in(&Html::parse_document(html)),
+            Some("2024-03-05T08:15:00+01:00".to_string())
+        );
+    }
+
+    #[test]
+    fn test_extract_published_at_from_time_element() {
+        let html = r#"<html><body><article>
+            <time>yesterday</time>
+            <time datetime="2024-03-05">March 5</time>
+            </article></body></html>"#;
+
+        assert_eq!(
+            HtmlParserAdapter::published_at_in(&Html::parse_document(html)),
+            Some("2024-03-05T00:00:00Z".to_string())
+        );
+    }
+
+    #[test]
+    fn test_extract_published_at_from_json_ld() {
+        let html = r#"<html><head>
+            <script type="application/ld+json">
+                {"@context": "https://schema.org", "@graph": [
+                    {"@type": "WebPage"},
+                    {"@type": "NewsArticle", "datePublished": "2024-03-05T08:15:00"}
+                ]}
+            </script>
+            </head><body></body></html>"#;
+
+        assert_eq!(
+            HtmlParserAdapter::published_at_in(&Html::parse_document(html)),
+            Some("2024-03-05T08:15:00Z".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_parse_truncated_html_sets_warning() {
+        let adapter = HtmlParserAdapter::new();
+        let html = "<html><head><title>Cut Off</title></head><body><article><p>First paragraph.</p><p>Second para";
+
+        let content = adapter.parse_html(html, "https://example.com").await.unwrap();
+
+        assert_eq!(content.metadata.html_truncated, Some(true));
+        assert_eq!(content.title, Some("Cut Off".to_string()));
+        assert!(content.text_content.contains("First paragraph."));
+        assert!(content.text_content.contains("Second para"));
+    }
+
+    #[test]
+    fn test_is_truncated() {
+        assert!(HtmlParserAdapter::is_truncated("<html><body><div><p>Hello</p>"));
+        assert!(HtmlParserAdapter::is_truncated("<html><body><p>Hello</p><img src=\"a.png"));
+        assert!(HtmlParserAdapter::is_truncated("<p>Hello</p></"));
+        assert!(HtmlParserAdapter::is_truncated("<table><tr><td>1<td>2"));
+        assert!(HtmlParserAdapter::is_truncated("<p>Hi</p><script>var a = 1;"));
+        assert!(HtmlParserAdapter::is_truncated("<p>Hi</p><!-- a comment"));
+        assert!(!HtmlParserAdapter::is_truncated("<!DOCTYPE html><HTML><BODY><p>Hi</p></BODY></HTML>\n"));
+        assert!(!HtmlParserAdapter::is_truncated("<p>Fragments have no closing html tag</p>"));
+        assert!(!HtmlParserAdapter::is_truncated("<html><body><p>1 < 2</p></body></html>"));
+        // Valid HTML may leave out these end tags
+        assert!(!HtmlParserAdapter::is_truncated("<!DOCTYPE html><title>Hi</title><p>One<p>Two<ul><li>A<li>B</ul>"));
+        assert!(!HtmlParserAdapter::is_truncated("<html><body><div>Hi<br/><img src=x></div><svg><path d=\"M0\"/></svg>"));
+        assert!(!HtmlParserAdapter::is_truncated("<SCRIPT>if (a < b) document.write('<div>');</Script><p>Hi"));
+    }
+
+    #[tokio::test]
+    async fn test_parse_html_without_published_at() {
+        let adapter = HtmlParserAdapter::new();
+        let html = r#"<html><head><meta property="article:published_time" content="soon"></head>
+            <body><time datetime="not a date">?</time></body></html>"#;
+
+        let content = adapter.parse_html(html, "https://example.com").await.unwrap();
+
+        assert_eq!(content.metadata.published_at, None);
+    }
+
     #[tokio::test]
     async fn test_adapter_creation() {
         let _adapter = HtmlParserAdapter::new();
@@ -0,0 +1,152 @@
+use scraper::{ElementRef, Node};
+use url::Url;
+
+/// Formatting elements kept in sanitized output.
+const ALLOWED_TAGS: &[&str] = &[
+    "p", "a", "strong", "em", "ul", "ol", "li", "br", "h1", "h2", "h3", "h4", "h5", "h6",
+];
+
+/// Elements dropped together with everything inside them: executable or
+/// embedded content, and page chrome and form controls that don't belong in
+/// the main content.
+const DROPPED_TAGS: &[&str] = &[
+    "script", "style", "noscript", "template", "iframe", "object", "embed", "svg", "math",
+    "nav", "aside", "header", "footer", "form", "button", "input", "select", "textarea",
+];
+
+/// Reduces an element's content to a small set of safe formatting tags. Allowed
+/// elements are rebuilt keeping their attributes except `on*` event handlers
+/// (and `style`, when inline styles are stripped); any other element is
+/// replaced by its (sanitized) children, unless it's one of `DROPPED_TAGS`.
+/// Link targets are resolved against `base`, and links that aren't http(s)
+/// lose their `href`.
+pub struct HtmlSanitizer<'a> {
+    base: Option<&'a Url>,
+    strip_inline_styles: bool,
+}
+
+impl<'a> HtmlSanitizer<'a> {
+    pub fn new(base: Option<&'a Url>) -> Self {
+        Self { base, strip_inline_styles: false }
+    }
+
+    /// Drops `style` attributes as well as event handlers.
+    pub fn with_inline_styles_stripped(mut self, strip_inline_styles: bool) -> Self {
+        self.strip_inline_styles = strip_inline_styles;
+        self
+    }
+
+    /// The sanitized inner HTML of `element`.
+    pub fn sanitize_children(&self, element: ElementRef) -> String {
+        let mut output = String::new();
+        self.write_children(element, &mut output);
+        output.trim().to_string()
+    }
+
+    fn write_children(&self, element: ElementRef, output: &mut String) {
+        for child in element.children() {
+            match child.value() {
+                Node::Text(text) => output.push_str(&html_escape::encode_text(&**text)),
+                Node::Element(_) => {
+                    if let Some(child) = ElementRef::wrap(child) {
+                        self.write_element(child, output);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn write_element(&self, element: ElementRef, output: &mut String) {
+        let name = element.value().name();
+        if DROPPED_TAGS.contains(&name) {
+            return;
+        }
+        if !ALLOWED_TAGS.contains(&name) {
+            self.write_children(element, output);
+            return;
+        }
+        if name == "br" {
+            output.push_str("<br>");
+            return;
+        }
+
+        output.push('<');
+        output.push_str(name);
+        for (attribute, value) in element.value().attrs() {
+            let value = match attribute {
+                "href" => match self.resolve_href(value) {
+                    Some(href) if name == "a" => href,
+                    _ => continue,
+                },
+                "style" if self.strip_inline_styles => continue,
+                _ if attribute.starts_with("on") => continue,
+                _ => value.to_string(),
+            };
+            output.push_str(&format!(" {}=\"{}\"", attribute, html_escape::encode_double_quoted_attribute(&value)));
+        }
+        output.push('>');
+        self.write_children(element, output);
+        output.push_str(&format!("</{}>", name));
+    }
+
+    fn resolve_href(&self, href: &str) -> Option<String> {
+        let resolved = Url::options().base_url(self.base).parse(href.trim()).ok()?;
+        matches!(resolved.scheme(), "http" | "https").then(|| resolved.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use scraper::{Html, Selector};
+
+    fn sanitize(body: &str) -> String {
+        sanitize_with(body, false)
+    }
+
+    fn sanitize_with(body: &str, strip_inline_styles: bool) -> String {
+        let document = Html::parse_document(&format!("<html><body>{}</body></html>", body));
+        let body = document.select(&Selector::parse("body").unwrap()).next().unwrap();
+        let base = Url::parse("https://example.com/post/").unwrap();
+        HtmlSanitizer::new(Some(&base)).with_inline_styles_stripped(strip_inline_styles).sanitize_children(body)
+    }
+
+    #[test]
+    fn test_keeps_formatting_and_resolves_links() {
+        let html = r#"<h2 class="title">Intro</h2><p>Read <a href="../docs" title="Docs">the <strong>docs</strong></a>, <em>then</em> <span>try it</span>.</p><ul><li>One</li><li>Two &amp; three</li></ul>"#;
+
+        assert_eq!(
+            sanitize(html),
+            r#"<h2 class="title">Intro</h2><p>Read <a href="https://example.com/docs" title="Docs">the <strong>docs</strong></a>, <em>then</em> try it.</p><ul><li>One</li><li>Two &amp; three</li></ul>"#
+        );
+    }
+
+    #[test]
+    fn test_strips_scripts_and_unsafe_attributes() {
+        let html = r#"<p onclick="steal()" style="color:red">Hello<script>alert(1)</script></p>
+            <div onmouseover="track()"><a href="javascript:alert(1)" onclick="x()">Click</a></div>
+            <style>p { display: none }</style><iframe src="https://ads.example"></iframe><img src="x.png" onerror="boom()">"#;
+
+        let sanitized = sanitize_with(html, true);
+
+        assert_eq!(sanitized, "<p>Hello</p>\n            <a>Click</a>");
+        for unsafe_fragment in ["script", "onclick", "onmouseover", "style", "javascript:", "iframe", "onerror", "<img"] {
+            assert!(!sanitized.contains(unsafe_fragment), "{} survived in {}", unsafe_fragment, sanitized);
+        }
+    }
+
+    #[test]
+    fn test_strips_event_handlers_and_optionally_styles() {
+        let html = r#"<p class="lead" onclick="steal()" style="color:red">Hi <a href="/docs" class="more" onmouseover="track()" ONFOCUS="x()">docs</a></p>"#;
+
+        assert_eq!(
+            sanitize(html),
+            r#"<p class="lead" style="color:red">Hi <a class="more" href="https://example.com/docs">docs</a></p>"#
+        );
+        assert_eq!(
+            sanitize_with(html, true),
+            r#"<p class="lead">Hi <a class="more" href="https://example.com/docs">docs</a></p>"#
+        );
+    }
+}
@@ -0,0 +1,77 @@
+use std::io;
+use std::path::{Path, PathBuf};
+use async_trait::async_trait;
+use tokio::fs::{File, OpenOptions};
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Mutex;
+use tracing::warn;
+use domain::port::audit_sink::{AuditEntry, AuditSink};
+
+/// `AuditSink` appending each entry to a file as one JSON line. The file is
+/// created when missing and never truncated; every line is flushed as it is
+/// written, so the trail survives a crash.
+pub struct JsonlFileAuditSink {
+    path: PathBuf,
+    file: Mutex<File>,
+}
+
+impl JsonlFileAuditSink {
+    pub async fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let file = OpenOptions::new().create(true).append(true).open(&path).await?;
+        Ok(Self { path, file: Mutex::new(file) })
+    }
+}
+
+#[async_trait]
+impl AuditSink for JsonlFileAuditSink {
+    async fn record(&self, entry: AuditEntry) {
+        let mut line = match serde_json::to_vec(&entry) {
+            Ok(line) => line,
+            Err(error) => {
+                warn!("Could not serialize the audit entry for {}: {}", entry.url, error);
+                return;
+            }
+        };
+        line.push(b'\n');
+
+        // One write per line under the lock keeps concurrent entries from interleaving
+        let mut file = self.file.lock().await;
+        if let Err(error) = async { file.write_all(&line).await?; file.flush().await }.await {
+            warn!("Could not write the audit entry for {} to {}: {}", entry.url, self.path.display(), error);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(url: &str, status: Option<u16>) -> AuditEntry {
+        AuditEntry {
+            timestamp: "2026-01-02T03:04:05.678Z".to_string(),
+            url: url.to_string(),
+            status,
+            bytes: status.map(|_| 512),
+            client_id: Some("203.0.113.7".to_string()),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_entries_appended_as_json_lines() {
+        let path = std::env::temp_dir().join(format!("audit-{}.jsonl", uuid::Uuid::new_v4()));
+        std::fs::write(&path, "{\"earlier\":true}\n").unwrap();
+
+        let sink = JsonlFileAuditSink::open(&path).await.unwrap();
+        sink.record(entry("https://example.com/", Some(200))).await;
+        sink.record(entry("https://down.example/", None)).await;
+
+        let written = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let lines: Vec<&str> = written.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0], "{\"earlier\":true}");
+        assert_eq!(serde_json::from_str::<AuditEntry>(lines[1]).unwrap(), entry("https://example.com/", Some(200)));
+        assert_eq!(serde_json::from_str::<AuditEntry>(lines[2]).unwrap(), entry("https://down.example/", None));
+    }
+}
@@ -0,0 +1,328 @@
+use ego_tree::NodeRef;
+use scraper::{ElementRef, Html, Node, Selector};
+use url::Url;
+
+/// Elements dropped from the Markdown along with everything inside them.
+const SKIPPED_TAGS: &[&str] = &[
+    "head", "script", "style", "noscript", "template", "iframe", "svg", "canvas",
+];
+
+/// Elements that start a new Markdown block; everything else is rendered inline.
+const BLOCK_TAGS: &[&str] = &[
+    "address", "article", "aside", "blockquote", "body", "caption", "dd", "details",
+    "div", "dl", "dt", "fieldset", "figcaption", "figure", "footer", "form",
+    "h1", "h2", "h3", "h4", "h5", "h6", "header", "hr", "html", "li", "main", "nav",
+    "ol", "p", "pre", "section", "summary", "table", "tbody", "td", "tfoot", "th",
+    "thead", "tr", "ul",
+];
+
+/// Converts an HTML document to CommonMark-style Markdown: headings, paragraphs,
+/// emphasis, links and images (resolved against `base`), lists, block quotes,
+/// code and simple tables. Anything else contributes only its text.
+pub struct MarkdownConverter<'a> {
+    base: Option<&'a Url>,
+}
+
+impl<'a> MarkdownConverter<'a> {
+    pub fn new(base: Option<&'a Url>) -> Self {
+        Self { base }
+    }
+
+    pub fn convert(&self, document: &Html) -> String {
+        let root = Selector::parse("body")
+            .ok()
+            .and_then(|body| document.select(&body).next())
+            .unwrap_or_else(|| document.root_element());
+
+        let mut blocks = Vec::new();
+        self.render_blocks(*root, &mut blocks);
+        blocks.join("\n\n")
+    }
+
+    /// Renders the children of `parent` as Markdown blocks, gathering runs of
+    /// inline content between block elements into paragraphs.
+    fn render_blocks(&self, parent: NodeRef<Node>, blocks: &mut Vec<String>) {
+        let mut paragraph = String::new();
+
+        for child in parent.children() {
+            match ElementRef::wrap(child) {
+                Some(element) if is_block(element) => {
+                    push_paragraph(&mut paragraph, blocks);
+                    self.render_block(element, blocks);
+                }
+                _ => paragraph.push_str(&self.render_inline(child)),
+            }
+        }
+
+        push_paragraph(&mut paragraph, blocks);
+    }
+
+    fn render_block(&self, element: ElementRef, blocks: &mut Vec<String>) {
+        let name = element.value().name();
+        match name {
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                let level = name[1..].parse::<usize>().unwrap_or(1);
+                let text = normalize_inline(&self.render_children_inline(element));
+                if !text.is_empty() {
+                    blocks.push(format!("{} {}", "#".repeat(level), text.replace('\n', " ")));
+                }
+            }
+            "p" => {
+                let mut paragraph = self.render_children_inline(element);
+                push_paragraph(&mut paragraph, blocks);
+            }
+            "pre" => {
+                let code = element.text().collect::<String>();
+                let code = code.trim_matches('\n');
+                if !code.trim().is_empty() {
+                    blocks.push(format!("```\n{}\n```", code));
+                }
+            }
+            "hr" => blocks.push("---".to_string()),
+            "blockquote" => {
+                let mut inner = Vec::new();
+                self.render_blocks(*element, &mut inner);
+                if !inner.is_empty() {
+                    let quoted = inner
+                        .join("\n\n")
+                        .lines()
+                        .map(|line| if line.is_empty() { ">".to_string() } else { format!("> {}", line) })
+                        .collect::<Vec<_>>()
+                        .join("\n");
+                    blocks.push(quoted);
+                }
+            }
+            "ul" | "ol" => {
+                let lines = self.render_list(element, 0);
+                if !lines.is_empty() {
+                    blocks.push(lines.join("\n"));
+                }
+            }
+            "table" => {
+                let table = self.render_table(element);
+                if !table.is_empty() {
+                    blocks.push(table);
+                }
+            }
+            _ => self.render_blocks(*element, blocks),
+        }
+    }
+
+    /// Renders list items as `-` or `1.` lines, nesting sublists two spaces deeper.
+    fn render_list(&self, list: ElementRef, depth: usize) -> Vec<String> {
+        let ordered = list.value().name() == "ol";
+        let indent = "  ".repeat(depth);
+        let mut lines = Vec::new();
+
+        let items = list
+            .children()
+            .filter_map(ElementRef::wrap)
+            .filter(|child| child.value().name() == "li");
+
+        for (index, item) in items.enumerate() {
+            let marker = if ordered { format!("{}.", index + 1) } else { "-".to_string() };
+            let mut text = String::new();
+            let mut nested = Vec::new();
+
+            for child in item.children() {
+                match ElementRef::wrap(child) {
+                    Some(sublist) if matches!(sublist.value().name(), "ul" | "ol") => {
+                        nested.extend(self.render_list(sublist, depth + 1));
+                    }
+                    _ => text.push_str(&self.render_inline(child)),
+                }
+            }
+
+            lines.push(format!("{}{} {}", indent, marker, normalize_inline(&text).replace('\n', " ")));
+            lines.extend(nested);
+        }
+
+        lines
+    }
+
+    /// Renders a table as a pipe table, taking the first row as the header.
+    fn render_table(&self, table: ElementRef) -> String {
+        let Ok(row_selector) = Selector::parse("tr") else {
+            return String::new();
+        };
+
+        let rows: Vec<Vec<String>> = table
+            .select(&row_selector)
+            .map(|row| {
+                row.children()
+                    .filter_map(ElementRef::wrap)
+                    .filter(|cell| matches!(cell.value().name(), "th" | "td"))
+                    .map(|cell| {
+                        normalize_inline(&self.render_children_inline(cell))
+                            .replace('\n', " ")
+                            .replace('|', "\\|")
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .filter(|cells| !cells.is_empty())
+            .collect();
+
+        let Some(columns) = rows.iter().map(|cells| cells.len()).max() else {
+            return String::new();
+        };
+
+        let format_row = |cells: &[String]| {
+            let mut cells = cells.to_vec();
+            cells.resize(columns, String::new());
+            format!("| {} |", cells.join(" | "))
+        };
+
+        let mut lines = vec![format_row(&rows[0]), format!("|{}", " --- |".repeat(columns))];
+        lines.extend(rows[1..].iter().map(|cells| format_row(cells)));
+        lines.join("\n")
+    }
+
+    fn render_children_inline(&self, element: ElementRef) -> String {
+        element.children().map(|child| self.render_inline(child)).collect()
+    }
+
+    fn render_inline(&self, node: NodeRef<Node>) -> String {
+        if let Node::Text(text) = node.value() {
+            return collapse_whitespace(text);
+        }
+
+        let Some(element) = ElementRef::wrap(node) else {
+            return String::new();
+        };
+
+        match element.value().name() {
+            name if SKIPPED_TAGS.contains(&name) => String::new(),
+            "br" => "\n".to_string(),
+            "strong" | "b" => wrap_inline(&self.render_children_inline(element), "**"),
+            "em" | "i" => wrap_inline(&self.render_children_inline(element), "*"),
+            "code" => {
+                let code = element.text().collect::<String>();
+                if code.trim().is_empty() { String::new() } else { format!("`{}`", code.trim()) }
+            }
+            "a" => {
+                let text = normalize_inline(&self.render_children_inline(element)).replace('\n', " ");
+                match element.value().attr("href").and_then(|href| self.resolve(href)) {
+                    Some(href) if !text.is_empty() => format!("[{}]({})", text, href),
+                    _ => text,
+                }
+            }
+            "img" => match element.value().attr("src").and_then(|src| self.resolve(src)) {
+                Some(src) => format!("![{}]({})", element.value().attr("alt").unwrap_or("").trim(), src),
+                None => String::new(),
+            },
+            _ => self.render_children_inline(element),
+        }
+    }
+
+    fn resolve(&self, reference: &str) -> Option<String> {
+        let reference = reference.trim();
+        if reference.is_empty() || reference.starts_with("javascript:") {
+            return None;
+        }
+
+        match self.base {
+            Some(base) => base.join(reference).ok().map(|url| url.to_string()),
+            None => Some(reference.to_string()),
+        }
+    }
+}
+
+fn is_block(element: ElementRef) -> bool {
+    BLOCK_TAGS.contains(&element.value().name())
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    let mut collapsed = String::with_capacity(text.len());
+    let mut previous_space = false;
+    for c in text.chars() {
+        if c.is_whitespace() {
+            if !previous_space {
+                collapsed.push(' ');
+            }
+            previous_space = true;
+        } else {
+            collapsed.push(c);
+            previous_space = false;
+        }
+    }
+    collapsed
+}
+
+/// Trims each line of inline output (lines come from `<br>`) and drops empty ones.
+fn normalize_inline(text: &str) -> String {
+    text.split('\n')
+        .map(|line| collapse_whitespace(line).trim().to_string())
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Wraps `text` in `marker`, keeping surrounding spaces outside the markers so
+/// `<b> bold </b>` doesn't become the invalid `** bold **`.
+fn wrap_inline(text: &str, marker: &str) -> String {
+    let trimmed = text.trim();
+    if trimmed.is_empty() {
+        return text.to_string();
+    }
+
+    let leading = if text.starts_with(char::is_whitespace) { " " } else { "" };
+    let trailing = if text.ends_with(char::is_whitespace) { " " } else { "" };
+    format!("{}{}{}{}{}", leading, marker, trimmed, marker, trailing)
+}
+
+fn push_paragraph(paragraph: &mut String, blocks: &mut Vec<String>) {
+    let text = normalize_inline(paragraph);
+    if !text.is_empty() {
+        blocks.push(text);
+    }
+    paragraph.clear();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn convert(html: &str, base: Option<&str>) -> String {
+        let base = base.map(|base| Url::parse(base).unwrap());
+        MarkdownConverter::new(base.as_ref()).convert(&Html::parse_document(html))
+    }
+
+    #[test]
+    fn test_convert_headings_paragraphs_and_inline() {
+        let html = r#"<html><head><title>T</title><style>p{}</style></head><body>
+            <h1>Guide</h1>
+            <p>Read the <a href="/docs/start">getting   started</a> page, it's <strong>really</strong> <em>short</em>.</p>
+            <p>Run <code>cargo build</code><br>then test.</p>
+            <script>alert(1)</script>
+        </body></html>"#;
+
+        assert_eq!(
+            convert(html, Some("https://example.com/guide/")),
+            "# Guide\n\n\
+             Read the [getting started](https://example.com/docs/start) page, it's **really** *short*.\n\n\
+             Run `cargo build`\nthen test."
+        );
+    }
+
+    #[test]
+    fn test_convert_lists_quotes_code_and_tables() {
+        let html = r#"<body>
+            <ul><li>One</li><li>Two<ol><li>Nested</li></ol></li></ul>
+            <blockquote><p>Quoted</p><p>Twice</p></blockquote>
+            <pre>fn main() {
+    println!("hi");
+}</pre>
+            <table><tr><th>Name</th><th>Price</th></tr><tr><td>Shoe</td><td>$1|2</td></tr></table>
+            <div>Loose <b> bold </b>text<img src="a.png" alt="A"></div>
+        </body>"#;
+
+        assert_eq!(
+            convert(html, None),
+            "- One\n- Two\n  1. Nested\n\n\
+             > Quoted\n>\n> Twice\n\n\
+             ```\nfn main() {\n    println!(\"hi\");\n}\n```\n\n\
+             | Name | Price |\n| --- | --- |\n| Shoe | $1\\|2 |\n\n\
+             Loose **bold** text![A](a.png)"
+        );
+    }
+}
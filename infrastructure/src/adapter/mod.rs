@@ -1 +1,7 @@
-pub mod html_parser_adapter;
\ No newline at end of file
+pub mod article_extractor;
+pub mod html_parser_adapter;
+pub mod html_sanitizer;
+pub mod jsonl_file_audit_sink;
+pub mod markdown_converter;
+pub mod static_allowlist_guard;
+pub mod whitespace_normalizer;
\ No newline at end of file
@@ -0,0 +1,54 @@
+use async_trait::async_trait;
+use url::Url;
+use domain::port::fetch_guard::FetchGuard;
+
+/// `FetchGuard` that only lets through URLs on a fixed list of hosts. A listed
+/// host also admits its subdomains, so `example.com` allows `docs.example.com`.
+#[derive(Debug, Clone)]
+pub struct StaticAllowlistGuard {
+    hosts: Vec<String>,
+}
+
+impl StaticAllowlistGuard {
+    pub fn new(hosts: Vec<String>) -> Self {
+        Self {
+            hosts: hosts.into_iter().map(|host| host.trim().trim_end_matches('.').to_ascii_lowercase()).collect(),
+        }
+    }
+
+    fn is_allowed(&self, host: &str) -> bool {
+        let host = host.trim_end_matches('.').to_ascii_lowercase();
+        self.hosts.iter().any(|allowed| {
+            host == *allowed || host.strip_suffix(allowed.as_str()).is_some_and(|prefix| prefix.ends_with('.'))
+        })
+    }
+}
+
+#[async_trait]
+impl FetchGuard for StaticAllowlistGuard {
+    async fn allow(&self, url: &Url) -> Result<(), String> {
+        match url.host_str() {
+            Some(host) if self.is_allowed(host) => Ok(()),
+            Some(host) => Err(format!("host {} is not on the allowlist", host)),
+            None => Err("URL has no host to check against the allowlist".to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_allowlist_admits_listed_hosts_and_subdomains() {
+        let guard = StaticAllowlistGuard::new(vec!["Example.com".to_string()]);
+
+        assert!(guard.allow(&Url::parse("https://example.com/").unwrap()).await.is_ok());
+        assert!(guard.allow(&Url::parse("https://docs.example.com/guide").unwrap()).await.is_ok());
+        assert_eq!(
+            guard.allow(&Url::parse("https://notexample.com/").unwrap()).await,
+            Err("host notexample.com is not on the allowlist".to_string())
+        );
+        assert!(guard.allow(&Url::parse("https://example.org/").unwrap()).await.is_err());
+    }
+}
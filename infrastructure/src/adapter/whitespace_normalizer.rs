@@ -0,0 +1,65 @@
+use async_trait::async_trait;
+use domain::model::content::HtmlContent;
+use domain::port::content_parser::ContentParserResult;
+use domain::port::content_transformer::ContentTransformer;
+
+/// Example `ContentTransformer`: collapses runs of spaces and tabs within each
+/// line of `text_content`, trims the lines and keeps at most one blank line
+/// between paragraphs.
+pub struct WhitespaceNormalizer;
+
+impl WhitespaceNormalizer {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn normalize(&self, text: &str) -> String {
+        let mut lines: Vec<String> = Vec::new();
+
+        for line in text.lines() {
+            let line = line.split_whitespace().collect::<Vec<_>>().join(" ");
+            let previous_blank = lines.last().is_none_or(|last| last.is_empty());
+            if line.is_empty() && previous_blank {
+                continue;
+            }
+            lines.push(line);
+        }
+
+        if lines.last().is_some_and(|last| last.is_empty()) {
+            lines.pop();
+        }
+
+        lines.join("\n")
+    }
+}
+
+impl Default for WhitespaceNormalizer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl ContentTransformer for WhitespaceNormalizer {
+    async fn transform(&self, mut content: HtmlContent) -> ContentParserResult<HtmlContent> {
+        content.text_content = self.normalize(&content.text_content);
+        Ok(content)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_whitespace_normalizer() {
+        let content = HtmlContent {
+            text_content: "\n\n  Title \t here  \n\n\n\nFirst   paragraph\n   \nSecond\n\n".to_string(),
+            ..Default::default()
+        };
+
+        let content = WhitespaceNormalizer::new().transform(content).await.unwrap();
+
+        assert_eq!(content.text_content, "Title here\n\nFirst paragraph\n\nSecond");
+    }
+}
@@ -0,0 +1,143 @@
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::num::NonZeroU32;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use axum::http::HeaderMap;
+
+/// Length of a rate limiting window.
+const RATE_LIMIT_WINDOW: Duration = Duration::from_secs(60);
+
+/// Once this many clients are tracked, clients whose window has ended are forgotten.
+const MAX_TRACKED_CLIENTS: usize = 10_000;
+
+/// The address a request is attributed to for logging and rate limiting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ClientAddr(pub IpAddr);
+
+/// Determines the client behind a request. Forwarding headers are only believed
+/// when the socket peer is one of `trusted_proxies`; anyone else could set them.
+/// `X-Forwarded-For` is walked from the right past trusted proxies, and the first
+/// address that isn't one is the client: entries further left were supplied by
+/// that client and can't be trusted. `X-Real-IP` is used when `X-Forwarded-For`
+/// is missing or names only trusted proxies.
+pub fn resolve_client_addr(peer: IpAddr, headers: &HeaderMap, trusted_proxies: &[IpAddr]) -> ClientAddr {
+    if !trusted_proxies.contains(&peer) {
+        return ClientAddr(peer);
+    }
+
+    let forwarded_for = headers
+        .get_all("x-forwarded-for")
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .filter_map(|hop| hop.trim().parse::<IpAddr>().ok())
+        .collect::<Vec<_>>();
+
+    let real_ip = || {
+        headers
+            .get("x-real-ip")
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.trim().parse::<IpAddr>().ok())
+    };
+
+    let client = forwarded_for
+        .iter()
+        .rev()
+        .find(|hop| !trusted_proxies.contains(hop))
+        .copied()
+        .or_else(real_ip)
+        .unwrap_or(peer);
+
+    ClientAddr(client)
+}
+
+/// Allows each client a fixed number of requests per one-minute window.
+pub struct ClientRateLimiter {
+    max_requests: NonZeroU32,
+    windows: Mutex<HashMap<IpAddr, (Instant, u32)>>,
+}
+
+impl ClientRateLimiter {
+    pub fn per_minute(max_requests: NonZeroU32) -> Self {
+        Self {
+            max_requests,
+            windows: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Counts a request from `client`, returning whether it's within the limit.
+    pub fn check(&self, client: ClientAddr) -> bool {
+        let now = Instant::now();
+        let mut windows = self.windows.lock().unwrap();
+
+        if windows.len() >= MAX_TRACKED_CLIENTS {
+            windows.retain(|_, (started, _)| now.duration_since(*started) < RATE_LIMIT_WINDOW);
+        }
+
+        let (started, count) = windows.entry(client.0).or_insert((now, 0));
+        if now.duration_since(*started) >= RATE_LIMIT_WINDOW {
+            *started = now;
+            *count = 0;
+        }
+
+        *count += 1;
+        *count <= self.max_requests.get()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PROXY: &str = "10.0.0.1";
+
+    fn ip(addr: &str) -> IpAddr {
+        addr.parse().unwrap()
+    }
+
+    fn headers(pairs: &[(&'static str, &str)]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for (name, value) in pairs {
+            headers.append(*name, value.parse().unwrap());
+        }
+        headers
+    }
+
+    #[test]
+    fn test_headers_ignored_from_untrusted_peer() {
+        let headers = headers(&[("x-forwarded-for", "203.0.113.7"), ("x-real-ip", "203.0.113.8")]);
+
+        assert_eq!(resolve_client_addr(ip("198.51.100.2"), &headers, &[]), ClientAddr(ip("198.51.100.2")));
+        assert_eq!(
+            resolve_client_addr(ip("198.51.100.2"), &headers, &[ip(PROXY)]),
+            ClientAddr(ip("198.51.100.2"))
+        );
+    }
+
+    #[test]
+    fn test_forwarded_for_from_trusted_proxy() {
+        let trusted = [ip(PROXY), ip("10.0.0.2")];
+
+        // The client forged the first entry; the nearest untrusted hop is the real client
+        let headers = headers(&[("x-forwarded-for", "1.2.3.4, 203.0.113.7, 10.0.0.2")]);
+        assert_eq!(resolve_client_addr(ip(PROXY), &headers, &trusted), ClientAddr(ip("203.0.113.7")));
+
+        let only_proxies = self::headers(&[("x-forwarded-for", "10.0.0.2"), ("x-real-ip", "203.0.113.9")]);
+        assert_eq!(resolve_client_addr(ip(PROXY), &only_proxies, &trusted), ClientAddr(ip("203.0.113.9")));
+
+        assert_eq!(resolve_client_addr(ip(PROXY), &HeaderMap::new(), &trusted), ClientAddr(ip(PROXY)));
+    }
+
+    #[test]
+    fn test_rate_limiter_counts_per_client() {
+        let limiter = ClientRateLimiter::per_minute(NonZeroU32::new(2).unwrap());
+        let first = ClientAddr(ip("203.0.113.7"));
+        let second = ClientAddr(ip("203.0.113.8"));
+
+        assert!(limiter.check(first));
+        assert!(limiter.check(first));
+        assert!(!limiter.check(first));
+        assert!(limiter.check(second));
+    }
+}
@@ -0,0 +1,273 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tokio::sync::watch;
+use domain::model::response::BatchResponse;
+use crate::api::client_addr::ClientAddr;
+
+/// How long a batch result is replayed for its `Idempotency-Key` by default.
+pub const DEFAULT_IDEMPOTENCY_TTL: Duration = Duration::from_secs(300);
+
+/// Longest `Idempotency-Key` accepted; longer keys are rejected rather than stored.
+pub const MAX_IDEMPOTENCY_KEY_LEN: usize = 255;
+
+/// Most finished batches remembered at once by default; the oldest are
+/// forgotten first once the cache is full.
+pub const DEFAULT_IDEMPOTENCY_MAX_ENTRIES: usize = 10_000;
+
+/// A finished batch and the URLs it was requested for.
+struct StoredBatch {
+    urls: Vec<String>,
+    response: BatchResponse,
+    stored_at: Instant,
+}
+
+/// What the cache holds for a key: a batch still being fetched, which later
+/// requests with the key wait for, or its result.
+enum Entry {
+    Pending {
+        id: u64,
+        urls: Vec<String>,
+        done: watch::Receiver<()>,
+    },
+    Stored(StoredBatch),
+}
+
+impl Entry {
+    fn urls(&self) -> &[String] {
+        match self {
+            Entry::Pending { urls, .. } | Entry::Stored(StoredBatch { urls, .. }) => urls,
+        }
+    }
+}
+
+/// What the cache holds for a key.
+pub enum IdempotentLookup<'a> {
+    /// Nothing live; fetch the batch and hand the result to the claim.
+    Miss(PendingBatch<'a>),
+    /// The result of an earlier batch with the same key and URLs.
+    Hit(BatchResponse),
+    /// The key was already used for a different list of URLs.
+    Mismatch,
+}
+
+/// The right to fetch a batch for a key. Requests with the same key wait
+/// until it is completed; if it is dropped instead, e.g. because the client
+/// went away, the next of them fetches the batch itself.
+pub struct PendingBatch<'a> {
+    cache: &'a IdempotencyCache,
+    entry_key: (ClientAddr, String),
+    id: u64,
+    urls: Vec<String>,
+    done: watch::Sender<()>,
+    completed: bool,
+}
+
+impl PendingBatch<'_> {
+    /// Stores the finished batch and hands it to the requests waiting for it.
+    pub fn complete(mut self, response: BatchResponse) {
+        self.cache.store(self.entry_key.clone(), std::mem::take(&mut self.urls), response);
+        self.completed = true;
+        let _ = self.done.send(());
+    }
+}
+
+impl Drop for PendingBatch<'_> {
+    fn drop(&mut self) {
+        if self.completed {
+            return;
+        }
+        let mut entries = self.cache.entries.lock().unwrap();
+        if matches!(entries.get(&self.entry_key), Some(Entry::Pending { id, .. }) if *id == self.id) {
+            entries.remove(&self.entry_key);
+        }
+    }
+}
+
+/// Remembers batch results by client and `Idempotency-Key` for `ttl`, so a
+/// client retrying a batch gets the first result back instead of a second
+/// round of fetches, even while the first is still running. Keys are scoped
+/// to the client that sent them. At most `max_entries` results are kept.
+pub struct IdempotencyCache {
+    ttl: Duration,
+    max_entries: usize,
+    entries: Mutex<HashMap<(ClientAddr, String), Entry>>,
+    next_id: AtomicU64,
+}
+
+impl IdempotencyCache {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            max_entries: DEFAULT_IDEMPOTENCY_MAX_ENTRIES,
+            entries: Mutex::new(HashMap::new()),
+            next_id: AtomicU64::new(0),
+        }
+    }
+
+    pub fn with_max_entries(mut self, max_entries: usize) -> Self {
+        self.max_entries = max_entries;
+        self
+    }
+
+    /// Looks up `key`, waiting for the batch if it is still being fetched.
+    pub async fn lookup(&self, client: ClientAddr, key: &str, urls: &[String]) -> IdempotentLookup<'_> {
+        let entry_key = (client, key.to_string());
+
+        loop {
+            let mut done = {
+                let mut entries = self.entries.lock().unwrap();
+                if matches!(entries.get(&entry_key), Some(Entry::Stored(stored)) if stored.stored_at.elapsed() >= self.ttl) {
+                    entries.remove(&entry_key);
+                }
+
+                match entries.get(&entry_key) {
+                    Some(entry) if entry.urls() != urls => return IdempotentLookup::Mismatch,
+                    Some(Entry::Stored(stored)) => return IdempotentLookup::Hit(stored.response.clone()),
+                    Some(Entry::Pending { done, .. }) => done.clone(),
+                    None => {
+                        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+                        let (sender, done) = watch::channel(());
+                        entries.insert(entry_key.clone(), Entry::Pending { id, urls: urls.to_vec(), done });
+                        return IdempotentLookup::Miss(PendingBatch {
+                            cache: self,
+                            entry_key,
+                            id,
+                            urls: urls.to_vec(),
+                            done: sender,
+                            completed: false,
+                        });
+                    }
+                }
+            };
+            // Wakes on completion or when the claim is dropped; either way the
+            // entry has changed, so look again
+            let _ = done.changed().await;
+        }
+    }
+
+    /// Stores a finished batch, dropping any entries that have expired and,
+    /// when the cache is still full, the oldest finished ones.
+    fn store(&self, entry_key: (ClientAddr, String), urls: Vec<String>, response: BatchResponse) {
+        let now = Instant::now();
+        let mut entries = self.entries.lock().unwrap();
+        // The batch's own pending entry is replaced, not counted
+        entries.remove(&entry_key);
+        entries.retain(|_, entry| match entry {
+            Entry::Stored(stored) => now.duration_since(stored.stored_at) < self.ttl,
+            Entry::Pending { .. } => true,
+        });
+
+        while entries.len() >= self.max_entries {
+            let oldest = entries
+                .iter()
+                .filter_map(|(key, entry)| match entry {
+                    Entry::Stored(stored) => Some((key, stored.stored_at)),
+                    Entry::Pending { .. } => None,
+                })
+                .min_by_key(|(_, stored_at)| *stored_at)
+                .map(|(key, _)| key.clone());
+            match oldest {
+                Some(key) => entries.remove(&key),
+                None => break,
+            };
+        }
+
+        entries.insert(entry_key, Entry::Stored(StoredBatch { urls, response, stored_at: now }));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use domain::model::response::BatchResult;
+
+    fn client(addr: &str) -> ClientAddr {
+        ClientAddr(addr.parse().unwrap())
+    }
+
+    fn batch(url: &str) -> (Vec<String>, BatchResponse) {
+        let result = BatchResult { url: url.to_string(), content: None, error: Some("down".to_string()) };
+        (vec![url.to_string()], BatchResponse { results: vec![result] })
+    }
+
+    async fn store(cache: &IdempotencyCache, client: ClientAddr, key: &str, urls: &[String], response: BatchResponse) {
+        match cache.lookup(client, key, urls).await {
+            IdempotentLookup::Miss(pending) => pending.complete(response),
+            _ => panic!("{} should not be stored yet", key),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_lookup_scoped_by_client_urls_and_ttl() {
+        let cache = IdempotencyCache::new(Duration::from_millis(50));
+        let (urls, response) = batch("https://a.example");
+        store(&cache, client("203.0.113.7"), "key-1", &urls, response).await;
+
+        assert!(matches!(cache.lookup(client("203.0.113.7"), "key-1", &urls).await, IdempotentLookup::Hit(_)));
+        assert!(matches!(cache.lookup(client("203.0.113.8"), "key-1", &urls).await, IdempotentLookup::Miss(_)));
+        assert!(matches!(
+            cache.lookup(client("203.0.113.7"), "key-1", &["https://b.example".to_string()]).await,
+            IdempotentLookup::Mismatch
+        ));
+
+        tokio::time::sleep(Duration::from_millis(60)).await;
+        assert!(matches!(cache.lookup(client("203.0.113.7"), "key-1", &urls).await, IdempotentLookup::Miss(_)));
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_lookup_waits_for_the_pending_batch() {
+        let cache = Arc::new(IdempotencyCache::new(Duration::from_secs(60)));
+        let (urls, response) = batch("https://a.example");
+
+        let IdempotentLookup::Miss(pending) = cache.lookup(client("203.0.113.7"), "key-1", &urls).await else {
+            panic!("the first lookup should miss");
+        };
+
+        let waiter = {
+            let cache = cache.clone();
+            let urls = urls.clone();
+            tokio::spawn(async move {
+                match cache.lookup(client("203.0.113.7"), "key-1", &urls).await {
+                    IdempotentLookup::Hit(response) => Some(response),
+                    _ => None,
+                }
+            })
+        };
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(!waiter.is_finished(), "the repeat must wait for the pending batch");
+
+        pending.complete(response.clone());
+        let replayed = waiter.await.unwrap().expect("the repeat should replay the batch");
+        assert_eq!(replayed.results[0].url, response.results[0].url);
+    }
+
+    #[tokio::test]
+    async fn test_abandoned_batch_lets_the_next_request_fetch() {
+        let cache = IdempotencyCache::new(Duration::from_secs(60));
+        let (urls, _) = batch("https://a.example");
+
+        let first = cache.lookup(client("203.0.113.7"), "key-1", &urls).await;
+        drop(first);
+
+        assert!(matches!(cache.lookup(client("203.0.113.7"), "key-1", &urls).await, IdempotentLookup::Miss(_)));
+    }
+
+    #[tokio::test]
+    async fn test_oldest_batches_evicted_past_max_entries() {
+        let cache = IdempotencyCache::new(Duration::from_secs(60)).with_max_entries(2);
+        let (urls, response) = batch("https://a.example");
+
+        for key in ["key-1", "key-2", "key-3"] {
+            store(&cache, client("203.0.113.7"), key, &urls, response.clone()).await;
+            tokio::time::sleep(Duration::from_millis(2)).await;
+        }
+
+        assert_eq!(cache.entries.lock().unwrap().len(), 2);
+        assert!(matches!(cache.lookup(client("203.0.113.7"), "key-3", &urls).await, IdempotentLookup::Hit(_)));
+        assert!(matches!(cache.lookup(client("203.0.113.7"), "key-2", &urls).await, IdempotentLookup::Hit(_)));
+        assert!(matches!(cache.lookup(client("203.0.113.7"), "key-1", &urls).await, IdempotentLookup::Miss(_)));
+    }
+}
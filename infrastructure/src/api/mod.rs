@@ -1 +1,5 @@
+pub mod client_addr;
+pub mod idempotency;
+pub mod pretty_json;
+pub mod request_id;
 pub mod server;
\ No newline at end of file
@@ -0,0 +1,63 @@
+use std::convert::Infallible;
+use axum::{
+    extract::FromRequestParts,
+    http::{header, request::Parts, HeaderName, HeaderValue},
+    response::{IntoResponse, Json, Response},
+};
+use serde::Serialize;
+
+pub const PRETTY_HEADER: HeaderName = HeaderName::from_static("x-pretty");
+
+/// Whether the client asked for indented JSON, with `?pretty=true` or an
+/// `X-Pretty: true` header. Anything else, including no answer, means compact.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct JsonStyle {
+    pub pretty: bool,
+}
+
+impl JsonStyle {
+    /// `value` as JSON in this style.
+    pub fn json<T: Serialize>(self, value: T) -> ApiJson<T> {
+        ApiJson { value, pretty: self.pretty }
+    }
+}
+
+fn is_true(value: &str) -> bool {
+    matches!(value.trim().to_ascii_lowercase().as_str(), "true" | "1")
+}
+
+impl<S: Send + Sync> FromRequestParts<S> for JsonStyle {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let from_query = parts.uri.query().is_some_and(|query| {
+            url::form_urlencoded::parse(query.as_bytes()).any(|(name, value)| name == "pretty" && is_true(&value))
+        });
+        let from_header = parts
+            .headers
+            .get(&PRETTY_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .is_some_and(is_true);
+        Ok(Self { pretty: from_query || from_header })
+    }
+}
+
+/// Axum's `Json`, indented when the client asked for it.
+pub struct ApiJson<T> {
+    value: T,
+    pretty: bool,
+}
+
+impl<T: Serialize> IntoResponse for ApiJson<T> {
+    fn into_response(self) -> Response {
+        if !self.pretty {
+            return Json(self.value).into_response();
+        }
+
+        match serde_json::to_string_pretty(&self.value) {
+            Ok(body) => ([(header::CONTENT_TYPE, HeaderValue::from_static("application/json"))], body).into_response(),
+            // Fails the same way Json would
+            Err(_) => Json(self.value).into_response(),
+        }
+    }
+}
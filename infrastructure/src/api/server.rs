@@ -1,63 +1,248 @@
+use std::convert::Infallible;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::num::NonZeroU32;
+use std::ops::ControlFlow;
 use std::sync::Arc;
+use std::time::Duration;
 use axum::{
-    extract::State,
-    http::StatusCode,
-    response::Json,
+    body::Body,
+    extract::{rejection::ExtensionRejection, ConnectInfo, Extension, Query, Request, State},
+    http::{header, HeaderMap, StatusCode},
+    middleware::{self, Next},
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse, Json, Response,
+    },
     routing::{get, post},
     Router,
 };
-use tracing::{info, error};
-use tower_http::cors::CorsLayer;
+use futures::{Stream, StreamExt};
+use tokio::sync::{mpsc, Semaphore, SemaphorePermit};
+use tracing::{info, error, warn};
+use tower_http::{
+    compression::{
+        predicate::{DefaultPredicate, NotForContentType, Predicate, SizeAbove},
+        CompressionLayer,
+    },
+    cors::CorsLayer,
+};
 
 use domain::model::{
-    request::{FetchContentRequest, ApiErrorResponse, HealthResponse},
+    request::{FetchContentRequest, BatchFetchRequest, CrawlRequest, MarkdownQuery, ApiErrorResponse, HealthResponse},
+    response::{BatchResponse, BatchResult, CrawlEvent},
     content::HtmlContent,
 };
-use application::use_case::fetch_web_content_use_case::FetchWebContentUseCase;
+use application::use_case::{
+    crawl_site_use_case::CrawlSiteUseCase,
+    fetch_web_content_use_case::FetchWebContentUseCase,
+};
 use domain::port::{content_fetcher::ContentFetcher, content_parser::ContentParser};
+use crate::api::client_addr::{resolve_client_addr, ClientAddr, ClientRateLimiter};
+use crate::api::idempotency::{IdempotencyCache, IdempotentLookup, DEFAULT_IDEMPOTENCY_TTL, MAX_IDEMPOTENCY_KEY_LEN};
+use crate::api::pretty_json::{ApiJson, JsonStyle};
+use crate::api::request_id::assign_request_id;
+
+/// Responses smaller than this are sent uncompressed; the savings don't pay for the CPU.
+const COMPRESSION_MIN_BYTES: u16 = 1024;
+
+/// Most URLs accepted in a single `/api/batch` request.
+const MAX_BATCH_URLS: usize = 50;
+
+/// Pages fetched at once while serving a batch.
+const BATCH_CONCURRENCY: usize = 5;
+
+/// Media type for the streamed batch variant: one JSON `BatchResult` per line.
+const NDJSON_CONTENT_TYPE: &str = "application/x-ndjson";
+
+const IDEMPOTENCY_KEY_HEADER: &str = "idempotency-key";
+
+const MARKDOWN_CONTENT_TYPE: &str = "text/markdown; charset=utf-8";
+
+/// Longest file name stem offered for a Markdown download.
+const MAX_FILENAME_STEM_CHARS: usize = 80;
 
 pub struct ApiServer<F, P>
 where
-    F: ContentFetcher,
+    F: ContentFetcher + ?Sized,
     P: ContentParser,
 {
     use_case: Arc<FetchWebContentUseCase<F, P>>,
+    crawl_use_case: CrawlSiteUseCase<F, P>,
+    trusted_proxies: Vec<IpAddr>,
+    rate_limiter: Option<ClientRateLimiter>,
+    idempotency: IdempotencyCache,
+    sanitize_errors: bool,
+    fetch_slots: Option<Arc<Semaphore>>,
 }
 
 impl<F, P> ApiServer<F, P>
 where
-    F: ContentFetcher + Send + Sync + 'static,
+    F: ContentFetcher + ?Sized + Send + Sync + 'static,
     P: ContentParser + Send + Sync + 'static,
 {
     pub fn new(use_case: Arc<FetchWebContentUseCase<F, P>>) -> Self {
-        Self { use_case }
+        let crawl_use_case = CrawlSiteUseCase::new(use_case.clone());
+        Self {
+            use_case,
+            crawl_use_case,
+            trusted_proxies: Vec::new(),
+            rate_limiter: None,
+            idempotency: IdempotencyCache::new(DEFAULT_IDEMPOTENCY_TTL),
+            sanitize_errors: false,
+            fetch_slots: None,
+        }
+    }
+
+    /// Limits concurrent page fetches, crawls and batch items included, to the
+    /// permits of `fetch_slots`, which may be shared with other servers for one
+    /// limit across all of them.
+    pub fn with_fetch_slots(mut self, fetch_slots: Arc<Semaphore>) -> Self {
+        self.crawl_use_case = self.crawl_use_case.with_fetch_slots(fetch_slots.clone());
+        self.fetch_slots = Some(fetch_slots);
+        self
+    }
+
+    /// Waits for a fetch slot when fetches are limited; the slot is held until
+    /// the permit is dropped.
+    async fn fetch_slot(&self) -> Option<SemaphorePermit<'_>> {
+        self.fetch_slots.as_ref()?.acquire().await.ok()
+    }
+
+    /// Believe `X-Forwarded-For`/`X-Real-IP` on requests arriving from these
+    /// addresses; from anywhere else the socket peer is the client.
+    pub fn with_trusted_proxies(mut self, trusted_proxies: Vec<IpAddr>) -> Self {
+        self.trusted_proxies = trusted_proxies;
+        self
+    }
+
+    /// Answer `/api` requests beyond this many per client per minute with 429.
+    pub fn with_rate_limit(mut self, max_requests_per_minute: NonZeroU32) -> Self {
+        self.rate_limiter = Some(ClientRateLimiter::per_minute(max_requests_per_minute));
+        self
+    }
+
+    /// How long a batch result is replayed for repeats of its `Idempotency-Key`.
+    pub fn with_idempotency_ttl(mut self, ttl: Duration) -> Self {
+        self.idempotency = IdempotencyCache::new(ttl);
+        self
+    }
+
+    /// Send clients a generic message for failed fetches instead of the error
+    /// itself, which can name internal hosts, addresses or parser details. The
+    /// `error` code is unchanged and the full error is still logged.
+    pub fn with_sanitized_errors(mut self, sanitize_errors: bool) -> Self {
+        self.sanitize_errors = sanitize_errors;
+        self
+    }
+
+    /// The message a client is sent for a failed fetch.
+    fn client_message(&self, error_msg: String) -> String {
+        if self.sanitize_errors {
+            sanitized_message(&error_msg).to_string()
+        } else {
+            error_msg
+        }
     }
 
     pub fn create_router(self) -> Router {
         let shared_state = Arc::new(self);
         
         Router::new()
-            .route("/health", get(health_check))
             .route("/api/fetch", post(fetch_content))
+            .route("/api/batch", post(fetch_batch))
+            .route("/api/markdown", get(fetch_markdown))
+            .route("/api/crawl/stream", get(crawl_stream))
+            .route_layer(middleware::from_fn_with_state(shared_state.clone(), identify_client))
+            .route("/health", get(health_check))
             .with_state(shared_state)
+            .layer(
+                // Streamed NDJSON is left uncompressed so each line reaches the client as soon as it's ready
+                CompressionLayer::new().compress_when(
+                    DefaultPredicate::new()
+                        .and(SizeAbove::new(COMPRESSION_MIN_BYTES))
+                        .and(NotForContentType::const_new(NDJSON_CONTENT_TYPE)),
+                ),
+            )
             .layer(CorsLayer::permissive())
+            .layer(middleware::from_fn(assign_request_id))
+    }
+
+    async fn fetch_batch_item(&self, url: String, client: ClientAddr) -> BatchResult {
+        let request = FetchContentRequest {
+            url: url.clone(),
+            client_id: Some(client.0.to_string()),
+            ..Default::default()
+        };
+
+        let _slot = self.fetch_slot().await;
+        match self.use_case.execute_for_api(request).await {
+            Ok(content) => BatchResult { url, content: Some(content), error: None },
+            Err(error_msg) => {
+                error!("Failed to fetch batch item {}: {}", url, error_msg);
+                BatchResult { url, content: None, error: Some(self.client_message(error_msg)) }
+            }
+        }
     }
 }
 
-async fn health_check() -> Json<HealthResponse> {
+/// Attributes the request to a client, logs it and applies the per-client rate
+/// limit. Without `ConnectInfo` (i.e. not served with
+/// `into_make_service_with_connect_info`) the peer is taken to be `0.0.0.0`.
+async fn identify_client<F, P>(
+    State(server): State<Arc<ApiServer<F, P>>>,
+    connect_info: Result<ConnectInfo<SocketAddr>, ExtensionRejection>,
+    mut request: Request,
+    next: Next,
+) -> Response
+where
+    F: ContentFetcher + ?Sized + Send + Sync,
+    P: ContentParser + Send + Sync,
+{
+    let peer = connect_info
+        .map(|ConnectInfo(addr)| addr.ip())
+        .unwrap_or(IpAddr::V4(Ipv4Addr::UNSPECIFIED));
+    let client = resolve_client_addr(peer, request.headers(), &server.trusted_proxies);
+
+    info!("{} {} from {}", request.method(), request.uri().path(), client.0);
+
+    if let Some(limiter) = &server.rate_limiter {
+        if !limiter.check(client) {
+            warn!("Rate limit exceeded for {}", client.0);
+            return (
+                StatusCode::TOO_MANY_REQUESTS,
+                Json(ApiErrorResponse {
+                    error: "RATE_LIMITED".to_string(),
+                    message: "Too many requests, try again in a minute".to_string(),
+                }),
+            ).into_response();
+        }
+    }
+
+    request.extensions_mut().insert(client);
+    next.run(request).await
+}
+
+async fn health_check<F, P>(State(server): State<Arc<ApiServer<F, P>>>) -> Json<HealthResponse>
+where
+    F: ContentFetcher + ?Sized + Send + Sync,
+    P: ContentParser + Send + Sync,
+{
     Json(HealthResponse {
         status: "healthy".to_string(),
         version: "0.1.0".to_string(),
+        browser_available: server.use_case.browser_available(),
     })
 }
 
 async fn fetch_content<F, P>(
     State(server): State<Arc<ApiServer<F, P>>>,
+    Extension(client): Extension<ClientAddr>,
+    style: JsonStyle,
     Json(mut request): Json<FetchContentRequest>,
-) -> Result<Json<HtmlContent>, (StatusCode, Json<ApiErrorResponse>)>
+) -> Result<ApiJson<HtmlContent>, (StatusCode, Json<ApiErrorResponse>)>
 where
-    F: ContentFetcher + Send + Sync,
-    P: ContentParser + Send + Sync,
+    F: ContentFetcher + ?Sized + Send + Sync + 'static,
+    P: ContentParser + Send + Sync + 'static,
 {
     if request.url.is_empty() {
         return Err((
@@ -70,24 +255,15 @@ where
     }
 
     // Apply defaults for optional fields
-    request.extract_text_only = request.extract_text_only.or(Some(true));
     request.follow_redirects = request.follow_redirects.or(Some(true));
-    request.timeout_seconds = request.timeout_seconds.or(Some(30));
     request.user_agent = request.user_agent.or(Some("html-api-reader/0.1.0".to_string()));
+    request.client_id = Some(client.0.to_string());
 
-    // Convert optional fields to non-optional for internal processing
-    let internal_request = domain::model::request::FetchContentRequest {
-        url: request.url,
-        extract_text_only: request.extract_text_only,
-        follow_redirects: request.follow_redirects,
-        timeout_seconds: request.timeout_seconds,
-        user_agent: request.user_agent,
-    };
-
-    match server.use_case.execute_for_api(internal_request).await {
+    let _slot = server.fetch_slot().await;
+    match server.use_case.execute_for_api(request).await {
         Ok(content) => {
             info!("Successfully fetched content from: {}", content.url);
-            Ok(Json(content))
+            Ok(style.json(content))
         }
         Err(error_msg) => {
             error!("Failed to fetch content: {}", error_msg);
@@ -95,24 +271,300 @@ where
                 StatusCode::INTERNAL_SERVER_ERROR,
                 Json(ApiErrorResponse {
                     error: "FETCH_ERROR".to_string(),
-                    message: error_msg,
+                    message: server.client_message(error_msg),
+                })
+            ))
+        }
+    }
+}
+
+/// Fetches every URL of the batch. A JSON batch sent with an `Idempotency-Key`
+/// is remembered, and a repeat with the same key and URLs gets the stored
+/// result without fetching again; streamed batches aren't remembered.
+async fn fetch_batch<F, P>(
+    State(server): State<Arc<ApiServer<F, P>>>,
+    Extension(client): Extension<ClientAddr>,
+    style: JsonStyle,
+    headers: HeaderMap,
+    Json(batch): Json<BatchFetchRequest>,
+) -> Result<Response, (StatusCode, Json<ApiErrorResponse>)>
+where
+    F: ContentFetcher + ?Sized + Send + Sync + 'static,
+    P: ContentParser + Send + Sync + 'static,
+{
+    if batch.urls.is_empty() || batch.urls.len() > MAX_BATCH_URLS {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(ApiErrorResponse {
+                error: "INVALID_BATCH".to_string(),
+                message: format!("Batch must contain between 1 and {} URLs", MAX_BATCH_URLS),
+            })
+        ));
+    }
+
+    let wants_ndjson = headers
+        .get(header::ACCEPT)
+        .and_then(|accept| accept.to_str().ok())
+        .is_some_and(|accept| accept.contains(NDJSON_CONTENT_TYPE));
+
+    if wants_ndjson {
+        return Ok(stream_batch(server, batch.urls, client));
+    }
+
+    let idempotency_key = idempotency_key(&headers)?;
+    let mut pending = None;
+    if let Some(key) = &idempotency_key {
+        match server.idempotency.lookup(client, key, &batch.urls).await {
+            IdempotentLookup::Hit(response) => {
+                info!("Replaying batch for idempotency key {}", key);
+                return Ok(style.json(response).into_response());
+            }
+            IdempotentLookup::Mismatch => {
+                return Err((
+                    StatusCode::UNPROCESSABLE_ENTITY,
+                    Json(ApiErrorResponse {
+                        error: "IDEMPOTENCY_KEY_REUSED".to_string(),
+                        message: "Idempotency-Key was already used for a different batch".to_string(),
+                    })
+                ));
+            }
+            IdempotentLookup::Miss(claim) => pending = Some(claim),
+        }
+    }
+
+    let results = futures::stream::iter(batch.urls.clone())
+        .map(|url| server.fetch_batch_item(url, client))
+        .buffered(BATCH_CONCURRENCY)
+        .collect()
+        .await;
+    let response = BatchResponse { results };
+
+    if let Some(pending) = pending {
+        pending.complete(response.clone());
+    }
+    Ok(style.json(response).into_response())
+}
+
+/// The request's `Idempotency-Key`, if it sent a usable one.
+fn idempotency_key(headers: &HeaderMap) -> Result<Option<String>, (StatusCode, Json<ApiErrorResponse>)> {
+    let Some(value) = headers.get(IDEMPOTENCY_KEY_HEADER) else {
+        return Ok(None);
+    };
+
+    match value.to_str().map(str::trim) {
+        Ok(key) if !key.is_empty() && key.len() <= MAX_IDEMPOTENCY_KEY_LEN => Ok(Some(key.to_string())),
+        _ => Err((
+            StatusCode::BAD_REQUEST,
+            Json(ApiErrorResponse {
+                error: "INVALID_IDEMPOTENCY_KEY".to_string(),
+                message: format!("Idempotency-Key must be 1 to {} visible ASCII characters", MAX_IDEMPOTENCY_KEY_LEN),
+            })
+        )),
+    }
+}
+
+async fn fetch_markdown<F, P>(
+    State(server): State<Arc<ApiServer<F, P>>>,
+    Extension(client): Extension<ClientAddr>,
+    Query(query): Query<MarkdownQuery>,
+) -> Result<Response, (StatusCode, Json<ApiErrorResponse>)>
+where
+    F: ContentFetcher + ?Sized + Send + Sync + 'static,
+    P: ContentParser + Send + Sync + 'static,
+{
+    if query.url.is_empty() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(ApiErrorResponse {
+                error: "INVALID_URL".to_string(),
+                message: "URL cannot be empty".to_string(),
+            })
+        ));
+    }
+
+    let request = FetchContentRequest {
+        url: query.url,
+        client_id: Some(client.0.to_string()),
+        ..Default::default()
+    };
+
+    let _slot = server.fetch_slot().await;
+    match server.use_case.execute_markdown(request).await {
+        Ok((content, markdown)) => {
+            info!("Converted {} to Markdown", content.url);
+            let disposition = format!(
+                "attachment; filename=\"{}\"",
+                markdown_filename(content.title.as_deref())
+            );
+            Ok((
+                [
+                    (header::CONTENT_TYPE, MARKDOWN_CONTENT_TYPE.to_string()),
+                    (header::CONTENT_DISPOSITION, disposition),
+                ],
+                markdown,
+            ).into_response())
+        }
+        Err(error_msg) => {
+            error!("Failed to fetch Markdown: {}", error_msg);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiErrorResponse {
+                    error: "FETCH_ERROR".to_string(),
+                    message: server.client_message(error_msg),
                 })
             ))
         }
     }
 }
 
+/// Crawls from `start_url` in the background and streams progress as server-sent
+/// events: a `page` event per crawled page, `error` for pages that failed, and a
+/// final `complete` event with the number of pages crawled.
+async fn crawl_stream<F, P>(
+    State(server): State<Arc<ApiServer<F, P>>>,
+    Extension(client): Extension<ClientAddr>,
+    Query(mut request): Query<CrawlRequest>,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, (StatusCode, Json<ApiErrorResponse>)>
+where
+    F: ContentFetcher + ?Sized + Send + Sync + 'static,
+    P: ContentParser + Send + Sync + 'static,
+{
+    if let Err(message) = server.crawl_use_case.validate_request(&request) {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(ApiErrorResponse {
+                error: "INVALID_CRAWL".to_string(),
+                message,
+            })
+        ));
+    }
+
+    request.client_id = Some(client.0.to_string());
+    let (sender, receiver) = mpsc::unbounded_channel::<CrawlEvent>();
+    tokio::spawn(async move {
+        // Sending fails only once the client has gone away, which ends the crawl
+        server.crawl_use_case.crawl(&request, |event| {
+            let event = match event {
+                CrawlEvent::Error { url, message } => {
+                    error!("Failed to crawl {}: {}", url, message);
+                    CrawlEvent::Error { url, message: server.client_message(message) }
+                }
+                event => event,
+            };
+            match sender.send(event) {
+                Ok(()) => ControlFlow::Continue(()),
+                Err(_) => ControlFlow::Break(()),
+            }
+        }).await;
+    });
+
+    let events = futures::stream::unfold(receiver, |mut receiver| async move {
+        let event = receiver.recv().await?;
+        let sse_event = Event::default()
+            .event(event.name())
+            .json_data(&event)
+            .unwrap_or_default();
+        Some((Ok::<_, Infallible>(sse_event), receiver))
+    });
+
+    Ok(Sse::new(events).keep_alive(KeepAlive::default()))
+}
+
+/// The generic stand-in for a fetch error message, chosen by the kind of
+/// error its prefix names; see `FetchWebContentUseCase::execute_for_api`.
+fn sanitized_message(error_msg: &str) -> &'static str {
+    if error_msg.starts_with("Invalid parameters:") || error_msg.starts_with("Invalid URL:") {
+        "The request was not valid"
+    } else if error_msg.starts_with("Request timeout") {
+        "The page took too long to respond"
+    } else {
+        "Could not retrieve the page"
+    }
+}
+
+/// Builds a download name from the page title: lowercase ASCII letters and
+/// digits joined by single dashes, falling back to `page.md`.
+fn markdown_filename(title: Option<&str>) -> String {
+    let mut stem = String::new();
+    for c in title.unwrap_or_default().chars() {
+        if c.is_ascii_alphanumeric() {
+            if stem.len() >= MAX_FILENAME_STEM_CHARS {
+                break;
+            }
+            stem.push(c.to_ascii_lowercase());
+        } else if !stem.is_empty() && !stem.ends_with('-') {
+            stem.push('-');
+        }
+    }
+
+    let stem = stem.trim_end_matches('-');
+    if stem.is_empty() {
+        "page.md".to_string()
+    } else {
+        format!("{}.md", stem)
+    }
+}
+
+/// Fetches the batch concurrently and streams each result as an NDJSON line
+/// the moment it completes, so results arrive in completion order. Once the
+/// client disconnects, fetches that haven't started are skipped.
+fn stream_batch<F, P>(server: Arc<ApiServer<F, P>>, urls: Vec<String>, client: ClientAddr) -> Response
+where
+    F: ContentFetcher + ?Sized + Send + Sync + 'static,
+    P: ContentParser + Send + Sync + 'static,
+{
+    let (sender, receiver) = mpsc::channel::<BatchResult>(BATCH_CONCURRENCY);
+    let slots = Arc::new(Semaphore::new(BATCH_CONCURRENCY));
+
+    for url in urls {
+        let server = server.clone();
+        let sender = sender.clone();
+        let slots = slots.clone();
+        tokio::spawn(async move {
+            let Ok(_slot) = slots.clone().acquire_owned().await else {
+                return;
+            };
+            if sender.is_closed() {
+                return;
+            }
+            let result = server.fetch_batch_item(url, client).await;
+            // The client has disconnected; release everyone still waiting for a slot
+            if sender.send(result).await.is_err() {
+                slots.close();
+            }
+        });
+    }
+    drop(sender);
+
+    let lines = futures::stream::unfold(receiver, |mut receiver| async move {
+        let result = receiver.recv().await?;
+        let mut line = serde_json::to_vec(&result).unwrap_or_default();
+        line.push(b'\n');
+        Some((Ok::<_, Infallible>(line), receiver))
+    });
+
+    ([(header::CONTENT_TYPE, NDJSON_CONTENT_TYPE)], Body::from_stream(lines)).into_response()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use axum::extract::connect_info::MockConnectInfo;
     use axum::http::StatusCode;
     use axum_test::TestServer;
     use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
     use async_trait::async_trait;
     
     use domain::model::content::{ContentMetadata, HtmlContent};
     use domain::port::content_fetcher::{ContentFetcher, ContentFetcherError, ContentFetcherResult};
     use domain::port::content_parser::{ContentParser, ContentParserResult};
+    use crate::adapter::html_parser_adapter::HtmlParserAdapter;
+    use crate::mcp::server::{parse_request, McpServer};
+    use application::test_support::CapturedLogs;
+    use futures::future::join_all;
+    use std::future::IntoFuture;
+    use serde_json::json;
     use application::service::{
         content_fetch_service::ContentFetchService,
         content_parse_service::ContentParseService,
@@ -120,21 +572,43 @@ mod tests {
 
     struct MockContentFetcher {
         should_succeed: bool,
+        text_content: String,
+        raw_html: String,
+        fetches: Arc<AtomicUsize>,
     }
 
     impl MockContentFetcher {
         fn new_success() -> Self {
-            Self { should_succeed: true }
+            Self::new_with_text("Test content")
         }
 
         fn new_failure() -> Self {
-            Self { should_succeed: false }
+            Self {
+                should_succeed: false,
+                text_content: String::new(),
+                raw_html: String::new(),
+                fetches: Arc::new(AtomicUsize::new(0)),
+            }
+        }
+
+        fn new_with_text(text_content: &str) -> Self {
+            Self {
+                should_succeed: true,
+                text_content: text_content.to_string(),
+                raw_html: "<html><body>Test</body></html>".to_string(),
+                fetches: Arc::new(AtomicUsize::new(0)),
+            }
+        }
+
+        fn new_with_html(raw_html: &str) -> Self {
+            Self { raw_html: raw_html.to_string(), ..Self::new_success() }
         }
     }
 
     #[async_trait]
     impl ContentFetcher for MockContentFetcher {
         async fn fetch_content(&self, request: FetchContentRequest) -> ContentFetcherResult<HtmlContent> {
+            self.fetches.fetch_add(1, Ordering::SeqCst);
             if self.should_succeed {
                 let metadata = ContentMetadata {
                     content_type: "text/html".to_string(),
@@ -144,17 +618,19 @@ mod tests {
                     charset: Some("utf-8".to_string()),
             javascript_detected: None,
             fetch_method: None,
+                    ..Default::default()
                 };
 
                 Ok(HtmlContent {
                     url: request.url,
                     title: Some("Test Title".to_string()),
-                    text_content: "Test content".to_string(),
-                    raw_html: "<html><body>Test</body></html>".to_string(),
+                    text_content: self.text_content.clone(),
+                    raw_html: self.raw_html.clone(),
                     metadata,
+                    ..Default::default()
                 })
             } else {
-                Err(ContentFetcherError::Network("Connection failed".to_string()))
+                Err(ContentFetcherError::network("Connection failed".to_string()))
             }
         }
     }
@@ -172,6 +648,7 @@ mod tests {
                 charset: Some("utf-8".to_string()),
             javascript_detected: None,
             fetch_method: None,
+                ..Default::default()
             };
 
             Ok(HtmlContent {
@@ -180,6 +657,7 @@ mod tests {
                 text_content: "Parsed content".to_string(),
                 raw_html: raw_html.to_string(),
                 metadata,
+                ..Default::default()
             })
         }
 
@@ -189,13 +667,21 @@ mod tests {
     }
 
     fn create_test_server(should_succeed: bool) -> TestServer {
-        let fetcher = Arc::new(if should_succeed {
+        create_test_server_with_fetcher(if should_succeed {
             MockContentFetcher::new_success()
         } else {
             MockContentFetcher::new_failure()
-        });
-        let parser = Arc::new(MockContentParser);
-        
+        })
+    }
+
+    fn create_test_server_with_fetcher(fetcher: MockContentFetcher) -> TestServer {
+        create_test_server_with(fetcher, MockContentParser)
+    }
+
+    fn create_test_server_with<P: ContentParser + 'static>(fetcher: MockContentFetcher, parser: P) -> TestServer {
+        let fetcher = Arc::new(fetcher);
+        let parser = Arc::new(parser);
+
         let fetch_service = Arc::new(ContentFetchService::new(fetcher));
         let parse_service = Arc::new(ContentParseService::new(parser));
         let use_case = Arc::new(FetchWebContentUseCase::new(fetch_service, parse_service));
@@ -204,6 +690,29 @@ mod tests {
         TestServer::new(server.create_router()).unwrap()
     }
 
+    /// Rate limited to one request per client, as if every request came from `PROXY`.
+    fn create_rate_limited_server(trusted_proxies: Vec<IpAddr>) -> TestServer {
+        let fetch_service = Arc::new(ContentFetchService::new(Arc::new(MockContentFetcher::new_success())));
+        let parse_service = Arc::new(ContentParseService::new(Arc::new(MockContentParser)));
+        let use_case = Arc::new(FetchWebContentUseCase::new(fetch_service, parse_service));
+
+        let router = ApiServer::new(use_case)
+            .with_trusted_proxies(trusted_proxies)
+            .with_rate_limit(NonZeroU32::new(1).unwrap())
+            .create_router()
+            .layer(MockConnectInfo(SocketAddr::from(([10, 0, 0, 1], 40000))));
+        TestServer::new(router).unwrap()
+    }
+
+    async fn fetch_as(server: &TestServer, forwarded_for: &str) -> StatusCode {
+        server
+            .post("/api/fetch")
+            .json(&json!({ "url": "https://example.com" }))
+            .add_header("x-forwarded-for", forwarded_for)
+            .await
+            .status_code()
+    }
+
     #[tokio::test]
     async fn test_health_check() {
         let server = create_test_server(true);
@@ -215,6 +724,51 @@ mod tests {
         let health: HealthResponse = response.json();
         assert_eq!(health.status, "healthy");
         assert_eq!(health.version, "0.1.0");
+        assert!(health.browser_available);
+    }
+
+    #[tokio::test]
+    async fn test_request_id_echoed_or_generated() {
+        let server = create_test_server(true);
+
+        let response = server.get("/health").add_header("x-request-id", "trace-abc-123").await;
+        assert_eq!(response.header("x-request-id"), "trace-abc-123");
+
+        let response = server.post("/api/fetch").json(&json!({ "url": "https://example.com" })).await;
+        let generated = response.header("x-request-id");
+        assert!(uuid::Uuid::parse_str(generated.to_str().unwrap()).is_ok(), "not a UUID: {:?}", generated);
+
+        // IDs that couldn't be logged or echoed safely are replaced
+        let response = server.get("/health").add_header("x-request-id", "has spaces").await;
+        assert_ne!(response.header("x-request-id"), "has spaces");
+    }
+
+    #[tokio::test]
+    async fn test_forwarded_for_honored_from_trusted_proxy() {
+        let server = create_rate_limited_server(vec!["10.0.0.1".parse().unwrap()]);
+
+        assert_eq!(fetch_as(&server, "203.0.113.7").await, StatusCode::OK);
+        // A different forwarded client has its own allowance
+        assert_eq!(fetch_as(&server, "203.0.113.8").await, StatusCode::OK);
+        assert_eq!(fetch_as(&server, "203.0.113.7").await, StatusCode::TOO_MANY_REQUESTS);
+    }
+
+    #[tokio::test]
+    async fn test_forwarded_for_ignored_without_trusted_proxy() {
+        let server = create_rate_limited_server(Vec::new());
+
+        assert_eq!(fetch_as(&server, "203.0.113.7").await, StatusCode::OK);
+        // The header can't be used to dodge the limit: both count against the proxy's address
+        let response = server
+            .post("/api/fetch")
+            .json(&json!({ "url": "https://example.com" }))
+            .add_header("x-forwarded-for", "203.0.113.8")
+            .await;
+        assert_eq!(response.status_code(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(response.json::<ApiErrorResponse>().error, "RATE_LIMITED");
+
+        // Health checks aren't rate limited
+        assert_eq!(server.get("/health").await.status_code(), StatusCode::OK);
     }
 
     #[tokio::test]
@@ -227,6 +781,7 @@ mod tests {
             follow_redirects: Some(true),
             timeout_seconds: Some(30),
             user_agent: Some("test".to_string()),
+            ..Default::default()
         };
         
         let response = server.post("/api/fetch").json(&request).await;
@@ -248,6 +803,7 @@ mod tests {
             follow_redirects: Some(true),
             timeout_seconds: Some(30),
             user_agent: Some("test".to_string()),
+            ..Default::default()
         };
         
         let response = server.post("/api/fetch").json(&request).await;
@@ -269,6 +825,7 @@ mod tests {
             follow_redirects: None,
             timeout_seconds: None,
             user_agent: None,
+            ..Default::default()
         };
         
         let response = server.post("/api/fetch").json(&request).await;
@@ -278,4 +835,422 @@ mod tests {
         let content: HtmlContent = response.json();
         assert_eq!(content.url, "https://example.com");
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn test_fetch_content_pretty_json() {
+        let server = create_test_server(true);
+        let request = FetchContentRequest { url: "https://example.com".to_string(), ..Default::default() };
+
+        let compact = server.post("/api/fetch").json(&request).await.text();
+        assert!(!compact.contains('\n'), "{}", compact);
+        let not_pretty = server.post("/api/fetch?pretty=false").json(&request).await.text();
+        assert!(!not_pretty.contains('\n'));
+
+        for response in [
+            server.post("/api/fetch?pretty=true").json(&request).await,
+            server.post("/api/fetch").add_header("x-pretty", "true").json(&request).await,
+        ] {
+            assert_eq!(response.status_code(), StatusCode::OK);
+            assert_eq!(response.header("content-type"), "application/json");
+            let pretty = response.text();
+            assert!(pretty.starts_with("{\n  \"url\": \"https://example.com\""), "{}", pretty);
+            let content: HtmlContent = serde_json::from_str(&pretty).unwrap();
+            assert_eq!(content.title, Some("Test Title".to_string()));
+        }
+
+        let batch = server
+            .post("/api/batch?pretty=true")
+            .json(&serde_json::json!({"urls": ["https://example.com"]}))
+            .await
+            .text();
+        assert!(batch.starts_with("{\n  \"results\": ["), "{}", batch);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_content_compressed() {
+        use std::io::Read;
+
+        let large_text = "Lorem ipsum dolor sit amet. ".repeat(200);
+        let server = create_test_server_with_fetcher(MockContentFetcher::new_with_text(&large_text));
+
+        let request = FetchContentRequest {
+            url: "https://example.com".to_string(),
+            ..Default::default()
+        };
+
+        let response = server
+            .post("/api/fetch")
+            .add_header("Accept-Encoding", "gzip")
+            .json(&request)
+            .await;
+
+        assert_eq!(response.status_code(), StatusCode::OK);
+        assert_eq!(response.header("content-encoding"), "gzip");
+
+        let mut decoder = flate2::read::GzDecoder::new(response.as_bytes().as_ref());
+        let mut decompressed = String::new();
+        decoder.read_to_string(&mut decompressed).unwrap();
+
+        let content: HtmlContent = serde_json::from_str(&decompressed).unwrap();
+        assert_eq!(content.text_content, large_text);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_batch_json() {
+        let server = create_test_server(true);
+
+        let response = server
+            .post("/api/batch")
+            .json(&BatchFetchRequest {
+                urls: vec!["https://a.example".to_string(), "https://b.example".to_string()],
+            })
+            .await;
+
+        assert_eq!(response.status_code(), StatusCode::OK);
+        let batch: BatchResponse = response.json();
+        let urls: Vec<_> = batch.results.iter().map(|r| r.url.as_str()).collect();
+        assert_eq!(urls, ["https://a.example", "https://b.example"]);
+        assert!(batch.results.iter().all(|r| r.content.is_some() && r.error.is_none()));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_batch_reports_errors_per_url() {
+        let server = create_test_server(false);
+
+        let response = server
+            .post("/api/batch")
+            .json(&BatchFetchRequest { urls: vec!["https://a.example".to_string()] })
+            .await;
+
+        assert_eq!(response.status_code(), StatusCode::OK);
+        let batch: BatchResponse = response.json();
+        assert!(batch.results[0].content.is_none());
+        assert!(batch.results[0].error.as_ref().unwrap().contains("Connection failed"));
+    }
+
+    #[tokio::test]
+    async fn test_sanitized_errors_logged_in_full() {
+        let (logs, _guard) = CapturedLogs::capture(tracing::Level::INFO);
+
+        let fetch_service = Arc::new(ContentFetchService::new(Arc::new(MockContentFetcher::new_failure())));
+        let parse_service = Arc::new(ContentParseService::new(Arc::new(MockContentParser)));
+        let use_case = Arc::new(FetchWebContentUseCase::new(fetch_service, parse_service));
+        let server = TestServer::new(ApiServer::new(use_case).with_sanitized_errors(true).create_router()).unwrap();
+
+        let response = server.post("/api/fetch").json(&json!({ "url": "https://internal.example" })).await;
+        assert_eq!(response.status_code(), StatusCode::INTERNAL_SERVER_ERROR);
+        let error: ApiErrorResponse = response.json();
+        assert_eq!(error.error, "FETCH_ERROR");
+        assert_eq!(error.message, "Could not retrieve the page");
+
+        let response = server
+            .post("/api/batch")
+            .json(&BatchFetchRequest { urls: vec!["https://internal.example".to_string()] })
+            .await;
+        let batch: BatchResponse = response.json();
+        assert_eq!(batch.results[0].error.as_deref(), Some("Could not retrieve the page"));
+
+        let response = server
+            .get("/api/crawl/stream")
+            .add_query_param("start_url", "https://internal.example/")
+            .await;
+        let error_frame = response.text().split("\n\n").find(|frame| frame.starts_with("event: error")).unwrap().to_string();
+        let data = error_frame.lines().find_map(|line| line.strip_prefix("data: ")).unwrap();
+        assert_eq!(
+            serde_json::from_str::<CrawlEvent>(data).unwrap(),
+            CrawlEvent::Error { url: "https://internal.example/".to_string(), message: "Could not retrieve the page".to_string() }
+        );
+
+        let output = logs.output();
+        assert!(output.contains("Failed to fetch content: Network error: Connection failed"), "{}", output);
+        assert!(output.contains("Failed to fetch batch item https://internal.example: Network error: Connection failed"), "{}", output);
+        assert!(output.contains("Failed to crawl https://internal.example/: Network error: Connection failed"), "{}", output);
+
+        assert_eq!(sanitized_message("Invalid URL: ftp://internal"), "The request was not valid");
+        assert_eq!(sanitized_message("Request timeout after 30 seconds"), "The page took too long to respond");
+        assert_eq!(sanitized_message("Parse error: unexpected token"), "Could not retrieve the page");
+    }
+
+    #[tokio::test]
+    async fn test_fetch_batch_idempotency_key_fetches_once() {
+        let fetcher = MockContentFetcher::new_success();
+        let fetches = fetcher.fetches.clone();
+        let server = create_test_server_with_fetcher(fetcher);
+        let batch = json!({ "urls": ["https://a.example", "https://b.example"] });
+
+        let first = server.post("/api/batch").json(&batch).add_header("idempotency-key", "retry-42").await;
+        let second = server.post("/api/batch").json(&batch).add_header("idempotency-key", "retry-42").await;
+
+        assert_eq!(fetches.load(Ordering::SeqCst), 2, "the repeat must not fetch again");
+        assert_eq!(second.status_code(), StatusCode::OK);
+        assert_eq!(second.json::<serde_json::Value>(), first.json::<serde_json::Value>());
+
+        // Without a key, or with a new one, the batch is fetched again
+        server.post("/api/batch").json(&batch).await;
+        server.post("/api/batch").json(&batch).add_header("idempotency-key", "retry-43").await;
+        assert_eq!(fetches.load(Ordering::SeqCst), 6);
+
+        let reused = server
+            .post("/api/batch")
+            .json(&json!({ "urls": ["https://c.example"] }))
+            .add_header("idempotency-key", "retry-42")
+            .await;
+        assert_eq!(reused.status_code(), StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(reused.json::<ApiErrorResponse>().error, "IDEMPOTENCY_KEY_REUSED");
+    }
+
+    #[tokio::test]
+    async fn test_fetch_batch_rejects_empty() {
+        let server = create_test_server(true);
+
+        let response = server
+            .post("/api/batch")
+            .json(&BatchFetchRequest { urls: vec![] })
+            .await;
+
+        assert_eq!(response.status_code(), StatusCode::BAD_REQUEST);
+        let error: ApiErrorResponse = response.json();
+        assert_eq!(error.error, "INVALID_BATCH");
+    }
+
+    #[tokio::test]
+    async fn test_fetch_batch_ndjson_stream() {
+        let server = create_test_server(true);
+        let urls: Vec<String> = (0..7).map(|i| format!("https://example.com/{}", i)).collect();
+
+        let response = server
+            .post("/api/batch")
+            .add_header("Accept", NDJSON_CONTENT_TYPE)
+            .json(&BatchFetchRequest { urls: urls.clone() })
+            .await;
+
+        assert_eq!(response.status_code(), StatusCode::OK);
+        assert_eq!(response.header("content-type"), NDJSON_CONTENT_TYPE);
+
+        let text = response.text();
+        let results: Vec<BatchResult> = text
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(results.len(), urls.len());
+
+        let mut received: Vec<_> = results.iter().map(|r| r.url.clone()).collect();
+        received.sort();
+        let mut expected = urls;
+        expected.sort();
+        assert_eq!(received, expected);
+        assert!(results.iter().all(|r| r.content.is_some()));
+    }
+
+    #[tokio::test]
+    async fn test_stream_batch_skips_fetches_after_disconnect() {
+        let fetcher = MockContentFetcher::new_success();
+        let fetches = fetcher.fetches.clone();
+        let fetch_service = Arc::new(ContentFetchService::new(Arc::new(fetcher)));
+        let parse_service = Arc::new(ContentParseService::new(Arc::new(MockContentParser)));
+        let server = Arc::new(ApiServer::new(Arc::new(FetchWebContentUseCase::new(fetch_service, parse_service))));
+        let urls = (0..7).map(|i| format!("https://example.com/{}", i)).collect();
+
+        // Dropping the response is what a disconnecting client does to the body
+        drop(stream_batch(server, urls, ClientAddr(IpAddr::V4(Ipv4Addr::LOCALHOST))));
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        assert_eq!(fetches.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_markdown_download() {
+        let html = r#"<html><head><title>Test Title</title></head><body>
+            <h1>Release Notes</h1>
+            <p>See the <a href="/changelog">full changelog</a>.</p>
+            <ul><li>Faster</li><li>Smaller</li></ul>
+        </body></html>"#;
+        let server = create_test_server_with(MockContentFetcher::new_with_html(html), HtmlParserAdapter::new());
+
+        let response = server
+            .get("/api/markdown")
+            .add_query_param("url", "https://example.com/notes")
+            .await;
+
+        assert_eq!(response.status_code(), StatusCode::OK);
+        assert_eq!(response.header("content-type"), MARKDOWN_CONTENT_TYPE);
+        assert_eq!(response.header("content-disposition"), "attachment; filename=\"test-title.md\"");
+        assert_eq!(
+            response.text(),
+            "# Release Notes\n\n\
+             See the [full changelog](https://example.com/changelog).\n\n\
+             - Faster\n- Smaller"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_fetch_content_text_and_markdown_formats() {
+        let html = r#"<html><body><h2>Hours</h2><p>Open <em>daily</em>.</p></body></html>"#;
+        let server = create_test_server_with(MockContentFetcher::new_with_html(html), HtmlParserAdapter::new());
+
+        let response = server
+            .post("/api/fetch")
+            .json(&json!({ "url": "https://example.com", "output_formats": ["text", "markdown"] }))
+            .await;
+
+        assert_eq!(response.status_code(), StatusCode::OK);
+        let content: HtmlContent = response.json();
+        let alternates = content.alternate_contents.unwrap();
+        assert_eq!(content.text_content, "Test content");
+        assert_eq!(alternates["text"], "Test content");
+        assert_eq!(alternates["markdown"], "## Hours\n\nOpen *daily*.");
+    }
+
+    #[tokio::test]
+    async fn test_crawl_stream_emits_pages_then_complete() {
+        let html = r##"<html><head><title>Site</title></head><body>
+            <a href="/a">A</a> <a href="/b">B</a> <a href="#top">Top</a> <a href="https://other.example/">Elsewhere</a>
+        </body></html>"##;
+        let server = create_test_server_with(MockContentFetcher::new_with_html(html), HtmlParserAdapter::new());
+
+        let response = server
+            .get("/api/crawl/stream")
+            .add_query_param("start_url", "https://example.com/")
+            .add_query_param("max_pages", 5)
+            .await;
+
+        assert_eq!(response.status_code(), StatusCode::OK);
+        assert_eq!(response.header("content-type"), "text/event-stream");
+
+        let events: Vec<(String, CrawlEvent)> = response
+            .text()
+            .split("\n\n")
+            .filter(|frame| !frame.trim().is_empty())
+            .map(|frame| {
+                let field = |name: &str| frame
+                    .lines()
+                    .find_map(|line| line.strip_prefix(name))
+                    .unwrap()
+                    .to_string();
+                (field("event: "), serde_json::from_str(&field("data: ")).unwrap())
+            })
+            .collect();
+
+        let mut pages: Vec<_> = events
+            .iter()
+            .filter_map(|(name, event)| match event {
+                CrawlEvent::Page { url, title } => {
+                    assert_eq!(name, "page");
+                    assert_eq!(title.as_deref(), Some("Test Title"));
+                    Some(url.as_str())
+                }
+                _ => None,
+            })
+            .collect();
+        pages.sort();
+        assert_eq!(pages, vec!["https://example.com/", "https://example.com/a", "https://example.com/b"]);
+        assert_eq!(events.last().unwrap(), &("complete".to_string(), CrawlEvent::Complete { total: 3 }));
+    }
+
+    #[tokio::test]
+    async fn test_crawl_stream_rejects_invalid_max_pages() {
+        let server = create_test_server(true);
+
+        let response = server
+            .get("/api/crawl/stream")
+            .add_query_param("start_url", "https://example.com/")
+            .add_query_param("max_pages", 0)
+            .await;
+
+        assert_eq!(response.status_code(), StatusCode::BAD_REQUEST);
+        let error: ApiErrorResponse = response.json();
+        assert_eq!(error.error, "INVALID_CRAWL");
+    }
+
+    #[tokio::test]
+    async fn test_fetch_markdown_empty_url() {
+        let server = create_test_server(true);
+
+        let response = server.get("/api/markdown").add_query_param("url", "").await;
+
+        assert_eq!(response.status_code(), StatusCode::BAD_REQUEST);
+        let error: ApiErrorResponse = response.json();
+        assert_eq!(error.error, "INVALID_URL");
+    }
+
+    #[test]
+    fn test_markdown_filename() {
+        assert_eq!(markdown_filename(Some("Rust 2024: What's New?")), "rust-2024-what-s-new.md");
+        assert_eq!(markdown_filename(Some("  ")), "page.md");
+        assert_eq!(markdown_filename(Some("\"quoted\"/..")), "quoted.md");
+        assert_eq!(markdown_filename(None), "page.md");
+    }
+
+    #[tokio::test]
+    async fn test_fetch_content_small_response_not_compressed() {
+        let server = create_test_server(true);
+
+        let request = FetchContentRequest {
+            url: "https://example.com".to_string(),
+            ..Default::default()
+        };
+
+        let response = server
+            .post("/api/fetch")
+            .add_header("Accept-Encoding", "gzip")
+            .json(&request)
+            .await;
+
+        assert_eq!(response.status_code(), StatusCode::OK);
+        assert!(response.maybe_header("content-encoding").is_none());
+        let content: HtmlContent = response.json();
+        assert_eq!(content.text_content, "Test content");
+    }
+
+    /// Takes a while over each fetch, recording the most fetches it saw at once.
+    #[derive(Default)]
+    struct GaugedFetcher {
+        in_flight: AtomicUsize,
+        max_in_flight: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl ContentFetcher for GaugedFetcher {
+        async fn fetch_content(&self, request: FetchContentRequest) -> ContentFetcherResult<HtmlContent> {
+            let in_flight = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            self.max_in_flight.fetch_max(in_flight, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            self.in_flight.fetch_sub(1, Ordering::SeqCst);
+            Ok(HtmlContent {
+                url: request.url,
+                metadata: ContentMetadata { status_code: 200, ..Default::default() },
+                ..Default::default()
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fetch_slots_shared_with_mcp_server() {
+        let fetcher = Arc::new(GaugedFetcher::default());
+        let fetch_service = Arc::new(ContentFetchService::new(fetcher.clone()));
+        let parse_service = Arc::new(ContentParseService::new(Arc::new(MockContentParser)));
+        let use_case = Arc::new(FetchWebContentUseCase::new(fetch_service, parse_service));
+        let fetch_slots = Arc::new(Semaphore::new(2));
+        let api = TestServer::new(ApiServer::new(use_case.clone()).with_fetch_slots(fetch_slots.clone()).create_router()).unwrap();
+        let mcp = McpServer::new(use_case).with_fetch_slots(fetch_slots);
+
+        let api_fetches = join_all((0..4).map(|page| {
+            api.post("/api/fetch").json(&json!({ "url": format!("https://api.example/{}", page) })).into_future()
+        }));
+        let mcp_fetches = join_all((0..4).map(|page| {
+            let request = parse_request(&json!({
+                "jsonrpc": "2.0",
+                "id": page,
+                "method": "tools/call",
+                "params": {"name": "fetch_web_content", "arguments": {"url": format!("https://mcp.example/{}", page)}}
+            }))
+            .unwrap();
+            mcp.handle_request(request)
+        }));
+        let (api_responses, mcp_responses) = tokio::join!(api_fetches, mcp_fetches);
+
+        assert!(api_responses.iter().all(|response| response.status_code() == StatusCode::OK));
+        assert!(mcp_responses.iter().all(|response| response["result"]["success"] == true));
+        assert_eq!(fetcher.max_in_flight.load(Ordering::SeqCst), 2);
+    }
+}
@@ -0,0 +1,242 @@
+use std::sync::Arc;
+use async_trait::async_trait;
+use chrono::{NaiveDateTime, SecondsFormat};
+use serde_json::Value;
+use tracing::{info, warn};
+use url::Url;
+use domain::model::{content::HtmlContent, request::FetchContentRequest};
+use domain::port::content_fetcher::{ContentFetcher, ContentFetcherError, ContentFetcherResult};
+
+/// The Wayback Machine's API for looking up the snapshot closest to now.
+pub const WAYBACK_AVAILABILITY_URL: &str = "http://archive.org/wayback/available";
+
+/// A snapshot reported by the availability API.
+#[derive(Debug, Clone, PartialEq)]
+struct Snapshot {
+    url: String,
+    /// RFC3339, when the 14-digit Wayback timestamp could be read.
+    timestamp: Option<String>,
+}
+
+/// Wraps another fetcher and, for requests with `archive_fallback`, answers a
+/// fetch that failed because the origin is unreachable or erroring (connection
+/// errors, timeouts and 5xx statuses) with the Wayback Machine's closest
+/// snapshot of the page. Both the snapshot lookup and the snapshot itself are
+/// fetched through the wrapped fetcher; when either fails, or there is no
+/// snapshot, the original error is returned.
+pub struct ArchiveFallbackFetcher<F: ContentFetcher + ?Sized> {
+    inner: Arc<F>,
+    availability_url: String,
+}
+
+impl<F: ContentFetcher + ?Sized> ArchiveFallbackFetcher<F> {
+    pub fn new(inner: Arc<F>) -> Self {
+        Self {
+            inner,
+            availability_url: WAYBACK_AVAILABILITY_URL.to_string(),
+        }
+    }
+
+    /// Looks snapshots up at another endpoint speaking the Wayback availability API.
+    pub fn with_availability_url(mut self, availability_url: impl Into<String>) -> Self {
+        self.availability_url = availability_url.into();
+        self
+    }
+
+    async fn closest_snapshot(&self, request: &FetchContentRequest) -> Option<Snapshot> {
+        let lookup_url = Url::parse_with_params(&self.availability_url, &[("url", request.url.as_str())]).ok()?;
+        let lookup = FetchContentRequest {
+            url: lookup_url.to_string(),
+            timeout_seconds: request.timeout_seconds,
+            user_agent: request.user_agent.clone(),
+            ..Default::default()
+        };
+
+        match self.inner.fetch_static(lookup).await {
+            Ok(response) => parse_availability(&response.raw_html),
+            Err(error) => {
+                warn!("Wayback lookup for {} failed: {}", request.url, error);
+                None
+            }
+        }
+    }
+
+    async fn fetch_snapshot(&self, request: FetchContentRequest, error: ContentFetcherError) -> ContentFetcherResult<HtmlContent> {
+        let Some(snapshot) = self.closest_snapshot(&request).await else {
+            return Err(error);
+        };
+
+        info!("{} failed ({}), using the archived copy from {}", request.url, error, snapshot.url);
+        let snapshot_request = FetchContentRequest {
+            url: snapshot.url.clone(),
+            archive_fallback: None,
+            ..request
+        };
+        let mut content = self.inner.fetch_content(snapshot_request).await.map_err(|snapshot_error| {
+            warn!("Fetching the archived copy {} failed: {}", snapshot.url, snapshot_error);
+            error
+        })?;
+        content.metadata.from_archive = Some(true);
+        content.metadata.archive_timestamp = snapshot.timestamp;
+        content.metadata.effective_url = Some(snapshot.url);
+        Ok(content)
+    }
+}
+
+/// Whether the error says the origin is down rather than that the page is
+/// missing or the request was wrong.
+fn is_origin_failure(error: &ContentFetcherError) -> bool {
+    match error {
+        ContentFetcherError::Network { .. } | ContentFetcherError::Timeout(_) => true,
+        ContentFetcherError::Http { status, .. } => *status >= 500,
+        ContentFetcherError::InvalidUrl(_) | ContentFetcherError::Parse(_) => false,
+    }
+}
+
+/// Reads `archived_snapshots.closest` from an availability API response.
+fn parse_availability(body: &str) -> Option<Snapshot> {
+    let response: Value = serde_json::from_str(body).ok()?;
+    let closest = response.get("archived_snapshots")?.get("closest")?;
+    if closest.get("available").and_then(Value::as_bool) == Some(false) {
+        return None;
+    }
+
+    let url = closest.get("url")?.as_str()?.to_string();
+    let timestamp = closest
+        .get("timestamp")
+        .and_then(Value::as_str)
+        .and_then(|timestamp| NaiveDateTime::parse_from_str(timestamp, "%Y%m%d%H%M%S").ok())
+        .map(|captured| captured.and_utc().to_rfc3339_opts(SecondsFormat::Secs, true));
+    Some(Snapshot { url, timestamp })
+}
+
+#[async_trait]
+impl<F: ContentFetcher + ?Sized> ContentFetcher for ArchiveFallbackFetcher<F> {
+    async fn fetch_content(&self, request: FetchContentRequest) -> ContentFetcherResult<HtmlContent> {
+        // A snapshot is a page to read, not somewhere to post an upload
+        if !request.archive_fallback.unwrap_or(false) || request.multipart.is_some() {
+            return self.inner.fetch_content(request).await;
+        }
+
+        match self.inner.fetch_content(request.clone()).await {
+            Err(error) if is_origin_failure(&error) => self.fetch_snapshot(request, error).await,
+            result => result,
+        }
+    }
+
+    async fn fetch_static(&self, request: FetchContentRequest) -> ContentFetcherResult<HtmlContent> {
+        self.inner.fetch_static(request).await
+    }
+
+    async fn shutdown(&self) {
+        self.inner.shutdown().await
+    }
+
+    fn cached_urls(&self) -> Vec<String> {
+        self.inner.cached_urls()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    const AVAILABILITY_URL: &str = "https://archive.test/wayback/available";
+    const SNAPSHOT_URL: &str = "https://archive.test/web/20240315083000/https://down.example/post";
+
+    /// Serves fixed responses by URL; any other URL fails with a network error.
+    struct MockFetcher {
+        responses: HashMap<String, ContentFetcherResult<String>>,
+    }
+
+    impl MockFetcher {
+        fn new(responses: Vec<(String, ContentFetcherResult<String>)>) -> Arc<Self> {
+            Arc::new(Self { responses: responses.into_iter().collect() })
+        }
+    }
+
+    #[async_trait]
+    impl ContentFetcher for MockFetcher {
+        async fn fetch_content(&self, request: FetchContentRequest) -> ContentFetcherResult<HtmlContent> {
+            let body = self
+                .responses
+                .get(&request.url)
+                .cloned()
+                .unwrap_or_else(|| Err(ContentFetcherError::network(format!("no route to {}", request.url))))?;
+            Ok(HtmlContent {
+                url: request.url,
+                raw_html: body,
+                ..Default::default()
+            })
+        }
+    }
+
+    fn lookup_url(page: &str) -> String {
+        Url::parse_with_params(AVAILABILITY_URL, &[("url", page)]).unwrap().to_string()
+    }
+
+    fn request(url: &str, archive_fallback: Option<bool>) -> FetchContentRequest {
+        FetchContentRequest {
+            url: url.to_string(),
+            archive_fallback,
+            ..Default::default()
+        }
+    }
+
+    fn down_origin(primary: ContentFetcherError, availability: &str) -> ArchiveFallbackFetcher<MockFetcher> {
+        let inner = MockFetcher::new(vec![
+            ("https://down.example/post".to_string(), Err(primary)),
+            (lookup_url("https://down.example/post"), Ok(availability.to_string())),
+            (SNAPSHOT_URL.to_string(), Ok("<html><body>Archived post</body></html>".to_string())),
+        ]);
+        ArchiveFallbackFetcher::new(inner).with_availability_url(AVAILABILITY_URL)
+    }
+
+    fn available_snapshot() -> String {
+        serde_json::json!({
+            "url": "https://down.example/post",
+            "archived_snapshots": {
+                "closest": {"status": "200", "available": true, "url": SNAPSHOT_URL, "timestamp": "20240315083000"}
+            }
+        })
+        .to_string()
+    }
+
+    #[tokio::test]
+    async fn test_failed_primary_falls_back_to_snapshot() {
+        let fetcher = down_origin(ContentFetcherError::Http { status: 503, message: "Service Unavailable".to_string() }, &available_snapshot());
+
+        let content = fetcher.fetch_content(request("https://down.example/post", Some(true))).await.unwrap();
+
+        assert_eq!(content.raw_html, "<html><body>Archived post</body></html>");
+        assert_eq!(content.metadata.from_archive, Some(true));
+        assert_eq!(content.metadata.archive_timestamp, Some("2024-03-15T08:30:00Z".to_string()));
+        assert_eq!(content.metadata.effective_url, Some(SNAPSHOT_URL.to_string()));
+
+        let connection_refused = down_origin(ContentFetcherError::network("connection refused"), &available_snapshot());
+        let content = connection_refused.fetch_content(request("https://down.example/post", Some(true))).await.unwrap();
+        assert_eq!(content.metadata.from_archive, Some(true));
+    }
+
+    #[tokio::test]
+    async fn test_original_error_kept_without_fallback() {
+        let not_found = ContentFetcherError::Http { status: 404, message: "Not Found".to_string() };
+        let unavailable = ContentFetcherError::Http { status: 503, message: "Service Unavailable".to_string() };
+
+        // Not requested
+        let fetcher = down_origin(unavailable.clone(), &available_snapshot());
+        let error = fetcher.fetch_content(request("https://down.example/post", None)).await.unwrap_err();
+        assert!(matches!(error, ContentFetcherError::Http { status: 503, .. }));
+
+        // The page is missing rather than the origin down
+        let fetcher = down_origin(not_found, &available_snapshot());
+        let error = fetcher.fetch_content(request("https://down.example/post", Some(true))).await.unwrap_err();
+        assert!(matches!(error, ContentFetcherError::Http { status: 404, .. }));
+
+        // Never archived
+        let fetcher = down_origin(unavailable, r#"{"url": "https://down.example/post", "archived_snapshots": {}}"#);
+        let error = fetcher.fetch_content(request("https://down.example/post", Some(true))).await.unwrap_err();
+        assert!(matches!(error, ContentFetcherError::Http { status: 503, .. }));
+    }
+}
@@ -0,0 +1,666 @@
+use std::collections::{HashMap, HashSet};
+use std::num::NonZeroUsize;
+use std::sync::{Arc, Mutex};
+use std::time::{Instant, SystemTime};
+use async_trait::async_trait;
+use chrono::{DateTime, SecondsFormat, Utc};
+use futures::future::{BoxFuture, FutureExt, Shared};
+use futures::stream::{self, StreamExt};
+use lru::LruCache;
+use tokio::sync::Semaphore;
+use tracing::debug;
+use url::Url;
+use domain::model::{content::HtmlContent, request::{FetchContentRequest, ParseOptions}};
+use domain::port::audit_sink::{AuditEntry, AuditSink};
+use domain::port::content_fetcher::{ContentFetcher, ContentFetcherResult};
+use domain::port::content_parser::ContentParser;
+use domain::port::fetch_guard::FetchGuard;
+use crate::adapter::html_parser_adapter::HtmlParserAdapter;
+
+/// Links prefetched at once for a single page.
+const PREFETCH_CONCURRENCY: usize = 4;
+
+/// Limits for the in-memory page cache.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CacheConfig {
+    pub max_entries: NonZeroUsize,
+    /// Upper bound on the summed `raw_html` + `text_content` length of all entries.
+    pub max_bytes: Option<usize>,
+    /// Whether concurrent misses for the same request share one fetch.
+    pub single_flight: bool,
+}
+
+/// A cached page and when it was fetched.
+struct CachedPage {
+    content: HtmlContent,
+    fetched_at: Instant,
+}
+
+/// LRU store that evicts the least recently used pages once either the entry
+/// count or the byte budget is exceeded.
+struct CacheStore {
+    entries: LruCache<String, CachedPage>,
+    max_bytes: Option<usize>,
+    total_bytes: usize,
+}
+
+impl CacheStore {
+    fn new(config: CacheConfig) -> Self {
+        Self {
+            entries: LruCache::new(config.max_entries),
+            max_bytes: config.max_bytes,
+            total_bytes: 0,
+        }
+    }
+
+    fn entry_size(content: &HtmlContent) -> usize {
+        content.raw_html.len() + content.text_content.len()
+    }
+
+    /// The cached content marked as served from cache, with its age.
+    fn get(&mut self, key: &str) -> Option<HtmlContent> {
+        self.entries.get(key).map(|page| {
+            let mut content = page.content.clone();
+            content.metadata.from_cache = Some(true);
+            content.metadata.cache_age_seconds = Some(page.fetched_at.elapsed().as_secs());
+            content
+        })
+    }
+
+    fn insert(&mut self, key: String, content: HtmlContent) {
+        let size = Self::entry_size(&content);
+        if self.max_bytes.is_some_and(|max| size > max) {
+            debug!("Not caching {} ({} bytes exceeds the cache budget)", content.url, size);
+            return;
+        }
+
+        // `push` hands back whatever it displaced: the old value for this key,
+        // or the least recently used entry when the cache is full.
+        self.total_bytes += size;
+        if let Some((_, displaced)) = self.entries.push(key, CachedPage { content, fetched_at: Instant::now() }) {
+            self.total_bytes -= Self::entry_size(&displaced.content);
+        }
+
+        if let Some(max) = self.max_bytes {
+            while self.total_bytes > max {
+                match self.entries.pop_lru() {
+                    Some((_, evicted)) => self.total_bytes -= Self::entry_size(&evicted.content),
+                    None => break,
+                }
+            }
+        }
+    }
+}
+
+/// A fetch that every concurrent caller for the same key awaits.
+type InFlightFetch = Shared<BoxFuture<'static, ContentFetcherResult<HtmlContent>>>;
+
+/// Wraps another fetcher and serves repeated requests from memory. Requests
+/// are keyed on every field, so different options never share an entry.
+/// With `single_flight`, requests that miss while the same request is being
+/// fetched wait for that fetch instead of starting their own, and all receive
+/// its result. A request with `prefetch_links` also fills the cache with that
+/// many of the page's same-origin links in the background.
+pub struct CachingContentFetcher<F: ContentFetcher + ?Sized> {
+    inner: Arc<F>,
+    store: Arc<Mutex<CacheStore>>,
+    single_flight: bool,
+    in_flight: Arc<Mutex<HashMap<String, InFlightFetch>>>,
+    fetch_guard: Option<Arc<dyn FetchGuard>>,
+    fetch_slots: Option<Arc<Semaphore>>,
+    audit_sink: Option<Arc<dyn AuditSink>>,
+}
+
+impl<F: ContentFetcher + ?Sized + 'static> CachingContentFetcher<F> {
+    pub fn new(inner: Arc<F>, config: CacheConfig) -> Self {
+        Self {
+            inner,
+            store: Arc::new(Mutex::new(CacheStore::new(config))),
+            single_flight: config.single_flight,
+            in_flight: Arc::new(Mutex::new(HashMap::new())),
+            fetch_guard: None,
+            fetch_slots: None,
+            audit_sink: None,
+        }
+    }
+
+    /// Skips prefetching links the guard denies. Requests made by callers are
+    /// checked before they get here, but prefetched links are not.
+    pub fn with_fetch_guard(mut self, guard: Arc<dyn FetchGuard>) -> Self {
+        self.fetch_guard = Some(guard);
+        self
+    }
+
+    /// Makes each prefetch hold one of `slots` while it runs, so background
+    /// fetches count towards the same concurrency limit as requested ones.
+    pub fn with_fetch_slots(mut self, slots: Arc<Semaphore>) -> Self {
+        self.fetch_slots = Some(slots);
+        self
+    }
+
+    /// Records each prefetched URL in `sink`, attributed to the client whose
+    /// request led to it. Requested fetches are recorded by the use case.
+    pub fn with_audit_sink(mut self, sink: Arc<dyn AuditSink>) -> Self {
+        self.audit_sink = Some(sink);
+        self
+    }
+
+    /// Fetches `request` and caches the result.
+    async fn fetch_and_store(inner: Arc<F>, store: Arc<Mutex<CacheStore>>, key: String, request: FetchContentRequest) -> ContentFetcherResult<HtmlContent> {
+        let mut content = inner.fetch_content(request).await?;
+        content.metadata.from_cache = Some(false);
+        store.lock().unwrap().insert(key, content.clone());
+        Ok(content)
+    }
+
+    /// Joins the fetch already running for `key`, or starts one that others
+    /// can join. Returns the result and whether this call started the fetch.
+    /// The fetch removes itself from the in-flight table when it finishes; if
+    /// every caller gives up first, the next caller for `key` resumes it.
+    async fn fetch_single_flight(&self, key: String, request: FetchContentRequest) -> (ContentFetcherResult<HtmlContent>, bool) {
+        let url = request.url.clone();
+        let (fetch, started) = {
+            let mut in_flight = self.in_flight.lock().unwrap();
+            match in_flight.get(&key) {
+                Some(fetch) => (fetch.clone(), false),
+                None => {
+                    let fetch = Self::fetch_and_store(self.inner.clone(), self.store.clone(), key.clone(), request);
+                    let in_flight_table = self.in_flight.clone();
+                    let entry_key = key.clone();
+                    let fetch = async move {
+                        let result = fetch.await;
+                        in_flight_table.lock().unwrap().remove(&entry_key);
+                        result
+                    }
+                    .boxed()
+                    .shared();
+                    in_flight.insert(key, fetch.clone());
+                    (fetch, true)
+                }
+            }
+        };
+
+        if !started {
+            debug!("Joining the in-flight fetch for {}", url);
+        }
+        (fetch.await, started)
+    }
+
+    fn cache_key(request: &FetchContentRequest) -> String {
+        serde_json::to_string(request).unwrap_or_else(|_| request.url.clone())
+    }
+
+    /// Fetches the first `count` distinct links of `page` on its own origin into
+    /// the cache, with the options of the request that fetched it, so following
+    /// one of them with the same options is a cache hit. Sharing the page's
+    /// scheme, host and port, the links pass the same URL checks the page did.
+    /// Runs in a background task, a few links at a time; failures are only logged.
+    fn spawn_prefetch(&self, page: HtmlContent, request: FetchContentRequest, count: usize) {
+        let inner = self.inner.clone();
+        let store = self.store.clone();
+        let fetch_guard = self.fetch_guard.clone();
+        let fetch_slots = self.fetch_slots.clone();
+        let audit_sink = self.audit_sink.clone();
+
+        tokio::spawn(async move {
+            let links = same_origin_links(&page, count).await;
+            debug!("Prefetching {} links from {}", links.len(), page.url);
+
+            stream::iter(links)
+                .for_each_concurrent(PREFETCH_CONCURRENCY, |link| {
+                    let inner = inner.clone();
+                    let store = store.clone();
+                    let fetch_guard = fetch_guard.clone();
+                    let fetch_slots = fetch_slots.clone();
+                    let audit_sink = audit_sink.clone();
+                    let request = FetchContentRequest {
+                        url: link.to_string(),
+                        prefetch_links: None,
+                        ..request.clone()
+                    };
+                    async move {
+                        let key = Self::cache_key(&request);
+                        if store.lock().unwrap().entries.contains(&key) {
+                            return;
+                        }
+                        if let Some(guard) = &fetch_guard {
+                            if let Err(reason) = guard.allow(&link).await {
+                                debug!("Not prefetching {}: {}", link, reason);
+                                return;
+                            }
+                        }
+                        let _slot = match &fetch_slots {
+                            Some(slots) => match slots.clone().acquire_owned().await {
+                                Ok(permit) => Some(permit),
+                                Err(_) => return,
+                            },
+                            None => None,
+                        };
+                        let client_id = request.client_id.clone();
+                        let result = inner.fetch_content(request).await;
+                        if let Some(sink) = &audit_sink {
+                            let timestamp = DateTime::<Utc>::from(SystemTime::now()).to_rfc3339_opts(SecondsFormat::Millis, true);
+                            sink.record(AuditEntry::for_fetch(timestamp, link.to_string(), client_id, &result)).await;
+                        }
+                        match result {
+                            Ok(mut content) => {
+                                content.metadata.from_cache = Some(false);
+                                store.lock().unwrap().insert(key, content);
+                            }
+                            Err(error) => debug!("Prefetch failed: {}", error),
+                        }
+                    }
+                })
+                .await;
+        });
+    }
+}
+
+/// The first `count` distinct links on the page's origin, other than the page itself.
+async fn same_origin_links(page: &HtmlContent, count: usize) -> Vec<Url> {
+    let Ok(page_url) = Url::parse(&page.url) else {
+        return Vec::new();
+    };
+
+    let links = match &page.links {
+        Some(links) => links.clone(),
+        None => {
+            let options = ParseOptions { include_links: true, ..Default::default() };
+            HtmlParserAdapter::new()
+                .parse_html_with_options(&page.raw_html, &page.url, &options)
+                .await
+                .ok()
+                .and_then(|parsed| parsed.links)
+                .unwrap_or_default()
+        }
+    };
+
+    let mut seen = HashSet::from([page_url.clone()]);
+    links
+        .into_iter()
+        .filter_map(|link| {
+            let mut url = Url::parse(&link.href).ok()?;
+            url.set_fragment(None);
+            (url.origin() == page_url.origin()).then_some(url)
+        })
+        .filter(|url| seen.insert(url.clone()))
+        .take(count)
+        .collect()
+}
+
+#[async_trait]
+impl<F: ContentFetcher + ?Sized + 'static> ContentFetcher for CachingContentFetcher<F> {
+    async fn fetch_content(&self, request: FetchContentRequest) -> ContentFetcherResult<HtmlContent> {
+        // Uploads are not idempotent, so they are never answered from the cache
+        if request.multipart.is_some() {
+            return self.inner.fetch_content(request).await;
+        }
+        let key = Self::cache_key(&request);
+
+        if let Some(content) = self.store.lock().unwrap().get(&key) {
+            debug!("Cache hit for {}", request.url);
+            return Ok(content);
+        }
+
+        let prefetch_request = request.prefetch_links.filter(|count| *count > 0).map(|count| (request.clone(), count));
+        let (result, started) = if self.single_flight {
+            self.fetch_single_flight(key, request).await
+        } else {
+            (Self::fetch_and_store(self.inner.clone(), self.store.clone(), key, request).await, true)
+        };
+        let content = result?;
+
+        // Only the caller that started the fetch prefetches its links
+        if let Some((request, count)) = prefetch_request.filter(|_| started) {
+            self.spawn_prefetch(content.clone(), request, count);
+        }
+        Ok(content)
+    }
+
+    async fn fetch_static(&self, request: FetchContentRequest) -> ContentFetcherResult<HtmlContent> {
+        self.inner.fetch_static(request).await
+    }
+
+    async fn shutdown(&self) {
+        self.inner.shutdown().await
+    }
+
+    fn cached_urls(&self) -> Vec<String> {
+        let store = self.store.lock().unwrap();
+        let mut urls: Vec<String> = Vec::new();
+        // Entries for the same page fetched with different options share a URL
+        for (_, page) in store.entries.iter() {
+            if !urls.contains(&page.content.url) {
+                urls.push(page.content.url.clone());
+            }
+        }
+        urls
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Returns a page whose `raw_html` and `text_content` are each `page_size`
+    /// bytes, after `delay`.
+    struct CountingFetcher {
+        calls: AtomicUsize,
+        page_size: usize,
+        delay: std::time::Duration,
+    }
+
+    impl CountingFetcher {
+        fn new(page_size: usize) -> Self {
+            Self { calls: AtomicUsize::new(0), page_size, delay: std::time::Duration::ZERO }
+        }
+
+        fn slow(delay: std::time::Duration) -> Self {
+            Self { delay, ..Self::new(10) }
+        }
+
+        fn calls(&self) -> usize {
+            self.calls.load(Ordering::SeqCst)
+        }
+    }
+
+    #[async_trait]
+    impl ContentFetcher for CountingFetcher {
+        async fn fetch_content(&self, request: FetchContentRequest) -> ContentFetcherResult<HtmlContent> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            tokio::time::sleep(self.delay).await;
+            Ok(HtmlContent {
+                url: request.url,
+                raw_html: "h".repeat(self.page_size),
+                text_content: "t".repeat(self.page_size),
+                ..Default::default()
+            })
+        }
+    }
+
+    fn request(url: &str) -> FetchContentRequest {
+        FetchContentRequest {
+            url: url.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn caching(inner: &Arc<CountingFetcher>, max_entries: usize, max_bytes: Option<usize>) -> CachingContentFetcher<CountingFetcher> {
+        CachingContentFetcher::new(inner.clone(), CacheConfig {
+            max_entries: NonZeroUsize::new(max_entries).unwrap(),
+            max_bytes,
+            single_flight: true,
+        })
+    }
+
+    #[tokio::test]
+    async fn test_repeated_request_served_from_cache() {
+        let inner = Arc::new(CountingFetcher::new(10));
+        let fetcher = caching(&inner, 4, None);
+
+        fetcher.fetch_content(request("https://a.example")).await.unwrap();
+        let content = fetcher.fetch_content(request("https://a.example")).await.unwrap();
+
+        assert_eq!(content.url, "https://a.example");
+        assert_eq!(inner.calls(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_identical_requests_share_one_fetch() {
+        let inner = Arc::new(CountingFetcher::slow(std::time::Duration::from_millis(50)));
+        let fetcher = caching(&inner, 4, None);
+
+        let results = futures::future::join_all((0..5).map(|_| fetcher.fetch_content(request("https://a.example")))).await;
+
+        assert_eq!(inner.calls(), 1);
+        for result in results {
+            let content = result.unwrap();
+            assert_eq!(content.url, "https://a.example");
+            assert_eq!(content.metadata.from_cache, Some(false));
+        }
+        assert!(fetcher.in_flight.lock().unwrap().is_empty());
+
+        // Without single flight every concurrent miss goes to the origin
+        let inner = Arc::new(CountingFetcher::slow(std::time::Duration::from_millis(50)));
+        let fetcher = CachingContentFetcher::new(inner.clone(), CacheConfig {
+            max_entries: NonZeroUsize::new(4).unwrap(),
+            max_bytes: None,
+            single_flight: false,
+        });
+        futures::future::join_all((0..5).map(|_| fetcher.fetch_content(request("https://a.example")))).await;
+        assert_eq!(inner.calls(), 5);
+    }
+
+    #[tokio::test]
+    async fn test_cache_hits_report_source_and_age() {
+        let inner = Arc::new(CountingFetcher::new(10));
+        let fetcher = caching(&inner, 4, None);
+
+        let miss = fetcher.fetch_content(request("https://a.example")).await.unwrap();
+        assert_eq!(miss.metadata.from_cache, Some(false));
+        assert_eq!(miss.metadata.cache_age_seconds, None);
+
+        // Pretend the page was fetched a minute and a half ago
+        for (_, page) in fetcher.store.lock().unwrap().entries.iter_mut() {
+            page.fetched_at -= std::time::Duration::from_secs(90);
+        }
+
+        let hit = fetcher.fetch_content(request("https://a.example")).await.unwrap();
+        assert_eq!(hit.metadata.from_cache, Some(true));
+        let age = hit.metadata.cache_age_seconds.unwrap();
+        assert!((90..95).contains(&age), "age {}", age);
+    }
+
+    #[tokio::test]
+    async fn test_evicts_least_recently_used_entry() {
+        let inner = Arc::new(CountingFetcher::new(10));
+        let fetcher = caching(&inner, 2, None);
+
+        fetcher.fetch_content(request("https://a.example")).await.unwrap();
+        fetcher.fetch_content(request("https://b.example")).await.unwrap();
+        // Touch `a` so `b` becomes the least recently used entry
+        fetcher.fetch_content(request("https://a.example")).await.unwrap();
+        fetcher.fetch_content(request("https://c.example")).await.unwrap();
+        assert_eq!(inner.calls(), 3);
+
+        fetcher.fetch_content(request("https://a.example")).await.unwrap();
+        assert_eq!(inner.calls(), 3, "a should still be cached");
+
+        fetcher.fetch_content(request("https://b.example")).await.unwrap();
+        assert_eq!(inner.calls(), 4, "b should have been evicted");
+    }
+
+    #[tokio::test]
+    async fn test_byte_budget_triggers_eviction() {
+        // Each page accounts for 20 bytes, so only two fit in 50
+        let inner = Arc::new(CountingFetcher::new(10));
+        let fetcher = caching(&inner, 10, Some(50));
+
+        for url in ["https://a.example", "https://b.example", "https://c.example"] {
+            fetcher.fetch_content(request(url)).await.unwrap();
+        }
+
+        {
+            let store = fetcher.store.lock().unwrap();
+            assert_eq!(store.entries.len(), 2);
+            assert_eq!(store.total_bytes, 40);
+            assert!(store.entries.iter().all(|(_, page)| page.content.url != "https://a.example"));
+        }
+
+        fetcher.fetch_content(request("https://a.example")).await.unwrap();
+        assert_eq!(inner.calls(), 4, "a should have been evicted for exceeding the byte budget");
+    }
+
+    #[tokio::test]
+    async fn test_cached_urls_most_recent_first() {
+        let inner = Arc::new(CountingFetcher::new(10));
+        let fetcher = caching(&inner, 4, None);
+
+        fetcher.fetch_content(request("https://a.example")).await.unwrap();
+        fetcher.fetch_content(request("https://b.example")).await.unwrap();
+        fetcher.fetch_content(FetchContentRequest {
+            include_links: Some(true),
+            ..request("https://a.example")
+        }).await.unwrap();
+
+        assert_eq!(fetcher.cached_urls(), vec!["https://a.example", "https://b.example"]);
+    }
+
+    #[tokio::test]
+    async fn test_page_larger_than_budget_not_cached() {
+        let inner = Arc::new(CountingFetcher::new(100));
+        let fetcher = caching(&inner, 10, Some(50));
+
+        fetcher.fetch_content(request("https://a.example")).await.unwrap();
+        fetcher.fetch_content(request("https://a.example")).await.unwrap();
+
+        assert_eq!(inner.calls(), 2);
+        assert_eq!(fetcher.store.lock().unwrap().total_bytes, 0);
+    }
+
+    /// Serves pages whose HTML links to `/a`, `/b`, `/c`, an anchor on the page,
+    /// another host, and the same host on another scheme and port.
+    struct LinkedPagesFetcher;
+
+    #[async_trait]
+    impl ContentFetcher for LinkedPagesFetcher {
+        async fn fetch_content(&self, request: FetchContentRequest) -> ContentFetcherResult<HtmlContent> {
+            Ok(HtmlContent {
+                raw_html: r##"<html><body>
+                    <a href="#top">Top</a>
+                    <a href="https://other.example/">Elsewhere</a>
+                    <a href="http://site.example/plain">Plain</a> <a href="https://site.example:8443/admin">Admin</a>
+                    <a href="/a">A</a> <a href="/a#more">A again</a> <a href="/b">B</a> <a href="/c">C</a>
+                </body></html>"##.to_string(),
+                url: request.url,
+                ..Default::default()
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_prefetched_links_appear_in_cache() {
+        let fetcher = CachingContentFetcher::new(Arc::new(LinkedPagesFetcher), CacheConfig {
+            max_entries: NonZeroUsize::new(10).unwrap(),
+            max_bytes: None,
+            single_flight: true,
+        });
+
+        let content = fetcher.fetch_content(FetchContentRequest {
+            prefetch_links: Some(2),
+            ..request("https://site.example/")
+        }).await.unwrap();
+        assert_eq!(content.metadata.from_cache, Some(false));
+
+        let deadline = Instant::now() + std::time::Duration::from_secs(2);
+        while fetcher.cached_urls().len() < 3 && Instant::now() < deadline {
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+
+        let mut cached = fetcher.cached_urls();
+        cached.sort();
+        assert_eq!(cached, vec!["https://site.example/", "https://site.example/a", "https://site.example/b"]);
+
+        // Prefetched with the original request's options, so following a link hits the cache
+        let followed = fetcher.fetch_content(request("https://site.example/a")).await.unwrap();
+        assert_eq!(followed.metadata.from_cache, Some(true));
+    }
+
+    async fn wait_for_cached(fetcher: &CachingContentFetcher<LinkedPagesFetcher>, count: usize) -> Vec<String> {
+        let deadline = Instant::now() + std::time::Duration::from_secs(2);
+        while fetcher.cached_urls().len() < count && Instant::now() < deadline {
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        let mut cached = fetcher.cached_urls();
+        cached.sort();
+        cached
+    }
+
+    /// Denies every URL whose path is `/a`.
+    struct DenyPathA;
+
+    #[async_trait]
+    impl FetchGuard for DenyPathA {
+        async fn allow(&self, url: &Url) -> Result<(), String> {
+            if url.path() == "/a" { Err("denied".to_string()) } else { Ok(()) }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_prefetch_skips_links_the_guard_denies() {
+        let fetcher = CachingContentFetcher::new(Arc::new(LinkedPagesFetcher), CacheConfig {
+            max_entries: NonZeroUsize::new(10).unwrap(),
+            max_bytes: None,
+            single_flight: true,
+        })
+        .with_fetch_guard(Arc::new(DenyPathA));
+
+        fetcher.fetch_content(FetchContentRequest {
+            prefetch_links: Some(3),
+            ..request("https://site.example/")
+        }).await.unwrap();
+
+        let cached = wait_for_cached(&fetcher, 3).await;
+        assert_eq!(cached, vec!["https://site.example/", "https://site.example/b", "https://site.example/c"]);
+    }
+
+    #[tokio::test]
+    async fn test_prefetch_waits_for_a_fetch_slot() {
+        let slots = Arc::new(Semaphore::new(0));
+        let fetcher = CachingContentFetcher::new(Arc::new(LinkedPagesFetcher), CacheConfig {
+            max_entries: NonZeroUsize::new(10).unwrap(),
+            max_bytes: None,
+            single_flight: true,
+        })
+        .with_fetch_slots(slots.clone());
+
+        fetcher.fetch_content(FetchContentRequest {
+            prefetch_links: Some(2),
+            ..request("https://site.example/")
+        }).await.unwrap();
+
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        assert_eq!(fetcher.cached_urls(), vec!["https://site.example/"]);
+
+        slots.add_permits(1);
+        let cached = wait_for_cached(&fetcher, 3).await;
+        assert_eq!(cached, vec!["https://site.example/", "https://site.example/a", "https://site.example/b"]);
+    }
+
+    #[derive(Default)]
+    struct RecordingSink {
+        entries: Mutex<Vec<AuditEntry>>,
+    }
+
+    #[async_trait]
+    impl AuditSink for RecordingSink {
+        async fn record(&self, entry: AuditEntry) {
+            self.entries.lock().unwrap().push(entry);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_prefetches_are_audited() {
+        let sink = Arc::new(RecordingSink::default());
+        let fetcher = CachingContentFetcher::new(Arc::new(LinkedPagesFetcher), CacheConfig {
+            max_entries: NonZeroUsize::new(10).unwrap(),
+            max_bytes: None,
+            single_flight: true,
+        })
+        .with_audit_sink(sink.clone());
+
+        fetcher.fetch_content(FetchContentRequest {
+            prefetch_links: Some(2),
+            client_id: Some("203.0.113.7".to_string()),
+            ..request("https://site.example/")
+        }).await.unwrap();
+        wait_for_cached(&fetcher, 3).await;
+
+        let mut entries = sink.entries.lock().unwrap().clone();
+        entries.sort_by(|a, b| a.url.cmp(&b.url));
+        let urls: Vec<&str> = entries.iter().map(|entry| entry.url.as_str()).collect();
+        assert_eq!(urls, vec!["https://site.example/a", "https://site.example/b"]);
+        assert!(entries.iter().all(|entry| entry.client_id.as_deref() == Some("203.0.113.7")));
+    }
+}
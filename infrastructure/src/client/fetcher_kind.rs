@@ -0,0 +1,125 @@
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+use domain::port::content_fetcher::{ContentFetcher, ContentFetcherError};
+
+use super::browser_client::{BrowserContentFetcher, BrowserLimits};
+use super::http_client::HttpClient;
+use super::hybrid_fetcher::HybridContentFetcher;
+
+/// Which `ContentFetcher` implementation the servers are wired with at startup.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FetcherKind {
+    /// Plain HTTP requests only; no Chrome required.
+    Static,
+    /// Always render pages in headless Chrome.
+    Browser,
+    /// Fetch statically and fall back to Chrome for JavaScript-heavy pages.
+    #[default]
+    Hybrid,
+}
+
+impl FetcherKind {
+    /// Whether this fetcher renders pages in Chrome.
+    pub fn uses_browser(self) -> bool {
+        self != FetcherKind::Static
+    }
+
+    /// `http_client` makes the static requests of every fetcher: all of the
+    /// static fetcher's, the hybrid fetcher's first try, and those forced to
+    /// static on the browser fetcher.
+    pub async fn build(self, browser_limits: BrowserLimits, http_client: HttpClient) -> Result<Arc<dyn ContentFetcher>, ContentFetcherError> {
+        Ok(match self {
+            FetcherKind::Static => Arc::new(http_client),
+            FetcherKind::Browser => Arc::new(BrowserContentFetcher::with_limits(browser_limits).await?.with_http_client(http_client)),
+            FetcherKind::Hybrid => Arc::new(HybridContentFetcher::new(None, browser_limits).await?.with_http_client(http_client)),
+        })
+    }
+}
+
+impl FromStr for FetcherKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "static" => Ok(FetcherKind::Static),
+            "browser" => Ok(FetcherKind::Browser),
+            "hybrid" => Ok(FetcherKind::Hybrid),
+            other => Err(format!("unknown fetcher '{}', expected static, browser or hybrid", other)),
+        }
+    }
+}
+
+impl fmt::Display for FetcherKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            FetcherKind::Static => "static",
+            FetcherKind::Browser => "browser",
+            FetcherKind::Hybrid => "hybrid",
+        };
+        f.write_str(name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{response::Html, routing::get, Router};
+    use serde_json::json;
+    use domain::model::request::McpRequest;
+    use application::service::{
+        content_fetch_service::ContentFetchService,
+        content_parse_service::ContentParseService,
+    };
+    use application::use_case::fetch_web_content_use_case::FetchWebContentUseCase;
+    use crate::adapter::html_parser_adapter::HtmlParserAdapter;
+    use crate::mcp::server::McpServer;
+    use crate::test_support::spawn_server;
+
+    async fn spawn_page_server() -> String {
+        let app = Router::new().route("/", get(|| async {
+            Html("<html><head><title>Selected</title></head><body><p>Hello</p></body></html>")
+        }));
+        format!("{}/", spawn_server(app).await)
+    }
+
+    async fn fetch_title_with(fetcher: Arc<dyn ContentFetcher>, url: &str) -> serde_json::Value {
+        // The page server listens on a random port
+        let fetch_service = Arc::new(ContentFetchService::new(fetcher).with_allowed_ports(None));
+        let parse_service = Arc::new(ContentParseService::new(Arc::new(HtmlParserAdapter::new())));
+        let use_case = Arc::new(FetchWebContentUseCase::new(fetch_service, parse_service));
+        let server: McpServer<dyn ContentFetcher, HtmlParserAdapter> = McpServer::new(use_case);
+
+        let response = server.handle_request(McpRequest {
+            id: "1".to_string(),
+            method: "tools/call".to_string(),
+            params: json!({ "name": "fetch_web_content", "arguments": { "url": url } }),
+        }).await;
+
+        response["result"]["content"]["title"].clone()
+    }
+
+    #[test]
+    fn test_fetcher_kind_from_str() {
+        assert_eq!("static".parse::<FetcherKind>(), Ok(FetcherKind::Static));
+        assert_eq!("Browser".parse::<FetcherKind>(), Ok(FetcherKind::Browser));
+        assert_eq!("hybrid".parse::<FetcherKind>(), Ok(FetcherKind::Hybrid));
+        assert!("curl".parse::<FetcherKind>().is_err());
+        assert_eq!(FetcherKind::default().to_string(), "hybrid");
+    }
+
+    #[tokio::test]
+    async fn test_each_fetcher_kind_serves_request() {
+        let url = spawn_page_server().await;
+
+        for kind in [FetcherKind::Static, FetcherKind::Browser, FetcherKind::Hybrid] {
+            let fetcher = match kind.build(BrowserLimits { max_pages: 1, per_host: 1, max_memory_mb: None }, HttpClient::new()).await {
+                Ok(fetcher) => fetcher,
+                Err(_) if kind != FetcherKind::Static => continue, // Chrome not available
+                Err(e) => panic!("static fetcher failed to build: {}", e),
+            };
+
+            assert_eq!(fetch_title_with(fetcher, &url).await, "Selected", "fetcher: {}", kind);
+        }
+    }
+}
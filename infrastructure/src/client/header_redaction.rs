@@ -0,0 +1,75 @@
+use reqwest::header::HeaderMap;
+
+/// Headers whose values are hidden by default.
+pub const DEFAULT_REDACTED_HEADERS: &[&str] = &["Authorization", "Cookie", "Set-Cookie", "X-API-Key"];
+
+/// What a redacted header's value is shown as.
+pub const REDACTED_VALUE: &str = "***";
+
+/// Hides the values of sensitive headers before a header map is logged or
+/// returned. Names are matched case-insensitively.
+#[derive(Debug, Clone)]
+pub struct HeaderRedactor {
+    names: Vec<String>,
+}
+
+impl HeaderRedactor {
+    pub fn new(names: Vec<String>) -> Self {
+        Self { names }
+    }
+
+    pub fn is_redacted(&self, name: &str) -> bool {
+        self.names.iter().any(|redacted| redacted.eq_ignore_ascii_case(name))
+    }
+
+    /// The headers as name/value pairs in order, with redacted values replaced
+    /// by `***`. Values that aren't valid UTF-8 are shown lossily.
+    pub fn redact(&self, headers: &HeaderMap) -> Vec<(String, String)> {
+        headers
+            .iter()
+            .map(|(name, value)| {
+                let shown = if self.is_redacted(name.as_str()) {
+                    REDACTED_VALUE.to_string()
+                } else {
+                    String::from_utf8_lossy(value.as_bytes()).into_owned()
+                };
+                (name.to_string(), shown)
+            })
+            .collect()
+    }
+}
+
+impl Default for HeaderRedactor {
+    fn default() -> Self {
+        Self::new(DEFAULT_REDACTED_HEADERS.iter().map(|name| name.to_string()).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    #[test]
+    fn test_redacts_sensitive_headers_case_insensitively() {
+        let mut headers = HeaderMap::new();
+        headers.insert("authorization", HeaderValue::from_static("Bearer secret-token"));
+        headers.insert("cookie", HeaderValue::from_static("session=abc"));
+        headers.insert("x-api-key", HeaderValue::from_static("key-123"));
+        headers.insert("accept", HeaderValue::from_static("text/html"));
+        headers.insert("x-signature", HeaderValue::from_static("t=1,sig=ff"));
+
+        let shown = HeaderRedactor::default().redact(&headers);
+        let value = |name: &str| shown.iter().find(|(shown_name, _)| shown_name == name).map(|(_, value)| value.as_str());
+
+        assert_eq!(value("authorization"), Some("***"));
+        assert_eq!(value("cookie"), Some("***"));
+        assert_eq!(value("x-api-key"), Some("***"));
+        assert_eq!(value("accept"), Some("text/html"));
+        assert_eq!(value("x-signature"), Some("t=1,sig=ff"));
+
+        let custom = HeaderRedactor::new(vec!["X-SIGNATURE".to_string()]).redact(&headers);
+        assert!(custom.contains(&("x-signature".to_string(), "***".to_string())));
+        assert!(custom.contains(&("authorization".to_string(), "Bearer secret-token".to_string())));
+    }
+}
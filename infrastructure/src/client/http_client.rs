@@ -1,56 +1,660 @@
-use std::time::Duration;
+use std::collections::HashMap;
+use std::future::Future;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use async_trait::async_trait;
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
 use reqwest::{Client, Response};
-use tracing::{info, debug};
+use scraper::Html;
+use sha2::{Digest, Sha256};
+use tracing::{info, debug, warn};
 use domain::model::{
-    content::{HtmlContent, ContentMetadata},
-    request::FetchContentRequest,
+    content::{CacheDirectives, ContentMetadata, ContentType, HtmlContent, PhaseTiming},
+    request::{FetchContentRequest, MultipartPart},
 };
 use domain::port::content_fetcher::{ContentFetcher, ContentFetcherResult, ContentFetcherError};
+use domain::port::fetch_guard::FetchGuard;
+use crate::adapter::html_parser_adapter::HtmlParserAdapter;
+use super::header_redaction::HeaderRedactor;
+use super::request_signing::SigningProfile;
+use super::timing_fetcher::elapsed_ms;
 
+/// Protocol selection requested through `FetchContentRequest::http_version`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum HttpVersionPreference {
+    Auto,
+    Http1,
+    Http2,
+}
+
+impl HttpVersionPreference {
+    /// Unknown values are rejected by request validation, so they fall back to `Auto` here.
+    fn from_request(http_version: Option<&str>) -> Self {
+        match http_version {
+            Some("http1") => HttpVersionPreference::Http1,
+            Some("http2") => HttpVersionPreference::Http2,
+            _ => HttpVersionPreference::Auto,
+        }
+    }
+}
+
+/// Address family requested through `FetchContentRequest::ip_version`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum IpVersionPreference {
+    /// Both families, raced by hyper's happy-eyeballs connector.
+    Auto,
+    V4,
+    V6,
+}
+
+impl IpVersionPreference {
+    /// Unknown values are rejected by request validation, so they fall back to `Auto` here.
+    fn from_request(ip_version: Option<&str>) -> Self {
+        match ip_version {
+            Some("v4") => IpVersionPreference::V4,
+            Some("v6") => IpVersionPreference::V6,
+            _ => IpVersionPreference::Auto,
+        }
+    }
+}
+
+/// Resolves host names with the system resolver, keeping only the addresses of
+/// one family so connections never fall back to the other.
+struct SingleFamilyResolver {
+    ipv6: bool,
+}
+
+impl Resolve for SingleFamilyResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let host = name.as_str().to_string();
+        let ipv6 = self.ipv6;
+        Box::pin(async move {
+            let addrs: Vec<SocketAddr> = tokio::net::lookup_host((host.as_str(), 0))
+                .await?
+                .filter(|addr| addr.is_ipv6() == ipv6)
+                .collect();
+
+            if addrs.is_empty() {
+                let family = if ipv6 { "IPv6" } else { "IPv4" };
+                return Err(format!("no {} address found for {}", family, host).into());
+            }
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+/// Longest wait for a connection, TLS handshake included. Kept well under the
+/// usual request timeout so an unreachable HTTPS port still leaves time for the
+/// HTTP and `www.` fallbacks.
+pub const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Wait before the first retry; each later retry waits twice as long.
+const RETRY_INITIAL_BACKOFF: Duration = Duration::from_millis(250);
+
+/// Time left for a request and all of its retries and fallbacks, counted from
+/// the first attempt. Without `timeout_seconds` there is no deadline and only
+/// `max_retries` limits retrying.
+#[derive(Debug, Clone, Copy)]
+struct RetryBudget {
+    deadline: Option<Instant>,
+}
+
+impl RetryBudget {
+    fn new(timeout: Option<Duration>) -> Self {
+        Self {
+            deadline: timeout.map(|timeout| Instant::now() + timeout),
+        }
+    }
+
+    fn remaining(&self) -> Option<Duration> {
+        self.deadline.map(|deadline| deadline.saturating_duration_since(Instant::now()))
+    }
+
+    /// Whether time is left for another attempt after waiting `backoff`.
+    fn allows(&self, backoff: Duration) -> bool {
+        self.remaining().is_none_or(|remaining| remaining > backoff)
+    }
+}
+
+/// Most redirects followed for one request, as in reqwest's default policy.
+const MAX_REDIRECTS: usize = 10;
+
+/// Where a redirect response points, resolved against the URL that answered.
+/// `None` for any other response, or a redirect without a usable `Location`.
+fn redirect_target(response: &Response) -> Option<url::Url> {
+    if !matches!(response.status().as_u16(), 301 | 302 | 303 | 307 | 308) {
+        return None;
+    }
+    let location = response.headers().get(reqwest::header::LOCATION)?.to_str().ok()?;
+    response.url().join(location).ok()
+}
+
+/// Network failures, timeouts and responses the server may answer differently next time.
+fn is_retryable(error: &ContentFetcherError) -> bool {
+    match error {
+        ContentFetcherError::Network { .. } | ContentFetcherError::Timeout(_) => true,
+        ContentFetcherError::Http { status, .. } => *status >= 500 || *status == 429,
+        ContentFetcherError::InvalidUrl(_) | ContentFetcherError::Parse(_) => false,
+    }
+}
+
+/// The URL with a leading `www.` removed, or added when it has none. `None` for
+/// URLs without a domain name, such as IP addresses, where there is nothing to toggle.
+fn toggle_www(url: &str) -> Option<String> {
+    let mut url = url::Url::parse(url).ok()?;
+    let host = match url.host()? {
+        url::Host::Domain(domain) => domain.to_string(),
+        url::Host::Ipv4(_) | url::Host::Ipv6(_) => return None,
+    };
+
+    let toggled = match host.strip_prefix("www.") {
+        Some(bare) => bare.to_string(),
+        None => format!("www.{}", host),
+    };
+    url.set_host(Some(&toggled)).ok()?;
+    Some(url.to_string())
+}
+
+/// Runs `fetch` for the request and, when `www_fallback` is set and the host
+/// couldn't be reached (a connect timeout included), once more for the URL with
+/// its `www.` prefix toggled. Both runs share the caller's deadline.
+/// HTTP error responses mean the host answered, so they are returned as is. If
+/// the second attempt fails too, the original error is returned, as it is when
+/// `check` refuses the toggled URL, which is then never requested.
+async fn fetch_with_www_fallback<Fut, CheckFut>(
+    request: FetchContentRequest,
+    check: impl Fn(url::Url) -> CheckFut,
+    fetch: impl Fn(FetchContentRequest) -> Fut,
+) -> ContentFetcherResult<HtmlContent>
+where
+    Fut: Future<Output = ContentFetcherResult<HtmlContent>>,
+    CheckFut: Future<Output = Result<(), ContentFetcherError>>,
+{
+    let error = match fetch(request.clone()).await {
+        Err(error @ ContentFetcherError::Network { .. }) if request.www_fallback == Some(true) => error,
+        result => return result,
+    };
+    let Some(alternate_url) = toggle_www(&request.url) else {
+        return Err(error);
+    };
+    if let Err(denied) = check(url::Url::parse(&alternate_url).expect("toggle_www returns a valid URL")).await {
+        warn!("Fetching {} failed, and {} may not be fetched instead: {}", request.url, alternate_url, denied);
+        return Err(error);
+    }
+
+    warn!("Fetching {} failed, trying {}: {}", request.url, alternate_url, error);
+    let mut content = fetch(FetchContentRequest { url: alternate_url.clone(), ..request })
+        .await
+        .map_err(|_| error)?;
+    content.metadata.effective_url = Some(alternate_url);
+    Ok(content)
+}
+
+/// Runs `fetch` for the request, first over HTTPS when `upgrade_insecure` is
+/// set and the URL is `http://`. Only a failure to connect (which includes a
+/// failed TLS handshake) falls back to the original URL; once the host has
+/// answered over HTTPS, its answer stands. `metadata.scheme` records which
+/// scheme was used.
+async fn fetch_with_https_upgrade<Fut>(
+    request: FetchContentRequest,
+    fetch: impl Fn(FetchContentRequest) -> Fut,
+) -> ContentFetcherResult<HtmlContent>
+where
+    Fut: Future<Output = ContentFetcherResult<HtmlContent>>,
+{
+    let secure_url = match url::Url::parse(&request.url) {
+        // An explicit port is kept, as browsers do when upgrading
+        Ok(mut url) if request.upgrade_insecure == Some(true) && url.scheme() == "http" => {
+            url.set_scheme("https").ok().map(|_| url.to_string())
+        }
+        _ => None,
+    };
+    let Some(secure_url) = secure_url else {
+        return fetch(request).await;
+    };
+
+    match fetch(FetchContentRequest { url: secure_url.clone(), ..request.clone() }).await {
+        Ok(mut content) => {
+            content.metadata.effective_url.get_or_insert(secure_url);
+            content.metadata.scheme = Some("https".to_string());
+            Ok(content)
+        }
+        Err(error @ ContentFetcherError::Network { .. }) => {
+            warn!("HTTPS fetch of {} failed, falling back to HTTP: {}", secure_url, error);
+            let mut content = fetch(request).await?;
+            content.metadata.scheme = Some("http".to_string());
+            Ok(content)
+        }
+        Err(error) => Err(error),
+    }
+}
+
+/// Sniffed charsets less certain than this are logged as a warning.
+const LOW_CHARSET_CONFIDENCE: f32 = 0.5;
+
+/// The encoding a body was decoded with and how sure that choice is, from 0 to 1.
+#[derive(Debug, Clone, Copy)]
+struct DetectedCharset {
+    encoding: &'static encoding_rs::Encoding,
+    confidence: f32,
+}
+
+/// A fully read response body: its decoded text, the charset used, and the
+/// hex SHA-256 of the bytes received.
+struct DownloadedBody {
+    text: String,
+    charset: DetectedCharset,
+    content_hash: String,
+    /// Bytes received, before decoding.
+    length: usize,
+}
+
+/// Guesses the charset of a body whose response didn't declare one, using
+/// chardetng with the URL's top-level domain as a hint. Confidence grows with
+/// the number of non-ASCII bytes the guess rests on: valid UTF-8 is rarely an
+/// accident, so a few multi-byte characters are convincing, while legacy
+/// encodings are only told apart by byte statistics and need many more. A guess
+/// chardetng itself doubts is scaled down further. Pure ASCII decodes the same
+/// in every candidate encoding, so it can't be wrong.
+fn sniff_charset(body: &[u8], url: &url::Url) -> DetectedCharset {
+    let non_ascii = body.iter().filter(|byte| !byte.is_ascii()).count();
+    if non_ascii == 0 {
+        return DetectedCharset { encoding: encoding_rs::UTF_8, confidence: 1.0 };
+    }
+
+    let mut detector = chardetng::EncodingDetector::new();
+    detector.feed(body, true);
+    let tld = url
+        .domain()
+        .and_then(|domain| domain.rsplit('.').next())
+        .map(|label| label.to_ascii_lowercase())
+        .filter(|label| !label.is_empty() && label.is_ascii());
+    let (encoding, plausible) = detector.guess_assess(tld.as_deref().map(str::as_bytes), true);
+
+    let evidence = non_ascii as f32;
+    let needed = if encoding == encoding_rs::UTF_8 { 1.0 } else { 16.0 };
+    let mut confidence = evidence / (evidence + needed);
+    if !plausible {
+        confidence *= 0.25;
+    }
+
+    DetectedCharset { encoding, confidence }
+}
+
+/// Holds one client per protocol and address family preference so connection
+/// pools aren't mixed and clients aren't rebuilt per request. Clients are built
+/// on first use. Redirects are followed here rather than by reqwest, so each
+/// target is checked before it is requested.
 pub struct HttpClient {
-    client: Client,
+    clients: Mutex<HashMap<(HttpVersionPreference, IpVersionPreference), Client>>,
+    signing_profiles: Vec<SigningProfile>,
+    header_redactor: HeaderRedactor,
+    allowed_ports: Option<Vec<u16>>,
+    fetch_guard: Option<Arc<dyn FetchGuard>>,
+    connect_timeout: Duration,
 }
 
 impl HttpClient {
     pub fn new() -> Self {
-        let client = Client::builder()
+        Self {
+            clients: Mutex::new(HashMap::new()),
+            signing_profiles: Vec::new(),
+            header_redactor: HeaderRedactor::default(),
+            allowed_ports: None,
+            fetch_guard: None,
+            connect_timeout: DEFAULT_CONNECT_TIMEOUT,
+        }
+    }
+
+    /// Gives up on connecting after `connect_timeout`, replacing `DEFAULT_CONNECT_TIMEOUT`.
+    pub fn with_connect_timeout(mut self, connect_timeout: Duration) -> Self {
+        self.connect_timeout = connect_timeout;
+        self
+    }
+
+    /// Refuses to follow redirects to a port outside `allowed_ports`, the same
+    /// rule request URLs are held to; `None` follows redirects to any port.
+    pub fn with_allowed_ports(mut self, allowed_ports: Option<Vec<u16>>) -> Self {
+        self.allowed_ports = allowed_ports;
+        self
+    }
+
+    /// Consults `guard` before following each redirect and before falling back
+    /// to the `www.`-toggled host. The request URL itself is left to the
+    /// caller, which checks it before fetching.
+    pub fn with_fetch_guard(mut self, guard: Arc<dyn FetchGuard>) -> Self {
+        self.fetch_guard = Some(guard);
+        self
+    }
+
+    /// Headers whose values are logged as `***`, replacing `DEFAULT_REDACTED_HEADERS`.
+    pub fn with_redacted_headers(mut self, names: Vec<String>) -> Self {
+        self.header_redactor = HeaderRedactor::new(names);
+        self
+    }
+
+    /// Signs requests to the profiles' hosts; the first matching profile is used.
+    /// A redirect is only signed while it stays on the request's origin.
+    pub fn with_signing_profiles(mut self, signing_profiles: Vec<SigningProfile>) -> Self {
+        self.signing_profiles = signing_profiles;
+        self
+    }
+
+    fn build_client(version: HttpVersionPreference, ip_version: IpVersionPreference, connect_timeout: Duration) -> Client {
+        let builder = Client::builder()
             .user_agent("html-mcp-reader/0.1.0")
-            .build()
-            .expect("Failed to create HTTP client");
+            .connect_timeout(connect_timeout)
+            .redirect(reqwest::redirect::Policy::none());
+
+        let builder = match version {
+            HttpVersionPreference::Auto => builder,
+            HttpVersionPreference::Http1 => builder.http1_only(),
+            HttpVersionPreference::Http2 => builder.http2_prior_knowledge(),
+        };
+
+        let builder = match ip_version {
+            IpVersionPreference::Auto => builder,
+            IpVersionPreference::V4 => builder
+                .local_address(IpAddr::V4(Ipv4Addr::UNSPECIFIED))
+                .dns_resolver(Arc::new(SingleFamilyResolver { ipv6: false })),
+            IpVersionPreference::V6 => builder
+                .local_address(IpAddr::V6(Ipv6Addr::UNSPECIFIED))
+                .dns_resolver(Arc::new(SingleFamilyResolver { ipv6: true })),
+        };
+
+        builder.build().expect("Failed to create HTTP client")
+    }
+
+    fn client_for(&self, request: &FetchContentRequest) -> Client {
+        let key = (
+            HttpVersionPreference::from_request(request.http_version.as_deref()),
+            IpVersionPreference::from_request(request.ip_version.as_deref()),
+        );
 
-        Self { client }
+        self.clients
+            .lock()
+            .unwrap()
+            .entry(key)
+            .or_insert_with(|| Self::build_client(key.0, key.1, self.connect_timeout))
+            .clone()
     }
 
-    async fn build_request(&self, request: &FetchContentRequest) -> Result<reqwest::Request, ContentFetcherError> {
-        let mut req_builder = self.client.get(&request.url);
+    fn signing_profile_for(&self, url: &str) -> Option<&SigningProfile> {
+        let url = url::Url::parse(url).ok()?;
+        self.signing_profiles.iter().find(|profile| profile.matches(&url))
+    }
+
+    /// `timeout` is what remains of the request's overall budget for this attempt.
+    async fn build_request(&self, request: &FetchContentRequest, timeout: Option<Duration>) -> Result<reqwest::Request, ContentFetcherError> {
+        let mut req_builder = match &request.multipart {
+            Some(parts) => self.client_for(request).post(&request.url).multipart(multipart_form(parts).await?),
+            None => self.client_for(request).get(&request.url),
+        };
 
-        if let Some(timeout) = request.timeout_seconds {
-            req_builder = req_builder.timeout(Duration::from_secs(timeout));
+        if let Some(timeout) = timeout {
+            req_builder = req_builder.timeout(timeout);
         }
 
         if let Some(user_agent) = &request.user_agent {
             req_builder = req_builder.header("User-Agent", user_agent);
         }
 
-        req_builder = req_builder.header("Accept", "text/html,application/xhtml+xml,application/xml;q=0.9,*/*;q=0.8");
+        if let Some(referer) = &request.referer {
+            req_builder = req_builder.header("Referer", referer);
+        }
+
+        req_builder = req_builder.header("Accept", accept_header(request.output_format));
+
+        if let Some(profile) = self.signing_profile_for(&request.url) {
+            let (name, value) = profile.sign_now(&request.url);
+            req_builder = req_builder.header(name, value);
+        }
 
         req_builder.build().map_err(|e| {
-            ContentFetcherError::Network(format!("Failed to build request: {}", e))
+            ContentFetcherError::network_with_source(format!("Failed to build request: {}", e), e)
         })
     }
 
-    async fn execute_request(&self, req: reqwest::Request) -> Result<Response, ContentFetcherError> {
+    /// `timeout_seconds` is the request's whole deadline, reported when the
+    /// part of it left for this attempt runs out.
+    async fn execute_request(&self, client: &Client, req: reqwest::Request, timeout_seconds: u64) -> Result<Response, ContentFetcherError> {
         debug!("Executing HTTP request to: {}", req.url());
-        
-        self.client.execute(req).await.map_err(|e| {
-            if e.is_timeout() {
-                ContentFetcherError::Timeout(30) // Default timeout
-            } else if e.is_connect() {
-                ContentFetcherError::Network(format!("Connection failed: {}", e))
+        debug!("Request headers: {:?}", self.header_redactor.redact(req.headers()));
+
+        let response = client.execute(req).await.map_err(|e| {
+            // A connect timeout is a failure to connect, which the fallbacks act on
+            if e.is_connect() {
+                ContentFetcherError::network_with_source(format!("Connection failed: {}", e), e)
+            } else if e.is_timeout() {
+                ContentFetcherError::Timeout(timeout_seconds)
             } else {
-                ContentFetcherError::Network(format!("Request failed: {}", e))
+                ContentFetcherError::network_with_source(format!("Request failed: {}", e), e)
+            }
+        })?;
+        debug!("Response headers: {:?}", self.header_redactor.redact(response.headers()));
+        Ok(response)
+    }
+
+    /// Rejects a redirect to anything the request itself couldn't have named:
+    /// a scheme other than HTTP(S), a port outside `allowed_ports`, or a URL the
+    /// fetch guard denies.
+    async fn check_redirect(&self, target: &url::Url) -> Result<(), ContentFetcherError> {
+        if !matches!(target.scheme(), "http" | "https") {
+            return Err(ContentFetcherError::InvalidUrl(format!("redirect to unsupported scheme: {}", target)));
+        }
+        if let Some(allowed_ports) = &self.allowed_ports {
+            // `port()` is `None` for the scheme's default port, as for request URLs
+            if target.port().is_some_and(|port| !allowed_ports.contains(&port)) {
+                return Err(ContentFetcherError::InvalidUrl(format!("redirect to a port that is not allowed: {}", target)));
+            }
+        }
+        if let Some(guard) = &self.fetch_guard {
+            guard.allow(target).await.map_err(|reason| {
+                info!("Fetch guard denied the redirect to {}: {}", target, reason);
+                ContentFetcherError::InvalidUrl(reason)
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Sends the request and follows up to `MAX_REDIRECTS` redirects, checking
+    /// each target with `check_redirect` first. The whole chain shares `timeout`.
+    /// A 303, or a 301/302, turns into a GET without the body, as in browsers.
+    /// Hops to another origin are never signed, so a signature can't be
+    /// replayed by whoever the signed host redirects to. Returns the final
+    /// response and the credential scheme the request that got it carried.
+    async fn send_following_redirects(&self, request: &FetchContentRequest, timeout: Option<Duration>) -> Result<(Response, Option<String>), ContentFetcherError> {
+        let budget = RetryBudget::new(timeout);
+        let origin = url::Url::parse(&request.url).ok().map(|url| url.origin());
+        let mut hop = request.clone();
+        let mut same_origin = true;
+        let mut redirects = 0;
+
+        loop {
+            let mut req = self.build_request(&hop, budget.remaining()).await?;
+            if !same_origin {
+                if let Some(profile) = self.signing_profile_for(&hop.url) {
+                    req.headers_mut().remove(profile.header_name());
+                }
+            }
+            // Credentials in the URL's userinfo have already become a Basic header here
+            let credential_scheme = req
+                .headers()
+                .get(reqwest::header::AUTHORIZATION)
+                .and_then(|h| h.to_str().ok())
+                .and_then(|value| value.split_whitespace().next())
+                .map(str::to_string);
+            // Without `timeout_seconds` no timeout is set, so none is reported
+            let response = self
+                .execute_request(&self.client_for(&hop), req, request.timeout_seconds.unwrap_or_default())
+                .await?;
+
+            let Some(target) = redirect_target(&response) else {
+                return Ok((response, credential_scheme));
+            };
+            if redirects == MAX_REDIRECTS {
+                return Err(ContentFetcherError::network(format!("Too many redirects from {}", request.url)));
+            }
+            redirects += 1;
+            self.check_redirect(&target).await?;
+
+            debug!("Following redirect from {} to {}", hop.url, target);
+            let keeps_body = matches!(response.status().as_u16(), 307 | 308);
+            same_origin = same_origin && origin.as_ref() == Some(&target.origin());
+            hop = FetchContentRequest {
+                url: target.to_string(),
+                multipart: hop.multipart.filter(|_| keeps_body),
+                ..hop
+            };
+        }
+    }
+
+    /// Rejects the response before its body is read when the `content-type` header
+    /// matches none of the expected prefixes (compared case-insensitively).
+    fn check_content_type(&self, response: &Response, expected: &[String]) -> Result<(), ContentFetcherError> {
+        let content_type = response
+            .headers()
+            .get("content-type")
+            .and_then(|h| h.to_str().ok())
+            .unwrap_or("")
+            .trim()
+            .to_ascii_lowercase();
+
+        if expected.iter().any(|prefix| content_type.starts_with(&prefix.trim().to_ascii_lowercase())) {
+            Ok(())
+        } else {
+            Err(ContentFetcherError::Parse(format!("unexpected content type: {}", content_type)))
+        }
+    }
+
+    /// Streams the body chunk by chunk so a stalled transfer can be detected: with
+    /// `read_timeout_seconds` set, waiting longer than that for the next chunk fails
+    /// with `Timeout`. The bytes are decoded using the `content-type` charset when
+    /// the server declares one, and otherwise with the charset sniffed from the
+    /// body. Each chunk is also fed to a SHA-256 hasher as it arrives, so the
+    /// body's hash needs no second pass over the buffer.
+    async fn read_body(&self, mut response: Response, read_timeout_seconds: Option<u64>) -> Result<DownloadedBody, ContentFetcherError> {
+        let declared = response
+            .headers()
+            .get("content-type")
+            .and_then(|h| h.to_str().ok())
+            .and_then(|content_type| {
+                content_type
+                    .split(';')
+                    .filter_map(|param| param.trim().strip_prefix("charset="))
+                    .next()
+                    .and_then(|charset| encoding_rs::Encoding::for_label(charset.trim_matches('"').as_bytes()))
+            });
+        let url = response.url().clone();
+
+        let mut body = Vec::new();
+        let mut hasher = Sha256::new();
+        loop {
+            let next_chunk = match read_timeout_seconds {
+                Some(seconds) => tokio::time::timeout(Duration::from_secs(seconds), response.chunk())
+                    .await
+                    .map_err(|_| ContentFetcherError::Timeout(seconds))?,
+                None => response.chunk().await,
+            };
+
+            match next_chunk.map_err(|e| ContentFetcherError::network_with_source(format!("Failed to read response body: {}", e), e))? {
+                Some(chunk) => {
+                    hasher.update(&chunk);
+                    body.extend_from_slice(&chunk);
+                }
+                None => break,
             }
+        }
+
+        let charset = match declared {
+            Some(encoding) => DetectedCharset { encoding, confidence: 1.0 },
+            None => sniff_charset(&body, &url),
+        };
+        let (text, _, _) = charset.encoding.decode(&body);
+        Ok(DownloadedBody {
+            text: text.into_owned(),
+            charset,
+            content_hash: hasher.finalize().iter().map(|byte| format!("{:02x}", byte)).collect(),
+            length: body.len(),
+        })
+    }
+
+    /// One attempt at the request, given `timeout` of the overall budget.
+    async fn fetch_once(&self, request: &FetchContentRequest, timeout: Option<Duration>) -> ContentFetcherResult<HtmlContent> {
+        let sent = Instant::now();
+        let (response, credential_scheme) = self.send_following_redirects(request, timeout).await?;
+        let ttfb_ms = elapsed_ms(sent);
+
+        if response.status() == reqwest::StatusCode::UNAUTHORIZED {
+            if let Some(error) = auth_challenge_error(&response, credential_scheme.as_deref()) {
+                return Err(error);
+            }
+        }
+
+        if !response.status().is_success() {
+            return Err(ContentFetcherError::Http {
+                status: response.status().as_u16(),
+                message: format!("HTTP {} {}", response.status().as_u16(), response.status().canonical_reason().unwrap_or("Unknown")),
+            });
+        }
+
+        if let Some(expected) = &request.expected_content_types {
+            self.check_content_type(&response, expected)?;
+        }
+
+        let mut metadata = self.create_metadata(&response);
+        let final_url = response.url().to_string();
+        
+        let download_started = Instant::now();
+        let DownloadedBody { text: raw_html, charset, content_hash, length } = self.read_body(response, request.read_timeout_seconds).await?;
+        let download_ms = elapsed_ms(download_started);
+        let parse_started = Instant::now();
+        metadata.content_hash = Some(content_hash);
+        let cut_short = metadata.content_length.is_some_and(|declared| length < declared);
+        // Chunked responses carry no Content-Length, so report what actually arrived
+        metadata.content_length.get_or_insert(length);
+        metadata.charset = Some(charset.encoding.name().to_ascii_lowercase());
+        metadata.charset_confidence = Some(charset.confidence);
+        if charset.confidence < LOW_CHARSET_CONFIDENCE {
+            warn!(
+                "Guessed charset {} for {} with low confidence ({:.2})",
+                charset.encoding.name(), final_url, charset.confidence
+            );
+        }
+        metadata.html_truncated = Some(cut_short || HtmlParserAdapter::is_truncated(&raw_html));
+        if metadata.html_truncated == Some(true) {
+            warn!("Response from {} looks truncated", final_url);
+        }
+
+        // Extract text content if requested, or by default for HTML; the
+        // title and publish date are read from the same parse
+        let document = request
+            .extracts_text_for(&metadata.content_type)
+            .then(|| Html::parse_document(&raw_html));
+        let (title, published_at) = HtmlParserAdapter::title_and_published_at(&raw_html, document.as_ref());
+        metadata.published_at = published_at;
+        let text_content = match &document {
+            Some(document) => extract_text_content(document),
+            None => raw_html.clone(),
+        };
+
+        metadata.timing = Some(PhaseTiming {
+            ttfb_ms: Some(ttfb_ms),
+            download_ms: Some(download_ms),
+            parse_ms: Some(elapsed_ms(parse_started)),
+            ..Default::default()
+        });
+
+        info!("Successfully fetched {} bytes from {}", raw_html.len(), final_url);
+
+        Ok(HtmlContent {
+            url: final_url,
+            title,
+            text_content,
+            raw_html,
+            metadata,
+            ..Default::default()
         })
     }
 
@@ -69,71 +673,140 @@ impl HttpClient {
                 .get("last-modified")
                 .and_then(|h| h.to_str().ok())
                 .map(|s| s.to_string()),
-            charset: None, // Could be extracted from content-type header
+            charset: None, // Set once the body has been decoded
+            charset_confidence: None,
             javascript_detected: None,
             fetch_method: Some(domain::model::content::FetchMethod::Static),
+            http_version: Some(format!("{:?}", response.version())),
+            published_at: None,
+            content_encoding: None,
+            likely_soft_error: None,
+            fallback_reason: None,
+            text_truncated: None,
+            html_truncated: None,
+            effective_url: None,
+            content_below_minimum: None,
+            from_cache: None,
+            cache_age_seconds: None,
+            content_hash: None,
+            cache_directives: parse_cache_directives(response.headers()),
+            retried_with_browser: None,
+            elements_truncated: None,
+            from_archive: None,
+            archive_timestamp: None,
+            keywords: None,
+            scheme: None,
+            timing: None,
+            requested_url: None,
+            url_used: None,
+            fallback_errors: None,
+        }
+    }
+
+    /// Retries up to `max_retries` times with doubling backoff, within `budget`:
+    /// each attempt gets only the time that's left, and when the next backoff
+    /// would use it up the last error is returned instead.
+    async fn fetch_with_retries(&self, request: FetchContentRequest, budget: RetryBudget) -> ContentFetcherResult<HtmlContent> {
+        let max_retries = request.max_retries.unwrap_or(0);
+        let mut backoff = RETRY_INITIAL_BACKOFF;
+        let mut retries = 0;
+
+        loop {
+            match self.fetch_once(&request, budget.remaining()).await {
+                Err(error) if retries < max_retries && is_retryable(&error) => {
+                    if !budget.allows(backoff) {
+                        warn!("Not retrying {}, the request deadline would pass: {}", request.url, error);
+                        return Err(error);
+                    }
+
+                    retries += 1;
+                    warn!("Retrying {} in {:?} (retry {} of {}): {}", request.url, backoff, retries, max_retries, error);
+                    tokio::time::sleep(backoff).await;
+                    backoff *= 2;
+                }
+                result => return result,
+            }
         }
     }
 }
 
+impl Default for HttpClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[async_trait]
 impl ContentFetcher for HttpClient {
+    /// Retries as described on `fetch_with_retries`, then tries the host with its
+    /// `www.` prefix toggled if `www_fallback` asks for it. With
+    /// `upgrade_insecure`, all of that happens over HTTPS first. `timeout_seconds`
+    /// is one deadline for all of it. A multipart upload is sent once, with none
+    /// of the above, so it can't be posted twice.
     async fn fetch_content(&self, request: FetchContentRequest) -> ContentFetcherResult<HtmlContent> {
         info!("Fetching content from URL: {}", request.url);
-
-        let req = self.build_request(&request).await?;
-        let response = self.execute_request(req).await?;
-
-        if !response.status().is_success() {
-            return Err(ContentFetcherError::Http {
-                status: response.status().as_u16(),
-                message: format!("HTTP {} {}", response.status().as_u16(), response.status().canonical_reason().unwrap_or("Unknown")),
-            });
+        let budget = RetryBudget::new(request.timeout_seconds.map(Duration::from_secs));
+        if request.multipart.is_some() {
+            return self.fetch_once(&request, budget.remaining()).await;
         }
 
-        let metadata = self.create_metadata(&response);
-        let final_url = response.url().to_string();
-        
-        let raw_html = response.text().await.map_err(|e| {
-            ContentFetcherError::Network(format!("Failed to read response body: {}", e))
-        })?;
-
-        // Extract title using basic regex for now
-        let title = extract_title(&raw_html);
-        
-        // Extract text content if requested
-        let text_content = if request.extract_text_only.unwrap_or(true) {
-            extract_text_content(&raw_html)
-        } else {
-            raw_html.clone()
-        };
-
-        info!("Successfully fetched {} bytes from {}", raw_html.len(), final_url);
-
-        Ok(HtmlContent {
-            url: final_url,
-            title,
-            text_content,
-            raw_html,
-            metadata,
+        fetch_with_https_upgrade(request, |request| {
+            fetch_with_www_fallback(
+                request,
+                move |url| async move { self.check_redirect(&url).await },
+                move |request| async move { self.fetch_with_retries(request, budget).await },
+            )
         })
+        .await
     }
 }
 
-fn extract_title(html: &str) -> Option<String> {
-    use regex::Regex;
-    
-    let title_regex = Regex::new(r"<title[^>]*>([^<]*)</title>").ok()?;
-    title_regex
-        .captures(html)
-        .and_then(|caps| caps.get(1))
-        .map(|m| html_escape::decode_html_entities(m.as_str().trim()).to_string())
+/// The `Accept` header for a request preferring `output_format`: its media types
+/// first, with HTML (and then anything) still accepted for servers that don't
+/// content-negotiate.
+fn accept_header(output_format: Option<ContentType>) -> &'static str {
+    match output_format {
+        None | Some(ContentType::Html) => "text/html,application/xhtml+xml,application/xml;q=0.9,*/*;q=0.8",
+        Some(ContentType::Json) => "application/json,text/html;q=0.9,application/xhtml+xml;q=0.9,*/*;q=0.8",
+        Some(ContentType::Xml) => "application/xml,text/xml,text/html;q=0.9,application/xhtml+xml;q=0.9,*/*;q=0.8",
+        Some(ContentType::PlainText) => "text/plain,text/html;q=0.9,application/xhtml+xml;q=0.9,*/*;q=0.8",
+        Some(ContentType::Markdown) => "text/markdown,text/html;q=0.9,application/xhtml+xml;q=0.9,*/*;q=0.8",
+    }
 }
 
-fn extract_text_content(html: &str) -> String {
-    use scraper::{Html, Selector};
-    
-    let document = Html::parse_document(html);
+/// Builds the form for a `multipart` request. File parts are read from disk
+/// here; the service only lets them through when file URLs are allowed.
+async fn multipart_form(parts: &[MultipartPart]) -> Result<reqwest::multipart::Form, ContentFetcherError> {
+    let mut form = reqwest::multipart::Form::new();
+    for part in parts {
+        let mut form_part = match (&part.value, &part.file_url) {
+            (Some(value), _) => reqwest::multipart::Part::text(value.clone()),
+            (None, Some(file_url)) => {
+                let path = url::Url::parse(file_url)
+                    .ok()
+                    .filter(|url| url.scheme() == "file")
+                    .and_then(|url| url.to_file_path().ok())
+                    .ok_or_else(|| ContentFetcherError::InvalidUrl(format!("not a file URL: {}", file_url)))?;
+                let bytes = tokio::fs::read(&path).await.map_err(|e| {
+                    ContentFetcherError::network_with_source(format!("Failed to read {}: {}", path.display(), e), e)
+                })?;
+                let file_name = path.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default();
+                reqwest::multipart::Part::bytes(bytes).file_name(file_name)
+            }
+            (None, None) => reqwest::multipart::Part::text(String::new()),
+        };
+        if let Some(content_type) = &part.content_type {
+            form_part = form_part.mime_str(content_type).map_err(|_| {
+                ContentFetcherError::InvalidUrl(format!("invalid content type for multipart part {}: {}", part.name, content_type))
+            })?;
+        }
+        form = form.part(part.name.clone(), form_part);
+    }
+    Ok(form)
+}
+
+fn extract_text_content(document: &Html) -> String {
+    use scraper::Selector;
     
     // Remove script and style elements
     let _script_selector = Selector::parse("script, style").unwrap();
@@ -153,4 +826,911 @@ fn extract_text_content(html: &str) -> String {
             .collect::<Vec<_>>()
             .join(" ")
     }
-}
\ No newline at end of file
+}
+
+/// Reads the response's caching headers, or `None` when it sends none of them.
+/// Unknown `Cache-Control` directives are ignored, and an `Expires` date that
+/// can't be read is left out.
+fn parse_cache_directives(headers: &reqwest::header::HeaderMap) -> Option<CacheDirectives> {
+    let header = |name: reqwest::header::HeaderName| {
+        headers.get(name).and_then(|h| h.to_str().ok()).map(str::trim).filter(|value| !value.is_empty())
+    };
+    let cache_control = header(reqwest::header::CACHE_CONTROL);
+    let expires = header(reqwest::header::EXPIRES);
+    let etag = header(reqwest::header::ETAG);
+    if cache_control.is_none() && expires.is_none() && etag.is_none() {
+        return None;
+    }
+
+    let mut directives = CacheDirectives {
+        expires: expires
+            .and_then(|date| chrono::DateTime::parse_from_rfc2822(date).ok())
+            .map(|date| date.to_rfc3339_opts(chrono::SecondsFormat::Secs, true)),
+        etag: etag.map(str::to_string),
+        ..Default::default()
+    };
+    for directive in cache_control.into_iter().flat_map(|value| value.split(',')) {
+        let (name, value) = match directive.split_once('=') {
+            Some((name, value)) => (name.trim(), Some(value.trim().trim_matches('"'))),
+            None => (directive.trim(), None),
+        };
+        match name.to_ascii_lowercase().as_str() {
+            "max-age" => directives.max_age_seconds = value.and_then(|seconds| seconds.parse().ok()),
+            "no-store" => directives.no_store = true,
+            "no-cache" => directives.no_cache = true,
+            _ => {}
+        }
+    }
+
+    Some(directives)
+}
+
+/// For a 401, the scheme the server's `WWW-Authenticate` challenge asks for,
+/// when the request carried no credentials for that scheme. A challenge for the
+/// scheme that was sent means the credentials were rejected, which the plain
+/// HTTP error already says.
+fn auth_challenge_error(response: &Response, credential_scheme: Option<&str>) -> Option<ContentFetcherError> {
+    let scheme = response
+        .headers()
+        .get(reqwest::header::WWW_AUTHENTICATE)
+        .and_then(|h| h.to_str().ok())
+        .and_then(|challenge| challenge.split_whitespace().next())
+        .map(|scheme| scheme.trim_end_matches(','))
+        .filter(|scheme| !scheme.is_empty())?;
+
+    if credential_scheme.is_some_and(|sent| sent.eq_ignore_ascii_case(scheme)) {
+        return None;
+    }
+
+    Some(ContentFetcherError::Http {
+        status: 401,
+        message: format!("authentication required: {}", scheme),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use axum::{http::{HeaderMap, StatusCode}, response::Html, routing::get, Router};
+    use crate::client::request_signing::SigningAlgorithm;
+    use crate::test_support::spawn_server;
+
+    async fn spawn_echo_referer_server() -> String {
+        let app = Router::new().route("/", get(|headers: HeaderMap| async move {
+            let referer = headers
+                .get("referer")
+                .and_then(|h| h.to_str().ok())
+                .unwrap_or("none")
+                .to_string();
+            Html(format!("<html><head><title>{}</title></head><body></body></html>", referer))
+        }));
+        format!("{}/", spawn_server(app).await)
+    }
+
+    async fn spawn_content_type_server() -> String {
+        let app = Router::new()
+            .route("/page", get(|| async { Html("<html><head><title>Page</title></head></html>") }))
+            .route("/data", get(|| async { axum::Json(serde_json::json!({"ok": true})) }));
+        spawn_server(app).await
+    }
+
+    /// Answers every request with 503, counting them.
+    async fn spawn_unavailable_server() -> (String, Arc<AtomicUsize>) {
+        let attempts = Arc::new(AtomicUsize::new(0));
+        let counter = attempts.clone();
+        let app = Router::new().route("/", get(move || {
+            let counter = counter.clone();
+            async move {
+                counter.fetch_add(1, Ordering::SeqCst);
+                StatusCode::SERVICE_UNAVAILABLE
+            }
+        }));
+        let url = format!("{}/", spawn_server(app).await);
+        (url, attempts)
+    }
+
+    #[tokio::test]
+    async fn test_streamed_hash_matches_buffered_body() {
+        // Large enough to arrive in several chunks
+        let body = format!("<html><body>{}</body></html>", "<p>streamed paragraph</p>".repeat(20_000));
+        let served = body.clone();
+        let app = Router::new().route("/", get(move || async move { Html(served) }));
+        let url = format!("{}/", spawn_server(app).await);
+
+        let content = HttpClient::new().fetch_content(FetchContentRequest { url, ..Default::default() }).await.unwrap();
+
+        let expected: String = Sha256::digest(body.as_bytes()).iter().map(|byte| format!("{:02x}", byte)).collect();
+        assert_eq!(content.metadata.content_hash, Some(expected));
+    }
+
+    #[tokio::test]
+    async fn test_multipart_request_posts_form() {
+        let app = Router::new().route("/upload", axum::routing::any(|method: axum::http::Method, headers: HeaderMap, body: String| async move {
+            let content_type = headers.get("content-type").and_then(|h| h.to_str().ok()).unwrap_or("none").to_string();
+            Html(format!("<html><head><title>{}</title></head><body><pre>{}\n{}</pre></body></html>", method, content_type, body))
+        }));
+        let url = format!("{}/upload", spawn_server(app).await);
+        let request = FetchContentRequest {
+            url,
+            multipart: Some(vec![MultipartPart {
+                name: "comment".to_string(),
+                value: Some("hello form".to_string()),
+                file_url: None,
+                content_type: Some("text/plain".to_string()),
+            }]),
+            ..Default::default()
+        };
+
+        let client = HttpClient::new();
+        let built = client.build_request(&request, None).await.unwrap();
+        assert_eq!(built.method(), reqwest::Method::POST);
+
+        let content = client.fetch_content(request).await.unwrap();
+        assert_eq!(content.title, Some("POST".to_string()));
+        assert!(content.raw_html.contains("multipart/form-data; boundary="), "{}", content.raw_html);
+        assert!(content.raw_html.contains("Content-Disposition: form-data; name=\"comment\""), "{}", content.raw_html);
+        assert!(content.raw_html.contains("Content-Type: text/plain"), "{}", content.raw_html);
+        assert!(content.raw_html.contains("hello form"), "{}", content.raw_html);
+    }
+
+    #[tokio::test]
+    async fn test_multipart_request_sent_once() {
+        let attempts = Arc::new(AtomicUsize::new(0));
+        let counter = attempts.clone();
+        let app = Router::new().route("/upload", axum::routing::post(move || {
+            let counter = counter.clone();
+            async move {
+                counter.fetch_add(1, Ordering::SeqCst);
+                StatusCode::SERVICE_UNAVAILABLE
+            }
+        }));
+        let request = FetchContentRequest {
+            url: format!("{}/upload", spawn_server(app).await),
+            multipart: Some(vec![MultipartPart {
+                name: "comment".to_string(),
+                value: Some("hello form".to_string()),
+                file_url: None,
+                content_type: None,
+            }]),
+            max_retries: Some(3),
+            www_fallback: Some(true),
+            upgrade_insecure: Some(true),
+            ..Default::default()
+        };
+
+        let result = HttpClient::new().fetch_content(request).await;
+
+        assert!(matches!(result, Err(ContentFetcherError::Http { status: 503, .. })), "{:?}", result.map(|content| content.url));
+        assert_eq!(attempts.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_extract_text_only_defaults_by_content_type() {
+        let base = spawn_content_type_server().await;
+        let client = HttpClient::new();
+
+        let page = client.fetch_content(FetchContentRequest { url: format!("{}/page", base), ..Default::default() }).await.unwrap();
+        assert_ne!(page.text_content, page.raw_html);
+        assert!(!page.text_content.contains("<html>"), "{}", page.text_content);
+
+        let data = client.fetch_content(FetchContentRequest { url: format!("{}/data", base), ..Default::default() }).await.unwrap();
+        assert_eq!(data.text_content, r#"{"ok":true}"#);
+
+        // An explicit setting still wins
+        let page = client.fetch_content(FetchContentRequest {
+            url: format!("{}/page", base),
+            extract_text_only: Some(false),
+            ..Default::default()
+        }).await.unwrap();
+        assert_eq!(page.text_content, page.raw_html);
+    }
+
+    #[tokio::test]
+    async fn test_chunked_response_reports_downloaded_length() {
+        let parts = ["<html><body>", "<p>first chunk</p>", "<p>second chunk, café</p>", "</body></html>"];
+        let expected_length: usize = parts.iter().map(|part| part.len()).sum();
+        let app = Router::new().route("/", get(move || async move {
+            let chunks = futures::stream::iter(parts.map(Ok::<_, std::convert::Infallible>));
+            ([("content-type", "text/html; charset=utf-8")], axum::body::Body::from_stream(chunks))
+        }));
+        let url = format!("{}/", spawn_server(app).await);
+
+        let response = reqwest::get(&url).await.unwrap();
+        assert_eq!(response.headers().get("transfer-encoding").unwrap(), "chunked");
+        assert_eq!(response.content_length(), None);
+
+        let content = HttpClient::new().fetch_content(FetchContentRequest { url, ..Default::default() }).await.unwrap();
+
+        assert_eq!(content.metadata.content_length, Some(expected_length));
+        assert_eq!(content.raw_html.len(), expected_length);
+    }
+
+    fn cache_headers(pairs: &[(&'static str, &str)]) -> reqwest::header::HeaderMap {
+        let mut headers = reqwest::header::HeaderMap::new();
+        for (name, value) in pairs {
+            headers.append(*name, value.parse().unwrap());
+        }
+        headers
+    }
+
+    #[test]
+    fn test_parse_cache_directives_max_age() {
+        let directives = parse_cache_directives(&cache_headers(&[
+            ("cache-control", "public, max-age=3600"),
+            ("expires", "Thu, 01 Dec 2033 16:00:00 GMT"),
+            ("etag", "\"abc123\""),
+        ]))
+        .unwrap();
+
+        assert_eq!(directives, CacheDirectives {
+            max_age_seconds: Some(3600),
+            no_store: false,
+            no_cache: false,
+            expires: Some("2033-12-01T16:00:00Z".to_string()),
+            etag: Some("\"abc123\"".to_string()),
+        });
+        assert_eq!(parse_cache_directives(&cache_headers(&[("content-type", "text/html")])), None);
+    }
+
+    #[test]
+    fn test_parse_cache_directives_no_store() {
+        let directives = parse_cache_directives(&cache_headers(&[("cache-control", "No-Store, no-cache")])).unwrap();
+
+        assert!(directives.no_store);
+        assert!(directives.no_cache);
+        assert_eq!(directives.max_age_seconds, None);
+
+        let unreadable_expiry = parse_cache_directives(&cache_headers(&[("expires", "0")])).unwrap();
+        assert_eq!(unreadable_expiry, CacheDirectives::default());
+    }
+
+    /// Answers every request with 401 and the given `WWW-Authenticate` challenge.
+    async fn spawn_challenge_server(challenge: &'static str) -> String {
+        let app = Router::new().route("/", get(move || async move {
+            (StatusCode::UNAUTHORIZED, [("www-authenticate", challenge)], "")
+        }));
+        format!("{}/", spawn_server(app).await)
+    }
+
+    #[tokio::test]
+    async fn test_basic_challenge_reports_scheme() {
+        let url = spawn_challenge_server("Basic realm=\"staff\"").await;
+        let client = HttpClient::new();
+
+        let result = client.fetch_content(FetchContentRequest { url: url.clone(), ..Default::default() }).await;
+        match result {
+            Err(ContentFetcherError::Http { status, message }) => {
+                assert_eq!(status, 401);
+                assert_eq!(message, "authentication required: Basic");
+            }
+            other => panic!("expected a 401, got {:?}", other.map(|c| c.url)),
+        }
+
+        // Basic credentials were sent and refused, so the plain error stands
+        let with_credentials = url.replacen("http://", "http://user:wrong@", 1);
+        let result = client.fetch_content(FetchContentRequest { url: with_credentials, ..Default::default() }).await;
+        match result {
+            Err(ContentFetcherError::Http { status, message }) => {
+                assert_eq!(status, 401);
+                assert_eq!(message, "HTTP 401 Unauthorized");
+            }
+            other => panic!("expected a 401, got {:?}", other.map(|c| c.url)),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_bearer_challenge_reports_scheme_despite_basic_credentials() {
+        let url = spawn_challenge_server("Bearer realm=\"api\", error=\"invalid_token\"").await;
+        let with_credentials = url.replacen("http://", "http://user:secret@", 1);
+
+        let result = HttpClient::new().fetch_content(FetchContentRequest { url: with_credentials, ..Default::default() }).await;
+
+        match result {
+            Err(ContentFetcherError::Http { status, message }) => {
+                assert_eq!(status, 401);
+                assert_eq!(message, "authentication required: Bearer");
+            }
+            other => panic!("expected a 401, got {:?}", other.map(|c| c.url)),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_retries_stay_within_overall_deadline() {
+        let (url, attempts) = spawn_unavailable_server().await;
+        let client = HttpClient::new();
+        let request = FetchContentRequest {
+            url,
+            timeout_seconds: Some(1),
+            max_retries: Some(3),
+            ..Default::default()
+        };
+
+        let started = Instant::now();
+        let result = client.fetch_content(request).await;
+
+        assert!(matches!(result, Err(ContentFetcherError::Http { status: 503, .. })));
+        assert!(started.elapsed() < Duration::from_secs(1), "took {:?}", started.elapsed());
+        // The 250ms and 500ms backoffs fit in the second; the 1s one would not
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_retry_budget_shrinks() {
+        let budget = RetryBudget::new(Some(Duration::from_millis(500)));
+
+        let first = budget.remaining().unwrap();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        let second = budget.remaining().unwrap();
+
+        assert!(first <= Duration::from_millis(500));
+        assert!(second <= first - Duration::from_millis(100));
+        assert!(budget.allows(Duration::from_millis(100)));
+        assert!(!budget.allows(second));
+
+        let unbounded = RetryBudget::new(None);
+        assert_eq!(unbounded.remaining(), None);
+        assert!(unbounded.allows(Duration::MAX));
+    }
+
+    #[test]
+    fn test_is_retryable() {
+        assert!(is_retryable(&ContentFetcherError::network("reset")));
+        assert!(is_retryable(&ContentFetcherError::Timeout(30)));
+        assert!(is_retryable(&ContentFetcherError::Http { status: 502, message: String::new() }));
+        assert!(is_retryable(&ContentFetcherError::Http { status: 429, message: String::new() }));
+        assert!(!is_retryable(&ContentFetcherError::Http { status: 404, message: String::new() }));
+        assert!(!is_retryable(&ContentFetcherError::InvalidUrl("bad".to_string())));
+    }
+
+    /// Stands in for the network: the bare `example.com` host is unreachable, its
+    /// `www.` host serves a page and `status.example.com` answers with a 503.
+    async fn fetch_from_mock_hosts(request: FetchContentRequest) -> ContentFetcherResult<HtmlContent> {
+        match url::Url::parse(&request.url).unwrap().host_str() {
+            Some("www.example.com") => Ok(HtmlContent { url: request.url, ..Default::default() }),
+            Some("status.example.com") | Some("www.status.example.com") => Err(ContentFetcherError::Http {
+                status: 503,
+                message: "HTTP 503 Service Unavailable".to_string(),
+            }),
+            _ => Err(ContentFetcherError::network("Connection failed: connection refused")),
+        }
+    }
+
+    async fn allow_all(_url: url::Url) -> Result<(), ContentFetcherError> {
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_www_fallback_retries_unreachable_host_with_www() {
+        let request = FetchContentRequest {
+            url: "https://example.com/docs?page=2".to_string(),
+            www_fallback: Some(true),
+            ..Default::default()
+        };
+
+        let content = fetch_with_www_fallback(request, allow_all, fetch_from_mock_hosts).await.unwrap();
+
+        assert_eq!(content.url, "https://www.example.com/docs?page=2");
+        assert_eq!(content.metadata.effective_url, Some("https://www.example.com/docs?page=2".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_www_fallback_only_on_network_errors_when_requested() {
+        let disabled = FetchContentRequest {
+            url: "https://example.com/".to_string(),
+            ..Default::default()
+        };
+        let error = fetch_with_www_fallback(disabled, allow_all, fetch_from_mock_hosts).await.unwrap_err();
+        assert!(matches!(error, ContentFetcherError::Network { .. }));
+
+        let http_error = FetchContentRequest {
+            url: "https://status.example.com/".to_string(),
+            www_fallback: Some(true),
+            ..Default::default()
+        };
+        let error = fetch_with_www_fallback(http_error, allow_all, fetch_from_mock_hosts).await.unwrap_err();
+        assert!(matches!(error, ContentFetcherError::Http { status: 503, .. }));
+    }
+
+    #[tokio::test]
+    async fn test_www_fallback_denied_by_fetch_guard_is_not_requested() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        // www.localhost doesn't resolve, so the fallback would reach localhost
+        let hits = Arc::new(AtomicUsize::new(0));
+        let app = Router::new().route("/", get({
+            let hits = hits.clone();
+            move || async move {
+                hits.fetch_add(1, Ordering::SeqCst);
+                Html("<html><head><title>Bare host</title></head></html>")
+            }
+        }));
+        let port = url::Url::parse(&spawn_server(app).await).unwrap().port().unwrap();
+        let request = FetchContentRequest {
+            url: format!("http://www.localhost:{}/", port),
+            www_fallback: Some(true),
+            ..Default::default()
+        };
+        let guard = Arc::new(crate::adapter::static_allowlist_guard::StaticAllowlistGuard::new(vec!["www.localhost".to_string()]));
+
+        let result = HttpClient::new().with_fetch_guard(guard).fetch_content(request.clone()).await;
+        assert!(matches!(result, Err(ContentFetcherError::Network { .. })), "{:?}", result.map(|content| content.url));
+        assert_eq!(hits.load(Ordering::SeqCst), 0);
+
+        // Without the guard the fallback goes ahead
+        let content = HttpClient::new().fetch_content(request).await.unwrap();
+        assert_eq!(content.title, Some("Bare host".to_string()));
+        assert_eq!(hits.load(Ordering::SeqCst), 1);
+    }
+
+    /// Stands in for the network: `secure.example.com` serves both schemes,
+    /// `legacy.example.com` refuses HTTPS connections.
+    async fn fetch_from_mock_schemes(request: FetchContentRequest) -> ContentFetcherResult<HtmlContent> {
+        let url = url::Url::parse(&request.url).unwrap();
+        match (url.scheme(), url.host_str()) {
+            ("https", Some("legacy.example.com")) => Err(ContentFetcherError::network("Connection failed: invalid TLS handshake")),
+            _ => Ok(HtmlContent { url: request.url, ..Default::default() }),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_upgrade_insecure_prefers_https() {
+        let request = FetchContentRequest {
+            url: "http://secure.example.com/login?next=/".to_string(),
+            upgrade_insecure: Some(true),
+            ..Default::default()
+        };
+
+        let content = fetch_with_https_upgrade(request, fetch_from_mock_schemes).await.unwrap();
+
+        assert_eq!(content.url, "https://secure.example.com/login?next=/");
+        assert_eq!(content.metadata.scheme, Some("https".to_string()));
+        assert_eq!(content.metadata.effective_url, Some("https://secure.example.com/login?next=/".to_string()));
+
+        let not_requested = FetchContentRequest { url: "http://secure.example.com/".to_string(), ..Default::default() };
+        let content = fetch_with_https_upgrade(not_requested, fetch_from_mock_schemes).await.unwrap();
+        assert_eq!(content.url, "http://secure.example.com/");
+        assert_eq!(content.metadata.scheme, None);
+    }
+
+    #[tokio::test]
+    async fn test_upgrade_insecure_falls_back_to_http() {
+        let request = FetchContentRequest {
+            url: "http://legacy.example.com/".to_string(),
+            upgrade_insecure: Some(true),
+            ..Default::default()
+        };
+
+        let content = fetch_with_https_upgrade(request, fetch_from_mock_schemes).await.unwrap();
+
+        assert_eq!(content.url, "http://legacy.example.com/");
+        assert_eq!(content.metadata.scheme, Some("http".to_string()));
+        assert_eq!(content.metadata.effective_url, None);
+
+        // A plain HTTP server fails the TLS handshake
+        let url = spawn_echo_referer_server().await;
+        let content = HttpClient::new().fetch_content(FetchContentRequest {
+            url: url.clone(),
+            upgrade_insecure: Some(true),
+            ..Default::default()
+        }).await.unwrap();
+        assert_eq!(content.url, url);
+        assert_eq!(content.metadata.scheme, Some("http".to_string()));
+    }
+
+    #[test]
+    fn test_sniff_charset_clear_utf8_is_confident() {
+        let url = url::Url::parse("https://example.com/").unwrap();
+        let body = "<html><body><p>Crème brûlée, smörgåsbord, naïve café — déjà vu.</p></body></html>";
+
+        let charset = sniff_charset(body.as_bytes(), &url);
+
+        assert_eq!(charset.encoding, encoding_rs::UTF_8);
+        assert!(charset.confidence > 0.9, "confidence {}", charset.confidence);
+    }
+
+    #[test]
+    fn test_sniff_charset_ambiguous_short_page_is_uncertain() {
+        let url = url::Url::parse("https://example.com/").unwrap();
+        // "café" in a legacy single-byte encoding: one non-UTF-8 byte to go on
+        let charset = sniff_charset(b"<p>caf\xe9</p>", &url);
+
+        assert_ne!(charset.encoding, encoding_rs::UTF_8);
+        assert!(charset.confidence < LOW_CHARSET_CONFIDENCE, "confidence {}", charset.confidence);
+
+        let ascii = sniff_charset(b"<p>plain</p>", &url);
+        assert_eq!(ascii.confidence, 1.0);
+    }
+
+    #[test]
+    fn test_toggle_www() {
+        assert_eq!(toggle_www("https://example.com/a"), Some("https://www.example.com/a".to_string()));
+        assert_eq!(toggle_www("http://www.example.com:8080/"), Some("http://example.com:8080/".to_string()));
+        assert_eq!(toggle_www("http://127.0.0.1:3000/"), None);
+        assert_eq!(toggle_www("not a url"), None);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_accepts_expected_content_type() {
+        let base = spawn_content_type_server().await;
+        let client = HttpClient::new();
+
+        let request = FetchContentRequest {
+            url: format!("{}/page", base),
+            expected_content_types: Some(vec!["text/html".to_string()]),
+            ..Default::default()
+        };
+        let content = client.fetch_content(request).await.unwrap();
+        assert_eq!(content.title, Some("Page".to_string()));
+        // axum's Html declares the charset, so nothing had to be guessed
+        assert_eq!(content.metadata.charset, Some("utf-8".to_string()));
+        assert_eq!(content.metadata.charset_confidence, Some(1.0));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_rejects_unexpected_content_type() {
+        let base = spawn_content_type_server().await;
+        let client = HttpClient::new();
+
+        let request = FetchContentRequest {
+            url: format!("{}/data", base),
+            expected_content_types: Some(vec!["text/html".to_string(), "application/xhtml+xml".to_string()]),
+            ..Default::default()
+        };
+        let error = client.fetch_content(request).await.unwrap_err();
+        assert!(matches!(error, ContentFetcherError::Parse(ref msg) if msg == "unexpected content type: application/json"));
+
+        let request = FetchContentRequest {
+            url: format!("{}/data", base),
+            ..Default::default()
+        };
+        assert!(client.fetch_content(request).await.is_ok());
+    }
+
+    async fn spawn_stalling_server(stall: Duration) -> String {
+        use futures::StreamExt;
+
+        let app = Router::new().route("/", get(move || async move {
+            let chunks = futures::stream::iter([
+                (Duration::ZERO, "<html><head><title>Slow</title></head><body>"),
+                (stall, "<p>finally</p></body></html>"),
+            ])
+            .then(|(delay, chunk)| async move {
+                tokio::time::sleep(delay).await;
+                Ok::<_, std::convert::Infallible>(chunk)
+            });
+            ([("content-type", "text/html; charset=utf-8")], axum::body::Body::from_stream(chunks))
+        }));
+        format!("{}/", spawn_server(app).await)
+    }
+
+    #[tokio::test]
+    async fn test_fetch_read_timeout_on_stalled_body() {
+        let url = spawn_stalling_server(Duration::from_secs(3)).await;
+        let client = HttpClient::new();
+
+        let request = FetchContentRequest {
+            url,
+            read_timeout_seconds: Some(1),
+            ..Default::default()
+        };
+        let started = std::time::Instant::now();
+        let error = client.fetch_content(request).await.unwrap_err();
+
+        assert!(matches!(error, ContentFetcherError::Timeout(1)));
+        assert!(started.elapsed() < Duration::from_secs(3));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_read_timeout_not_hit_by_steady_body() {
+        let url = spawn_stalling_server(Duration::from_millis(200)).await;
+        let client = HttpClient::new();
+
+        let request = FetchContentRequest {
+            url,
+            read_timeout_seconds: Some(2),
+            ..Default::default()
+        };
+        let content = client.fetch_content(request).await.unwrap();
+
+        assert_eq!(content.title, Some("Slow".to_string()));
+        assert!(content.raw_html.ends_with("<p>finally</p></body></html>"));
+    }
+
+    #[tokio::test]
+    async fn test_connection_error_keeps_reqwest_source() {
+        use std::error::Error;
+
+        let client = HttpClient::new();
+        let request = FetchContentRequest {
+            url: "http://127.0.0.1:1/".to_string(),
+            ..Default::default()
+        };
+
+        let error = client.fetch_content(request).await.unwrap_err();
+
+        assert!(error.to_string().starts_with("Network error: Connection failed: "));
+        let source = error.source().expect("reqwest error should be attached");
+        assert!(source.downcast_ref::<reqwest::Error>().is_some());
+    }
+
+    #[test]
+    fn test_http_version_preference_from_request() {
+        assert_eq!(HttpVersionPreference::from_request(None), HttpVersionPreference::Auto);
+        assert_eq!(HttpVersionPreference::from_request(Some("auto")), HttpVersionPreference::Auto);
+        assert_eq!(HttpVersionPreference::from_request(Some("http1")), HttpVersionPreference::Http1);
+        assert_eq!(HttpVersionPreference::from_request(Some("http2")), HttpVersionPreference::Http2);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_records_http_version() {
+        let url = spawn_echo_referer_server().await;
+        let client = HttpClient::new();
+
+        for http_version in [None, Some("http1".to_string())] {
+            let request = FetchContentRequest {
+                url: url.clone(),
+                http_version,
+                ..Default::default()
+            };
+            let content = client.fetch_content(request).await.unwrap();
+            assert_eq!(content.metadata.http_version, Some("HTTP/1.1".to_string()));
+        }
+    }
+
+    #[test]
+    fn test_ip_version_preference_from_request() {
+        assert_eq!(IpVersionPreference::from_request(None), IpVersionPreference::Auto);
+        assert_eq!(IpVersionPreference::from_request(Some("auto")), IpVersionPreference::Auto);
+        assert_eq!(IpVersionPreference::from_request(Some("v4")), IpVersionPreference::V4);
+        assert_eq!(IpVersionPreference::from_request(Some("v6")), IpVersionPreference::V6);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_ipv4_only_resolves_localhost() {
+        // The server only listens on 127.0.0.1, so this goes through the IPv4-only resolver
+        let url = spawn_echo_referer_server().await.replace("127.0.0.1", "localhost");
+        let client = HttpClient::new();
+
+        let request = FetchContentRequest {
+            url,
+            ip_version: Some("v4".to_string()),
+            ..Default::default()
+        };
+        let content = client.fetch_content(request).await.unwrap();
+
+        assert_eq!(content.title, Some("none".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_http2_prior_knowledge_is_applied() {
+        // Plain-text HTTP has no ALPN, so HTTP/2 is only used when forced with prior knowledge
+        let url = spawn_echo_referer_server().await;
+        let client = HttpClient::new();
+
+        let request = FetchContentRequest {
+            url,
+            http_version: Some("http2".to_string()),
+            ..Default::default()
+        };
+        let content = client.fetch_content(request).await.unwrap();
+
+        assert_eq!(content.metadata.http_version, Some("HTTP/2.0".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_build_request_signs_matching_hosts() {
+        let profile = SigningProfile::new(vec!["api.example.com".to_string()], "X-Signature", "top-secret", SigningAlgorithm::HmacSha256).unwrap();
+        let client = HttpClient::new().with_signing_profiles(vec![profile.clone()]);
+
+        let signed = FetchContentRequest {
+            url: "https://api.example.com/v1/items".to_string(),
+            ..Default::default()
+        };
+        let req = client.build_request(&signed, None).await.unwrap();
+        let header = req.headers().get("x-signature").unwrap().to_str().unwrap();
+        let timestamp: u64 = header.strip_prefix("t=").unwrap().split(',').next().unwrap().parse().unwrap();
+        assert_eq!(header, profile.signature_header(&signed.url, timestamp).1);
+
+        let unsigned = FetchContentRequest {
+            url: "https://www.example.com/".to_string(),
+            ..Default::default()
+        };
+        let req = client.build_request(&unsigned, None).await.unwrap();
+        assert!(req.headers().get("x-signature").is_none());
+    }
+
+    /// Redirects `/` to `target`, `/old` to `/new`, and serves a page at `/new`.
+    async fn spawn_redirect_server(target: &'static str) -> String {
+        let app = Router::new()
+            .route("/", get(move || async move { axum::response::Redirect::temporary(target) }))
+            .route("/old", get(|| async { axum::response::Redirect::permanent("/new") }))
+            .route("/new", get(|| async { Html("<html><head><title>New</title></head></html>") }));
+        spawn_server(app).await
+    }
+
+    #[tokio::test]
+    async fn test_follows_redirects() {
+        let base = spawn_redirect_server("/old").await;
+        let port = url::Url::parse(&base).unwrap().port().unwrap();
+        let client = HttpClient::new().with_allowed_ports(Some(vec![port]));
+
+        let content = client.fetch_content(FetchContentRequest { url: format!("{}/", base), ..Default::default() }).await.unwrap();
+
+        assert_eq!(content.url, format!("{}/new", base));
+        assert_eq!(content.title, Some("New".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_redirect_to_blocked_port_is_rejected() {
+        let base = spawn_redirect_server("http://127.0.0.1:6379/").await;
+        let port = url::Url::parse(&base).unwrap().port().unwrap();
+        let request = FetchContentRequest { url: format!("{}/", base), ..Default::default() };
+
+        let result = HttpClient::new().with_allowed_ports(Some(vec![port])).fetch_content(request.clone()).await;
+        match result {
+            Err(ContentFetcherError::InvalidUrl(message)) => assert!(message.contains("127.0.0.1:6379"), "{}", message),
+            other => panic!("expected the redirect to be rejected, got {:?}", other.map(|content| content.url)),
+        }
+
+        // Without a port list the redirect is followed, and fails to connect
+        let result = HttpClient::new().fetch_content(request).await;
+        assert!(matches!(result, Err(ContentFetcherError::Network { .. })), "{:?}", result.map(|content| content.url));
+    }
+
+    #[tokio::test]
+    async fn test_redirect_denied_by_fetch_guard_is_rejected() {
+        // Sends 127.0.0.1 to the same server under the name localhost
+        let app = Router::new().route("/", get(|headers: HeaderMap| async move {
+            let host = headers.get("host").and_then(|h| h.to_str().ok()).unwrap_or_default().replace("127.0.0.1", "localhost");
+            axum::response::Redirect::temporary(&format!("http://{}/", host))
+        }));
+        let base = spawn_server(app).await;
+        let guard = Arc::new(crate::adapter::static_allowlist_guard::StaticAllowlistGuard::new(vec!["127.0.0.1".to_string()]));
+
+        let result = HttpClient::new().with_fetch_guard(guard).fetch_content(FetchContentRequest { url: format!("{}/", base), ..Default::default() }).await;
+
+        match result {
+            Err(ContentFetcherError::InvalidUrl(message)) => assert_eq!(message, "host localhost is not on the allowlist"),
+            other => panic!("expected the redirect to be denied, got {:?}", other.map(|content| content.url)),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_signature_not_sent_on_cross_origin_redirect() {
+        let app = Router::new()
+            .route("/same", get(|| async { axum::response::Redirect::temporary("/echo") }))
+            .route("/cross", get(|headers: HeaderMap| async move {
+                let host = headers.get("host").and_then(|h| h.to_str().ok()).unwrap_or_default().replace("127.0.0.1", "localhost");
+                axum::response::Redirect::temporary(&format!("http://{}/echo", host))
+            }))
+            .route("/echo", get(|headers: HeaderMap| async move {
+                let signature = if headers.contains_key("x-signature") { "signed" } else { "unsigned" };
+                Html(format!("<html><head><title>{}</title></head></html>", signature))
+            }));
+        let base = spawn_server(app).await;
+        // Both names are signed hosts, so only the change of origin drops the signature
+        let profile = SigningProfile::new(vec!["127.0.0.1".to_string(), "localhost".to_string()], "X-Signature", "top-secret", SigningAlgorithm::HmacSha256).unwrap();
+        let client = HttpClient::new().with_signing_profiles(vec![profile]);
+
+        let same = client.fetch_content(FetchContentRequest { url: format!("{}/same", base), ..Default::default() }).await.unwrap();
+        assert_eq!(same.title, Some("signed".to_string()));
+
+        let cross = client.fetch_content(FetchContentRequest { url: format!("{}/cross", base), ..Default::default() }).await.unwrap();
+        assert!(cross.url.starts_with("http://localhost:"), "{}", cross.url);
+        assert_eq!(cross.title, Some("unsigned".to_string()));
+    }
+
+    /// Answers plain HTTP after `delay` but never completes a TLS handshake, like
+    /// a host whose firewall drops HTTPS.
+    async fn spawn_http_only_server(delay: Duration) -> String {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            loop {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                tokio::spawn(async move {
+                    let mut request = [0u8; 1024];
+                    let read = socket.read(&mut request).await.unwrap_or(0);
+                    // A TLS ClientHello starts with a handshake record
+                    if read == 0 || request[0] == 0x16 {
+                        tokio::time::sleep(Duration::from_secs(60)).await;
+                        return;
+                    }
+                    tokio::time::sleep(delay).await;
+                    let body = "<html><head><title>Plain</title></head></html>";
+                    let response = format!(
+                        "HTTP/1.1 200 OK\r\nContent-Type: text/html\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                        body.len(), body
+                    );
+                    let _ = socket.write_all(response.as_bytes()).await;
+                });
+            }
+        });
+        base
+    }
+
+    #[tokio::test]
+    async fn test_https_connect_timeout_falls_back_to_http() {
+        let base = spawn_http_only_server(Duration::ZERO).await;
+        let client = HttpClient::new().with_connect_timeout(Duration::from_millis(200));
+        let request = FetchContentRequest {
+            url: format!("{}/", base),
+            upgrade_insecure: Some(true),
+            timeout_seconds: Some(5),
+            ..Default::default()
+        };
+
+        let started = Instant::now();
+        let content = client.fetch_content(request).await.unwrap();
+
+        assert_eq!(content.metadata.scheme, Some("http".to_string()));
+        assert_eq!(content.title, Some("Plain".to_string()));
+        assert!(started.elapsed() < Duration::from_secs(2), "took {:?}", started.elapsed());
+    }
+
+    #[tokio::test]
+    async fn test_fallback_shares_the_request_deadline() {
+        // The HTTPS attempt uses 600ms of the second, so the slow HTTP answer is too late
+        let base = spawn_http_only_server(Duration::from_millis(700)).await;
+        let client = HttpClient::new().with_connect_timeout(Duration::from_millis(600));
+        let request = FetchContentRequest {
+            url: format!("{}/", base),
+            upgrade_insecure: Some(true),
+            timeout_seconds: Some(1),
+            ..Default::default()
+        };
+
+        let started = Instant::now();
+        let result = client.fetch_content(request).await;
+
+        // Reported against the request's deadline, not the part left for the HTTP attempt
+        assert!(matches!(result, Err(ContentFetcherError::Timeout(1))), "{:?}", result.map(|content| content.url));
+        assert!(started.elapsed() < Duration::from_millis(1200), "took {:?}", started.elapsed());
+    }
+
+    async fn accept_for(output_format: Option<ContentType>) -> String {
+        let request = FetchContentRequest {
+            url: "https://api.example.com/items".to_string(),
+            output_format,
+            ..Default::default()
+        };
+        let req = HttpClient::new().build_request(&request, None).await.unwrap();
+        req.headers().get("accept").unwrap().to_str().unwrap().to_string()
+    }
+
+    #[tokio::test]
+    async fn test_build_request_accept_follows_output_format() {
+        let default = accept_for(None).await;
+        assert!(default.starts_with("text/html,"), "{}", default);
+        assert_eq!(accept_for(Some(ContentType::Html)).await, default);
+
+        let json = accept_for(Some(ContentType::Json)).await;
+        assert!(json.starts_with("application/json,"), "{}", json);
+        assert!(json.contains("text/html;q=0.9"), "HTML must stay acceptable: {}", json);
+        assert!(accept_for(Some(ContentType::Markdown)).await.starts_with("text/markdown,"));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_sends_referer_header() {
+        let url = spawn_echo_referer_server().await;
+        let client = HttpClient::new();
+
+        let request = FetchContentRequest {
+            url: url.clone(),
+            referer: Some("https://www.google.com/".to_string()),
+            ..Default::default()
+        };
+        let content = client.fetch_content(request).await.unwrap();
+        assert_eq!(content.title, Some("https://www.google.com/".to_string()));
+
+        let request = FetchContentRequest {
+            url,
+            ..Default::default()
+        };
+        let content = client.fetch_content(request).await.unwrap();
+        assert_eq!(content.title, Some("none".to_string()));
+    }
+}
@@ -1,9 +1,10 @@
 use async_trait::async_trait;
-use domain::model::content::{BrowserOptions, FetchMethod};
+use domain::model::content::{BrowserOptions, FetchMethod, HtmlContent};
 use domain::port::content_fetcher::{ContentFetcher, ContentFetcherError};
 use std::sync::Arc;
+use tracing::warn;
 
-use super::browser_client::BrowserContentFetcher;
+use super::browser_client::{BrowserContentFetcher, BrowserLimits};
 use super::http_client::HttpClient;
 
 pub struct HybridContentFetcher {
@@ -13,10 +14,13 @@ pub struct HybridContentFetcher {
 }
 
 impl HybridContentFetcher {
-    pub async fn new(browser_options: Option<BrowserOptions>) -> Result<Self, ContentFetcherError> {
-        let http_fetcher = Arc::new(HttpClient::new());
-        let browser_fetcher = Arc::new(BrowserContentFetcher::new().await?);
-        
+    pub async fn new(browser_options: Option<BrowserOptions>, browser_limits: BrowserLimits) -> Result<Self, ContentFetcherError> {
+        let browser_fetcher = BrowserContentFetcher::with_limits(browser_limits).await?;
+        Ok(Self::with_browser(browser_fetcher, browser_options))
+    }
+
+    /// Renders with an already started `browser_fetcher`.
+    pub(crate) fn with_browser(browser_fetcher: BrowserContentFetcher, browser_options: Option<BrowserOptions>) -> Self {
         let default_browser_options = BrowserOptions {
             wait_for_js: true,
             timeout_ms: 10000,
@@ -25,11 +29,17 @@ impl HybridContentFetcher {
             user_agent: Some("Mozilla/5.0 (X11; Linux x86_64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36".to_string()),
         };
 
-        Ok(Self {
-            http_fetcher,
-            browser_fetcher,
+        Self {
+            http_fetcher: Arc::new(HttpClient::new()),
+            browser_fetcher: Arc::new(browser_fetcher),
             browser_options: browser_options.unwrap_or(default_browser_options),
-        })
+        }
+    }
+
+    /// Replaces the client used for static fetches, e.g. with one that signs requests.
+    pub fn with_http_client(mut self, http_client: HttpClient) -> Self {
+        self.http_fetcher = Arc::new(http_client);
+        self
     }
 
     pub async fn fetch_with_method(
@@ -37,10 +47,12 @@ impl HybridContentFetcher {
         request: &domain::model::request::FetchContentRequest,
         method: FetchMethod,
     ) -> Result<domain::model::content::HtmlContent, ContentFetcherError> {
-        match method {
-            FetchMethod::Static => self.http_fetcher.fetch_content(request.clone()).await,
-            FetchMethod::Browser => self.browser_fetcher.fetch_content(request.clone()).await,
-        }
+        let mut content = match method {
+            FetchMethod::Static => self.http_fetcher.fetch_content(request.clone()).await?,
+            FetchMethod::Browser => self.browser_fetcher.fetch_content(request.clone()).await?,
+        };
+        content.metadata.fetch_method = Some(method);
+        Ok(content)
     }
 
     pub async fn detect_and_fetch(&self, request: &domain::model::request::FetchContentRequest) -> Result<(domain::model::content::HtmlContent, FetchMethod), ContentFetcherError> {
@@ -51,21 +63,9 @@ impl HybridContentFetcher {
         let has_javascript = self.browser_fetcher.detect_javascript(&static_content.raw_html).await;
         
         if has_javascript {
-            // Try browser fetcher for JavaScript content, fallback to static if it fails
-            match self.browser_fetcher.fetch_content(request.clone()).await {
-                Ok(mut browser_content) => {
-                    browser_content.metadata.javascript_detected = Some(true);
-                    browser_content.metadata.fetch_method = Some(FetchMethod::Browser);
-                    Ok((browser_content, FetchMethod::Browser))
-                }
-                Err(_) => {
-                    // Browser failed, use static content as fallback
-                    let mut static_result = static_content;
-                    static_result.metadata.javascript_detected = Some(true);
-                    static_result.metadata.fetch_method = Some(FetchMethod::Static);
-                    Ok((static_result, FetchMethod::Static))
-                }
-            }
+            let browser_result = self.browser_fetcher.fetch_content(request.clone()).await;
+            let fallback = request.browser_fallback.unwrap_or(true);
+            resolve_browser_fetch(static_content, browser_result, fallback)
         } else {
             // Use static content for plain HTML
             let mut static_result = static_content;
@@ -84,12 +84,55 @@ impl HybridContentFetcher {
     }
 }
 
+/// Picks the result for a page that needed JavaScript: the browser render when it
+/// succeeded, otherwise the static fetch annotated with the reason, or the browser
+/// error when fallback is disabled.
+fn resolve_browser_fetch(
+    static_content: HtmlContent,
+    browser_result: Result<HtmlContent, ContentFetcherError>,
+    fallback: bool,
+) -> Result<(HtmlContent, FetchMethod), ContentFetcherError> {
+    match browser_result {
+        Ok(mut browser_content) => {
+            browser_content.metadata.javascript_detected = Some(true);
+            browser_content.metadata.fetch_method = Some(FetchMethod::Browser);
+            Ok((browser_content, FetchMethod::Browser))
+        }
+        Err(error) if fallback => {
+            warn!("Browser fetch failed for {}, using the static result: {}", static_content.url, error);
+            let mut static_result = static_content;
+            static_result.metadata.javascript_detected = Some(true);
+            static_result.metadata.fetch_method = Some(FetchMethod::Static);
+            static_result.metadata.fallback_reason = Some(format!("Browser fetch failed: {}", error));
+            Ok((static_result, FetchMethod::Static))
+        }
+        Err(error) => Err(error),
+    }
+}
+
 #[async_trait]
 impl ContentFetcher for HybridContentFetcher {
     async fn fetch_content(&self, request: domain::model::request::FetchContentRequest) -> Result<domain::model::content::HtmlContent, ContentFetcherError> {
+        if let Some(method) = request.force_fetch_method {
+            return self.fetch_with_method(&request, method).await;
+        }
+
+        // The browser can only GET, so uploads skip detection
+        if request.multipart.is_some() {
+            return self.http_fetcher.fetch_content(request).await;
+        }
+
         let (content, _method) = self.detect_and_fetch(&request).await?;
         Ok(content)
     }
+
+    async fn fetch_static(&self, request: domain::model::request::FetchContentRequest) -> Result<domain::model::content::HtmlContent, ContentFetcherError> {
+        self.http_fetcher.fetch_content(request).await
+    }
+
+    async fn shutdown(&self) {
+        self.browser_fetcher.shutdown().await
+    }
 }
 
 pub struct JavaScriptDetector;
@@ -165,6 +208,95 @@ impl JavaScriptDetector {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_support::{spawn_server, StubBrowser};
+
+    async fn spawn_js_page_server() -> String {
+        use axum::{response::Html, routing::get, Router};
+
+        let app = Router::new().route("/", get(|| async {
+            Html("<html><head><title>App</title></head><body><div data-reactroot></div><script>render()</script></body></html>")
+        }));
+        format!("{}/", spawn_server(app).await)
+    }
+
+    #[tokio::test]
+    async fn test_force_fetch_method_overrides_detection() {
+        let url = spawn_js_page_server().await;
+        let browser = StubBrowser::default().with_page(&url, "<html><head><title>Rendered</title></head></html>");
+        let browser_fetcher = BrowserContentFetcher::with_engine(Box::new(browser), BrowserLimits::default());
+        let fetcher = HybridContentFetcher::with_browser(browser_fetcher, None);
+
+        for (method, title) in [(FetchMethod::Static, "App"), (FetchMethod::Browser, "Rendered")] {
+            let request = domain::model::request::FetchContentRequest {
+                url: url.clone(),
+                timeout_seconds: Some(1),
+                force_fetch_method: Some(method),
+                ..Default::default()
+            };
+            let content = fetcher.fetch_content(request).await.unwrap();
+            assert_eq!(content.metadata.fetch_method, Some(method));
+            assert_eq!(content.title.as_deref(), Some(title));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_javascript_page_is_rendered_in_the_browser() {
+        let url = spawn_js_page_server().await;
+        let browser = StubBrowser::default().with_page(&url, "<html><head><title>Rendered</title></head></html>");
+        let browser_fetcher = BrowserContentFetcher::with_engine(Box::new(browser), BrowserLimits::default());
+        let fetcher = HybridContentFetcher::with_browser(browser_fetcher, None);
+
+        let request = domain::model::request::FetchContentRequest { url, ..Default::default() };
+        let content = fetcher.fetch_content(request).await.unwrap();
+
+        assert_eq!(content.metadata.fetch_method, Some(FetchMethod::Browser));
+        assert_eq!(content.metadata.javascript_detected, Some(true));
+        assert_eq!(content.title.as_deref(), Some("Rendered"));
+    }
+
+    fn static_page() -> HtmlContent {
+        HtmlContent {
+            url: "https://example.com/app".to_string(),
+            text_content: "Loading...".to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_browser_failure_falls_back_to_static_with_reason() {
+        let browser_error = Err(ContentFetcherError::network("Chrome crashed".to_string()));
+
+        let (content, method) = resolve_browser_fetch(static_page(), browser_error, true).unwrap();
+
+        assert_eq!(method, FetchMethod::Static);
+        assert_eq!(content.text_content, "Loading...");
+        assert_eq!(content.metadata.fetch_method, Some(FetchMethod::Static));
+        assert_eq!(content.metadata.javascript_detected, Some(true));
+        assert_eq!(
+            content.metadata.fallback_reason.as_deref(),
+            Some("Browser fetch failed: Network error: Chrome crashed")
+        );
+    }
+
+    #[test]
+    fn test_browser_failure_without_fallback_returns_error() {
+        let browser_error = Err(ContentFetcherError::Timeout(10));
+
+        let result = resolve_browser_fetch(static_page(), browser_error, false);
+
+        assert!(matches!(result, Err(ContentFetcherError::Timeout(10))));
+    }
+
+    #[test]
+    fn test_browser_success_has_no_fallback_reason() {
+        let rendered = HtmlContent { text_content: "Rendered".to_string(), ..static_page() };
+
+        let (content, method) = resolve_browser_fetch(static_page(), Ok(rendered), false).unwrap();
+
+        assert_eq!(method, FetchMethod::Browser);
+        assert_eq!(content.text_content, "Rendered");
+        assert!(content.metadata.fallback_reason.is_none());
+    }
 
     #[test]
     fn test_detect_spa_frameworks() {
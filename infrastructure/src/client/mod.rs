@@ -1,3 +1,10 @@
 pub mod http_client;
 pub mod browser_client;
-pub mod hybrid_fetcher;
\ No newline at end of file
+pub mod hybrid_fetcher;
+pub mod fetcher_kind;
+pub mod caching_fetcher;
+pub mod request_signing;
+pub mod archive_fallback_fetcher;
+pub mod timing_fetcher;
+pub mod header_redaction;
+
@@ -0,0 +1,164 @@
+use std::fmt;
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
+use hmac::{Hmac, Mac};
+use reqwest::header::{HeaderName, HeaderValue};
+use sha2::{Sha256, Sha512};
+use url::Url;
+
+/// Hash function the request signature's HMAC is computed with.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SigningAlgorithm {
+    #[default]
+    HmacSha256,
+    HmacSha512,
+}
+
+impl FromStr for SigningAlgorithm {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "hmac-sha256" => Ok(SigningAlgorithm::HmacSha256),
+            "hmac-sha512" => Ok(SigningAlgorithm::HmacSha512),
+            other => Err(format!("unknown signing algorithm '{}', expected hmac-sha256 or hmac-sha512", other)),
+        }
+    }
+}
+
+impl fmt::Display for SigningAlgorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            SigningAlgorithm::HmacSha256 => "hmac-sha256",
+            SigningAlgorithm::HmacSha512 => "hmac-sha512",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Signs requests to some hosts with an HMAC of the URL and the current time,
+/// sent as `<header_name>: t=<unix seconds>,sig=<hex HMAC>`. The HMAC covers
+/// `<url>\n<unix seconds>`, so the receiver can rebuild it from the request and
+/// reject stale timestamps. The secret is kept out of `Debug` output so it never
+/// reaches the logs.
+#[derive(Clone)]
+pub struct SigningProfile {
+    /// Hosts whose requests are signed; subdomains of a listed host match too.
+    hosts: Vec<String>,
+    header_name: HeaderName,
+    secret: Vec<u8>,
+    algorithm: SigningAlgorithm,
+}
+
+impl SigningProfile {
+    pub fn new(hosts: Vec<String>, header_name: &str, secret: impl Into<Vec<u8>>, algorithm: SigningAlgorithm) -> Result<Self, String> {
+        let header_name = HeaderName::from_bytes(header_name.as_bytes())
+            .map_err(|_| format!("invalid signing header name '{}'", header_name))?;
+        let secret = secret.into();
+        if secret.is_empty() {
+            return Err("signing secret must not be empty".to_string());
+        }
+
+        Ok(Self {
+            hosts: hosts.into_iter().map(|host| host.trim().trim_end_matches('.').to_ascii_lowercase()).collect(),
+            header_name,
+            secret,
+            algorithm,
+        })
+    }
+
+    pub fn matches(&self, url: &Url) -> bool {
+        let Some(host) = url.host_str() else {
+            return false;
+        };
+        let host = host.to_ascii_lowercase();
+        self.hosts.iter().any(|signed| {
+            host == *signed || host.strip_suffix(signed.as_str()).is_some_and(|prefix| prefix.ends_with('.'))
+        })
+    }
+
+    pub fn header_name(&self) -> &HeaderName {
+        &self.header_name
+    }
+
+    /// The header to attach to a request for `url` made at `timestamp` (Unix seconds).
+    pub fn signature_header(&self, url: &str, timestamp: u64) -> (HeaderName, HeaderValue) {
+        let message = format!("{}\n{}", url, timestamp);
+        let signature = match self.algorithm {
+            SigningAlgorithm::HmacSha256 => hmac_hex::<Hmac<Sha256>>(&self.secret, message.as_bytes()),
+            SigningAlgorithm::HmacSha512 => hmac_hex::<Hmac<Sha512>>(&self.secret, message.as_bytes()),
+        };
+        let value = HeaderValue::from_str(&format!("t={},sig={}", timestamp, signature))
+            .expect("timestamp and hex digest are valid header characters");
+        (self.header_name.clone(), value)
+    }
+
+    /// The header for a request to `url` made now.
+    pub fn sign_now(&self, url: &str) -> (HeaderName, HeaderValue) {
+        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |elapsed| elapsed.as_secs());
+        self.signature_header(url, timestamp)
+    }
+}
+
+impl fmt::Debug for SigningProfile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SigningProfile")
+            .field("hosts", &self.hosts)
+            .field("header_name", &self.header_name)
+            .field("secret", &"<redacted>")
+            .field("algorithm", &self.algorithm)
+            .finish()
+    }
+}
+
+fn hmac_hex<M: Mac + hmac::digest::KeyInit>(secret: &[u8], message: &[u8]) -> String {
+    let mut mac = <M as hmac::digest::KeyInit>::new_from_slice(secret).expect("HMAC accepts keys of any length");
+    mac.update(message);
+    mac.finalize().into_bytes().iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const URL: &str = "https://api.example.com/v1/items?page=2";
+
+    fn profile(algorithm: SigningAlgorithm) -> SigningProfile {
+        SigningProfile::new(vec!["example.com".to_string()], "X-Signature", "top-secret", algorithm).unwrap()
+    }
+
+    #[test]
+    fn test_signature_matches_known_hmac() {
+        let (name, value) = profile(SigningAlgorithm::HmacSha256).signature_header(URL, 1_700_000_000);
+        assert_eq!(name, "x-signature");
+        assert_eq!(
+            value,
+            "t=1700000000,sig=541755cc9212b36f33ebc03448e1eda4293b5a6a336be810816783220114bb09"
+        );
+
+        let (_, value) = profile(SigningAlgorithm::HmacSha512).signature_header(URL, 1_700_000_000);
+        assert_eq!(
+            value,
+            "t=1700000000,sig=ee770f6789c173c303151b500f84460f7d149d6449b2613872ed7f50a9f61702\
+             bf54e0402ba7d9f047d7d863fd6eca09998d81defc468b07a78f69fe33c5623f"
+        );
+    }
+
+    #[test]
+    fn test_profile_matches_host_and_subdomains() {
+        let profile = profile(SigningAlgorithm::HmacSha256);
+
+        assert!(profile.matches(&Url::parse("https://example.com/").unwrap()));
+        assert!(profile.matches(&Url::parse(URL).unwrap()));
+        assert!(!profile.matches(&Url::parse("https://notexample.com/").unwrap()));
+        assert!(!profile.matches(&Url::parse("https://example.org/").unwrap()));
+    }
+
+    #[test]
+    fn test_secret_not_in_debug_output() {
+        let debug = format!("{:?}", profile(SigningAlgorithm::HmacSha256));
+
+        assert!(!debug.contains("top-secret"));
+        assert!(debug.contains("<redacted>"));
+    }
+}
@@ -0,0 +1,116 @@
+use std::sync::Arc;
+use std::time::Instant;
+use async_trait::async_trait;
+use domain::model::{content::HtmlContent, request::FetchContentRequest};
+use domain::port::content_fetcher::{ContentFetcher, ContentFetcherResult};
+
+/// Whole milliseconds since `start`.
+pub fn elapsed_ms(start: Instant) -> u64 {
+    start.elapsed().as_millis().try_into().unwrap_or(u64::MAX)
+}
+
+/// Wraps another fetcher and records in `metadata.timing` how long each fetch
+/// took overall. Phases the wrapped fetcher measured itself, such as the time
+/// to first byte and download of a static fetch, are kept; for fetchers that
+/// measure nothing, the total is all there is.
+pub struct TimingFetcher<F: ContentFetcher + ?Sized> {
+    inner: Arc<F>,
+}
+
+impl<F: ContentFetcher + ?Sized> TimingFetcher<F> {
+    pub fn new(inner: Arc<F>) -> Self {
+        Self { inner }
+    }
+}
+
+/// Sets the total on whatever timing the fetch already carries.
+fn record_total(mut content: HtmlContent, start: Instant) -> HtmlContent {
+    content.metadata.timing.get_or_insert_with(Default::default).total_ms = Some(elapsed_ms(start));
+    content
+}
+
+#[async_trait]
+impl<F: ContentFetcher + ?Sized> ContentFetcher for TimingFetcher<F> {
+    async fn fetch_content(&self, request: FetchContentRequest) -> ContentFetcherResult<HtmlContent> {
+        let start = Instant::now();
+        self.inner.fetch_content(request).await.map(|content| record_total(content, start))
+    }
+
+    async fn fetch_static(&self, request: FetchContentRequest) -> ContentFetcherResult<HtmlContent> {
+        let start = Instant::now();
+        self.inner.fetch_static(request).await.map(|content| record_total(content, start))
+    }
+
+    async fn shutdown(&self) {
+        self.inner.shutdown().await
+    }
+
+    fn cached_urls(&self) -> Vec<String> {
+        self.inner.cached_urls()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use axum::{response::Html, routing::get, Router};
+    use domain::model::content::PhaseTiming;
+    use crate::client::http_client::HttpClient;
+    use crate::test_support::spawn_server;
+
+    /// Takes `delay` to answer and reports no timing of its own.
+    struct SlowFetcher {
+        delay: Duration,
+    }
+
+    #[async_trait]
+    impl ContentFetcher for SlowFetcher {
+        async fn fetch_content(&self, request: FetchContentRequest) -> ContentFetcherResult<HtmlContent> {
+            tokio::time::sleep(self.delay).await;
+            Ok(HtmlContent { url: request.url, ..Default::default() })
+        }
+    }
+
+    fn phase_sum(timing: &PhaseTiming) -> u64 {
+        [timing.dns_ms, timing.connect_ms, timing.ttfb_ms, timing.download_ms, timing.parse_ms]
+            .into_iter()
+            .flatten()
+            .sum()
+    }
+
+    #[tokio::test]
+    async fn test_total_recorded_for_fetchers_without_phases() {
+        let fetcher = TimingFetcher::new(Arc::new(SlowFetcher { delay: Duration::from_millis(30) }));
+
+        let content = fetcher
+            .fetch_content(FetchContentRequest { url: "https://example.com".to_string(), ..Default::default() })
+            .await
+            .unwrap();
+
+        let timing = content.metadata.timing.unwrap();
+        assert!(timing.total_ms.unwrap() >= 30);
+        assert_eq!(timing.ttfb_ms, None);
+        assert_eq!(timing.parse_ms, None);
+    }
+
+    #[tokio::test]
+    async fn test_static_phases_kept_within_total() {
+        let body = format!("<html><head><title>Timed</title></head><body>{}</body></html>", "<p>paragraph</p>".repeat(10_000));
+        let app = Router::new().route("/", get(move || async move {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            Html(body)
+        }));
+        let url = format!("{}/", spawn_server(app).await);
+        let fetcher = TimingFetcher::new(Arc::new(HttpClient::new()));
+
+        let content = fetcher.fetch_content(FetchContentRequest { url, ..Default::default() }).await.unwrap();
+
+        let timing = content.metadata.timing.unwrap();
+        let (ttfb, total) = (timing.ttfb_ms.unwrap(), timing.total_ms.unwrap());
+        assert!(timing.download_ms.is_some() && timing.parse_ms.is_some());
+        assert!(ttfb >= 20);
+        assert!(total >= ttfb);
+        assert!(total >= phase_sum(&timing), "{:?}", timing);
+    }
+}
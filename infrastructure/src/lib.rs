@@ -1,4 +1,10 @@
+// The fetch_web_content input schema is one large `json!` literal
+#![recursion_limit = "256"]
+
 pub mod client;
 pub mod api;
 pub mod mcp;
-pub mod adapter;
\ No newline at end of file
+pub mod adapter;
+
+#[cfg(test)]
+mod test_support;
@@ -0,0 +1,104 @@
+use std::io::{self, BufWriter, Write};
+use std::sync::{Arc, Mutex};
+use serde_json::Value;
+
+/// Writes JSON-RPC responses as newline-delimited JSON to a shared output.
+/// Each response is serialized before the lock is taken and then written,
+/// newline included, and flushed under it, so responses from concurrent
+/// handlers never interleave and every line is one complete message.
+pub struct ResponseWriter<W: Write> {
+    output: Arc<Mutex<BufWriter<W>>>,
+}
+
+impl<W: Write> ResponseWriter<W> {
+    pub fn new(output: W) -> Self {
+        Self {
+            output: Arc::new(Mutex::new(BufWriter::new(output))),
+        }
+    }
+
+    /// Writes `response` as one line and flushes it, returning the JSON written.
+    pub fn write_line(&self, response: &Value) -> io::Result<String> {
+        let response_json = serde_json::to_string(response)?;
+        let mut line = Vec::with_capacity(response_json.len() + 1);
+        line.extend_from_slice(response_json.as_bytes());
+        line.push(b'\n');
+
+        // A panic while writing leaves at worst a partial line; keep serving
+        let mut output = self.output.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        output.write_all(&line)?;
+        output.flush()?;
+        Ok(response_json)
+    }
+}
+
+impl<W: Write> Clone for ResponseWriter<W> {
+    fn clone(&self) -> Self {
+        Self {
+            output: self.output.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// Accepts at most a few bytes per call, yielding in between, so unguarded
+    /// concurrent writers would interleave.
+    #[derive(Clone, Default)]
+    struct TrickleWriter(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for TrickleWriter {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            let written = buf.len().min(3);
+            self.0.lock().unwrap().extend_from_slice(&buf[..written]);
+            std::thread::yield_now();
+            Ok(written)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_concurrent_responses_are_whole_lines() {
+        let output = TrickleWriter::default();
+        let writer = ResponseWriter::new(output.clone());
+
+        let handles: Vec<_> = (0..8)
+            .map(|thread| {
+                let writer = writer.clone();
+                std::thread::spawn(move || {
+                    for request in 0..50 {
+                        let response = json!({
+                            "jsonrpc": "2.0",
+                            "id": format!("{}-{}", thread, request),
+                            "result": { "text_content": "x".repeat(200) }
+                        });
+                        writer.write_line(&response).unwrap();
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        let written = String::from_utf8(output.0.lock().unwrap().clone()).unwrap();
+        let mut ids: Vec<String> = written
+            .lines()
+            .map(|line| {
+                let response: Value = serde_json::from_str(line).unwrap_or_else(|error| panic!("{}: {}", error, line));
+                response["id"].as_str().unwrap().to_string()
+            })
+            .collect();
+        assert!(written.ends_with('\n'));
+        assert_eq!(ids.len(), 400);
+        ids.sort();
+        ids.dedup();
+        assert_eq!(ids.len(), 400);
+    }
+}
@@ -1,28 +1,88 @@
+use std::borrow::Cow;
+use std::io::{self, BufRead, Write};
 use std::sync::Arc;
+use futures::future::join_all;
+use tokio::sync::{Semaphore, SemaphorePermit};
 use serde_json::{json, Value};
-use tracing::{info, error, debug};
+use tracing::{info, error, debug, warn};
+use tracing_subscriber::{filter::LevelFilter, reload, Registry};
 use domain::model::{
-    request::{FetchContentRequest, McpRequest},
+    request::{FetchContentRequest, McpRequest, ParseOptions},
     response::ToolCapabilities,
 };
+use application::service::content_chunk_service::DEFAULT_CHUNK_TARGET_CHARS;
 use application::use_case::fetch_web_content_use_case::FetchWebContentUseCase;
 use domain::port::{content_fetcher::ContentFetcher, content_parser::ContentParser};
+use crate::client::hybrid_fetcher::JavaScriptDetector;
+use crate::mcp::response_writer::ResponseWriter;
 
 pub struct McpServer<F, P>
 where
-    F: ContentFetcher,
+    F: ContentFetcher + ?Sized,
     P: ContentParser,
 {
     fetch_use_case: Arc<FetchWebContentUseCase<F, P>>,
+    log_level_handle: Option<LogLevelHandle>,
+    strict_args: bool,
+    max_body_log_bytes: usize,
+    fetch_slots: Option<Arc<Semaphore>>,
 }
 
+/// Most values returned by `completion/complete`, as the MCP spec allows.
+const MAX_COMPLETION_VALUES: usize = 100;
+
+/// How much of each request and response line is written to the debug log by default.
+pub const DEFAULT_MAX_BODY_LOG_BYTES: usize = 2048;
+
+/// Handle to the reloadable level filter installed by the runner, used to
+/// service `logging/setLevel` requests.
+pub type LogLevelHandle = reload::Handle<LevelFilter, Registry>;
+
 impl<F, P> McpServer<F, P>
 where
-    F: ContentFetcher,
+    F: ContentFetcher + ?Sized,
     P: ContentParser,
 {
     pub fn new(fetch_use_case: Arc<FetchWebContentUseCase<F, P>>) -> Self {
-        Self { fetch_use_case }
+        Self {
+            fetch_use_case,
+            log_level_handle: None,
+            strict_args: true,
+            max_body_log_bytes: DEFAULT_MAX_BODY_LOG_BYTES,
+            fetch_slots: None,
+        }
+    }
+
+    /// Limits concurrent fetching tool calls to the permits of `fetch_slots`,
+    /// which may be shared with other servers for one limit across all of them.
+    pub fn with_fetch_slots(mut self, fetch_slots: Arc<Semaphore>) -> Self {
+        self.fetch_slots = Some(fetch_slots);
+        self
+    }
+
+    /// Waits for a fetch slot when fetches are limited; the fetch may start once
+    /// this returns and the slot is held until the permit is dropped.
+    async fn fetch_slot(&self) -> Option<SemaphorePermit<'_>> {
+        self.fetch_slots.as_ref()?.acquire().await.ok()
+    }
+
+    /// Caps how many bytes of each request and response line are logged, so a
+    /// `parse_html` call with a large document doesn't flood the debug log.
+    pub fn with_max_body_log_bytes(mut self, max_body_log_bytes: usize) -> Self {
+        self.max_body_log_bytes = max_body_log_bytes;
+        self
+    }
+
+    /// When strict (the default), tool arguments of the wrong type are rejected
+    /// with `-32602`; otherwise they fall back to their defaults.
+    pub fn with_strict_args(mut self, strict_args: bool) -> Self {
+        self.strict_args = strict_args;
+        self
+    }
+
+    pub fn with_log_level_handle(mut self, handle: LogLevelHandle) -> Self {
+        self.log_level_handle = Some(handle);
+        self
     }
 
     pub async fn handle_request(&self, request: McpRequest) -> Value {
@@ -32,48 +92,104 @@ where
             "tools/list" => self.handle_tools_list(request.id).await,
             "tools/call" => self.handle_tools_call(request).await,
             "initialize" => self.handle_initialize(request.id).await,
+            "logging/setLevel" => self.handle_set_level(request).await,
+            "completion/complete" => self.handle_completion(request).await,
             _ => self.handle_unknown_method(request.id, &request.method).await,
         }
     }
 
+    /// Handles a JSON-RPC batch, running its requests concurrently and answering
+    /// in request order. Elements without an `id` are notifications: they run but
+    /// get no response, so a batch of only notifications returns `None`. An empty
+    /// batch is itself an invalid request.
+    pub async fn handle_batch(&self, batch: &[Value]) -> Option<Value> {
+        if batch.is_empty() {
+            return Some(invalid_request(Value::Null, "Invalid Request: empty batch"));
+        }
+
+        debug!("Handling batch of {} requests", batch.len());
+
+        let responses = join_all(batch.iter().map(|message| async move {
+            let is_notification = message.is_object() && message.get("id").is_none();
+            let response = match parse_request(message) {
+                Ok(request) => self.handle_request(request).await,
+                Err(error) => invalid_request(message.get("id").cloned().unwrap_or(Value::Null), &error),
+            };
+            (!is_notification).then_some(response)
+        }))
+        .await;
+
+        let responses: Vec<Value> = responses.into_iter().flatten().collect();
+        (!responses.is_empty()).then_some(Value::Array(responses))
+    }
+
+    /// Serves newline-delimited JSON-RPC read from `reader`, writing each response
+    /// as one line to `writer`, until the reader is exhausted. Lines are read as
+    /// bytes so one that isn't valid UTF-8 only fails itself, not the server.
+    /// Responses go through a `ResponseWriter`, so each is written whole.
+    pub async fn serve<R: BufRead, W: Write>(&self, mut reader: R, writer: W) -> io::Result<()> {
+        let writer = ResponseWriter::new(writer);
+        let mut line = Vec::new();
+        loop {
+            line.clear();
+            if reader.read_until(b'\n', &mut line)? == 0 {
+                return Ok(());
+            }
+
+            if let Some(response) = self.handle_line(&line).await {
+                let response_json = writer.write_line(&response)?;
+                debug!("Sent response: {}", truncate_for_log(&response_json, self.max_body_log_bytes));
+            }
+        }
+    }
+
+    /// Answers one line of input; blank lines and batches of notifications get no
+    /// response. A line with invalid UTF-8 is rejected with a parse error that
+    /// carries the request's id when the lossily decoded line still has one.
+    async fn handle_line(&self, line: &[u8]) -> Option<Value> {
+        let line = match std::str::from_utf8(line) {
+            Ok(line) => line.trim(),
+            Err(error) => {
+                warn!("Rejecting request line that isn't valid UTF-8: {}", error);
+                let id = serde_json::from_str::<Value>(&String::from_utf8_lossy(line))
+                    .map(|message| Value::String(request_id(&message)))
+                    .unwrap_or(Value::Null);
+                return Some(parse_error(id, "Invalid UTF-8 in request"));
+            }
+        };
+
+        if line.is_empty() {
+            return None;
+        }
+
+        debug!("Received request: {}", truncate_for_log(line, self.max_body_log_bytes));
+
+        let message = match serde_json::from_str::<Value>(line) {
+            Ok(message) => message,
+            Err(error) => {
+                error!("Failed to parse request: Invalid JSON: {}", error);
+                return Some(parse_error(Value::Null, &format!("Invalid JSON: {}", error)));
+            }
+        };
+
+        // A top-level array is a JSON-RPC batch
+        if let Value::Array(batch) = &message {
+            return self.handle_batch(batch).await;
+        }
+
+        match parse_request(&message) {
+            Ok(request) => Some(self.handle_request(request).await),
+            Err(error) => {
+                error!("Failed to parse request: {}", error);
+                Some(parse_error(Value::Null, &error))
+            }
+        }
+    }
+
     async fn handle_tools_list(&self, id: String) -> Value {
         info!("Handling tools/list request");
 
-        let tools = vec![ToolCapabilities {
-            name: "fetch_web_content".to_string(),
-            description: "Fetch and extract content from web pages. Supports HTML parsing and text extraction.".to_string(),
-            input_schema: json!({
-                "type": "object",
-                "properties": {
-                    "url": {
-                        "type": "string",
-                        "description": "The URL to fetch content from"
-                    },
-                    "extract_text_only": {
-                        "type": "boolean",
-                        "description": "Whether to extract only text content (default: true)",
-                        "default": true
-                    },
-                    "follow_redirects": {
-                        "type": "boolean", 
-                        "description": "Whether to follow HTTP redirects (default: true)",
-                        "default": true
-                    },
-                    "timeout_seconds": {
-                        "type": "integer",
-                        "description": "Request timeout in seconds (default: 30, max: 300)",
-                        "default": 30,
-                        "minimum": 1,
-                        "maximum": 300
-                    },
-                    "user_agent": {
-                        "type": "string",
-                        "description": "Custom User-Agent header (optional)"
-                    }
-                },
-                "required": ["url"]
-            })
-        }];
+        let tools = tool_definitions();
 
         json!({
             "jsonrpc": "2.0",
@@ -90,7 +206,7 @@ where
         let tool_name = request.params.get("name").and_then(|v| v.as_str());
         let arguments = request.params.get("arguments");
 
-        if tool_name != Some("fetch_web_content") {
+        let Some(tool) = tool_definitions().into_iter().find(|tool| Some(tool.name.as_str()) == tool_name) else {
             return json!({
                 "jsonrpc": "2.0",
                 "id": request.id,
@@ -99,7 +215,7 @@ where
                     "message": format!("Unknown tool: {:?}", tool_name)
                 }
             });
-        }
+        };
 
         let Some(args) = arguments else {
             return json!({
@@ -112,30 +228,162 @@ where
             });
         };
 
-        let fetch_request = match self.parse_fetch_request(args) {
-            Ok(req) => req,
-            Err(error_msg) => {
+        let checked = if self.strict_args {
+            validate_arguments(&tool.input_schema, args)
+        } else {
+            Ok(())
+        };
+        let invalid_params = |id: String, error_msg: String| json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "error": {
+                "code": -32602,
+                "message": error_msg
+            }
+        });
+
+        match tool.name.as_str() {
+            "compare_content" => {
+                let requests = checked.and_then(|_| {
+                    Ok((self.parse_compare_request(args, "url_a")?, self.parse_compare_request(args, "url_b")?))
+                });
+                match requests {
+                    Ok((first, second)) => {
+                        let _slot = self.fetch_slot().await;
+                        self.handle_compare_content(request.id, first, second).await
+                    }
+                    Err(error_msg) => invalid_params(request.id, error_msg),
+                }
+            }
+            "parse_html" => match checked.and_then(|_| self.parse_html_arguments(args)) {
+                Ok((html, url, options)) => self.handle_parse_html(request.id, &html, &url, &options).await,
+                Err(error_msg) => invalid_params(request.id, error_msg),
+            },
+            name => {
+                let fetch_request = match checked.and_then(|_| self.parse_fetch_request(args)) {
+                    Ok(req) => req,
+                    Err(error_msg) => return invalid_params(request.id, error_msg),
+                };
+
+                let _slot = self.fetch_slot().await;
+                match name {
+                    "analyze_url" => self.handle_analyze_url(request.id, fetch_request).await,
+                    "extract_article" => self.handle_extract_article(request.id, fetch_request).await,
+                    "chunk_content" => {
+                        let target_chars = args.get("target_chars")
+                            .and_then(|v| v.as_u64())
+                            .map(|v| v as usize)
+                            .unwrap_or(DEFAULT_CHUNK_TARGET_CHARS);
+                        self.handle_chunk_content(request.id, fetch_request, target_chars).await
+                    }
+                    // fetch_web_content
+                    _ => {
+                        let response = self.fetch_use_case.execute(fetch_request).await;
+
+                        json!({
+                            "jsonrpc": "2.0",
+                            "id": request.id,
+                            "result": response.result,
+                            "error": response.error
+                        })
+                    }
+                }
+            }
+        }
+    }
+
+    async fn handle_analyze_url(&self, id: String, fetch_request: FetchContentRequest) -> Value {
+        let content = match self.fetch_use_case.fetch_static(fetch_request).await {
+            Ok(content) => content,
+            Err(error) => {
                 return json!({
                     "jsonrpc": "2.0",
-                    "id": request.id,
-                    "error": {
-                        "code": -32602,
-                        "message": error_msg
-                    }
+                    "id": id,
+                    "error": error
                 });
             }
         };
 
-        let response = self.fetch_use_case.execute(fetch_request).await;
+        let frameworks = JavaScriptDetector::detect_spa_frameworks(&content.raw_html);
+        let javascript_heavy = JavaScriptDetector::has_significant_javascript(&content.raw_html);
+        // jQuery enhances server-rendered markup, so on its own it doesn't call for a browser
+        let needs_browser = javascript_heavy || frameworks.iter().any(|framework| framework != "jQuery");
 
         json!({
             "jsonrpc": "2.0",
-            "id": request.id,
-            "result": response.result,
-            "error": response.error
+            "id": id,
+            "result": {
+                "content_type": content.metadata.content_type,
+                "status": content.metadata.status_code,
+                "frameworks": frameworks,
+                "javascript_heavy": javascript_heavy,
+                "recommended_fetch_method": if needs_browser { "browser" } else { "static" }
+            }
         })
     }
 
+    async fn handle_extract_article(&self, id: String, fetch_request: FetchContentRequest) -> Value {
+        match self.fetch_use_case.extract_article(fetch_request).await {
+            Ok(article) => json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "result": article
+            }),
+            Err(error) => json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "error": error
+            }),
+        }
+    }
+
+    async fn handle_chunk_content(&self, id: String, fetch_request: FetchContentRequest, target_chars: usize) -> Value {
+        match self.fetch_use_case.chunk_content(fetch_request, target_chars).await {
+            Ok(chunks) => json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "result": {
+                    "chunks": chunks
+                }
+            }),
+            Err(error) => json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "error": error
+            }),
+        }
+    }
+
+    async fn handle_compare_content(&self, id: String, first: FetchContentRequest, second: FetchContentRequest) -> Value {
+        match self.fetch_use_case.compare_content(first, second).await {
+            Ok(comparison) => json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "result": comparison
+            }),
+            Err(error) => json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "error": error
+            }),
+        }
+    }
+
+    async fn handle_parse_html(&self, id: String, html: &str, url: &str, options: &ParseOptions) -> Value {
+        match self.fetch_use_case.parse_html(html, url, options).await {
+            Ok(content) => json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "result": content
+            }),
+            Err(error) => json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "error": error
+            }),
+        }
+    }
+
     async fn handle_initialize(&self, id: String) -> Value {
         info!("Handling initialize request");
 
@@ -147,6 +395,11 @@ where
                 "capabilities": {
                     "tools": {
                         "listChanged": false
+                    },
+                    "logging": {},
+                    "completions": {},
+                    "experimental": {
+                        "browser_available": self.fetch_use_case.browser_available()
                     }
                 },
                 "serverInfo": {
@@ -157,6 +410,105 @@ where
         })
     }
 
+    async fn handle_set_level(&self, request: McpRequest) -> Value {
+        info!("Handling logging/setLevel request");
+
+        let level = request.params.get("level").and_then(|v| v.as_str());
+
+        let Some(filter) = level.and_then(parse_log_level) else {
+            return json!({
+                "jsonrpc": "2.0",
+                "id": request.id,
+                "error": {
+                    "code": -32602,
+                    "message": format!("Invalid log level: {:?}", level)
+                }
+            });
+        };
+
+        let Some(handle) = &self.log_level_handle else {
+            return json!({
+                "jsonrpc": "2.0",
+                "id": request.id,
+                "error": {
+                    "code": -32603,
+                    "message": "Log level reloading is not available"
+                }
+            });
+        };
+
+        if let Err(e) = handle.reload(filter) {
+            return json!({
+                "jsonrpc": "2.0",
+                "id": request.id,
+                "error": {
+                    "code": -32603,
+                    "message": format!("Failed to set log level: {}", e)
+                }
+            });
+        }
+
+        json!({
+            "jsonrpc": "2.0",
+            "id": request.id,
+            "result": {}
+        })
+    }
+
+    /// Suggests values for a tool argument: recently fetched URLs still in the
+    /// cache for `url`, `true`/`false` for booleans and the allowed values of
+    /// enums, filtered by the prefix typed so far. `ref.name` names the tool;
+    /// unknown tools and arguments get no suggestions rather than an error.
+    async fn handle_completion(&self, request: McpRequest) -> Value {
+        info!("Handling completion/complete request");
+
+        let Some(argument) = request.params.get("argument") else {
+            return json!({
+                "jsonrpc": "2.0",
+                "id": request.id,
+                "error": {
+                    "code": -32602,
+                    "message": "Missing argument"
+                }
+            });
+        };
+
+        let tool_name = request.params["ref"]["name"].as_str();
+        let argument_name = argument["name"].as_str().unwrap_or_default();
+        let prefix = argument["value"].as_str().unwrap_or_default();
+
+        let property = tool_definitions()
+            .into_iter()
+            .find(|tool| Some(tool.name.as_str()) == tool_name)
+            .and_then(|tool| tool.input_schema["properties"].get(argument_name).cloned());
+
+        let candidates: Vec<String> = match property {
+            Some(_) if argument_name == "url" => self.fetch_use_case.cached_urls(),
+            Some(property) if property["type"] == "boolean" => vec!["true".to_string(), "false".to_string()],
+            Some(property) => property["enum"]
+                .as_array()
+                .map(|values| values.iter().filter_map(|v| v.as_str()).map(|v| v.to_string()).collect())
+                .unwrap_or_default(),
+            None => Vec::new(),
+        };
+
+        let values: Vec<String> = candidates.into_iter().filter(|value| value.starts_with(prefix)).collect();
+        let total = values.len();
+        let values: Vec<String> = values.into_iter().take(MAX_COMPLETION_VALUES).collect();
+
+        json!({
+            "jsonrpc": "2.0",
+            "id": request.id,
+            "result": {
+                "completion": {
+                    "values": values,
+                    "total": total,
+                    "hasMore": total > MAX_COMPLETION_VALUES
+                }
+            }
+        })
+    }
+
     async fn handle_unknown_method(&self, id: String, method: &str) -> Value {
         error!("Unknown method: {}", method);
 
@@ -170,34 +522,831 @@ where
         })
     }
 
+    /// Builds the request for one side of a comparison: the URL comes from
+    /// `url_field` and the other arguments are shared by both sides.
+    fn parse_compare_request(&self, args: &Value, url_field: &str) -> Result<FetchContentRequest, String> {
+        let url = args.get(url_field)
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| format!("Missing required field: {}", url_field))?;
+
+        let mut args = args.clone();
+        if let Some(args) = args.as_object_mut() {
+            args.insert("url".to_string(), json!(url));
+        }
+        self.parse_fetch_request(&args)
+    }
+
+    /// Reads the `parse_html` arguments: the HTML, the URL to resolve against
+    /// (empty when absent) and the parse options.
+    fn parse_html_arguments(&self, args: &Value) -> Result<(String, String, ParseOptions), String> {
+        let html = args.get("html")
+            .and_then(|v| v.as_str())
+            .ok_or("Missing required field: html")?
+            .to_string();
+        let url = args.get("url").and_then(|v| v.as_str()).unwrap_or_default().to_string();
+        let flag = |name: &str| args.get(name).and_then(|v| v.as_bool()).unwrap_or(false);
+
+        let options = ParseOptions {
+            fragment: flag("fragment"),
+            include_links: flag("include_links"),
+            include_images: flag("include_images"),
+            ..Default::default()
+        };
+        Ok((html, url, options))
+    }
+
     fn parse_fetch_request(&self, args: &Value) -> Result<FetchContentRequest, String> {
         let url = args.get("url")
             .and_then(|v| v.as_str())
             .ok_or("Missing required field: url")?
             .to_string();
 
+        // Left unset so the default can follow the response's content type
         let extract_text_only = args.get("extract_text_only")
+            .and_then(|v| v.as_bool());
+
+        let follow_redirects = args.get("follow_redirects")
             .and_then(|v| v.as_bool())
             .unwrap_or(true);
 
-        let follow_redirects = args.get("follow_redirects")
-            .and_then(|v| v.as_bool())
-            .unwrap_or(true);
+        let timeout_seconds = args.get("timeout_seconds")
+            .and_then(|v| v.as_u64());
+
+        let read_timeout_seconds = args.get("read_timeout_seconds")
+            .and_then(|v| v.as_u64());
+
+        let user_agent = args.get("user_agent")
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+
+        let http_version = args.get("http_version")
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+
+        let encode_base64 = args.get("encode_base64")
+            .and_then(|v| v.as_bool());
+
+        let force_fetch_method = match args.get("force_fetch_method") {
+            Some(value) => Some(
+                serde_json::from_value(value.clone())
+                    .map_err(|_| format!("Invalid force_fetch_method: {}", value))?,
+            ),
+            None => None,
+        };
+
+        let referer = args.get("referer")
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+
+        let expected_content_types = args.get("expected_content_types")
+            .and_then(|v| v.as_array())
+            .map(|types| types.iter().filter_map(|t| t.as_str()).map(|t| t.to_string()).collect());
+
+        let include_blocks = args.get("include_blocks")
+            .and_then(|v| v.as_bool());
+
+        let max_title_length = args.get("max_title_length")
+            .and_then(|v| v.as_u64())
+            .map(|v| v as usize);
+
+        let include_contacts = args.get("include_contacts")
+            .and_then(|v| v.as_bool());
+
+        let prettify_html = args.get("prettify_html")
+            .and_then(|v| v.as_bool());
+
+        let include_links = args.get("include_links")
+            .and_then(|v| v.as_bool());
+
+        let include_images = args.get("include_images")
+            .and_then(|v| v.as_bool());
+
+        let include_forms = args.get("include_forms")
+            .and_then(|v| v.as_bool());
+
+        let include_microdata = args.get("include_microdata")
+            .and_then(|v| v.as_bool());
+
+        let raw_text = args.get("raw_text")
+            .and_then(|v| v.as_bool());
+
+        let detect_soft_errors = args.get("detect_soft_errors")
+            .and_then(|v| v.as_bool());
+
+        let browser_fallback = args.get("browser_fallback")
+            .and_then(|v| v.as_bool());
+
+        let output_formats = match args.get("output_formats") {
+            Some(value) => Some(
+                serde_json::from_value(value.clone())
+                    .map_err(|_| format!("Invalid output_formats: {}", value))?,
+            ),
+            None => None,
+        };
+
+        let ip_version = args.get("ip_version")
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+
+        let max_text_bytes = args.get("max_text_bytes")
+            .and_then(|v| v.as_u64())
+            .map(|v| v as usize);
+
+        let max_retries = args.get("max_retries")
+            .and_then(|v| v.as_u64())
+            .map(|v| v as u32);
+
+        let www_fallback = args.get("www_fallback")
+            .and_then(|v| v.as_bool());
+
+        let min_content_length = args.get("min_content_length")
+            .and_then(|v| v.as_u64())
+            .map(|v| v as usize);
+
+        let include_block_lang = args.get("include_block_lang")
+            .and_then(|v| v.as_bool());
+
+        let lowercase_text = args.get("lowercase_text")
+            .and_then(|v| v.as_bool());
+
+        let include_breadcrumbs = args.get("include_breadcrumbs")
+            .and_then(|v| v.as_bool());
+
+        let prefetch_links = args.get("prefetch_links")
+            .and_then(|v| v.as_u64())
+            .map(|v| v as usize);
+
+        let output_html_fragment = args.get("output_html_fragment")
+            .and_then(|v| v.as_bool());
+
+        let retry_empty_with_browser = args.get("retry_empty_with_browser")
+            .and_then(|v| v.as_bool());
+
+        let max_elements = args.get("max_elements")
+            .and_then(|v| v.as_u64())
+            .map(|v| v as usize);
+
+        let archive_fallback = args.get("archive_fallback")
+            .and_then(|v| v.as_bool());
+
+        let output_format = match args.get("output_format") {
+            Some(value) => Some(
+                serde_json::from_value(value.clone())
+                    .map_err(|_| format!("Invalid output_format: {}", value))?,
+            ),
+            None => None,
+        };
+
+        let include_feeds = args.get("include_feeds")
+            .and_then(|v| v.as_bool());
+
+        let rank_links = args.get("rank_links")
+            .and_then(|v| v.as_bool());
+
+        let include_keywords = args.get("include_keywords")
+            .and_then(|v| v.as_bool());
+
+        let upgrade_insecure = args.get("upgrade_insecure")
+            .and_then(|v| v.as_bool());
+
+        let strip_tracking_params = args.get("strip_tracking_params")
+            .and_then(|v| v.as_bool());
+
+        let validate_anchors = args.get("validate_anchors")
+            .and_then(|v| v.as_bool());
+
+        let multipart = match args.get("multipart") {
+            Some(value) => Some(
+                serde_json::from_value(value.clone())
+                    .map_err(|_| format!("Invalid multipart: {}", value))?,
+            ),
+            None => None,
+        };
+
+        let strip_inline_styles = args.get("strip_inline_styles")
+            .and_then(|v| v.as_bool());
+
+        let fallback_urls = match args.get("fallback_urls") {
+            Some(value) => Some(
+                serde_json::from_value(value.clone())
+                    .map_err(|_| format!("Invalid fallback_urls: {}", value))?,
+            ),
+            None => None,
+        };
+
+        let inline_link_urls = args.get("inline_link_urls")
+            .and_then(|v| v.as_bool());
+
+        Ok(FetchContentRequest {
+            url,
+            extract_text_only,
+            follow_redirects: Some(follow_redirects),
+            timeout_seconds,
+            read_timeout_seconds,
+            user_agent,
+            http_version,
+            force_fetch_method,
+            include_blocks,
+            max_title_length,
+            include_contacts,
+            include_links,
+            include_images,
+            referer,
+            expected_content_types,
+            prettify_html,
+            include_forms,
+            include_microdata,
+            raw_text,
+            encode_base64,
+            detect_soft_errors,
+            browser_fallback,
+            output_formats,
+            ip_version,
+            max_text_bytes,
+            max_retries,
+            www_fallback,
+            min_content_length,
+            include_block_lang,
+            lowercase_text,
+            include_breadcrumbs,
+            prefetch_links,
+            output_html_fragment,
+            retry_empty_with_browser,
+            max_elements,
+            archive_fallback,
+            output_format,
+            include_feeds,
+            rank_links,
+            include_keywords,
+            upgrade_insecure,
+            strip_tracking_params,
+            validate_anchors,
+            multipart,
+            strip_inline_styles,
+            fallback_urls,
+            inline_link_urls,
+            // The MCP server has a single client on stdio
+            client_id: None,
+        })
+    }
+}
+
+/// `text` cut to at most `max_bytes` (on a character boundary), with a note of
+/// how much was left out.
+fn truncate_for_log(text: &str, max_bytes: usize) -> Cow<'_, str> {
+    if text.len() <= max_bytes {
+        return Cow::Borrowed(text);
+    }
+
+    let mut end = max_bytes;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    Cow::Owned(format!("{}...(truncated {} bytes)", &text[..end], text.len() - end))
+}
+
+/// The id of a JSON-RPC message as responses carry it: numeric ids as their
+/// decimal string, and `"unknown"` when it has none.
+fn request_id(message: &Value) -> String {
+    match message.get("id") {
+        Some(Value::String(id)) => id.clone(),
+        Some(id) if id.is_i64() => id.to_string(),
+        _ => "unknown".to_string(),
+    }
+}
+
+/// Reads a JSON-RPC request object, with its id as `request_id` gives it.
+pub fn parse_request(value: &Value) -> Result<McpRequest, String> {
+    let id = request_id(value);
+
+    let method = value.get("method")
+        .and_then(|v| v.as_str())
+        .ok_or("Missing method field")?
+        .to_string();
+
+    let params = value.get("params")
+        .cloned()
+        .unwrap_or(json!({}));
+
+    Ok(McpRequest { id, method, params })
+}
+
+fn parse_error(id: Value, error: &str) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": {
+            "code": -32700,
+            "message": format!("Parse error: {}", error)
+        }
+    })
+}
+
+fn invalid_request(id: Value, message: &str) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": {
+            "code": -32600,
+            "message": message
+        }
+    })
+}
+
+/// The tools advertised by `tools/list`. Their input schemas also drive
+/// argument type checking in strict mode.
+fn tool_definitions() -> Vec<ToolCapabilities> {
+    vec![ToolCapabilities {
+        name: "fetch_web_content".to_string(),
+        description: "Fetch and extract content from web pages. Supports HTML parsing and text extraction.".to_string(),
+        input_schema: json!({
+            "type": "object",
+            "properties": {
+                "url": {
+                    "type": "string",
+                    "description": "The URL to fetch content from"
+                },
+                "extract_text_only": {
+                    "type": "boolean",
+                    "description": "Whether to extract only text content (default: true for HTML, false for JSON, XML, plain text and Markdown)"
+                },
+                "follow_redirects": {
+                    "type": "boolean", 
+                    "description": "Whether to follow HTTP redirects (default: true)",
+                    "default": true
+                },
+                "timeout_seconds": {
+                    "type": "integer",
+                    "description": "Request timeout in seconds (default: 30, max: 300)",
+                    "default": 30,
+                    "minimum": 1,
+                    "maximum": 300
+                },
+                "read_timeout_seconds": {
+                    "type": "integer",
+                    "description": "Fail if the response body stalls for this many seconds between chunks (optional)",
+                    "minimum": 1
+                },
+                "user_agent": {
+                    "type": "string",
+                    "description": "Custom User-Agent header (optional)"
+                },
+                "http_version": {
+                    "type": "string",
+                    "enum": ["auto", "http1", "http2"],
+                    "description": "HTTP protocol to use: negotiate (auto, default), HTTP/1.1 only, or HTTP/2 with prior knowledge"
+                },
+                "force_fetch_method": {
+                    "type": "string",
+                    "enum": ["static", "browser"],
+                    "description": "Skip JavaScript detection and always fetch statically or with the browser (optional)"
+                },
+                "referer": {
+                    "type": "string",
+                    "description": "Referer header to send with the request; must be a valid URL (optional)"
+                },
+                "expected_content_types": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Fail without downloading the body unless the Content-Type starts with one of these, e.g. [\"text/html\"] (optional)"
+                },
+                "include_blocks": {
+                    "type": "boolean",
+                    "description": "Return text blocks tagged with their source element (default: false)",
+                    "default": false
+                },
+                "max_title_length": {
+                    "type": "integer",
+                    "description": "Truncate the title at a word boundary beyond this many characters (default: unlimited)",
+                    "minimum": 1
+                },
+                "include_contacts": {
+                    "type": "boolean",
+                    "description": "Return email addresses and phone numbers found in the page (default: false)",
+                    "default": false
+                },
+                "prettify_html": {
+                    "type": "boolean",
+                    "description": "Return raw_html re-serialized with one element per line and consistent indentation (default: false)",
+                    "default": false
+                },
+                "include_links": {
+                    "type": "boolean",
+                    "description": "Return the page's links resolved to absolute URLs (default: false)",
+                    "default": false
+                },
+                "include_images": {
+                    "type": "boolean",
+                    "description": "Return the page's images resolved to absolute URLs (default: false)",
+                    "default": false
+                },
+                "include_forms": {
+                    "type": "boolean",
+                    "description": "Return the page's forms with their action, method and named fields (default: false)",
+                    "default": false
+                },
+                "include_microdata": {
+                    "type": "boolean",
+                    "description": "Return the page's microdata (itemscope/itemprop) items with nested properties (default: false)",
+                    "default": false
+                },
+                "raw_text": {
+                    "type": "boolean",
+                    "description": "Return the page text exactly as written, keeping original whitespace and blank lines; only entities are decoded (default: false)",
+                    "default": false
+                },
+                "encode_base64": {
+                    "type": "boolean",
+                    "description": "Base64-encode text_content and raw_html for transports that mangle non-ASCII text; metadata.content_encoding is set to \"base64\" (default: false)",
+                    "default": false
+                },
+                "detect_soft_errors": {
+                    "type": "boolean",
+                    "description": "Flag pages that look like error pages or paywalls despite a 200 status in metadata.likely_soft_error, without failing the fetch (default: false)",
+                    "default": false
+                },
+                "browser_fallback": {
+                    "type": "boolean",
+                    "description": "When a JavaScript page fails to render in the browser, return the static fetch instead and explain why in metadata.fallback_reason; when false the browser error is returned (default: true)",
+                    "default": true
+                },
+                "output_formats": {
+                    "type": "array",
+                    "items": { "type": "string", "enum": ["text", "markdown", "html"] },
+                    "description": "Also return the page in each of these formats in alternate_contents, keyed by format name; text_content keeps the default text (optional)"
+                },
+                "ip_version": {
+                    "type": "string",
+                    "enum": ["auto", "v4", "v6"],
+                    "description": "Address family to connect over: IPv4 only, IPv6 only, or both raced with happy-eyeballs (auto, default)"
+                },
+                "max_text_bytes": {
+                    "type": "integer",
+                    "description": "Truncate text_content to at most this many UTF-8 bytes without splitting a character; metadata.text_truncated reports whether it was cut (optional)"
+                },
+                "max_retries": {
+                    "type": "integer",
+                    "description": "Retry network errors, timeouts and 5xx/429 responses up to this many times with doubling backoff; timeout_seconds bounds all attempts and backoff together (default: 0, max: 5)",
+                    "minimum": 0,
+                    "maximum": 5
+                },
+                "www_fallback": {
+                    "type": "boolean",
+                    "description": "Retry once with the www. prefix added or removed when connecting to the host fails (optional)",
+                    "default": false
+                },
+                "min_content_length": {
+                    "type": "integer",
+                    "description": "Flag pages whose extracted text is shorter than this many bytes in metadata.content_below_minimum (optional)"
+                },
+                "include_block_lang": {
+                    "type": "boolean",
+                    "description": "Tag each block in blocks with the language of its nearest lang attribute, falling back to <html lang> (requires include_blocks)",
+                    "default": false
+                },
+                "lowercase_text": {
+                    "type": "boolean",
+                    "description": "Return text_content lowercased, Unicode-aware, for case-insensitive indexing; title and raw_html keep their case",
+                    "default": false
+                },
+                "include_breadcrumbs": {
+                    "type": "boolean",
+                    "description": "Return the page's breadcrumb trail as [label, href] pairs, from JSON-LD BreadcrumbList or breadcrumb navigation markup",
+                    "default": false
+                },
+                "prefetch_links": {
+                    "type": "integer",
+                    "description": "With the page cache enabled, fetch up to this many of the page's same-host links into the cache in the background after responding (max: 20)",
+                    "minimum": 0,
+                    "maximum": 20
+                },
+                "output_html_fragment": {
+                    "type": "boolean",
+                    "description": "Return the main content region as sanitized HTML in content_html, keeping only p, a, strong, em, ul, ol, li, br and headings, without event handler attributes",
+                    "default": false
+                },
+                "retry_empty_with_browser": {
+                    "type": "boolean",
+                    "description": "Re-fetch with a headless browser when the static fetch of a script-driven page yields almost no text",
+                    "default": false
+                },
+                "max_elements": {
+                    "type": "integer",
+                    "description": "Return at most this many links and images each, the first in document order; metadata.elements_truncated reports whether any were left out (optional, requires include_links or include_images)"
+                },
+                "archive_fallback": {
+                    "type": "boolean",
+                    "description": "When the page can't be fetched (connection error, timeout or 5xx), return the Wayback Machine's closest snapshot instead; metadata.from_archive and metadata.archive_timestamp mark it",
+                    "default": false
+                },
+                "output_format": {
+                    "type": "string",
+                    "enum": ["html", "text", "markdown", "json", "xml"],
+                    "description": "Representation to ask the server for in the Accept header, for servers that content-negotiate; HTML stays acceptable as a fallback and metadata.content_type reports what was served (optional)"
+                },
+                "include_feeds": {
+                    "type": "boolean",
+                    "description": "Return the RSS, Atom and JSON feeds the page advertises through link rel=alternate, with absolute hrefs",
+                    "default": false
+                },
+                "rank_links": {
+                    "type": "boolean",
+                    "description": "Sort links by relevance, in-content and same-site links with descriptive text first, so max_elements keeps the most important ones",
+                    "default": false
+                },
+                "include_keywords": {
+                    "type": "boolean",
+                    "description": "Return the page's keywords and article:tag values in metadata.keywords",
+                    "default": false
+                },
+                "upgrade_insecure": {
+                    "type": "boolean",
+                    "description": "Fetch http:// URLs over https:// first, falling back to http:// only when the HTTPS connection fails; metadata.scheme reports the scheme used",
+                    "default": false
+                },
+                "strip_tracking_params": {
+                    "type": "boolean",
+                    "description": "Remove tracking query parameters (utm_*, fbclid, gclid, ...) from the returned URL; the page is still fetched with them and metadata.requested_url keeps the URL as requested",
+                    "default": false
+                },
+                "validate_anchors": {
+                    "type": "boolean",
+                    "description": "Return broken_anchors, the in-page #fragment links that no element id (or <a name>) in the document matches",
+                    "default": false
+                },
+                "multipart": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "name": { "type": "string" },
+                            "value": { "type": "string" },
+                            "file_url": { "type": "string" },
+                            "content_type": { "type": "string" }
+                        },
+                        "required": ["name"]
+                    },
+                    "description": "Send the request as a multipart/form-data POST with these parts, each {name, value} for an inline value or {name, file_url} for a local file:// URL (only when the server allows file URLs), with an optional content_type"
+                },
+                "strip_inline_styles": {
+                    "type": "boolean",
+                    "description": "With output_html_fragment, also remove style attributes from content_html (event handler attributes are always removed)",
+                    "default": false
+                },
+                "fallback_urls": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Mirror URLs tried in order when the url fails; the content of the first that succeeds is returned, with metadata.url_used and the earlier errors in metadata.fallback_errors (up to 5)"
+                },
+                "inline_link_urls": {
+                    "type": "boolean",
+                    "description": "Follow each link's text in text_content with its absolute URL in parentheses, e.g. 'the docs (https://example.com/docs)'",
+                    "default": false
+                }
+            },
+            "required": ["url"]
+        }),
+        examples: vec![
+            json!({ "url": "https://example.com" }),
+            json!({
+                "url": "https://blog.example.com/posts/launch",
+                "include_links": true,
+                "output_formats": ["markdown"],
+                "max_text_bytes": 20000
+            }),
+            json!({
+                "url": "https://app.example.com/dashboard",
+                "force_fetch_method": "browser",
+                "timeout_seconds": 60,
+                "max_retries": 2
+            }),
+        ],
+    }, ToolCapabilities {
+        name: "analyze_url".to_string(),
+        description: "Fetch a page statically and report its content type, detected JavaScript frameworks and the recommended fetch method, without returning the content.".to_string(),
+        input_schema: json!({
+            "type": "object",
+            "properties": {
+                "url": {
+                    "type": "string",
+                    "description": "The URL to analyze"
+                },
+                "timeout_seconds": {
+                    "type": "integer",
+                    "description": "Request timeout in seconds (default: 30, max: 300)",
+                    "default": 30,
+                    "minimum": 1,
+                    "maximum": 300
+                },
+                "user_agent": {
+                    "type": "string",
+                    "description": "Custom User-Agent header (optional)"
+                }
+            },
+            "required": ["url"]
+        }),
+        examples: vec![
+            json!({ "url": "https://example.com" }),
+            json!({ "url": "https://app.example.com/", "timeout_seconds": 10 }),
+        ],
+    }, ToolCapabilities {
+        name: "extract_article".to_string(),
+        description: "Fetch a page and return its main article as {title, byline, body_text, body_html, published, lead_image}, without navigation, sidebars or footers.".to_string(),
+        input_schema: json!({
+            "type": "object",
+            "properties": {
+                "url": {
+                    "type": "string",
+                    "description": "The URL of the article"
+                },
+                "timeout_seconds": {
+                    "type": "integer",
+                    "description": "Request timeout in seconds (default: 30, max: 300)",
+                    "default": 30,
+                    "minimum": 1,
+                    "maximum": 300
+                },
+                "user_agent": {
+                    "type": "string",
+                    "description": "Custom User-Agent header (optional)"
+                }
+            },
+            "required": ["url"]
+        }),
+        examples: vec![
+            json!({ "url": "https://news.example.com/2024/05/city-council-vote" }),
+            json!({ "url": "https://blog.example.com/posts/launch", "user_agent": "MyReader/1.0" }),
+        ],
+    }, ToolCapabilities {
+        name: "chunk_content".to_string(),
+        description: "Fetch a page and split its text into chunks for retrieval, breaking at headings and then between paragraphs or sentences. Each chunk carries the heading it falls under.".to_string(),
+        input_schema: json!({
+            "type": "object",
+            "properties": {
+                "url": {
+                    "type": "string",
+                    "description": "The URL to fetch content from"
+                },
+                "target_chars": {
+                    "type": "integer",
+                    "description": "Approximate chunk size in characters; a single sentence longer than this is kept whole (default: 1000)",
+                    "default": 1000,
+                    "minimum": 1
+                },
+                "timeout_seconds": {
+                    "type": "integer",
+                    "description": "Request timeout in seconds (default: 30, max: 300)",
+                    "default": 30,
+                    "minimum": 1,
+                    "maximum": 300
+                },
+                "user_agent": {
+                    "type": "string",
+                    "description": "Custom User-Agent header (optional)"
+                }
+            },
+            "required": ["url"]
+        }),
+        examples: vec![
+            json!({ "url": "https://docs.example.com/guide" }),
+            json!({ "url": "https://docs.example.com/guide", "target_chars": 500 }),
+        ],
+    }, ToolCapabilities {
+        name: "compare_content".to_string(),
+        description: "Fetch two pages and compare their text line by line, returning how similar they are and the lines only one of them has.".to_string(),
+        input_schema: json!({
+            "type": "object",
+            "properties": {
+                "url_a": {
+                    "type": "string",
+                    "description": "The first URL to compare"
+                },
+                "url_b": {
+                    "type": "string",
+                    "description": "The second URL to compare; its extra lines are reported as added"
+                },
+                "timeout_seconds": {
+                    "type": "integer",
+                    "description": "Request timeout in seconds for each page (default: 30, max: 300)",
+                    "default": 30,
+                    "minimum": 1,
+                    "maximum": 300
+                },
+                "user_agent": {
+                    "type": "string",
+                    "description": "Custom User-Agent header (optional)"
+                }
+            },
+            "required": ["url_a", "url_b"]
+        }),
+        examples: vec![
+            json!({ "url_a": "https://example.com/pricing", "url_b": "https://staging.example.com/pricing" }),
+            json!({ "url_a": "https://example.com/v1/docs", "url_b": "https://example.com/v2/docs", "timeout_seconds": 60 }),
+        ],
+    }, ToolCapabilities {
+        name: "parse_html".to_string(),
+        description: "Parse HTML you already have, without fetching anything, and return its title and text, and optionally its links and images.".to_string(),
+        input_schema: json!({
+            "type": "object",
+            "properties": {
+                "html": {
+                    "type": "string",
+                    "description": "The HTML to parse"
+                },
+                "url": {
+                    "type": "string",
+                    "description": "The page the HTML came from, used to resolve relative links and images; without it only absolute ones are kept"
+                },
+                "fragment": {
+                    "type": "boolean",
+                    "description": "Parse the HTML as a fragment, such as a bare <ul> list, instead of a complete document",
+                    "default": false
+                },
+                "include_links": {
+                    "type": "boolean",
+                    "description": "Return the links as {href, text} objects",
+                    "default": false
+                },
+                "include_images": {
+                    "type": "boolean",
+                    "description": "Return the images as {src, alt} objects",
+                    "default": false
+                }
+            },
+            "required": ["html"]
+        }),
+        examples: vec![
+            json!({ "html": "<html><head><title>Notes</title></head><body><p>Hello</p></body></html>" }),
+            json!({ "html": "<ul><li><a href=\"/a\">First</a></li><li>Second</li></ul>", "url": "https://example.com/", "fragment": true, "include_links": true }),
+        ],
+    }]
+}
+
+/// Checks each argument the schema declares against its declared type, so a
+/// mistyped value is reported instead of silently falling back to a default.
+/// Arguments the schema doesn't know are left alone.
+fn validate_arguments(schema: &Value, args: &Value) -> Result<(), String> {
+    let Some(args) = args.as_object() else {
+        return Err("Invalid arguments: expected object".to_string());
+    };
+    let Some(properties) = schema.get("properties").and_then(|p| p.as_object()) else {
+        return Ok(());
+    };
+
+    for (name, value) in args {
+        let Some(property) = properties.get(name) else {
+            continue;
+        };
+        let Some(expected) = property.get("type").and_then(|t| t.as_str()) else {
+            continue;
+        };
+
+        if !value_matches_type(value, expected) {
+            return Err(format!("Invalid argument '{}': expected {}, got {}", name, expected, json_type_name(value)));
+        }
+
+        if let (Some(items), Some(item_type)) = (value.as_array(), property["items"]["type"].as_str()) {
+            if let Some(item) = items.iter().find(|item| !value_matches_type(item, item_type)) {
+                return Err(format!("Invalid argument '{}': expected array of {}, got {} item", name, item_type, json_type_name(item)));
+            }
+        }
+    }
+
+    Ok(())
+}
 
-        let timeout_seconds = args.get("timeout_seconds")
-            .and_then(|v| v.as_u64());
+fn value_matches_type(value: &Value, expected: &str) -> bool {
+    match expected {
+        "boolean" => value.is_boolean(),
+        "integer" => value.is_u64() || value.is_i64(),
+        "number" => value.is_number(),
+        "string" => value.is_string(),
+        "array" => value.is_array(),
+        "object" => value.is_object(),
+        _ => true,
+    }
+}
 
-        let user_agent = args.get("user_agent")
-            .and_then(|v| v.as_str())
-            .map(|s| s.to_string());
+fn json_type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(n) if n.is_f64() => "number",
+        Value::Number(_) => "integer",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
 
-        Ok(FetchContentRequest {
-            url,
-            extract_text_only: Some(extract_text_only),
-            follow_redirects: Some(follow_redirects),
-            timeout_seconds,
-            user_agent,
-        })
+/// Maps MCP logging levels (RFC 5424 severities) onto tracing level filters.
+fn parse_log_level(level: &str) -> Option<LevelFilter> {
+    match level {
+        "debug" => Some(LevelFilter::DEBUG),
+        "info" | "notice" => Some(LevelFilter::INFO),
+        "warning" => Some(LevelFilter::WARN),
+        "error" | "critical" | "alert" | "emergency" => Some(LevelFilter::ERROR),
+        _ => None,
     }
 }
 
@@ -206,7 +1355,7 @@ mod tests {
     use super::*;
     use std::sync::Arc;
     use async_trait::async_trait;
-    use domain::model::content::{ContentMetadata, HtmlContent};
+    use domain::model::content::{ContentMetadata, ContentType, FetchMethod, HtmlContent};
     use domain::port::content_fetcher::{ContentFetcher, ContentFetcherError, ContentFetcherResult};
     use domain::port::content_parser::{ContentParser, ContentParserResult};
     use application::service::{
@@ -214,17 +1363,27 @@ mod tests {
         content_parse_service::ContentParseService,
     };
     use application::use_case::fetch_web_content_use_case::FetchWebContentUseCase;
+    use application::test_support::CapturedLogs;
+    use crate::adapter::html_parser_adapter::HtmlParserAdapter;
+    use crate::client::caching_fetcher::{CacheConfig, CachingContentFetcher};
+    use std::num::NonZeroUsize;
 
     struct MockContentFetcher {
         should_succeed: bool,
         return_error: Option<ContentFetcherError>,
+        raw_html: String,
     }
 
     impl MockContentFetcher {
         fn new_success() -> Self {
+            Self::new_with_html("<html><body>Test</body></html>")
+        }
+
+        fn new_with_html(raw_html: &str) -> Self {
             Self {
                 should_succeed: true,
                 return_error: None,
+                raw_html: raw_html.to_string(),
             }
         }
 
@@ -232,6 +1391,7 @@ mod tests {
             Self {
                 should_succeed: false,
                 return_error: Some(error),
+                raw_html: String::new(),
             }
         }
     }
@@ -248,14 +1408,16 @@ mod tests {
                     charset: Some("utf-8".to_string()),
             javascript_detected: None,
             fetch_method: None,
+                    ..Default::default()
                 };
 
                 Ok(HtmlContent {
                     url: request.url,
                     title: Some("Test Title".to_string()),
                     text_content: "Test content".to_string(),
-                    raw_html: "<html><body>Test</body></html>".to_string(),
+                    raw_html: self.raw_html.clone(),
                     metadata,
+                    ..Default::default()
                 })
             } else {
                 Err(self.return_error.as_ref().unwrap().clone())
@@ -276,6 +1438,7 @@ mod tests {
                 charset: Some("utf-8".to_string()),
             javascript_detected: None,
             fetch_method: None,
+                ..Default::default()
             };
 
             Ok(HtmlContent {
@@ -284,6 +1447,7 @@ mod tests {
                 text_content: "Parsed content".to_string(),
                 raw_html: raw_html.to_string(),
                 metadata,
+                ..Default::default()
             })
         }
 
@@ -293,8 +1457,16 @@ mod tests {
     }
 
     fn create_server() -> McpServer<MockContentFetcher, MockContentParser> {
-        let fetcher = Arc::new(MockContentFetcher::new_success());
-        let parser = Arc::new(MockContentParser);
+        create_server_with_fetcher(MockContentFetcher::new_success())
+    }
+
+    fn create_server_with_fetcher(fetcher: MockContentFetcher) -> McpServer<MockContentFetcher, MockContentParser> {
+        create_server_with_parser(fetcher, MockContentParser)
+    }
+
+    fn create_server_with_parser<P: ContentParser>(fetcher: MockContentFetcher, parser: P) -> McpServer<MockContentFetcher, P> {
+        let fetcher = Arc::new(fetcher);
+        let parser = Arc::new(parser);
         
         let fetch_service = Arc::new(ContentFetchService::new(fetcher));
         let parse_service = Arc::new(ContentParseService::new(parser));
@@ -305,7 +1477,7 @@ mod tests {
     }
 
     fn create_failing_server() -> McpServer<MockContentFetcher, MockContentParser> {
-        let error = ContentFetcherError::Network("Connection failed".to_string());
+        let error = ContentFetcherError::network("Connection failed".to_string());
         let fetcher = Arc::new(MockContentFetcher::new_with_error(error));
         let parser = Arc::new(MockContentParser);
         
@@ -317,6 +1489,87 @@ mod tests {
         McpServer::new(use_case)
     }
 
+    #[tokio::test]
+    async fn test_serve_survives_invalid_utf8_line() {
+        let server = create_server();
+        let mut input = b"{\"jsonrpc\": \"2.0\", \"id\": 7, \"method\": \"tools/list\", \"note\": \"caf\xe9\"}\n".to_vec();
+        input.extend_from_slice(b"\xff\xfe garbage\n");
+        input.extend_from_slice(b"{\"jsonrpc\": \"2.0\", \"id\": 8, \"method\": \"initialize\"}\n");
+        let mut output = Vec::new();
+
+        server.serve(io::Cursor::new(input), &mut output).await.unwrap();
+
+        let responses: Vec<Value> = String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(responses.len(), 3);
+        assert_eq!(responses[0]["id"], "7");
+        assert_eq!(responses[0]["error"]["code"], -32700);
+        assert_eq!(responses[1]["id"], Value::Null);
+        assert_eq!(responses[1]["error"]["code"], -32700);
+        assert_eq!(responses[2]["id"], "8");
+        assert!(responses[2]["result"]["serverInfo"].is_object());
+    }
+
+    #[tokio::test]
+    async fn test_long_request_lines_truncated_in_log() {
+        let (logs, _guard) = CapturedLogs::capture(tracing::Level::DEBUG);
+
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "tools/call",
+            "params": {"name": "parse_html", "arguments": {"html": format!("<p>{}</p>", "é".repeat(1000))}}
+        })
+        .to_string();
+        let server = create_server().with_max_body_log_bytes(100);
+        let mut output = Vec::new();
+
+        server.serve(io::Cursor::new(format!("{}\n", request)), &mut output).await.unwrap();
+
+        let logs = logs.output();
+        let received = logs.lines().find(|line| line.contains("Received request:")).unwrap();
+        assert!(received.ends_with(&format!("{}...(truncated {} bytes)", &request[..100], request.len() - 100)), "{}", received);
+        assert!(!logs.contains(&request));
+        assert!(logs.lines().any(|line| line.contains("Sent response:")));
+
+        assert_eq!(truncate_for_log("short", 100), "short");
+        // Never splits a character
+        assert_eq!(truncate_for_log("éé", 3), "é...(truncated 2 bytes)");
+    }
+
+    #[test]
+    fn test_tool_examples_satisfy_their_schemas() {
+        for tool in tool_definitions() {
+            assert!(!tool.examples.is_empty(), "{} has no examples", tool.name);
+
+            let properties = tool.input_schema["properties"].as_object().unwrap();
+            let required = tool.input_schema["required"].as_array().unwrap();
+            for example in &tool.examples {
+                assert_eq!(validate_arguments(&tool.input_schema, example), Ok(()), "{}: {}", tool.name, example);
+
+                let arguments = example.as_object().unwrap();
+                for name in required {
+                    assert!(arguments.contains_key(name.as_str().unwrap()), "{}: {} lacks {}", tool.name, example, name);
+                }
+                for (name, value) in arguments {
+                    let property = properties.get(name).unwrap_or_else(|| panic!("{}: unknown argument {}", tool.name, name));
+                    if let Some(allowed) = property["enum"].as_array() {
+                        assert!(allowed.contains(value), "{}: {} is not one of {:?}", tool.name, value, allowed);
+                    }
+                    if let Some(minimum) = property["minimum"].as_u64() {
+                        assert!(value.as_u64().unwrap() >= minimum, "{}: {} below minimum", tool.name, name);
+                    }
+                    if let Some(maximum) = property["maximum"].as_u64() {
+                        assert!(value.as_u64().unwrap() <= maximum, "{}: {} above maximum", tool.name, name);
+                    }
+                }
+            }
+        }
+    }
+
     #[tokio::test]
     async fn test_handle_tools_list() {
         let server = create_server();
@@ -333,10 +1586,255 @@ mod tests {
         assert!(response["result"]["tools"].is_array());
         
         let tools = response["result"]["tools"].as_array().unwrap();
-        assert_eq!(tools.len(), 1);
+        assert_eq!(tools.len(), 6);
         assert_eq!(tools[0]["name"], "fetch_web_content");
+        assert_eq!(tools[1]["name"], "analyze_url");
+        assert_eq!(tools[2]["name"], "extract_article");
+        assert_eq!(tools[3]["name"], "chunk_content");
+        assert_eq!(tools[4]["name"], "compare_content");
+        assert_eq!(tools[5]["name"], "parse_html");
         assert!(tools[0]["description"].is_string());
         assert!(tools[0]["input_schema"]["properties"]["url"].is_object());
+        assert!(tools.iter().all(|tool| !tool["examples"].as_array().unwrap().is_empty()));
+    }
+
+    #[tokio::test]
+    async fn test_handle_batch_answers_each_request() {
+        let server = create_server();
+        let batch = vec![
+            json!({ "jsonrpc": "2.0", "id": 1, "method": "tools/list" }),
+            json!({ "jsonrpc": "2.0", "id": "b", "method": "initialize", "params": {} }),
+            json!({ "jsonrpc": "2.0", "method": "notifications/initialized" }),
+        ];
+
+        let response = server.handle_batch(&batch).await.unwrap();
+        let responses = response.as_array().unwrap();
+
+        assert_eq!(responses.len(), 2);
+        assert_eq!(responses[0]["id"], "1");
+        assert!(responses[0]["result"]["tools"].is_array());
+        assert_eq!(responses[1]["id"], "b");
+        assert_eq!(responses[1]["result"]["serverInfo"]["name"], "html-mcp-reader");
+    }
+
+    #[tokio::test]
+    async fn test_handle_batch_rejects_empty_and_invalid_elements() {
+        let server = create_server();
+
+        let response = server.handle_batch(&[]).await.unwrap();
+        assert_eq!(response["error"]["code"], -32600);
+        assert!(response["id"].is_null());
+
+        let response = server.handle_batch(&[json!(42), json!({ "id": 7 })]).await.unwrap();
+        assert_eq!(response[0]["error"]["code"], -32600);
+        assert_eq!(response[1]["id"], 7);
+        assert_eq!(response[1]["error"]["message"], "Missing method field");
+
+        let notifications = [json!({ "jsonrpc": "2.0", "method": "notifications/initialized" })];
+        assert!(server.handle_batch(&notifications).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_analyze_url_react_page_recommends_browser() {
+        let html = r#"<html><body><div id="root" data-reactroot></div>
+            <script src="/static/js/react.production.min.js"></script>
+            <script>document.addEventListener('DOMContentLoaded', render);</script>
+            <script>fetch('/api/data');</script></body></html>"#;
+        let server = create_server_with_fetcher(MockContentFetcher::new_with_html(html));
+        let request = McpRequest {
+            id: "test-id".to_string(),
+            method: "tools/call".to_string(),
+            params: json!({
+                "name": "analyze_url",
+                "arguments": { "url": "https://example.com" }
+            }),
+        };
+
+        let response = server.handle_request(request).await;
+        let result = &response["result"];
+
+        assert_eq!(result["content_type"], "text/html");
+        assert_eq!(result["status"], 200);
+        assert_eq!(result["frameworks"], json!(["React"]));
+        assert_eq!(result["javascript_heavy"], true);
+        assert_eq!(result["recommended_fetch_method"], "browser");
+        assert!(result.get("text_content").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_analyze_url_plain_page_recommends_static() {
+        let server = create_server_with_fetcher(MockContentFetcher::new_with_html(
+            "<html><body><p>Just plain text</p></body></html>",
+        ));
+        let request = McpRequest {
+            id: "test-id".to_string(),
+            method: "tools/call".to_string(),
+            params: json!({
+                "name": "analyze_url",
+                "arguments": { "url": "https://example.com" }
+            }),
+        };
+
+        let response = server.handle_request(request).await;
+        let result = &response["result"];
+
+        assert_eq!(result["frameworks"], json!([]));
+        assert_eq!(result["javascript_heavy"], false);
+        assert_eq!(result["recommended_fetch_method"], "static");
+    }
+
+    #[tokio::test]
+    async fn test_analyze_url_fetch_error() {
+        let server = create_failing_server();
+        let request = McpRequest {
+            id: "test-id".to_string(),
+            method: "tools/call".to_string(),
+            params: json!({
+                "name": "analyze_url",
+                "arguments": { "url": "https://example.com" }
+            }),
+        };
+
+        let response = server.handle_request(request).await;
+
+        assert_eq!(response["error"]["code"], -32001);
+        assert!(response.get("result").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_extract_article_isolates_body() {
+        let html = r#"<html><head><title>Notes | Blog</title><meta name="author" content="Ada Park"></head><body>
+            <nav><a href="/">Home</a> <a href="/tags">Tags</a></nav>
+            <aside class="sidebar"><p>Subscribe to our newsletter for weekly tips, tricks, and news.</p></aside>
+            <article>
+              <h1>Release Notes</h1>
+              <p>This release makes the parser faster, smaller, and far more forgiving of broken markup.</p>
+              <p>Upgrading is a one-line change, and the old API keeps working for now.</p>
+            </article>
+            <footer><p>All content licensed under CC BY, unless stated otherwise.</p></footer>
+        </body></html>"#;
+        let server = create_server_with_parser(MockContentFetcher::new_with_html(html), HtmlParserAdapter::new());
+
+        let response = server.handle_request(McpRequest {
+            id: "test-id".to_string(),
+            method: "tools/call".to_string(),
+            params: json!({
+                "name": "extract_article",
+                "arguments": { "url": "https://example.com/notes" }
+            }),
+        }).await;
+        let article = &response["result"];
+
+        assert_eq!(article["title"], "Release Notes");
+        assert_eq!(article["byline"], "Ada Park");
+        assert_eq!(
+            article["body_text"],
+            "This release makes the parser faster, smaller, and far more forgiving of broken markup.\n\n\
+             Upgrading is a one-line change, and the old API keeps working for now."
+        );
+    }
+
+    #[tokio::test]
+    async fn test_extract_article_fetch_error() {
+        let server = create_failing_server();
+
+        let response = server.handle_request(McpRequest {
+            id: "test-id".to_string(),
+            method: "tools/call".to_string(),
+            params: json!({
+                "name": "extract_article",
+                "arguments": { "url": "https://example.com" }
+            }),
+        }).await;
+
+        assert_eq!(response["error"]["code"], -32001);
+        assert!(response.get("result").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_chunk_content_follows_headings() {
+        let html = r#"<html><body>
+            <h1>Guide</h1>
+            <p>Start here.</p>
+            <h2>Install</h2>
+            <p>Download the release. Unpack it anywhere.</p>
+            <h2>Run</h2>
+            <p>Start the server.</p>
+        </body></html>"#;
+        let server = create_server_with_parser(MockContentFetcher::new_with_html(html), HtmlParserAdapter::new());
+
+        let response = server.handle_request(McpRequest {
+            id: "test-id".to_string(),
+            method: "tools/call".to_string(),
+            params: json!({
+                "name": "chunk_content",
+                "arguments": { "url": "https://example.com/guide", "target_chars": 200 }
+            }),
+        }).await;
+
+        assert_eq!(response["result"]["chunks"], json!([
+            { "index": 0, "heading": "Guide", "text": "Start here." },
+            { "index": 1, "heading": "Install", "text": "Download the release. Unpack it anywhere." },
+            { "index": 2, "heading": "Run", "text": "Start the server." }
+        ]));
+    }
+
+    #[tokio::test]
+    async fn test_compare_content_requires_both_urls() {
+        let server = create_server();
+
+        let response = server.handle_request(McpRequest {
+            id: "test-id".to_string(),
+            method: "tools/call".to_string(),
+            params: json!({
+                "name": "compare_content",
+                "arguments": { "url_a": "https://example.com/a", "url_b": "https://example.com/b" }
+            }),
+        }).await;
+        assert_eq!(response["result"]["similarity"], 1.0);
+        assert_eq!(response["result"]["added_lines"], json!([]));
+        assert_eq!(response["result"]["removed_lines"], json!([]));
+
+        let response = server.handle_request(McpRequest {
+            id: "test-id".to_string(),
+            method: "tools/call".to_string(),
+            params: json!({
+                "name": "compare_content",
+                "arguments": { "url_a": "https://example.com/a" }
+            }),
+        }).await;
+        assert_eq!(response["error"]["code"], -32602);
+        assert_eq!(response["error"]["message"], "Missing required field: url_b");
+    }
+
+    #[tokio::test]
+    async fn test_parse_html_tool_parses_fragment() {
+        let server = create_server_with_parser(MockContentFetcher::new_success(), HtmlParserAdapter::new());
+        let response = server.handle_request(McpRequest {
+            id: "test-id".to_string(),
+            method: "tools/call".to_string(),
+            params: json!({
+                "name": "parse_html",
+                "arguments": {
+                    "html": "<ul><li>Milk</li><li><a href=\"/eggs\">Eggs</a></li></ul>",
+                    "url": "https://shop.example.com/list",
+                    "fragment": true,
+                    "include_links": true
+                }
+            }),
+        }).await;
+
+        assert!(response["error"].is_null(), "{}", response);
+        assert_eq!(response["result"]["text_content"], "Milk Eggs");
+        assert_eq!(response["result"]["links"], json!([{ "href": "https://shop.example.com/eggs", "text": "Eggs" }]));
+
+        let response = server.handle_request(McpRequest {
+            id: "test-id".to_string(),
+            method: "tools/call".to_string(),
+            params: json!({ "name": "parse_html", "arguments": { "fragment": true } }),
+        }).await;
+        assert_eq!(response["error"]["code"], -32602);
+        assert_eq!(response["error"]["message"], "Missing required field: html");
     }
 
     #[tokio::test]
@@ -412,6 +1910,25 @@ mod tests {
         assert!(response["error"]["message"].as_str().unwrap().contains("Unknown tool"));
     }
 
+    #[tokio::test]
+    async fn test_every_listed_tool_can_be_called() {
+        let server = create_server();
+
+        for tool in tool_definitions() {
+            let request = McpRequest {
+                id: "test-id".to_string(),
+                method: "tools/call".to_string(),
+                params: json!({
+                    "name": tool.name,
+                    "arguments": {}
+                }),
+            };
+
+            let response = server.handle_request(request).await;
+            assert_ne!(response["error"]["code"], -32601, "{} is listed but not dispatched", tool.name);
+        }
+    }
+
     #[tokio::test]
     async fn test_handle_tools_call_missing_arguments() {
         let server = create_server();
@@ -470,6 +1987,136 @@ mod tests {
         assert_eq!(response["result"]["serverInfo"]["name"], "html-mcp-reader");
         assert_eq!(response["result"]["serverInfo"]["version"], "0.1.0");
         assert!(response["result"]["capabilities"]["tools"].is_object());
+        assert_eq!(response["result"]["capabilities"]["experimental"]["browser_available"], true);
+    }
+
+    #[tokio::test]
+    async fn test_browser_requests_rejected_when_browser_disabled() {
+        let fetch_service = ContentFetchService::new(Arc::new(MockContentFetcher::new_success())).with_browser_available(false);
+        let parse_service = ContentParseService::new(Arc::new(MockContentParser));
+        let server = McpServer::new(Arc::new(FetchWebContentUseCase::new(Arc::new(fetch_service), Arc::new(parse_service))));
+
+        let initialize = server.handle_request(McpRequest {
+            id: "init".to_string(),
+            method: "initialize".to_string(),
+            params: json!({}),
+        }).await;
+        assert_eq!(initialize["result"]["capabilities"]["experimental"]["browser_available"], false);
+
+        let response = server.handle_request(McpRequest {
+            id: "test-id".to_string(),
+            method: "tools/call".to_string(),
+            params: json!({
+                "name": "fetch_web_content",
+                "arguments": { "url": "https://example.com", "force_fetch_method": "browser" }
+            }),
+        }).await;
+        assert_eq!(response["error"]["code"], -32602);
+        let message = response["error"]["message"].as_str().unwrap();
+        assert!(message.contains("browser mode disabled"), "{}", message);
+
+        let static_fetch = server.handle_request(McpRequest {
+            id: "test-id".to_string(),
+            method: "tools/call".to_string(),
+            params: json!({
+                "name": "fetch_web_content",
+                "arguments": { "url": "https://example.com" }
+            }),
+        }).await;
+        assert!(static_fetch["error"].is_null(), "{}", static_fetch);
+    }
+
+    fn completion_request(tool: &str, argument: &str, value: &str) -> McpRequest {
+        McpRequest {
+            id: "test-id".to_string(),
+            method: "completion/complete".to_string(),
+            params: json!({
+                "ref": { "type": "ref/tool", "name": tool },
+                "argument": { "name": argument, "value": value }
+            }),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_completion_suggests_cached_urls() {
+        let fetcher = CachingContentFetcher::new(Arc::new(MockContentFetcher::new_success()), CacheConfig {
+            max_entries: NonZeroUsize::new(10).unwrap(),
+            max_bytes: None,
+            single_flight: true,
+        });
+        let fetch_service = Arc::new(ContentFetchService::new(Arc::new(fetcher)));
+        let parse_service = Arc::new(ContentParseService::new(Arc::new(MockContentParser)));
+        let server = McpServer::new(Arc::new(FetchWebContentUseCase::new(fetch_service, parse_service)));
+
+        for url in ["https://docs.rs/tokio", "https://example.com", "https://docs.rs/serde"] {
+            server.handle_request(McpRequest {
+                id: "fetch".to_string(),
+                method: "tools/call".to_string(),
+                params: json!({ "name": "fetch_web_content", "arguments": { "url": url } }),
+            }).await;
+        }
+
+        let response = server.handle_request(completion_request("fetch_web_content", "url", "https://docs")).await;
+        let completion = &response["result"]["completion"];
+
+        assert_eq!(completion["values"], json!(["https://docs.rs/serde", "https://docs.rs/tokio"]));
+        assert_eq!(completion["total"], 2);
+        assert_eq!(completion["hasMore"], false);
+    }
+
+    #[tokio::test]
+    async fn test_completion_booleans_enums_and_unknown_refs() {
+        let server = create_server();
+
+        let response = server.handle_request(completion_request("fetch_web_content", "raw_text", "")).await;
+        assert_eq!(response["result"]["completion"]["values"], json!(["true", "false"]));
+
+        let response = server.handle_request(completion_request("fetch_web_content", "http_version", "http")).await;
+        assert_eq!(response["result"]["completion"]["values"], json!(["http1", "http2"]));
+
+        let response = server.handle_request(completion_request("fetch_web_content", "url", "")).await;
+        assert_eq!(response["result"]["completion"]["values"], json!([]));
+
+        let response = server.handle_request(completion_request("no_such_tool", "url", "")).await;
+        assert_eq!(response["result"]["completion"]["values"], json!([]));
+        assert!(response.get("error").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_handle_set_level() {
+        let (_filter, handle) = reload::Layer::<LevelFilter, Registry>::new(LevelFilter::INFO);
+        let server = create_server().with_log_level_handle(handle.clone());
+        let request = McpRequest {
+            id: "test-id".to_string(),
+            method: "logging/setLevel".to_string(),
+            params: json!({ "level": "debug" }),
+        };
+
+        let response = server.handle_request(request).await;
+
+        assert_eq!(response["jsonrpc"], "2.0");
+        assert_eq!(response["id"], "test-id");
+        assert!(response["result"].is_object());
+        assert!(response["error"].is_null());
+        assert_eq!(handle.clone_current(), Some(LevelFilter::DEBUG));
+    }
+
+    #[tokio::test]
+    async fn test_handle_set_level_invalid() {
+        let (_filter, handle) = reload::Layer::<LevelFilter, Registry>::new(LevelFilter::INFO);
+        let server = create_server().with_log_level_handle(handle.clone());
+        let request = McpRequest {
+            id: "test-id".to_string(),
+            method: "logging/setLevel".to_string(),
+            params: json!({ "level": "verbose" }),
+        };
+
+        let response = server.handle_request(request).await;
+
+        assert_eq!(response["id"], "test-id");
+        assert_eq!(response["error"]["code"], -32602);
+        assert!(response["error"]["message"].as_str().unwrap().contains("Invalid log level"));
+        assert_eq!(handle.clone_current(), Some(LevelFilter::INFO));
     }
 
     #[tokio::test]
@@ -501,10 +2148,11 @@ mod tests {
 
         let request = result.unwrap();
         assert_eq!(request.url, "https://example.com");
-        assert_eq!(request.extract_text_only, Some(true));
+        assert_eq!(request.extract_text_only, None); // Decided by the response content type
         assert_eq!(request.follow_redirects, Some(true));
         assert_eq!(request.timeout_seconds, None);
         assert_eq!(request.user_agent, None);
+        assert_eq!(request.include_blocks, None);
     }
 
     #[tokio::test]
@@ -515,7 +2163,49 @@ mod tests {
             "extract_text_only": false,
             "follow_redirects": false,
             "timeout_seconds": 60,
-            "user_agent": "Custom Agent"
+            "user_agent": "Custom Agent",
+            "include_blocks": true,
+            "max_title_length": 80,
+            "include_contacts": true,
+            "include_links": true,
+            "include_images": false,
+            "referer": "https://www.google.com/",
+            "expected_content_types": ["text/html"],
+            "prettify_html": true,
+            "include_forms": true,
+            "read_timeout_seconds": 5,
+            "http_version": "http2",
+            "force_fetch_method": "browser",
+            "encode_base64": true,
+            "include_microdata": true,
+            "raw_text": true,
+            "detect_soft_errors": true,
+            "browser_fallback": false,
+            "output_formats": ["text", "markdown"],
+            "ip_version": "v4",
+            "max_text_bytes": 4096,
+            "max_retries": 2,
+            "www_fallback": true,
+            "min_content_length": 200,
+            "include_block_lang": true,
+            "lowercase_text": true,
+            "include_breadcrumbs": true,
+            "prefetch_links": 3,
+            "output_html_fragment": true,
+            "retry_empty_with_browser": true,
+            "max_elements": 2,
+            "archive_fallback": true,
+            "output_format": "json",
+            "include_feeds": true,
+            "rank_links": true,
+            "include_keywords": true,
+            "upgrade_insecure": true,
+            "strip_tracking_params": true,
+            "validate_anchors": true,
+            "multipart": [{"name": "comment", "value": "hello"}],
+            "strip_inline_styles": true,
+            "fallback_urls": ["https://mirror.example.com"],
+            "inline_link_urls": true
         });
 
         let result = server.parse_fetch_request(&args);
@@ -527,6 +2217,48 @@ mod tests {
         assert_eq!(request.follow_redirects, Some(false));
         assert_eq!(request.timeout_seconds, Some(60));
         assert_eq!(request.user_agent, Some("Custom Agent".to_string()));
+        assert_eq!(request.include_blocks, Some(true));
+        assert_eq!(request.max_title_length, Some(80));
+        assert_eq!(request.include_contacts, Some(true));
+        assert_eq!(request.include_links, Some(true));
+        assert_eq!(request.include_images, Some(false));
+        assert_eq!(request.referer, Some("https://www.google.com/".to_string()));
+        assert_eq!(request.expected_content_types, Some(vec!["text/html".to_string()]));
+        assert_eq!(request.prettify_html, Some(true));
+        assert_eq!(request.include_forms, Some(true));
+        assert_eq!(request.read_timeout_seconds, Some(5));
+        assert_eq!(request.http_version, Some("http2".to_string()));
+        assert_eq!(request.force_fetch_method, Some(FetchMethod::Browser));
+        assert_eq!(request.encode_base64, Some(true));
+        assert_eq!(request.include_microdata, Some(true));
+        assert_eq!(request.raw_text, Some(true));
+        assert_eq!(request.detect_soft_errors, Some(true));
+        assert_eq!(request.browser_fallback, Some(false));
+        assert_eq!(request.output_formats, Some(vec![ContentType::PlainText, ContentType::Markdown]));
+        assert_eq!(request.ip_version, Some("v4".to_string()));
+        assert_eq!(request.max_text_bytes, Some(4096));
+        assert_eq!(request.max_retries, Some(2));
+        assert_eq!(request.www_fallback, Some(true));
+        assert_eq!(request.min_content_length, Some(200));
+        assert_eq!(request.include_block_lang, Some(true));
+        assert_eq!(request.lowercase_text, Some(true));
+        assert_eq!(request.include_breadcrumbs, Some(true));
+        assert_eq!(request.prefetch_links, Some(3));
+        assert_eq!(request.output_html_fragment, Some(true));
+        assert_eq!(request.retry_empty_with_browser, Some(true));
+        assert_eq!(request.max_elements, Some(2));
+        assert_eq!(request.archive_fallback, Some(true));
+        assert_eq!(request.output_format, Some(ContentType::Json));
+        assert_eq!(request.include_feeds, Some(true));
+        assert_eq!(request.rank_links, Some(true));
+        assert_eq!(request.include_keywords, Some(true));
+        assert_eq!(request.upgrade_insecure, Some(true));
+        assert_eq!(request.strip_tracking_params, Some(true));
+        assert_eq!(request.validate_anchors, Some(true));
+        assert_eq!(request.multipart.as_ref().unwrap()[0].name, "comment");
+        assert_eq!(request.strip_inline_styles, Some(true));
+        assert_eq!(request.fallback_urls, Some(vec!["https://mirror.example.com".to_string()]));
+        assert_eq!(request.inline_link_urls, Some(true));
     }
 
     #[tokio::test]
@@ -543,7 +2275,107 @@ mod tests {
         assert!(result.is_ok()); // Should use default value
 
         let request = result.unwrap();
-        assert_eq!(request.extract_text_only, Some(true)); // Should use default
+        assert_eq!(request.extract_text_only, None); // Should use default
+    }
+
+    fn call_fetch_with(arguments: Value) -> McpRequest {
+        McpRequest {
+            id: "test-id".to_string(),
+            method: "tools/call".to_string(),
+            params: json!({ "name": "fetch_web_content", "arguments": arguments }),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_tools_call_rejects_mistyped_arguments() {
+        let server = create_server();
+        let cases = [
+            ("url", json!(42), "expected string, got integer"),
+            ("extract_text_only", json!("yes"), "expected boolean, got string"),
+            ("follow_redirects", json!(1), "expected boolean, got integer"),
+            ("timeout_seconds", json!("30"), "expected integer, got string"),
+            ("read_timeout_seconds", json!(1.5), "expected integer, got number"),
+            ("user_agent", json!(true), "expected string, got boolean"),
+            ("http_version", json!(2), "expected string, got integer"),
+            ("force_fetch_method", json!(null), "expected string, got null"),
+            ("referer", json!({}), "expected string, got object"),
+            ("expected_content_types", json!("text/html"), "expected array, got string"),
+            ("expected_content_types", json!(["text/html", 1]), "expected array of string, got integer item"),
+            ("include_blocks", json!("true"), "expected boolean, got string"),
+            ("max_title_length", json!("80"), "expected integer, got string"),
+            ("include_contacts", json!(0), "expected boolean, got integer"),
+            ("prettify_html", json!([]), "expected boolean, got array"),
+            ("include_links", json!("no"), "expected boolean, got string"),
+            ("include_images", json!(1), "expected boolean, got integer"),
+            ("include_forms", json!("false"), "expected boolean, got string"),
+            ("include_microdata", json!(1), "expected boolean, got integer"),
+            ("raw_text", json!("yes"), "expected boolean, got string"),
+            ("encode_base64", json!("on"), "expected boolean, got string"),
+            ("detect_soft_errors", json!("true"), "expected boolean, got string"),
+            ("browser_fallback", json!(0), "expected boolean, got integer"),
+            ("output_formats", json!("markdown"), "expected array, got string"),
+            ("ip_version", json!(4), "expected string, got integer"),
+            ("max_text_bytes", json!("4096"), "expected integer, got string"),
+            ("max_retries", json!("2"), "expected integer, got string"),
+            ("www_fallback", json!("yes"), "expected boolean, got string"),
+            ("min_content_length", json!("200"), "expected integer, got string"),
+            ("include_block_lang", json!(1), "expected boolean, got integer"),
+            ("lowercase_text", json!("yes"), "expected boolean, got string"),
+            ("include_breadcrumbs", json!("yes"), "expected boolean, got string"),
+            ("prefetch_links", json!("3"), "expected integer, got string"),
+            ("output_html_fragment", json!("yes"), "expected boolean, got string"),
+            ("retry_empty_with_browser", json!(1), "expected boolean, got integer"),
+            ("max_elements", json!("ten"), "expected integer, got string"),
+            ("archive_fallback", json!("on"), "expected boolean, got string"),
+            ("output_format", json!(5), "expected string, got integer"),
+            ("include_feeds", json!("yes"), "expected boolean, got string"),
+            ("rank_links", json!("yes"), "expected boolean, got string"),
+            ("include_keywords", json!("yes"), "expected boolean, got string"),
+            ("upgrade_insecure", json!("yes"), "expected boolean, got string"),
+            ("strip_tracking_params", json!("yes"), "expected boolean, got string"),
+            ("validate_anchors", json!("yes"), "expected boolean, got string"),
+            ("multipart", json!("comment=hello"), "expected array, got string"),
+            ("strip_inline_styles", json!("yes"), "expected boolean, got string"),
+            ("fallback_urls", json!("https://mirror.example.com"), "expected array, got string"),
+            ("inline_link_urls", json!("yes"), "expected boolean, got string"),
+        ];
+
+        for (field, value, expected) in cases {
+            let mut arguments = json!({ "url": "https://example.com" });
+            arguments[field] = value;
+
+            let response = server.handle_request(call_fetch_with(arguments)).await;
+
+            assert_eq!(response["error"]["code"], -32602, "field: {}", field);
+            let message = response["error"]["message"].as_str().unwrap();
+            assert!(message.contains(field) && message.contains(expected), "field: {}, message: {}", field, message);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_tools_call_lenient_args_fall_back_to_defaults() {
+        let server = create_server().with_strict_args(false);
+
+        let response = server.handle_request(call_fetch_with(json!({
+            "url": "https://example.com",
+            "extract_text_only": "not_a_boolean",
+            "timeout_seconds": "30"
+        }))).await;
+
+        assert!(response["error"].is_null());
+        assert!(response["result"].is_object());
+    }
+
+    #[tokio::test]
+    async fn test_tools_call_strict_args_ignore_unknown_fields() {
+        let server = create_server();
+
+        let response = server.handle_request(call_fetch_with(json!({
+            "url": "https://example.com",
+            "not_in_schema": "whatever"
+        }))).await;
+
+        assert!(response["error"].is_null());
     }
 
     #[tokio::test]
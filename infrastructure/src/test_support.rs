@@ -0,0 +1,96 @@
+//! Helpers shared by the crate's tests.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use async_trait::async_trait;
+use axum::Router;
+use domain::model::content::BrowserOptions;
+use domain::port::content_fetcher::ContentFetcherError;
+use crate::client::browser_client::{BrowserEngine, BrowserPage};
+
+/// Serves `app` on a free local port for the rest of the test and returns its
+/// base URL, without a trailing slash.
+pub async fn spawn_server(app: Router) -> String {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    base
+}
+
+/// Stands in for Chrome. Pages render to the HTML given for their URL after
+/// `render_delay`, and fail for any other URL or when asked to wait for a
+/// selector, which never appears. Pages open at once are counted.
+#[derive(Default)]
+pub struct StubBrowser {
+    pages: HashMap<String, String>,
+    render_delay: Duration,
+    open_pages: Arc<AtomicUsize>,
+    peak_pages: Arc<AtomicUsize>,
+}
+
+impl StubBrowser {
+    pub fn with_page(mut self, url: &str, html: &str) -> Self {
+        self.pages.insert(url.to_string(), html.to_string());
+        self
+    }
+
+    pub fn with_render_delay(mut self, render_delay: Duration) -> Self {
+        self.render_delay = render_delay;
+        self
+    }
+
+    /// Counters of the pages open now and of the most ever open at once.
+    pub fn page_counters(&self) -> (Arc<AtomicUsize>, Arc<AtomicUsize>) {
+        (self.open_pages.clone(), self.peak_pages.clone())
+    }
+}
+
+#[async_trait]
+impl BrowserEngine for StubBrowser {
+    async fn open_page(&self, url: &str) -> Result<Box<dyn BrowserPage>, ContentFetcherError> {
+        let open = self.open_pages.fetch_add(1, Ordering::SeqCst) + 1;
+        self.peak_pages.fetch_max(open, Ordering::SeqCst);
+        Ok(Box::new(StubPage {
+            html: self.pages.get(url).cloned(),
+            render_delay: self.render_delay,
+            open_pages: self.open_pages.clone(),
+            closed: AtomicBool::new(false),
+        }))
+    }
+
+    async fn shutdown(&self) {}
+}
+
+struct StubPage {
+    html: Option<String>,
+    render_delay: Duration,
+    open_pages: Arc<AtomicUsize>,
+    closed: AtomicBool,
+}
+
+#[async_trait]
+impl BrowserPage for StubPage {
+    async fn render(
+        &self,
+        url: &str,
+        options: &BrowserOptions,
+        _max_document_bytes: Option<usize>,
+    ) -> Result<String, ContentFetcherError> {
+        tokio::time::sleep(self.render_delay).await;
+        if options.wait_for_selector.is_some() {
+            return Err(ContentFetcherError::Timeout(options.timeout_ms / 1000));
+        }
+        self.html
+            .clone()
+            .ok_or_else(|| ContentFetcherError::network(format!("Failed to navigate to {}", url)))
+    }
+
+    async fn close(&self) -> Result<(), ContentFetcherError> {
+        if !self.closed.swap(true, Ordering::SeqCst) {
+            self.open_pages.fetch_sub(1, Ordering::SeqCst);
+        }
+        Ok(())
+    }
+}
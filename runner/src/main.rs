@@ -1,27 +1,44 @@
+use std::net::{IpAddr, SocketAddr};
+use std::path::PathBuf;
+use std::num::{NonZeroU32, NonZeroUsize};
 use std::sync::Arc;
-use std::io::{self, BufRead, BufReader, Write};
-use serde_json::{json, Value};
-use tracing::{info, error, debug, Level};
-use tracing_subscriber::FmtSubscriber;
+use std::time::Duration;
+use std::io;
+use tracing::{info, warn};
+use tracing_subscriber::{filter::LevelFilter, fmt, prelude::*, reload};
 use clap::{Parser, Subcommand};
 use axum::serve;
 use tokio::net::TcpListener;
+use tokio::sync::Semaphore;
 
-use domain::model::request::McpRequest;
+use domain::port::{audit_sink::AuditSink, content_fetcher::ContentFetcher, fetch_guard::FetchGuard};
 use application::service::{
-    content_fetch_service::ContentFetchService,
+    content_fetch_service::{ContentFetchService, DEFAULT_ALLOWED_PORTS, DEFAULT_MAX_URL_LENGTH, DEFAULT_TIMEOUT_SECONDS},
     content_parse_service::ContentParseService,
 };
 use application::use_case::fetch_web_content_use_case::FetchWebContentUseCase;
 use infrastructure::{
-    client::hybrid_fetcher::HybridContentFetcher,
-    adapter::html_parser_adapter::HtmlParserAdapter,
-    mcp::server::McpServer,
-    api::server::ApiServer,
+    client::{
+        archive_fallback_fetcher::ArchiveFallbackFetcher,
+        browser_client::{BrowserLimits, DEFAULT_BROWSER_PER_HOST_CONCURRENCY, DEFAULT_MAX_BROWSER_PAGES},
+        caching_fetcher::{CacheConfig, CachingContentFetcher},
+        fetcher_kind::FetcherKind,
+        header_redaction::DEFAULT_REDACTED_HEADERS,
+        http_client::HttpClient,
+        request_signing::{SigningAlgorithm, SigningProfile},
+        timing_fetcher::TimingFetcher,
+    },
+    adapter::{
+        html_parser_adapter::HtmlParserAdapter,
+        jsonl_file_audit_sink::JsonlFileAuditSink,
+        static_allowlist_guard::StaticAllowlistGuard,
+    },
+    mcp::server::{LogLevelHandle, McpServer, DEFAULT_MAX_BODY_LOG_BYTES},
+    api::{idempotency::DEFAULT_IDEMPOTENCY_TTL, server::ApiServer},
 };
 
-type AppMcpServer = McpServer<HybridContentFetcher, HtmlParserAdapter>;
-type AppApiServer = ApiServer<HybridContentFetcher, HtmlParserAdapter>;
+type AppMcpServer = McpServer<dyn ContentFetcher, HtmlParserAdapter>;
+type AppApiServer = ApiServer<dyn ContentFetcher, HtmlParserAdapter>;
 
 #[derive(Parser)]
 #[command(name = "html-mcp-reader")]
@@ -29,8 +46,146 @@ type AppApiServer = ApiServer<HybridContentFetcher, HtmlParserAdapter>;
 struct Cli {
     #[command(subcommand)]
     command: Option<Commands>,
+
+    /// Content fetcher to use: static, browser or hybrid
+    #[arg(long, global = true, default_value_t = FetcherKind::Hybrid)]
+    fetcher: FetcherKind,
+
+    /// Never start Chrome: fetch statically and reject requests that need the browser
+    #[arg(long, global = true, conflicts_with = "fetcher")]
+    disable_browser: bool,
+
+    /// Maximum number of browser pages open at once when rendering JavaScript
+    #[arg(long, global = true, default_value_t = DEFAULT_MAX_BROWSER_PAGES)]
+    max_browser_pages: usize,
+
+    /// Maximum number of pages from the same host rendered in the browser at once
+    #[arg(long, global = true, default_value_t = DEFAULT_BROWSER_PER_HOST_CONCURRENCY)]
+    browser_per_host_concurrency: usize,
+
+    /// Cap each browser renderer's JavaScript heap at this many megabytes and abandon
+    /// renders that take over a minute or produce a larger document (no limit when unset)
+    #[arg(long, global = true)]
+    max_browser_memory_mb: Option<usize>,
+
+    /// Reject MCP tool arguments whose type doesn't match the tool's input schema;
+    /// pass `--strict-args false` to fall back to defaults instead
+    #[arg(long, global = true, default_value_t = true, action = clap::ArgAction::Set)]
+    strict_args: bool,
+
+    /// Log at most this many bytes of each MCP request and response line at debug level
+    #[arg(long, global = true, default_value_t = DEFAULT_MAX_BODY_LOG_BYTES)]
+    max_body_log_bytes: usize,
+
+    /// Header whose value is logged as *** (repeatable, case-insensitive); replaces the
+    /// default list
+    #[arg(long = "redact-header", global = true, default_values_t = DEFAULT_REDACTED_HEADERS.iter().map(|name| name.to_string()))]
+    redacted_headers: Vec<String>,
+
+    /// Cache up to this many fetched pages in memory (caching is off when unset)
+    #[arg(long, global = true)]
+    cache_max_entries: Option<NonZeroUsize>,
+
+    /// Also evict cached pages once their HTML and text exceed this many bytes in total
+    #[arg(long, global = true, requires = "cache_max_entries")]
+    cache_max_bytes: Option<usize>,
+
+    /// Let concurrent requests for the same uncached page each fetch it, instead
+    /// of waiting for the first one's fetch
+    #[arg(long, global = true, requires = "cache_max_entries")]
+    no_cache_single_flight: bool,
+
+    /// Fetch at most this many pages at once across the MCP and REST servers, with
+    /// further requests waiting their turn (no limit when unset)
+    #[arg(long, global = true)]
+    max_concurrency: Option<NonZeroUsize>,
+
+    /// Append a JSON line for every fetched URL (time, URL, status, bytes and the
+    /// REST client's address) to this file
+    #[arg(long, global = true)]
+    audit_log: Option<PathBuf>,
+
+    /// Report each fetch's total duration in `metadata.timing`, next to the
+    /// phases the fetcher measures itself
+    #[arg(long, global = true)]
+    record_timing: bool,
+
+    /// Timeout in seconds for requests that don't set their own (1-300)
+    #[arg(long, global = true, default_value_t = DEFAULT_TIMEOUT_SECONDS, value_parser = clap::value_parser!(u64).range(1..=300))]
+    timeout_default: u64,
+
+    /// Reject request URLs longer than this many bytes
+    #[arg(long, global = true, default_value_t = DEFAULT_MAX_URL_LENGTH)]
+    max_url_length: usize,
+
+    /// Ports request URLs may name; URLs without a port are always allowed
+    #[arg(long, global = true, value_delimiter = ',', default_values_t = DEFAULT_ALLOWED_PORTS.to_vec())]
+    allowed_ports: Vec<u16>,
+
+    /// Allow request URLs to name any port, ignoring --allowed-ports
+    #[arg(long, global = true)]
+    allow_any_port: bool,
+
+    /// Only fetch URLs on this host and its subdomains (repeatable); any host is
+    /// allowed when unset
+    #[arg(long = "allow-host", global = true)]
+    allowed_hosts: Vec<String>,
+
+    /// Let multipart requests upload local files named by file:// URLs
+    #[arg(long, global = true)]
+    allow_file_urls: bool,
+
+    /// Address of a reverse proxy whose X-Forwarded-For/X-Real-IP headers name the
+    /// real client (repeatable); without it those headers are ignored
+    #[arg(long = "trusted-proxy", global = true)]
+    trusted_proxies: Vec<IpAddr>,
+
+    /// Answer API requests beyond this many per client per minute with 429 (no limit when unset)
+    #[arg(long, global = true)]
+    rate_limit_per_minute: Option<NonZeroU32>,
+
+    /// Seconds a batch result is replayed for repeats of its Idempotency-Key
+    #[arg(long, global = true, default_value_t = DEFAULT_IDEMPOTENCY_TTL.as_secs(), value_parser = clap::value_parser!(u64).range(1..))]
+    idempotency_ttl: u64,
+
+    /// Answer failed API fetches with a generic message instead of the error,
+    /// which is still logged in full
+    #[arg(long, global = true)]
+    sanitize_errors: bool,
+
+    /// Sign static requests to this host and its subdomains with an HMAC of the URL
+    /// and timestamp (repeatable); the secret is read from SIGNING_SECRET
+    #[arg(long = "signing-host", global = true)]
+    signing_hosts: Vec<String>,
+
+    /// Header the request signature is sent in
+    #[arg(long, global = true, default_value = "X-Signature")]
+    signing_header: String,
+
+    /// HMAC used for request signatures: hmac-sha256 or hmac-sha512
+    #[arg(long, global = true, default_value_t = SigningAlgorithm::HmacSha256)]
+    signing_algorithm: SigningAlgorithm,
+
+    /// Write logs without ANSI colors (also implied by NO_COLOR or non-terminal output)
+    #[arg(long, global = true)]
+    no_color: bool,
+}
+
+/// Environment variable that turns off colored output when set to anything
+/// non-empty, per https://no-color.org.
+const NO_COLOR_ENV: &str = "NO_COLOR";
+
+/// Whether log lines get ANSI colors: only when writing to a terminal and
+/// neither `--no-color` nor a non-empty `NO_COLOR` asks otherwise.
+fn use_ansi_colors(no_color_flag: bool, no_color_env: Option<&std::ffi::OsStr>, is_terminal: bool) -> bool {
+    let no_color_env = no_color_env.is_some_and(|value| !value.is_empty());
+    is_terminal && !no_color_flag && !no_color_env
 }
 
+/// Environment variable holding the request signing secret, kept off the command
+/// line so it doesn't show up in process listings.
+const SIGNING_SECRET_ENV: &str = "SIGNING_SECRET";
+
 #[derive(Subcommand)]
 enum Commands {
     /// Run as MCP server (JSON-RPC over stdin/stdout)
@@ -43,35 +198,164 @@ enum Commands {
     },
 }
 
+/// Which content fetcher is built and how it makes requests.
+struct FetcherSetup {
+    kind: FetcherKind,
+    browser_limits: BrowserLimits,
+    signing_profiles: Vec<SigningProfile>,
+    /// Headers logged as `***`.
+    redacted_headers: Vec<String>,
+    /// Timeout for requests that don't set their own.
+    default_timeout_seconds: u64,
+    record_timing: bool,
+    /// Pages fetched at once by both servers together.
+    max_concurrency: Option<NonZeroUsize>,
+    /// File every fetched URL is appended to.
+    audit_log: Option<PathBuf>,
+}
+
+/// Which request URLs are accepted before anything is fetched.
+struct UrlPolicy {
+    max_url_length: usize,
+    /// `None` allows any port.
+    allowed_ports: Option<Vec<u16>>,
+    /// Empty allows any host.
+    allowed_hosts: Vec<String>,
+    allow_file_urls: bool,
+}
+
+/// How the REST API identifies and limits its clients and replays their retries.
+struct ApiOptions {
+    trusted_proxies: Vec<IpAddr>,
+    rate_limit_per_minute: Option<NonZeroU32>,
+    idempotency_ttl: Duration,
+    sanitize_errors: bool,
+}
+
 struct AppState {
     mcp_server: AppMcpServer,
     api_server: AppApiServer,
+    fetcher: Arc<dyn ContentFetcher>,
 }
 
 impl AppState {
-    async fn new() -> Result<Self, Box<dyn std::error::Error>> {
-        let hybrid_fetcher = HybridContentFetcher::new(None).await?;
-        let hybrid_fetcher_arc = Arc::new(hybrid_fetcher);
+    async fn new(
+        log_level_handle: LogLevelHandle,
+        fetcher_setup: FetcherSetup,
+        cache_config: Option<CacheConfig>,
+        strict_args: bool,
+        max_body_log_bytes: usize,
+        url_policy: UrlPolicy,
+        api_options: ApiOptions,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        let kind = fetcher_setup.kind;
+        let fetch_guard = (!url_policy.allowed_hosts.is_empty())
+            .then(|| Arc::new(StaticAllowlistGuard::new(url_policy.allowed_hosts.clone())) as Arc<dyn FetchGuard>);
+        let http_client = || {
+            let http_client = HttpClient::new()
+                .with_signing_profiles(fetcher_setup.signing_profiles.clone())
+                .with_redacted_headers(fetcher_setup.redacted_headers.clone())
+                .with_allowed_ports(url_policy.allowed_ports.clone());
+            match &fetch_guard {
+                Some(guard) => http_client.with_fetch_guard(guard.clone()),
+                None => http_client,
+            }
+        };
+        let (mut fetcher_arc, browser_available) = match kind.build(fetcher_setup.browser_limits, http_client()).await {
+            Ok(fetcher) => {
+                info!("Using {} content fetcher", kind);
+                (fetcher, kind.uses_browser())
+            }
+            Err(error) if kind.uses_browser() => {
+                warn!("Could not start the browser ({}); using the static content fetcher instead", error);
+                (FetcherKind::Static.build(fetcher_setup.browser_limits, http_client()).await?, false)
+            }
+            Err(error) => return Err(error.into()),
+        };
+        // Only acts on requests that set archive_fallback
+        fetcher_arc = Arc::new(ArchiveFallbackFetcher::new(fetcher_arc));
+        if fetcher_setup.record_timing {
+            fetcher_arc = Arc::new(TimingFetcher::new(fetcher_arc));
+        }
+
+        // One set of slots for both servers and the cache's prefetches, so
+        // running both servers doesn't double the limit
+        let fetch_slots = fetcher_setup.max_concurrency.map(|max_concurrency| {
+            info!("Fetching at most {} pages at once", max_concurrency);
+            Arc::new(Semaphore::new(max_concurrency.get()))
+        });
+
+        let audit_sink = match &fetcher_setup.audit_log {
+            Some(audit_log) => {
+                let sink = JsonlFileAuditSink::open(audit_log)
+                    .await
+                    .map_err(|error| format!("Could not open the audit log {}: {}", audit_log.display(), error))?;
+                info!("Recording every fetched URL in {}", audit_log.display());
+                Some(Arc::new(sink) as Arc<dyn AuditSink>)
+            }
+            None => None,
+        };
+
+        if let Some(cache_config) = cache_config {
+            info!("Caching up to {} pages in memory", cache_config.max_entries);
+            let mut caching_fetcher = CachingContentFetcher::new(fetcher_arc, cache_config);
+            if let Some(guard) = &fetch_guard {
+                caching_fetcher = caching_fetcher.with_fetch_guard(guard.clone());
+            }
+            if let Some(fetch_slots) = &fetch_slots {
+                caching_fetcher = caching_fetcher.with_fetch_slots(fetch_slots.clone());
+            }
+            if let Some(sink) = &audit_sink {
+                caching_fetcher = caching_fetcher.with_audit_sink(sink.clone());
+            }
+            fetcher_arc = Arc::new(caching_fetcher);
+        }
 
         let html_parser = HtmlParserAdapter::new();
         let html_parser_arc = Arc::new(html_parser);
 
-        let fetch_service = ContentFetchService::new(hybrid_fetcher_arc.clone());
+        let fetch_service = ContentFetchService::new(fetcher_arc.clone())
+            .with_max_url_length(url_policy.max_url_length)
+            .with_allowed_ports(url_policy.allowed_ports)
+            .with_default_timeout(fetcher_setup.default_timeout_seconds)
+            .with_browser_available(browser_available)
+            .with_file_urls_allowed(url_policy.allow_file_urls);
         let fetch_service_arc = Arc::new(fetch_service);
 
         let parse_service = ContentParseService::new(html_parser_arc.clone());
         let parse_service_arc = Arc::new(parse_service);
 
-        let web_content_use_case = FetchWebContentUseCase::new(
+        let mut web_content_use_case = FetchWebContentUseCase::new(
             fetch_service_arc,
             parse_service_arc,
         );
+        if let Some(guard) = fetch_guard {
+            info!("Only fetching from {}", url_policy.allowed_hosts.join(", "));
+            web_content_use_case = web_content_use_case.with_fetch_guard(guard);
+        }
+        if let Some(sink) = audit_sink {
+            web_content_use_case = web_content_use_case.with_audit_sink(sink);
+        }
         let web_content_use_case_arc = Arc::new(web_content_use_case);
 
-        let mcp_server = McpServer::new(web_content_use_case_arc.clone());
-        let api_server = ApiServer::new(web_content_use_case_arc);
+        let mut mcp_server = McpServer::new(web_content_use_case_arc.clone())
+            .with_log_level_handle(log_level_handle)
+            .with_strict_args(strict_args)
+            .with_max_body_log_bytes(max_body_log_bytes);
+        let mut api_server = ApiServer::new(web_content_use_case_arc)
+            .with_trusted_proxies(api_options.trusted_proxies)
+            .with_idempotency_ttl(api_options.idempotency_ttl)
+            .with_sanitized_errors(api_options.sanitize_errors);
+        if let Some(fetch_slots) = fetch_slots {
+            mcp_server = mcp_server.with_fetch_slots(fetch_slots.clone());
+            api_server = api_server.with_fetch_slots(fetch_slots);
+        }
+        if let Some(max_requests) = api_options.rate_limit_per_minute {
+            info!("Limiting API clients to {} requests per minute", max_requests);
+            api_server = api_server.with_rate_limit(max_requests);
+        }
 
-        Ok(Self { mcp_server, api_server })
+        Ok(Self { mcp_server, api_server, fetcher: fetcher_arc })
     }
 }
 
@@ -79,18 +363,72 @@ impl AppState {
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let cli = Cli::parse();
 
-    // Initialize logging
-    let subscriber = FmtSubscriber::builder()
-        .with_max_level(Level::INFO)
-        .finish();
-
-    tracing::subscriber::set_global_default(subscriber)
+    // Initialize logging with a reloadable level so MCP clients can change it at runtime
+    // Logs go to stdout, so colors only make sense when that's a terminal
+    let (level_filter, log_level_handle) = reload::Layer::new(LevelFilter::INFO);
+    let ansi = use_ansi_colors(
+        cli.no_color,
+        std::env::var_os(NO_COLOR_ENV).as_deref(),
+        atty::is(atty::Stream::Stdout),
+    );
+    tracing_subscriber::registry()
+        .with(level_filter)
+        .with(fmt::layer().with_ansi(ansi))
+        .try_init()
         .expect("Setting default subscriber failed");
 
     // Initialize application state
-    let state = AppState::new().await?;
-
-    match cli.command {
+    let cache_config = cli.cache_max_entries.map(|max_entries| CacheConfig {
+        max_entries,
+        max_bytes: cli.cache_max_bytes,
+        single_flight: !cli.no_cache_single_flight,
+    });
+    let browser_limits = BrowserLimits {
+        max_pages: cli.max_browser_pages,
+        per_host: cli.browser_per_host_concurrency,
+        max_memory_mb: cli.max_browser_memory_mb,
+    };
+    let signing_profiles = if cli.signing_hosts.is_empty() {
+        Vec::new()
+    } else {
+        let secret = std::env::var(SIGNING_SECRET_ENV)
+            .map_err(|_| format!("--signing-host requires the {} environment variable", SIGNING_SECRET_ENV))?;
+        info!("Signing requests to {} with {}", cli.signing_hosts.join(", "), cli.signing_algorithm);
+        vec![SigningProfile::new(cli.signing_hosts, &cli.signing_header, secret, cli.signing_algorithm)?]
+    };
+    let state = AppState::new(
+        log_level_handle,
+        FetcherSetup {
+            kind: if cli.disable_browser { FetcherKind::Static } else { cli.fetcher },
+            browser_limits,
+            signing_profiles,
+            redacted_headers: cli.redacted_headers,
+            default_timeout_seconds: cli.timeout_default,
+            record_timing: cli.record_timing,
+            max_concurrency: cli.max_concurrency,
+            audit_log: cli.audit_log,
+        },
+        cache_config,
+        cli.strict_args,
+        cli.max_body_log_bytes,
+        UrlPolicy {
+            max_url_length: cli.max_url_length,
+            allowed_ports: (!cli.allow_any_port).then_some(cli.allowed_ports),
+            allowed_hosts: cli.allowed_hosts,
+            allow_file_urls: cli.allow_file_urls,
+        },
+        ApiOptions {
+            trusted_proxies: cli.trusted_proxies,
+            rate_limit_per_minute: cli.rate_limit_per_minute,
+            idempotency_ttl: Duration::from_secs(cli.idempotency_ttl),
+            sanitize_errors: cli.sanitize_errors,
+        },
+    ).await?;
+
+    // Keep a handle so the browser can be torn down however the server exits
+    let fetcher = state.fetcher.clone();
+
+    let result = match cli.command {
         Some(Commands::Mcp) => {
             run_mcp_server(state).await
         }
@@ -111,7 +449,10 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 run_mcp_server(state).await
             }
         }
-    }
+    };
+
+    fetcher.shutdown().await;
+    result
 }
 
 async fn run_mcp_server(state: AppState) -> Result<(), Box<dyn std::error::Error>> {
@@ -119,45 +460,7 @@ async fn run_mcp_server(state: AppState) -> Result<(), Box<dyn std::error::Error
     info!("MCP server initialized, waiting for requests...");
 
     // Read JSON-RPC requests from stdin and write responses to stdout
-    let stdin = io::stdin();
-    let reader = BufReader::new(stdin.lock());
-
-    for line in reader.lines() {
-        let line = line?;
-        
-        if line.trim().is_empty() {
-            continue;
-        }
-
-        debug!("Received request: {}", line);
-
-        match parse_request(&line) {
-            Ok(request) => {
-                let response = state.mcp_server.handle_request(request).await;
-                let response_json = serde_json::to_string(&response)?;
-                
-                println!("{}", response_json);
-                io::stdout().flush().unwrap();
-                
-                debug!("Sent response: {}", response_json);
-            }
-            Err(error) => {
-                error!("Failed to parse request: {}", error);
-                
-                let error_response = json!({
-                    "jsonrpc": "2.0",
-                    "id": null,
-                    "error": {
-                        "code": -32700,
-                        "message": format!("Parse error: {}", error)
-                    }
-                });
-                
-                println!("{}", serde_json::to_string(&error_response)?);
-                io::stdout().flush().unwrap();
-            }
-        }
-    }
+    state.mcp_server.serve(io::stdin().lock(), io::stdout()).await?;
 
     info!("MCP server shutting down");
     Ok(())
@@ -175,31 +478,37 @@ async fn run_api_server(state: AppState, port: u16) -> Result<(), Box<dyn std::e
     info!("REST API server listening on {}", addr);
     info!("Health check available at: http://{}/health", addr);
     info!("Fetch endpoint available at: http://{}/api/fetch", addr);
+    info!("Batch endpoint available at: http://{}/api/batch", addr);
+    info!("Markdown endpoint available at: http://{}/api/markdown", addr);
+    info!("Crawl progress stream available at: http://{}/api/crawl/stream", addr);
 
-    serve(listener, app).await?;
+    // Peer addresses identify clients for logging and rate limiting
+    serve(listener, app.into_make_service_with_connect_info::<SocketAddr>())
+        .with_graceful_shutdown(async {
+            let _ = tokio::signal::ctrl_c().await;
+        })
+        .await?;
 
     info!("API server shutting down");
     Ok(())
 }
 
-fn parse_request(line: &str) -> Result<McpRequest, String> {
-    let value: Value = serde_json::from_str(line)
-        .map_err(|e| format!("Invalid JSON: {}", e))?;
-
-    let id = value.get("id")
-        .and_then(|v| v.as_str())
-        .or_else(|| value.get("id").and_then(|v| v.as_i64()).map(|i| Box::leak(i.to_string().into_boxed_str()) as &str))
-        .unwrap_or("unknown")
-        .to_string();
-
-    let method = value.get("method")
-        .and_then(|v| v.as_str())
-        .ok_or("Missing method field")?
-        .to_string();
-
-    let params = value.get("params")
-        .cloned()
-        .unwrap_or(json!({}));
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::OsStr;
+
+    #[test]
+    fn test_no_color_env_disables_ansi() {
+        assert!(!use_ansi_colors(false, Some(OsStr::new("1")), true));
+        // An empty NO_COLOR doesn't count as set
+        assert!(use_ansi_colors(false, Some(OsStr::new("")), true));
+        assert!(use_ansi_colors(false, None, true));
+    }
 
-    Ok(McpRequest { id, method, params })
-}
\ No newline at end of file
+    #[test]
+    fn test_no_color_flag_and_non_terminal_disable_ansi() {
+        assert!(!use_ansi_colors(true, None, true));
+        assert!(!use_ansi_colors(false, None, false));
+    }
+}